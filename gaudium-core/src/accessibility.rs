@@ -0,0 +1,88 @@
+//! Accessibility.
+//!
+//! Applications that draw their own user interfaces are opaque to assistive
+//! technologies, such as screen readers. This module exposes a minimal
+//! description of a `Window` and the custom elements drawn within it: the
+//! accessible name and role of the window and notifications when the focused
+//! element changes.
+//!
+//! This is not a complete element tree. Platforms announce focused elements
+//! and changes to them, but assistive technologies cannot navigate elements.
+//!
+//! # Examples
+//!
+//! Announcing the focused button of a custom user interface:
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::accessibility::{AccessibleElement, Role};
+//! use gaudium_core::window::Window;
+//! use gaudium_platform_empty::Binding;
+//!
+//! fn focus_play(window: &mut Window<Binding>) {
+//!     window.set_accessible_role(Role::Pane).unwrap();
+//!     window
+//!         .set_accessible_focus(Some(AccessibleElement::new(1, "Play", Role::Button)))
+//!         .unwrap();
+//! }
+//! # fn main() {}
+//! ```
+
+/// The role of a `Window` or custom element, which describes how it behaves
+/// to assistive technologies.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Role {
+    /// A top-level window.
+    #[default]
+    Window,
+    /// A region that contains other elements.
+    Pane,
+    /// A group of related elements.
+    Group,
+    /// A region of text or other content that can be read.
+    Document,
+    Button,
+    CheckBox,
+    /// Editable text.
+    Edit,
+    Image,
+    Link,
+    List,
+    ListItem,
+    Menu,
+    MenuItem,
+    Slider,
+    Tab,
+    /// Text that cannot be edited, such as a label.
+    Text,
+    /// An element that has no standard role.
+    Custom,
+}
+
+/// A custom element drawn within a `Window`.
+///
+/// Elements are identified by an arbitrary `id` that is chosen by the
+/// application and must be unique within a window.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AccessibleElement {
+    pub id: u64,
+    /// The name of the element as presented to the user, such as the label of
+    /// a button.
+    pub name: String,
+    pub role: Role,
+}
+
+impl AccessibleElement {
+    pub fn new<T>(id: u64, name: T, role: Role) -> Self
+    where
+        T: Into<String>,
+    {
+        AccessibleElement {
+            id,
+            name: name.into(),
+            role,
+        }
+    }
+}
@@ -1,12 +1,83 @@
-use crate::platform::{self, PlatformBinding};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+
+use crate::error::Error;
+use crate::platform::{self, Device as _, PlatformBinding};
+use crate::registry;
+#[cfg(feature = "serde")]
+use crate::serial;
 use crate::{FromRawHandle, IntoRawHandle};
 
 /// An opaque type that identifies an input device.
-#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct DeviceHandle<P>(platform::DeviceHandle<P>)
 where
     P: PlatformBinding;
 
+impl<P> Eq for DeviceHandle<P> where P: PlatformBinding {}
+
+impl<P> Hash for DeviceHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.0.hash(state)
+    }
+}
+
+/// Handles are ordered by their raw platform handles. This order is arbitrary
+/// but consistent for the lifetime of the handles, so handles can be used as
+/// keys in ordered collections like `BTreeMap`.
+impl<P> Ord for DeviceHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<P> PartialOrd for DeviceHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<P> Serialize for DeviceHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serial::serialize_handle(*self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P> Deserialize<'de> for DeviceHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serial::deserialize_handle(deserializer, "device")
+    }
+}
+
 impl<P> FromRawHandle<platform::DeviceHandle<P>> for DeviceHandle<P>
 where
     P: PlatformBinding,
@@ -25,12 +96,171 @@ where
     }
 }
 
+impl<P> DeviceHandle<P>
+where
+    P: PlatformBinding,
+{
+    /// Queries metadata for the device.
+    ///
+    /// Returns `None` if the device is no longer connected or the platform
+    /// cannot query metadata.
+    pub fn info(&self) -> Option<DeviceInfo> {
+        P::Device::info(self.0)
+    }
+
+    /// Queries the battery of the device.
+    ///
+    /// Returns `None` if the device has no battery, is no longer connected, or
+    /// the platform cannot query batteries. Changes to batteries are not
+    /// reported by events, so batteries of wireless devices should be queried
+    /// periodically.
+    pub fn battery(&self) -> Option<Battery> {
+        P::Device::battery(self.0)
+    }
+
+    /// Determines if the device reports samples of the given motion sensor via
+    /// `InputEvent::MotionSensor`.
+    pub fn has_sensor(&self, sensor: SensorType) -> bool {
+        P::Device::has_sensor(self.0, sensor)
+    }
+
+    /// Gets the handle of the device with the given identifier.
+    ///
+    /// Returns `None` if the device has been disconnected.
+    pub fn from_id(id: DeviceId) -> Option<Self> {
+        registry::handle(id.0)
+    }
+
+    /// Gets the stable identifier of the device.
+    ///
+    /// See `DeviceId`.
+    pub fn id(self) -> DeviceId {
+        DeviceId(registry::id(self))
+    }
+}
+
 unsafe impl<P> Send for DeviceHandle<P> where P: PlatformBinding {}
 unsafe impl<P> Sync for DeviceHandle<P> where P: PlatformBinding {}
 
+/// A stable identifier for an input device.
+///
+/// Platforms may reuse the raw handles of devices that have been disconnected.
+/// Unlike `DeviceHandle`, identifiers are never reused within a process. A
+/// device that is disconnected and connected again is assigned a new
+/// identifier.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct DeviceId(NonZeroU64);
+
+impl DeviceId {
+    /// Gets the identifier as an integer.
+    pub fn get(self) -> u64 {
+        self.0.get()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Usage {
     Keyboard,
     Mouse,
     GameController,
 }
+
+/// Output to an input device, such as its LEDs and HID output reports.
+///
+/// Platforms and devices that do not accept output fail with
+/// `ErrorKind::Unsupported`.
+pub trait Output {
+    /// Determines if the device has an LED that can be set via `set_led`.
+    fn has_led(&self, led: Led) -> bool;
+
+    /// Turns an LED of the device on or off.
+    ///
+    /// Setting a lock LED does not change the lock state of a keyboard, and
+    /// the platform may set the LED again when the lock state changes.
+    fn set_led(&self, led: Led, on: bool) -> Result<(), Error>;
+
+    /// Sends an output report to the device, such as a report that sets the
+    /// color of the light bar of a game controller.
+    ///
+    /// The first byte of `report` is the report identifier, which is zero for
+    /// devices that do not number their reports. The format of the remaining
+    /// bytes is specific to the device.
+    fn send_output_report(&self, report: &[u8]) -> Result<(), Error>;
+}
+
+impl<P> Output for DeviceHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn has_led(&self, led: Led) -> bool {
+        <P::Device as platform::Output>::has_led(self.0, led)
+    }
+
+    fn set_led(&self, led: Led, on: bool) -> Result<(), Error> {
+        <P::Device as platform::Output>::set_led(self.0, led, on)
+    }
+
+    fn send_output_report(&self, report: &[u8]) -> Result<(), Error> {
+        <P::Device as platform::Output>::send_output_report(self.0, report)
+    }
+}
+
+/// An LED of an input device, such as the caps lock light of a keyboard.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Led {
+    NumLock,
+    CapsLock,
+    ScrollLock,
+    Compose,
+    Kana,
+}
+
+/// A motion sensor of an input device.
+///
+/// Axes are relative to the device. For game controllers held in a neutral
+/// position, `x` is right, `y` is up, and `z` is toward the user.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum SensorType {
+    /// Linear acceleration, including gravity, in meters per second squared.
+    Accelerometer,
+    /// Angular velocity in radians per second.
+    Gyroscope,
+}
+
+/// Metadata describing an input device.
+///
+/// Fields are `None` if the platform or device does not report them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DeviceInfo {
+    pub usage: Option<Usage>,
+    /// The number of buttons reported by the device.
+    pub button_count: Option<u16>,
+    /// The USB vendor identifier of the device.
+    pub vendor_id: Option<u16>,
+    /// The USB product identifier of the device.
+    pub product_id: Option<u16>,
+    /// The version (release number) of the device.
+    pub version: Option<u16>,
+}
+
+/// Charging state of a battery.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+}
+
+/// Battery of an input device, such as a wireless game controller.
+///
+/// Fields are `None` if the platform or device does not report them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Battery {
+    /// The remaining charge in the range `[0.0, 1.0]`.
+    pub level: Option<f64>,
+    pub state: Option<BatteryState>,
+}
@@ -1,8 +1,198 @@
-use std::ops::Deref;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Deref, Div, Mul, Neg, Sub};
 
-// TODO: Use a platform binding to wrap a native handle.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct DisplayHandle(u64);
+use crate::error::Error;
+use crate::platform::{self, PlatformBinding};
+use crate::reactor::ThreadContext;
+#[cfg(feature = "serde")]
+use crate::serial;
+use crate::{FromRawHandle, IntoRawHandle};
+
+/// An opaque type that identifies a display.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayHandle<P>(platform::DisplayHandle<P>)
+where
+    P: PlatformBinding;
+
+impl<P> Eq for DisplayHandle<P> where P: PlatformBinding {}
+
+impl<P> Hash for DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.0.hash(state)
+    }
+}
+
+/// Handles are ordered by their raw platform handles. This order is arbitrary
+/// but consistent for the lifetime of the handles, so handles can be used as
+/// keys in ordered collections like `BTreeMap`.
+impl<P> Ord for DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<P> PartialOrd for DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<P> Serialize for DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serial::serialize_handle(*self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P> Deserialize<'de> for DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serial::deserialize_handle(deserializer, "display")
+    }
+}
+
+impl<P> FromRawHandle<platform::DisplayHandle<P>> for DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn from_raw_handle(handle: platform::DisplayHandle<P>) -> Self {
+        DisplayHandle(handle)
+    }
+}
+
+impl<P> IntoRawHandle<platform::DisplayHandle<P>> for DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn into_raw_handle(self) -> platform::DisplayHandle<P> {
+        self.0
+    }
+}
+
+impl<P> DisplayHandle<P>
+where
+    P: PlatformBinding,
+{
+    /// Gets the handles of all connected displays.
+    pub fn connected() -> Vec<Self> {
+        <P::Display as platform::Display>::connected()
+            .into_iter()
+            .map(|display| DisplayHandle(platform::Handle::handle(&display)))
+            .collect()
+    }
+
+    /// Gets the video modes supported by the display.
+    ///
+    /// Returns no modes if the display is no longer connected or the platform
+    /// cannot query modes.
+    pub fn modes(&self) -> Vec<VideoMode> {
+        <P::Display as platform::Display>::modes(self.0)
+    }
+
+    /// Gets the current video mode of the display.
+    pub fn mode(&self) -> Option<VideoMode> {
+        <P::Display as platform::Display>::mode(self.0)
+    }
+
+    /// Changes the video mode of the display.
+    ///
+    /// The mode should be one of the modes returned by `modes`. The mode
+    /// remains in effect until it is restored via `restore_mode`, though
+    /// platforms may also restore it when the process exits. Prefer
+    /// `Fullscreen::Exclusive`, which restores the mode automatically.
+    pub fn set_mode(&self, _: &ThreadContext, mode: VideoMode) -> Result<(), Error> {
+        <P::Display as platform::Display>::set_mode(self.0, Some(mode))
+    }
+
+    /// Restores the default video mode of the display.
+    pub fn restore_mode(&self, _: &ThreadContext) -> Result<(), Error> {
+        <P::Display as platform::Display>::set_mode(self.0, None)
+    }
+
+    /// Gets the work area of the display in physical units.
+    ///
+    /// The work area is the area of the display that is not occupied by
+    /// taskbars, docks, and similar. Positions are in the coordinate space
+    /// shared by all displays, so the work areas of secondary displays may
+    /// have negative positions.
+    ///
+    /// Returns `None` if the display is no longer connected.
+    pub fn work_area(&self) -> Option<(PhysicalPosition, PhysicalSize)> {
+        <P::Display as platform::Display>::work_area(self.0)
+    }
+
+    /// Gets the position at which a rectangle with the given dimensions is
+    /// centered in the work area of the display.
+    ///
+    /// See `center`.
+    pub fn center(&self, dimensions: PhysicalSize) -> Option<PhysicalPosition> {
+        self.work_area()
+            .map(|(position, area)| center(position, area, dimensions))
+    }
+}
+
+/// Gets the position at which a rectangle with the given dimensions is
+/// centered in an area.
+///
+/// If the rectangle is larger than the area along an axis, then it is aligned
+/// with the start of the area along that axis instead, so that the title bar
+/// of a window remains reachable.
+pub fn center(
+    position: PhysicalPosition,
+    area: PhysicalSize,
+    dimensions: PhysicalSize,
+) -> PhysicalPosition {
+    let center =
+        |start: f64, extent: f64, length: f64| start + ((extent - length) / 2.0).max(0.0).floor();
+    PhysicalPosition::new(
+        center(*position.x, *area.width, *dimensions.width),
+        center(*position.y, *area.height, *dimensions.height),
+    )
+}
+
+unsafe impl<P> Send for DisplayHandle<P> where P: PlatformBinding {}
+unsafe impl<P> Sync for DisplayHandle<P> where P: PlatformBinding {}
+
+/// A video mode of a display.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VideoMode {
+    /// Width in physical units.
+    pub width: u32,
+    /// Height in physical units.
+    pub height: u32,
+    /// Refresh rate in hertz.
+    pub refresh_rate: u32,
+    /// Color depth in bits per pixel.
+    pub bit_depth: u32,
+}
 
 pub trait FromLogical<T> {
     fn from_logical(logical: T, dpi: f64) -> Self;
@@ -59,6 +249,7 @@ where
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LogicalUnit(f64);
 
 impl AsRef<f64> for LogicalUnit {
@@ -118,6 +309,7 @@ impl Into<u32> for LogicalUnit {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct PhysicalUnit(f64);
 
 impl AsRef<f64> for PhysicalUnit {
@@ -175,3 +367,333 @@ impl Into<u32> for PhysicalUnit {
         self.0.round() as u32
     }
 }
+
+macro_rules! unit_arithmetic {
+    ($unit:ident) => {
+        impl Add for $unit {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self::Output {
+                $unit(self.0 + other.0)
+            }
+        }
+
+        impl Div<f64> for $unit {
+            type Output = Self;
+
+            fn div(self, scalar: f64) -> Self::Output {
+                $unit(self.0 / scalar)
+            }
+        }
+
+        impl Mul<f64> for $unit {
+            type Output = Self;
+
+            fn mul(self, scalar: f64) -> Self::Output {
+                $unit(self.0 * scalar)
+            }
+        }
+
+        impl Neg for $unit {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                $unit(-self.0)
+            }
+        }
+
+        impl Sub for $unit {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self::Output {
+                $unit(self.0 - other.0)
+            }
+        }
+    };
+}
+unit_arithmetic!(LogicalUnit);
+unit_arithmetic!(PhysicalUnit);
+
+/// A displacement, such as the difference between two positions or the
+/// relative motion of a mouse.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Vector<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Vector<T>
+where
+    T: Copy + Into<f64>,
+{
+    /// Gets the Euclidean length of the vector.
+    pub fn length(&self) -> f64 {
+        self.x.into().hypot(self.y.into())
+    }
+
+    /// Determines if both components of the vector are zero.
+    pub fn is_zero(&self) -> bool {
+        self.x.into() == 0.0 && self.y.into() == 0.0
+    }
+}
+
+impl<T> Add for Vector<T>
+where
+    T: Add<Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Vector {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<T> Div<f64> for Vector<T>
+where
+    T: Div<f64, Output = T>,
+{
+    type Output = Self;
+
+    fn div(self, scalar: f64) -> Self::Output {
+        Vector {
+            x: self.x / scalar,
+            y: self.y / scalar,
+        }
+    }
+}
+
+impl<T> Mul<f64> for Vector<T>
+where
+    T: Mul<f64, Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, scalar: f64) -> Self::Output {
+        Vector {
+            x: self.x * scalar,
+            y: self.y * scalar,
+        }
+    }
+}
+
+impl<T> Neg for Vector<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Vector {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<T> Sub for Vector<T>
+where
+    T: Sub<Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Vector {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl<T, U> From<(U, U)> for Vector<T>
+where
+    U: Into<T>,
+{
+    fn from((x, y): (U, U)) -> Self {
+        Vector {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+}
+
+impl FromLogical<Vector<LogicalUnit>> for Vector<PhysicalUnit> {
+    fn from_logical(logical: Vector<LogicalUnit>, dpi: f64) -> Self {
+        Vector {
+            x: logical.x.into_physical(dpi),
+            y: logical.y.into_physical(dpi),
+        }
+    }
+}
+
+impl FromPhysical<Vector<PhysicalUnit>> for Vector<LogicalUnit> {
+    fn from_physical(physical: Vector<PhysicalUnit>, dpi: f64) -> Self {
+        Vector {
+            x: physical.x.into_logical(dpi),
+            y: physical.y.into_logical(dpi),
+        }
+    }
+}
+
+macro_rules! point {
+    (
+        $(#[$attribute:meta])*
+        $name:ident<$unit:ident> { $a:ident, $b:ident }
+    ) => {
+        $(#[$attribute])*
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+        pub struct $name {
+            pub $a: $unit,
+            pub $b: $unit,
+        }
+
+        impl $name {
+            pub fn new<T>($a: T, $b: T) -> Self
+            where
+                T: Into<$unit>,
+            {
+                $name {
+                    $a: $a.into(),
+                    $b: $b.into(),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name::new(0.0, 0.0)
+            }
+        }
+
+        impl<T> From<(T, T)> for $name
+        where
+            T: Into<$unit>,
+        {
+            fn from(($a, $b): (T, T)) -> Self {
+                $name::new($a, $b)
+            }
+        }
+
+        impl From<$name> for ($unit, $unit) {
+            fn from(point: $name) -> Self {
+                (point.$a, point.$b)
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = Self;
+
+            fn mul(self, scalar: f64) -> Self::Output {
+                $name {
+                    $a: self.$a * scalar,
+                    $b: self.$b * scalar,
+                }
+            }
+        }
+    };
+}
+
+point! {
+    /// A position in logical units.
+    LogicalPosition<LogicalUnit> { x, y }
+}
+point! {
+    /// A position in physical units.
+    PhysicalPosition<PhysicalUnit> { x, y }
+}
+point! {
+    /// Dimensions in logical units.
+    LogicalSize<LogicalUnit> { width, height }
+}
+point! {
+    /// Dimensions in physical units.
+    PhysicalSize<PhysicalUnit> { width, height }
+}
+
+macro_rules! position_arithmetic {
+    ($position:ident<$unit:ident>) => {
+        impl Add<Vector<$unit>> for $position {
+            type Output = Self;
+
+            fn add(self, vector: Vector<$unit>) -> Self::Output {
+                $position {
+                    x: self.x + vector.x,
+                    y: self.y + vector.y,
+                }
+            }
+        }
+
+        impl Sub<Vector<$unit>> for $position {
+            type Output = Self;
+
+            fn sub(self, vector: Vector<$unit>) -> Self::Output {
+                $position {
+                    x: self.x - vector.x,
+                    y: self.y - vector.y,
+                }
+            }
+        }
+
+        impl Sub for $position {
+            type Output = Vector<$unit>;
+
+            fn sub(self, other: Self) -> Self::Output {
+                Vector {
+                    x: self.x - other.x,
+                    y: self.y - other.y,
+                }
+            }
+        }
+    };
+}
+position_arithmetic!(LogicalPosition<LogicalUnit>);
+position_arithmetic!(PhysicalPosition<PhysicalUnit>);
+
+macro_rules! point_conversion {
+    ($logical:ident, $physical:ident { $a:ident, $b:ident }) => {
+        impl FromLogical<$logical> for $physical {
+            fn from_logical(logical: $logical, dpi: f64) -> Self {
+                $physical {
+                    $a: logical.$a.into_physical(dpi),
+                    $b: logical.$b.into_physical(dpi),
+                }
+            }
+        }
+
+        impl FromPhysical<$physical> for $logical {
+            fn from_physical(physical: $physical, dpi: f64) -> Self {
+                $logical {
+                    $a: physical.$a.into_logical(dpi),
+                    $b: physical.$b.into_logical(dpi),
+                }
+            }
+        }
+    };
+}
+point_conversion!(LogicalPosition, PhysicalPosition { x, y });
+point_conversion!(LogicalSize, PhysicalSize { width, height });
+
+#[cfg(test)]
+mod tests {
+    use crate::display::{self, PhysicalPosition, PhysicalSize};
+
+    #[test]
+    fn center_in_secondary_display() {
+        // A display to the left of the primary display.
+        let position = PhysicalPosition::new(-1920, 40);
+        let area = PhysicalSize::new(1920, 1040);
+        assert_eq!(
+            PhysicalPosition::new(-1360, 260),
+            display::center(position, area, PhysicalSize::new(800, 600)),
+        );
+        // Rectangles larger than the area are aligned with its start.
+        assert_eq!(
+            PhysicalPosition::new(-1920, 40),
+            display::center(position, area, PhysicalSize::new(2560, 1440)),
+        );
+    }
+}
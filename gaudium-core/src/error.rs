@@ -0,0 +1,96 @@
+//! Errors.
+//!
+//! Fallible operations return an `Error`, which describes the kind of failure
+//! and the operation that failed. Errors reported by a platform also carry the
+//! platform's error code where one is available, such as the value of
+//! `GetLastError` on Windows.
+
+use std::borrow::Cow;
+use std::error;
+use std::fmt::{self, Display, Formatter};
+
+/// The kind of an `Error`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorKind {
+    /// The platform failed to perform an operation.
+    Platform,
+    /// An argument or configuration is invalid.
+    InvalidInput,
+    /// The operation is not supported by the platform.
+    Unsupported,
+    /// The operation requires state that is not available, such as a running
+    /// event thread.
+    InvalidState,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str(match *self {
+            ErrorKind::Platform => "platform error",
+            ErrorKind::InvalidInput => "invalid input",
+            ErrorKind::Unsupported => "unsupported operation",
+            ErrorKind::InvalidState => "invalid state",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    code: Option<i32>,
+    context: Cow<'static, str>,
+}
+
+impl Error {
+    /// Creates an error with the given kind and a description of the
+    /// operation that failed.
+    pub fn new<T>(kind: ErrorKind, context: T) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        Error {
+            kind,
+            code: None,
+            context: context.into(),
+        }
+    }
+
+    /// Creates a `Platform` error from a platform error code and a
+    /// description of the operation that failed.
+    pub fn with_code<T>(code: i32, context: T) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        Error {
+            kind: ErrorKind::Platform,
+            code: Some(code),
+            context: context.into(),
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Gets the platform error code, if any.
+    pub fn code(&self) -> Option<i32> {
+        self.code
+    }
+
+    /// Gets a description of the operation that failed.
+    pub fn context(&self) -> &str {
+        self.context.as_ref()
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}: {}", self.kind, self.context)?;
+        if let Some(code) = self.code {
+            write!(formatter, " (code {})", code)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {}
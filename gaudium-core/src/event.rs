@@ -1,26 +1,67 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
-use crate::device::{DeviceHandle, Usage};
-use crate::display::{LogicalUnit, PhysicalUnit};
+use crate::device::{DeviceHandle, DeviceId, SensorType, Usage};
+use crate::display::{
+    DisplayHandle, IntoLogical, LogicalPosition, LogicalSize, LogicalUnit, PhysicalPosition,
+    PhysicalSize, PhysicalUnit, Vector,
+};
+use crate::keyboard::KeyLayoutId;
 use crate::platform::PlatformBinding;
-use crate::window::WindowHandle;
+use crate::reactor::TimerId;
+use crate::shell::hotkey::HotkeyId;
+use crate::window::{WindowHandle, WindowId};
 
+/// An event dispatched by an event thread.
+///
+/// When the `serde` feature is enabled, events can be serialized. Handles are
+/// serialized as opaque identifiers that are stable for the lifetime of the
+/// process and timestamps are serialized relative to an epoch within the
+/// process, so serialized events preserve the identity and relative timing of
+/// handles and events.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[non_exhaustive]
 pub enum Event<P>
 where
     P: PlatformBinding,
 {
     Application {
         event: ApplicationEvent,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serial::instant"))]
+        timestamp: Instant,
+    },
+    Display {
+        display: DisplayHandle<P>,
+        event: DisplayEvent,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serial::instant"))]
+        timestamp: Instant,
     },
     Input {
         device: DeviceHandle<P>,
         window: Option<WindowHandle<P>>,
         event: InputEvent,
+        focus: InputFocus,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serial::instant"))]
+        timestamp: Instant,
     },
     Window {
         window: WindowHandle<P>,
         event: WindowEvent,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serial::instant"))]
+        timestamp: Instant,
+    },
+    /// A platform message that is not otherwise modeled by events.
+    ///
+    /// These events are only dispatched when enabled via
+    /// `EventThreadBuilder::with_platform_events`. Their data is specific to
+    /// the platform and is opaque, so they cannot be serialized nor recorded.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Platform {
+        data: P::RawEvent,
+        timestamp: Instant,
     },
 }
 
@@ -28,17 +69,114 @@ impl<P> Event<P>
 where
     P: PlatformBinding,
 {
-    pub fn into_window_event(self, window: WindowHandle<P>) -> Option<Self> {
-        let target = Some(window);
-        if match self {
-            Event::Input { window, .. } => match window {
-                Some(window) => Some(window),
-                None => target, // Do not filter non-windowed input events.
-            },
+    /// Gets the kind of the event.
+    ///
+    /// Unlike matching on the event itself, this does not require a variant
+    /// for each kind of event that is not of interest.
+    pub fn kind(&self) -> EventKind {
+        match *self {
+            Event::Application { .. } => EventKind::Application,
+            Event::Display { .. } => EventKind::Display,
+            Event::Input { .. } => EventKind::Input,
+            Event::Window { .. } => EventKind::Window,
+            Event::Platform { .. } => EventKind::Platform,
+        }
+    }
+
+    /// Gets the time at which the event occurred.
+    ///
+    /// Where possible, this is the time at which the platform generated the
+    /// event rather than the time at which it was dispatched.
+    pub fn timestamp(&self) -> Instant {
+        match *self {
+            Event::Application { timestamp, .. }
+            | Event::Display { timestamp, .. }
+            | Event::Input { timestamp, .. }
+            | Event::Window { timestamp, .. }
+            | Event::Platform { timestamp, .. } => timestamp,
+        }
+    }
+
+    /// Gets the handle of the window associated with the event, if any.
+    pub fn window_handle(&self) -> Option<WindowHandle<P>> {
+        match *self {
+            Event::Input { window, .. } => window,
             Event::Window { window, .. } => Some(window),
             _ => None,
-        } == target
-        {
+        }
+    }
+
+    /// Gets the handle of the display associated with the event, if any.
+    pub fn display_handle(&self) -> Option<DisplayHandle<P>> {
+        match *self {
+            Event::Display { display, .. } => Some(display),
+            _ => None,
+        }
+    }
+
+    /// Gets the application event, if any.
+    pub fn application_event(&self) -> Option<ApplicationEvent> {
+        match *self {
+            Event::Application { event, .. } => Some(event),
+            _ => None,
+        }
+    }
+
+    /// Gets the input event, if any.
+    pub fn input_event(&self) -> Option<InputEvent> {
+        match *self {
+            Event::Input { event, .. } => Some(event),
+            _ => None,
+        }
+    }
+
+    /// Gets the window event, if any.
+    pub fn window_event(&self) -> Option<WindowEvent> {
+        match *self {
+            Event::Window { event, .. } => Some(event),
+            _ => None,
+        }
+    }
+
+    /// Gets the handle of the device associated with the event, if any.
+    pub fn device_handle(&self) -> Option<DeviceHandle<P>> {
+        match *self {
+            Event::Input { device, .. } => Some(device),
+            _ => None,
+        }
+    }
+
+    /// Gets the stable identifier of the window associated with the event, if
+    /// any.
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window_handle().map(WindowHandle::id)
+    }
+
+    /// Gets the stable identifier of the device associated with the event, if
+    /// any.
+    pub fn device_id(&self) -> Option<DeviceId> {
+        self.device_handle().map(DeviceHandle::id)
+    }
+
+    /// Determines if the event is routed to the given window.
+    ///
+    /// Window events are routed to the window that they describe. Input events
+    /// are routed to the window that received them. Input events that are not
+    /// associated with any window are routed to all windows.
+    pub fn is_for_window(&self, window: WindowHandle<P>) -> bool {
+        match *self {
+            Event::Input { window: None, .. } => true,
+            _ => self.window_handle() == Some(window),
+        }
+    }
+
+    /// Determines if the event is an input event from the given device.
+    pub fn is_for_device(&self, device: DeviceHandle<P>) -> bool {
+        self.device_handle() == Some(device)
+    }
+
+    pub fn into_window_event(self, window: WindowHandle<P>) -> Option<Self> {
+        if self.is_for_window(window) {
             Some(self)
         }
         else {
@@ -47,28 +185,160 @@ where
     }
 
     pub fn into_device_event(self, device: DeviceHandle<P>) -> Option<Self> {
-        let target = device;
-        match self {
-            Event::Input { device, .. } if target == device => Some(self),
-            _ => None,
+        if self.is_for_device(device) {
+            Some(self)
+        }
+        else {
+            None
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// The kind of an `Event`. See `Event::kind`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum EventKind {
+    Application,
+    Display,
+    Input,
+    Window,
+    Platform,
+}
+
+/// Events that concern the application and its event loop.
+///
+/// Variants may be added in any release, so matches on this type outside of
+/// this crate require a wildcard arm. This also applies to `Event`,
+/// `InputEvent`, and `WindowEvent`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[non_exhaustive]
 pub enum ApplicationEvent {
+    /// The event loop resumed after polling the reactor.
+    ///
+    /// This is dispatched once per iteration of the event loop when it
+    /// resumes after waiting or polling. `Resumption` describes how the event
+    /// loop resumed, such as whether or not a `WaitUntil` deadline expired.
     Resumed(Resumption),
+    /// All pending events of the current iteration of the event loop have
+    /// been dispatched.
+    ///
+    /// Events that are queued by the platform, such as events emitted while
+    /// the reactor is reacting, may still be dispatched after this event and
+    /// before the reactor is polled.
     Flushed,
+    /// The system is suspending.
+    ///
+    /// Applications should pause and save state, because the application may
+    /// not be awakened.
+    Suspended,
+    /// The system has awakened from suspension.
+    ///
+    /// This is unrelated to `Resumed`, which describes the event loop.
+    Awakened,
+    /// The system is low on memory and applications should release resources
+    /// if possible. This is not dispatched on all platforms.
+    LowMemory,
+    /// The application is about to be terminated by the system, such as when
+    /// the user session ends.
+    Terminating,
+    /// The active keyboard layout has changed, such as when the user switches
+    /// input languages.
+    ///
+    /// The payload identifies the new layout. Labels provided by `KeyLayout`
+    /// may differ after this event, and its language can be queried via
+    /// `KeyLayout::language`.
+    KeyboardLayoutChanged(KeyLayoutId),
+    /// The event queue of the event thread overflowed and events were
+    /// discarded or merged.
+    ///
+    /// `discarded` is the number of events that were discarded or merged
+    /// since the previous overflow. Not all platforms queue events.
+    QueueOverflowed { discarded: usize },
+    /// The event thread is idle and is about to wait for events.
+    ///
+    /// This is dispatched at most once per flush, when no events other than
+    /// application events were dispatched and the reactor has polled `Wait`
+    /// or `WaitUntil`. Reactors can perform low-priority work, such as
+    /// cleanup or streaming assets, until `deadline`, which is determined by
+    /// the idle budget of the event thread (see
+    /// `ThreadContext::set_idle_budget`) and is never later than the instant
+    /// of `WaitUntil`. The reactor is polled again after this event, so it
+    /// can poll `Ready` to continue its work in the next idle period.
+    Idle {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serial::instant"))]
+        deadline: Instant,
+    },
+    /// A hotkey registered by the event thread was pressed.
+    ///
+    /// See `shell::hotkey::Hotkey`.
+    HotkeyPressed(HotkeyId),
+    /// A timer set by the event thread elapsed.
+    ///
+    /// See `ThreadContext::set_timer`.
+    Timer(TimerId),
+    /// Another instance of the application was launched and forwarded its
+    /// arguments.
+    ///
+    /// See `shell::instance::SingleInstance`.
+    InstanceLaunched,
+    /// Stopping the event loop was requested by another thread, such as a
+    /// control thread or signal handler.
+    ///
+    /// The event loop is not stopped unless the reactor aborts, so reactors can
+    /// clean up before aborting or ignore the request. See
+    /// `EventThreadProxy::request_abort`.
+    AbortRequested,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// Describes how an event loop resumed.
+///
+/// See `ApplicationEvent::Resumed` and `Poll`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Resumption {
+    /// The event loop resumed without waiting for a deadline, such as when
+    /// the reactor polled `Ready` or `Wait`.
     Poll,
-    Timeout(Instant),
-    Interrupt(Instant),
+    /// The event loop waited until the deadline of `WaitUntil` expired.
+    ///
+    /// The payload is the instant at which the event loop began waiting.
+    Timeout(#[cfg_attr(feature = "serde", serde(with = "crate::serial::instant"))] Instant),
+    /// The event loop was interrupted by events before the deadline of
+    /// `WaitUntil` expired.
+    ///
+    /// The payload is the instant at which the event loop began waiting.
+    Interrupt(#[cfg_attr(feature = "serde", serde(with = "crate::serial::instant"))] Instant),
+}
+
+impl Resumption {
+    /// Gets the instant at which the event loop began waiting for a deadline,
+    /// if any.
+    pub fn wait_started(&self) -> Option<Instant> {
+        match *self {
+            Resumption::Poll => None,
+            Resumption::Timeout(instant) | Resumption::Interrupt(instant) => Some(instant),
+        }
+    }
+
+    /// Determines if the deadline of `WaitUntil` expired.
+    pub fn is_timeout(&self) -> bool {
+        matches!(*self, Resumption::Timeout(_))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum DisplayEvent {
+    Connected,
+    Disconnected,
+    /// The resolution, refresh rate, or arrangement of the display changed.
+    ModeChanged,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[non_exhaustive]
 pub enum InputEvent {
     Connected {
         usage: Option<Usage>,
@@ -89,6 +359,8 @@ pub enum InputEvent {
         keycode: Option<KeyCode>,
         state: ElementState,
         modifier: ModifierState,
+        /// Whether or not the event is an auto-repeat of a key that is held.
+        repeat: bool,
     },
     MouseButtonChanged {
         button: MouseButton,
@@ -103,24 +375,158 @@ pub enum InputEvent {
         movement: MouseMovement,
         modifier: ModifierState,
     },
+    /// A motion sensor of the device, such as the gyroscope of a game
+    /// controller, was sampled.
+    ///
+    /// `values` are the `x`, `y`, and `z` components of the sample. See
+    /// `SensorType` for units and `DeviceHandle::has_sensor`.
+    MotionSensor {
+        sensor: SensorType,
+        values: [f64; 3],
+    },
+    /// A pen moved over or on a window, or its pressure, tilt, or buttons
+    /// changed.
+    ///
+    /// `position` is relative to the client area of the window. Pens also
+    /// emulate the mouse, so these events are accompanied by mouse events.
+    PenMoved {
+        position: WindowPosition,
+        info: PenInfo,
+    },
+    /// A pen entered (`true`) or left (`false`) the detection range of its
+    /// tablet or display over a window.
+    ///
+    /// While in range, a pen hovers until it makes contact. See
+    /// `PenInfo::hover`.
+    PenProximityChanged {
+        in_range: bool,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[non_exhaustive]
 pub enum WindowEvent {
     Closed(WindowCloseState),
     Activated,
     Deactivated,
-    Moved(i32, i32),
-    Resized(u32, u32),
+    /// The window gained (`true`) or lost (`false`) keyboard focus.
+    ///
+    /// Unlike `Activated`, which concerns top-level windows, focus may move
+    /// between a top-level window and its children. See
+    /// `ThreadContext::focused_window`.
+    Focused(bool),
+    /// The window moved.
+    ///
+    /// `position` is the position of the window in physical units and `dpi` is
+    /// the DPI factor of the window when the event occurred. See
+    /// `logical_position`.
+    Moved {
+        position: PhysicalPosition,
+        dpi: f64,
+    },
+    /// The client area of the window was resized.
+    ///
+    /// `dimensions` are the dimensions of the client area in physical units
+    /// and `dpi` is the DPI factor of the window when the event occurred. See
+    /// `logical_dimensions`.
+    Resized {
+        dimensions: PhysicalSize,
+        dpi: f64,
+    },
+    /// The user began resizing the window interactively, such as by dragging
+    /// its frame.
+    ///
+    /// Any number of `Resized` events may be dispatched before `ResizeEnded`.
+    /// Reactors may defer expensive work, such as relayout, until the resize
+    /// ends.
+    ResizeStarted,
+    /// The user stopped resizing the window interactively.
+    ResizeEnded,
+    MouseEntered,
+    MouseLeft,
+    /// The window was disabled and no longer receives input, such as while a
+    /// modal window that it owns is shown.
+    Disabled,
+    /// The window was enabled after being disabled.
+    Enabled,
+    /// The window may present a frame.
+    ///
+    /// This is dispatched once for each call to `Window::request_frame` when
+    /// the platform is ready to present, such as after the next vertical
+    /// blank. Reactors that render only in response to this event do not
+    /// render frames that cannot be presented.
+    FrameReady,
+    /// The window was minimized, maximized, made fullscreen, or restored.
+    StateChanged(WindowState),
+    /// A character was entered while the window had keyboard focus.
+    ///
+    /// Dead keys are composed with the key that follows them, so an accented
+    /// character is received once rather than as its accent and base
+    /// character. If they cannot be composed, then both characters are
+    /// received. Control characters, such as backspace, are not received;
+    /// see `InputEvent::KeyboardKeyChanged`.
+    ReceivedCharacter(char),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+impl WindowEvent {
+    /// Gets the position of a `Moved` event in logical units.
+    pub fn logical_position(&self) -> Option<LogicalPosition> {
+        match *self {
+            WindowEvent::Moved { position, dpi } => Some(position.into_logical(dpi)),
+            _ => None,
+        }
+    }
+
+    /// Gets the dimensions of a `Resized` event in logical units.
+    pub fn logical_dimensions(&self) -> Option<LogicalSize> {
+        match *self {
+            WindowEvent::Resized { dimensions, dpi } => Some(dimensions.into_logical(dpi)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum WindowCloseState {
+    /// Closing the window was requested, such as by the user. The window is
+    /// not closed unless it is dropped.
     Requested,
+    /// The window has been destroyed.
+    ///
+    /// This is the last event dispatched for the window. Any events for the
+    /// window that are pending when this event is dispatched are discarded,
+    /// and subsequent input is no longer attributed to the window. A handle
+    /// may be recycled by a window that is created later, after which events
+    /// for that handle refer to the new window.
     Committed,
 }
 
+/// How a window is shown on its display.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum WindowState {
+    #[default]
+    Normal,
+    Minimized,
+    Maximized,
+    Fullscreen,
+}
+
+/// Whether input was received while the application had input focus.
+///
+/// Input is only received in the background if a window opts into it, which
+/// may not be supported by all platforms.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum InputFocus {
+    Foreground,
+    Background,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum ElementState {
     Pressed,
     Released,
@@ -128,13 +534,84 @@ pub enum ElementState {
 
 pub type ScanCode = u32;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum KeyCode {}
+// Defines `KeyCode` and a table of all of its variants.
+macro_rules! key_codes {
+    ($($key:ident),* $(,)?) => {
+        /// A virtual key.
+        ///
+        /// Key codes identify keys by their meaning in the active keyboard
+        /// layout, whereas `ScanCode`s identify physical keys. Keys that have
+        /// no direct equivalent, such as punctuation, are named by their
+        /// position on a US layout. See `KeyLayout` to translate scan codes.
+        #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+        pub enum KeyCode {
+            $($key,)*
+        }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct ModifierState {}
+        impl KeyCode {
+            /// All key codes.
+            pub const ALL: &'static [KeyCode] = &[$(KeyCode::$key,)*];
+
+            /// Gets the name of the key code, which is the same as the name of
+            /// its variant.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    $(KeyCode::$key => stringify!($key),)*
+                }
+            }
+
+            /// Gets the key code with the given name.
+            ///
+            /// See `name`.
+            pub fn from_name(name: &str) -> Option<Self> {
+                KeyCode::ALL.iter().cloned().find(|key| key.name() == name)
+            }
+        }
+    };
+}
+
+key_codes! {
+    // Letters.
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    // Digits (not on the numeric keypad).
+    Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    // Function keys.
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16, F17, F18, F19, F20, F21,
+    F22, F23, F24,
+    // Editing, navigation, and system keys.
+    Escape, Tab, CapsLock, Space, Enter, Backspace, Insert, Delete, Home, End, PageUp, PageDown,
+    Left, Right, Up, Down, PrintScreen, ScrollLock, Pause, Menu,
+    // Modifiers.
+    LeftShift, RightShift, LeftControl, RightControl, LeftAlt, RightAlt, LeftSuper, RightSuper,
+    // Numeric keypad.
+    NumLock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8,
+    Numpad9, NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide, NumpadDecimal, NumpadEnter,
+    // Punctuation, named by their position on a US layout.
+    Grave, Minus, Equals, LeftBracket, RightBracket, Backslash, Semicolon, Apostrophe, Comma,
+    Period, Slash, NonUsBackslash,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ModifierState {
+    pub toggles: ToggleState,
+}
+
+/// State of the toggle keys of the keyboard.
+///
+/// A toggle is `true` if it is on (i.e., its indicator is lit), regardless of
+/// whether or not its key is held.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ToggleState {
+    pub caps_lock: bool,
+    pub num_lock: bool,
+    pub scroll_lock: bool,
+}
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum MouseButton {
     Left,
     Right,
@@ -142,21 +619,120 @@ pub enum MouseButton {
     Other(u8),
 }
 
-pub type WindowPosition = (LogicalUnit, LogicalUnit);
-pub type RelativeMotion = (PhysicalUnit, PhysicalUnit);
+pub type WindowPosition = LogicalPosition;
+pub type RelativeMotion = Vector<PhysicalUnit>;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct MouseMovement {
     pub absolute: Option<WindowPosition>,
     pub relative: Option<RelativeMotion>,
 }
 
+/// The state of a pen when it is sampled.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PenInfo {
+    /// The normalized pressure of the tip in the range `[0, 1]`.
+    ///
+    /// This is zero while the pen is hovering or if the pen does not report
+    /// pressure. Applications typically map this through a pressure curve.
+    pub pressure: f64,
+    /// The pressure of the tip as reported by the platform, before it is
+    /// normalized. On Windows, this is in the range `[0, 1024]`.
+    ///
+    /// This may be used to calibrate pressure for a particular pen.
+    pub raw_pressure: u32,
+    /// The tilt of the pen along the `x` and `y` axes in degrees in the range
+    /// `[-90, 90]`. Zero if the pen does not report tilt.
+    pub tilt: (f64, f64),
+    /// The barrel buttons that are pressed, where the first button is the
+    /// least significant bit.
+    pub barrel_buttons: u8,
+    /// Whether or not the eraser end of the pen is in use.
+    pub eraser: bool,
+    /// Whether or not the pen is in range but not in contact.
+    pub hover: bool,
+}
+
+/// The change in the position of a mouse wheel.
+///
+/// Rotational deltas are measured in detents (notches) of a wheel and
+/// positional deltas are measured in logical pixels, such as from a touchpad.
+/// See `to_pixels` and `to_lines` to normalize either into a single unit.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum MouseWheelDelta {
     Rotational(f64, f64),
     Positional(LogicalUnit, LogicalUnit),
 }
 
+impl MouseWheelDelta {
+    /// Converts the delta into logical pixels along the `x` and `y` axes.
+    ///
+    /// `scroll` is the distance scrolled per detent (see
+    /// `ThreadContext::wheel_scroll`), `line_height` is the height of a line
+    /// of content, and `page_height` is the height of the visible content,
+    /// both in logical pixels.
+    pub fn to_pixels(self, scroll: WheelScroll, line_height: f64, page_height: f64) -> (f64, f64) {
+        match self {
+            MouseWheelDelta::Rotational(x, y) => {
+                let detent = match scroll {
+                    WheelScroll::Lines(lines) => f64::from(lines) * line_height,
+                    WheelScroll::Page => page_height,
+                };
+                (x * detent, y * detent)
+            }
+            MouseWheelDelta::Positional(x, y) => (*x, *y),
+        }
+    }
+
+    /// Converts the delta into lines along the `x` and `y` axes.
+    ///
+    /// See `to_pixels`.
+    pub fn to_lines(self, scroll: WheelScroll, line_height: f64, page_height: f64) -> (f64, f64) {
+        let (x, y) = self.to_pixels(scroll, line_height, page_height);
+        (x / line_height, y / line_height)
+    }
+}
+
+/// The distance that content scrolls per detent of a mouse wheel, as
+/// configured by the user.
+///
+/// See `ThreadContext::wheel_scroll`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum WheelScroll {
+    /// Content scrolls by the given number of lines.
+    Lines(u32),
+    /// Content scrolls by a page.
+    Page,
+}
+
+impl Default for WheelScroll {
+    fn default() -> Self {
+        WheelScroll::Lines(3)
+    }
+}
+
 pub type GameControllerAxis = u8;
 
 pub type GameControllerButton = u8;
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::event::Resumption;
+
+    #[test]
+    fn resumption() {
+        let instant = Instant::now();
+        assert_eq!(None, Resumption::Poll.wait_started());
+        assert_eq!(Some(instant), Resumption::Timeout(instant).wait_started());
+        assert_eq!(Some(instant), Resumption::Interrupt(instant).wait_started());
+        assert!(Resumption::Timeout(instant + Duration::from_millis(1)).is_timeout());
+        assert!(!Resumption::Interrupt(instant).is_timeout());
+        assert!(!Resumption::Poll.is_timeout());
+    }
+}
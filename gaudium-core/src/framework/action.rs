@@ -0,0 +1,404 @@
+//! Mapping of physical inputs to logical actions.
+//!
+//! An `ActionMap` binds keys, buttons, and axis ranges to user-defined actions,
+//! such as "jump" or "fire", so that application logic can query actions
+//! rather than inputs and bindings can be changed at runtime.
+//!
+//! When the `serde` feature is enabled, `ActionMap` can be serialized and
+//! deserialized to persist bindings. Only bindings are serialized; input state
+//! is not.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::event::{Event, KeyCode, MouseButton};
+//! use gaudium_core::framework::action::{ActionMap, Binding};
+//! use gaudium_core::framework::React;
+//! use gaudium_platform_empty::Binding as Platform;
+//!
+//! # fn main() {
+//! #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+//! enum Action {
+//!     Jump,
+//!     Fire,
+//! }
+//!
+//! let mut actions = ActionMap::new();
+//! actions.bind(Action::Jump, Binding::Key(KeyCode::Space));
+//! actions.bind(Action::Fire, Binding::MouseButton(MouseButton::Left));
+//!
+//! fn update(actions: &mut ActionMap<Action>, events: &[Event<Platform>]) {
+//!     actions.snapshot();
+//!     for event in events {
+//!         actions.react(event);
+//!     }
+//!     if actions.is_just_pressed(Action::Jump) {
+//!         // ...
+//!     }
+//! }
+//! # update(&mut actions, &[]);
+//! # }
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::event::{
+    ElementState, Event, GameControllerAxis, GameControllerButton, InputEvent, KeyCode, MouseButton,
+};
+use crate::framework::React;
+use crate::platform::PlatformBinding;
+
+/// A user-defined logical action.
+///
+/// This is implemented for all types that satisfy its bounds. Actions are
+/// typically fieldless enums.
+pub trait Action: Copy + Eq + Hash {}
+
+impl<T> Action for T where T: Copy + Eq + Hash {}
+
+/// A physical input that can be bound to an action.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Binding {
+    Key(KeyCode),
+    MouseButton(MouseButton),
+    GameControllerButton(GameControllerButton),
+    /// A range of values of a game controller axis.
+    ///
+    /// The binding is active while the value of the axis is within the
+    /// inclusive range `[min, max]`. For example, a range of `[0.5, 1.0]`
+    /// activates an action when a stick is pushed at least halfway in the
+    /// positive direction.
+    GameControllerAxis {
+        axis: GameControllerAxis,
+        min: f64,
+        max: f64,
+    },
+}
+
+#[derive(Default)]
+struct InputState {
+    keys: HashSet<KeyCode>,
+    mouse_buttons: HashSet<MouseButton>,
+    game_controller_buttons: HashSet<GameControllerButton>,
+    game_controller_axes: HashMap<GameControllerAxis, f64>,
+}
+
+impl InputState {
+    fn update(&mut self, event: InputEvent) {
+        fn update<T>(set: &mut HashSet<T>, element: T, state: ElementState)
+        where
+            T: Eq + Hash,
+        {
+            match state {
+                ElementState::Pressed => {
+                    set.insert(element);
+                }
+                ElementState::Released => {
+                    set.remove(&element);
+                }
+            }
+        }
+
+        match event {
+            InputEvent::KeyboardKeyChanged {
+                keycode: Some(keycode),
+                state,
+                ..
+            } => update(&mut self.keys, keycode, state),
+            InputEvent::MouseButtonChanged { button, state, .. } => {
+                update(&mut self.mouse_buttons, button, state)
+            }
+            InputEvent::GameControllerButtonChanged { button, state } => {
+                update(&mut self.game_controller_buttons, button, state)
+            }
+            InputEvent::GameControllerAxisChanged { axis, value } => {
+                self.game_controller_axes.insert(axis, value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Gets the value of a binding if it is active.
+    ///
+    /// Axis bindings are active while their axis is within range, even if the
+    /// value of the axis is zero.
+    fn value(&self, binding: &Binding) -> Option<f64> {
+        let pressed = |is_pressed: bool| if is_pressed { Some(1.0) } else { None };
+        match *binding {
+            Binding::Key(key) => pressed(self.keys.contains(&key)),
+            Binding::MouseButton(button) => pressed(self.mouse_buttons.contains(&button)),
+            Binding::GameControllerButton(button) => {
+                pressed(self.game_controller_buttons.contains(&button))
+            }
+            Binding::GameControllerAxis { axis, min, max } => self
+                .game_controller_axes
+                .get(&axis)
+                .cloned()
+                .filter(|value| (min..=max).contains(value)),
+        }
+    }
+}
+
+/// Maps physical inputs to logical actions.
+///
+/// `ActionMap` reacts to input events and tracks which actions are active. An
+/// action is active (pressed) if any of its bindings is active. Like
+/// snapshots in the `input` module, `ActionMap` compares the live state with
+/// the state at the most recent call to `snapshot` to determine if actions
+/// have just been pressed or released.
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(serialize = "A: Serialize", deserialize = "A: Deserialize<'de>"))
+)]
+pub struct ActionMap<A>
+where
+    A: Action,
+{
+    bindings: Vec<(A, Binding)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: InputState,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    snapshot: HashSet<A>,
+}
+
+impl<A> ActionMap<A>
+where
+    A: Action,
+{
+    pub fn new() -> Self {
+        ActionMap::default()
+    }
+
+    /// Creates an action map with the given bindings.
+    pub fn from_bindings<I>(bindings: I) -> Self
+    where
+        I: IntoIterator<Item = (A, Binding)>,
+    {
+        ActionMap {
+            bindings: bindings.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Gets all bindings.
+    pub fn bindings(&self) -> &[(A, Binding)] {
+        &self.bindings
+    }
+
+    /// Gets the bindings of an action.
+    pub fn bindings_of(&self, action: A) -> impl '_ + Iterator<Item = &Binding> {
+        self.bindings
+            .iter()
+            .filter(move |(other, _)| *other == action)
+            .map(|(_, binding)| binding)
+    }
+
+    /// Binds an input to an action.
+    ///
+    /// An action may have any number of bindings and an input may be bound to
+    /// any number of actions.
+    pub fn bind(&mut self, action: A, binding: Binding) {
+        self.bindings.push((action, binding));
+    }
+
+    /// Removes a binding from an action.
+    pub fn unbind(&mut self, action: A, binding: Binding) {
+        self.bindings
+            .retain(|(other, another)| !(*other == action && *another == binding));
+    }
+
+    /// Removes all bindings from an action.
+    ///
+    /// This is typically used to rebind an action.
+    pub fn clear(&mut self, action: A) {
+        self.bindings.retain(|(other, _)| *other != action);
+    }
+
+    /// Determines if any binding of an action is active.
+    pub fn is_pressed(&self, action: A) -> bool {
+        self.bindings_of(action)
+            .any(|binding| self.state.value(binding).is_some())
+    }
+
+    /// Determines if an action has become active since the most recent
+    /// snapshot.
+    pub fn is_just_pressed(&self, action: A) -> bool {
+        self.is_pressed(action) && !self.snapshot.contains(&action)
+    }
+
+    /// Determines if an action has become inactive since the most recent
+    /// snapshot.
+    pub fn is_just_released(&self, action: A) -> bool {
+        !self.is_pressed(action) && self.snapshot.contains(&action)
+    }
+
+    /// Gets the value of an action.
+    ///
+    /// Active key and button bindings have a value of `1.0` and active axis
+    /// bindings have the value of their axis. If more than one binding is
+    /// active, then the value with the greatest magnitude is used. Inactive
+    /// actions have a value of `0.0`.
+    pub fn value(&self, action: A) -> f64 {
+        self.bindings_of(action)
+            .filter_map(|binding| self.state.value(binding))
+            .fold(0.0, |value, other| {
+                if other.abs() > value.abs() {
+                    other
+                }
+                else {
+                    value
+                }
+            })
+    }
+
+    /// Snapshots the active actions.
+    pub fn snapshot(&mut self) {
+        self.snapshot = self
+            .bindings
+            .iter()
+            .map(|(action, _)| *action)
+            .filter(|action| self.is_pressed(*action))
+            .collect();
+    }
+}
+
+impl<A> Default for ActionMap<A>
+where
+    A: Action,
+{
+    fn default() -> Self {
+        ActionMap {
+            bindings: vec![],
+            state: InputState::default(),
+            snapshot: HashSet::new(),
+        }
+    }
+}
+
+impl<P, A> React<P> for ActionMap<A>
+where
+    P: PlatformBinding,
+    A: Action,
+{
+    fn react(&mut self, event: &Event<P>) {
+        if let Event::Input { event, .. } = *event {
+            self.state.update(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{ElementState, InputEvent, KeyCode, ModifierState, MouseButton};
+    use crate::framework::action::{ActionMap, Binding};
+
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    enum Action {
+        Jump,
+        Fire,
+        Steer,
+    }
+
+    fn key(keycode: KeyCode, state: ElementState) -> InputEvent {
+        InputEvent::KeyboardKeyChanged {
+            scancode: 0,
+            keycode: Some(keycode),
+            state,
+            modifier: ModifierState::default(),
+            repeat: false,
+        }
+    }
+
+    fn axis(value: f64) -> InputEvent {
+        InputEvent::GameControllerAxisChanged { axis: 0, value }
+    }
+
+    fn actions() -> ActionMap<Action> {
+        ActionMap::from_bindings(vec![
+            (Action::Jump, Binding::Key(KeyCode::Space)),
+            (Action::Fire, Binding::MouseButton(MouseButton::Left)),
+            (Action::Fire, Binding::Key(KeyCode::F)),
+            (
+                Action::Steer,
+                Binding::GameControllerAxis {
+                    axis: 0,
+                    min: -0.5,
+                    max: 0.5,
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn press_and_release() {
+        let mut actions = actions();
+        actions.snapshot();
+        actions
+            .state
+            .update(key(KeyCode::Space, ElementState::Pressed));
+        assert!(actions.is_pressed(Action::Jump));
+        assert!(actions.is_just_pressed(Action::Jump));
+        assert!(!actions.is_pressed(Action::Fire));
+
+        actions.snapshot();
+        assert!(actions.is_pressed(Action::Jump));
+        assert!(!actions.is_just_pressed(Action::Jump));
+        actions
+            .state
+            .update(key(KeyCode::Space, ElementState::Released));
+        assert!(actions.is_just_released(Action::Jump));
+
+        // An action is pressed while any of its bindings is active.
+        actions.snapshot();
+        actions.state.update(key(KeyCode::F, ElementState::Pressed));
+        actions.state.update(InputEvent::MouseButtonChanged {
+            button: MouseButton::Left,
+            state: ElementState::Pressed,
+            modifier: ModifierState::default(),
+        });
+        actions
+            .state
+            .update(key(KeyCode::F, ElementState::Released));
+        assert!(actions.is_just_pressed(Action::Fire));
+        assert_eq!(1.0, actions.value(Action::Fire));
+    }
+
+    #[test]
+    fn axis_range() {
+        let mut actions = actions();
+        assert!(!actions.is_pressed(Action::Steer));
+        // A range that contains zero is active at zero.
+        actions.state.update(axis(0.0));
+        assert!(actions.is_pressed(Action::Steer));
+        assert_eq!(0.0, actions.value(Action::Steer));
+        actions.state.update(axis(-0.25));
+        assert!(actions.is_pressed(Action::Steer));
+        assert_eq!(-0.25, actions.value(Action::Steer));
+        actions.state.update(axis(0.75));
+        assert!(!actions.is_pressed(Action::Steer));
+        assert_eq!(0.0, actions.value(Action::Steer));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_bindings() {
+        let mut actions = actions();
+        actions
+            .state
+            .update(key(KeyCode::Space, ElementState::Pressed));
+        let text = serde_json::to_string(&actions).unwrap();
+        let other: ActionMap<Action> = serde_json::from_str(&text).unwrap();
+        assert_eq!(actions.bindings(), other.bindings());
+        // Input state is not serialized.
+        assert!(!other.is_pressed(Action::Jump));
+    }
+}
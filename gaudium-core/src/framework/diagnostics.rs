@@ -0,0 +1,246 @@
+//! Diagnostic reactors.
+//!
+//! `FpsCounter` is a reactor adapter that measures the cadence of the event
+//! loop and the number of events that it dispatches before forwarding events
+//! to another reactor. The event loop flushes once per iteration, so for
+//! applications that render once per flush, the rate of flushes is the frame
+//! rate. Measurements are published to an `FpsMonitor`, which can be shared
+//! with the reactor being measured, such as to display an overlay.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::framework::diagnostics::{FpsCounter, FpsMonitor};
+//! use gaudium_core::prelude::*;
+//! use gaudium_core::reactor::{EventThread, StatefulReactor, ThreadContext};
+//! use gaudium_core::window::{Window, WindowBuilder};
+//! use gaudium_platform_empty::Binding;
+//! use std::io;
+//!
+//! type State = (Window<Binding>, FpsMonitor);
+//!
+//! # fn main() {
+//! EventThread::<Binding, _>::run_and_abort_with(|context| {
+//!     let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+//!     let handle = window.handle();
+//!     let monitor = FpsMonitor::default();
+//!     let reactor = StatefulReactor::from((
+//!         (window, monitor.clone()),
+//!         |(_, monitor): &mut State, _: &ThreadContext, event| match event {
+//!             Event::Application {
+//!                 event: ApplicationEvent::Flushed,
+//!                 ..
+//!             } => {
+//!                 if let Some(statistics) = monitor.statistics() {
+//!                     let _ = statistics.flushes_per_second;
+//!                 }
+//!                 Continue(())
+//!             }
+//!             Event::Window {
+//!                 event: WindowEvent::Closed(..),
+//!                 ..
+//!             } => Abort,
+//!             _ => Continue(()),
+//!         },
+//!     ));
+//!     (
+//!         handle,
+//!         FpsCounter::new(reactor, monitor).with_log(io::stderr()),
+//!     )
+//! })
+//! # }
+//! ```
+
+use std::cell::Cell;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::event::{ApplicationEvent, Event};
+use crate::platform::PlatformBinding;
+use crate::reactor::{EventBatch, Poll, Reaction, Reactor, ThreadContext};
+
+/// Measurements of the event loop over an interval.
+///
+/// See `FpsCounter`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FpsStatistics {
+    /// The number of flushes per second.
+    pub flushes_per_second: f64,
+    /// The number of events dispatched per second, including `Flushed`.
+    pub events_per_second: f64,
+    /// The mean duration between consecutive flushes.
+    pub mean_flush_interval: Duration,
+    /// The longest duration between consecutive flushes.
+    pub max_flush_interval: Duration,
+}
+
+/// Queries the most recent measurements of an `FpsCounter`.
+///
+/// Monitors are cheap to clone and clones share measurements, but they are
+/// not `Send` and can only be used on the event thread.
+#[derive(Clone, Debug, Default)]
+pub struct FpsMonitor {
+    statistics: Rc<Cell<Option<FpsStatistics>>>,
+}
+
+impl FpsMonitor {
+    /// Gets the measurements of the most recently completed interval, if any.
+    pub fn statistics(&self) -> Option<FpsStatistics> {
+        self.statistics.get()
+    }
+}
+
+/// Accumulates measurements over the current interval.
+#[derive(Default)]
+struct Sample {
+    start: Option<Instant>,
+    previous: Option<Instant>,
+    flushes: u32,
+    events: u64,
+    max_flush_interval: Duration,
+}
+
+/// A reactor that measures the cadence of the event loop.
+///
+/// Measures the duration between `Flushed` events and counts the events that
+/// it receives before forwarding them to another reactor. Measurements are
+/// published to an `FpsMonitor` and optionally written to a log each time
+/// the interval elapses.
+pub struct FpsCounter<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    monitor: FpsMonitor,
+    interval: Duration,
+    log: Option<Box<dyn Write>>,
+    sample: Sample,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> FpsCounter<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R, monitor: FpsMonitor) -> Self {
+        FpsCounter {
+            reactor,
+            monitor,
+            interval: Duration::from_secs(1),
+            log: None,
+            sample: Sample::default(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets the interval over which measurements are made. Defaults to one
+    /// second.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Writes a line to the given log each time measurements are published.
+    ///
+    /// Errors writing to the log are ignored.
+    pub fn with_log<W>(mut self, log: W) -> Self
+    where
+        W: 'static + Write,
+    {
+        self.log = Some(Box::new(log));
+        self
+    }
+
+    pub fn monitor(&self) -> &FpsMonitor {
+        &self.monitor
+    }
+
+    fn count(&mut self, event: &Event<P>) {
+        self.sample.events += 1;
+        if let Event::Application {
+            event: ApplicationEvent::Flushed,
+            timestamp,
+        } = *event
+        {
+            self.flush(timestamp);
+        }
+    }
+
+    fn flush(&mut self, timestamp: Instant) {
+        let sample = &mut self.sample;
+        let start = *sample.start.get_or_insert(timestamp);
+        if let Some(previous) = sample.previous {
+            sample.flushes += 1;
+            sample.max_flush_interval = sample
+                .max_flush_interval
+                .max(timestamp.saturating_duration_since(previous));
+        }
+        sample.previous = Some(timestamp);
+        let elapsed = timestamp.saturating_duration_since(start);
+        if sample.flushes == 0 || elapsed.is_zero() || elapsed < self.interval {
+            return;
+        }
+        let seconds = elapsed.as_secs_f64();
+        let statistics = FpsStatistics {
+            flushes_per_second: f64::from(sample.flushes) / seconds,
+            events_per_second: sample.events as f64 / seconds,
+            mean_flush_interval: elapsed / sample.flushes,
+            max_flush_interval: sample.max_flush_interval,
+        };
+        *sample = Sample {
+            start: Some(timestamp),
+            previous: Some(timestamp),
+            ..Default::default()
+        };
+        self.monitor.statistics.set(Some(statistics));
+        if let Some(ref mut log) = self.log {
+            let _ = writeln!(
+                log,
+                "{:.1} flushes/s, {:.1} events/s, {:.2?} mean, {:.2?} max",
+                statistics.flushes_per_second,
+                statistics.events_per_second,
+                statistics.mean_flush_interval,
+                statistics.max_flush_interval,
+            );
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for FpsCounter<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        self.count(&event);
+        self.reactor.react(context, event)
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        for event in events.iter() {
+            self.count(event);
+        }
+        self.reactor.react_all(context, events)
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        let FpsCounter {
+            reactor, mut log, ..
+        } = self;
+        if let Some(ref mut log) = log {
+            let _ = log.flush();
+        }
+        reactor.abort();
+    }
+}
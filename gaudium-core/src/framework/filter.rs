@@ -0,0 +1,214 @@
+//! Reusable event predicates.
+//!
+//! Predicates borrow events, so they can be combined into routing logic that
+//! inspects an event several times before deciding where to send it.
+//!
+//! # Examples
+//!
+//! Routing keyboard input for a window:
+//!
+//! ```rust
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::event::Event;
+//! use gaudium_core::framework::filter::{self, Predicate};
+//! use gaudium_core::window::WindowHandle;
+//! use gaudium_platform_empty::Binding;
+//!
+//! # fn main() {
+//! fn route(window: WindowHandle<Binding>, events: &[Event<Binding>]) -> usize {
+//!     let predicate = filter::window(window).and(filter::keyboard());
+//!     events.iter().filter(|event| predicate.test(event)).count()
+//! }
+//! # }
+//! ```
+
+use crate::device::DeviceHandle;
+use crate::event::{Event, InputEvent};
+use crate::platform::PlatformBinding;
+use crate::window::WindowHandle;
+
+/// A predicate over borrowed events.
+///
+/// This is implemented for functions and closures of the form
+/// `Fn(&Event<P>) -> bool`.
+pub trait Predicate<P>: Sized
+where
+    P: PlatformBinding,
+{
+    fn test(&self, event: &Event<P>) -> bool;
+
+    /// Combines predicates such that both must pass.
+    fn and<Q>(self, other: Q) -> And<Self, Q>
+    where
+        Q: Predicate<P>,
+    {
+        And(self, other)
+    }
+
+    /// Combines predicates such that either must pass.
+    fn or<Q>(self, other: Q) -> Or<Self, Q>
+    where
+        Q: Predicate<P>,
+    {
+        Or(self, other)
+    }
+
+    /// Inverts the predicate.
+    fn not(self) -> Not<Self> {
+        Not(self)
+    }
+}
+
+impl<P, F> Predicate<P> for F
+where
+    P: PlatformBinding,
+    F: Fn(&Event<P>) -> bool,
+{
+    fn test(&self, event: &Event<P>) -> bool {
+        (self)(event)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct And<T, U>(T, U);
+
+impl<P, T, U> Predicate<P> for And<T, U>
+where
+    P: PlatformBinding,
+    T: Predicate<P>,
+    U: Predicate<P>,
+{
+    fn test(&self, event: &Event<P>) -> bool {
+        self.0.test(event) && self.1.test(event)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Or<T, U>(T, U);
+
+impl<P, T, U> Predicate<P> for Or<T, U>
+where
+    P: PlatformBinding,
+    T: Predicate<P>,
+    U: Predicate<P>,
+{
+    fn test(&self, event: &Event<P>) -> bool {
+        self.0.test(event) || self.1.test(event)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Not<T>(T);
+
+impl<P, T> Predicate<P> for Not<T>
+where
+    P: PlatformBinding,
+    T: Predicate<P>,
+{
+    fn test(&self, event: &Event<P>) -> bool {
+        !self.0.test(event)
+    }
+}
+
+/// Passes events that are routed to the given window.
+///
+/// See `Event::is_for_window`.
+pub fn window<P>(window: WindowHandle<P>) -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    move |event: &Event<P>| event.is_for_window(window)
+}
+
+/// Passes input events from the given device.
+pub fn device<P>(device: DeviceHandle<P>) -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    move |event: &Event<P>| event.is_for_device(device)
+}
+
+/// Passes application events.
+pub fn application<P>() -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    |event: &Event<P>| matches!(event, Event::Application { .. })
+}
+
+/// Passes display events.
+pub fn display<P>() -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    |event: &Event<P>| matches!(event, Event::Display { .. })
+}
+
+/// Passes input events.
+pub fn input<P>() -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    |event: &Event<P>| matches!(event, Event::Input { .. })
+}
+
+/// Passes keyboard input events.
+pub fn keyboard<P>() -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    |event: &Event<P>| {
+        matches!(
+            event,
+            Event::Input {
+                event: InputEvent::KeyboardKeyChanged { .. },
+                ..
+            }
+        )
+    }
+}
+
+/// Passes mouse input events.
+pub fn mouse<P>() -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    |event: &Event<P>| {
+        matches!(
+            event,
+            Event::Input {
+                event: InputEvent::MouseButtonChanged { .. }
+                    | InputEvent::MouseMoved { .. }
+                    | InputEvent::MouseWheelRotated { .. },
+                ..
+            }
+        )
+    }
+}
+
+/// Passes game controller input events.
+pub fn game_controller<P>() -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    |event: &Event<P>| {
+        matches!(
+            event,
+            Event::Input {
+                event: InputEvent::GameControllerAxisChanged { .. }
+                    | InputEvent::GameControllerButtonChanged { .. },
+                ..
+            }
+        )
+    }
+}
+
+/// Passes window events, excluding input events routed to windows.
+pub fn window_events<P>() -> impl Predicate<P> + Copy
+where
+    P: PlatformBinding,
+{
+    |event: &Event<P>| matches!(event, Event::Window { .. })
+}
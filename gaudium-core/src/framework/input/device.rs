@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::device::DeviceHandle;
+use crate::event::{ElementState, Event, InputEvent};
+use crate::framework::input::state::{CompositeState, Element, Snapshot};
+use crate::framework::React;
+use crate::platform::PlatformBinding;
+
+/// Per-device snapshots.
+///
+/// Snapshots like `KeyboardSnapshot` and `MouseSnapshot` merge input from all
+/// devices into a single state. `DeviceMap` instead maintains a snapshot for
+/// each device, such as when each player uses their own keyboard or mouse.
+///
+/// Entries are created when a device is connected or first produces input and
+/// are removed when a device is disconnected. Events that are not associated
+/// with a device, such as `MouseEntered`, are forwarded to all entries.
+pub struct DeviceMap<P, S>
+where
+    P: PlatformBinding,
+    S: Default + Snapshot<P>,
+{
+    snapshots: HashMap<DeviceHandle<P>, S>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, S> DeviceMap<P, S>
+where
+    P: PlatformBinding,
+    S: Default + Snapshot<P>,
+{
+    pub fn new() -> Self {
+        DeviceMap::default()
+    }
+
+    /// Gets the snapshot of a device, if any.
+    pub fn get(&self, device: DeviceHandle<P>) -> Option<&S> {
+        self.snapshots.get(&device)
+    }
+
+    /// Gets the handles of all devices with snapshots.
+    pub fn devices(&self) -> impl '_ + Iterator<Item = DeviceHandle<P>> {
+        self.snapshots.keys().cloned()
+    }
+
+    /// Gets the snapshots of all devices.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (DeviceHandle<P>, &S)> {
+        self.snapshots
+            .iter()
+            .map(|(device, snapshot)| (*device, snapshot))
+    }
+
+    /// Snapshots the new (live) state of all devices.
+    pub fn snapshot(&mut self) {
+        for snapshot in self.snapshots.values_mut() {
+            snapshot.snapshot();
+        }
+    }
+
+    /// Gets the state of an input element for each device.
+    pub fn states<E>(&self, element: E) -> impl '_ + Iterator<Item = (DeviceHandle<P>, E::State)>
+    where
+        S::State: CompositeState<E>,
+        E: 'static + Element,
+    {
+        self.snapshots
+            .iter()
+            .map(move |(device, snapshot)| (*device, snapshot.state(element)))
+    }
+
+    /// Gets the merged state of a button or key across all devices.
+    ///
+    /// The element is pressed if it is pressed on any device.
+    pub fn state<E>(&self, element: E) -> ElementState
+    where
+        S::State: CompositeState<E>,
+        E: 'static + Element<State = ElementState>,
+    {
+        if self
+            .states(element)
+            .any(|(_, state)| state == ElementState::Pressed)
+        {
+            ElementState::Pressed
+        }
+        else {
+            ElementState::Released
+        }
+    }
+}
+
+impl<P, S> Default for DeviceMap<P, S>
+where
+    P: PlatformBinding,
+    S: Default + Snapshot<P>,
+{
+    fn default() -> Self {
+        DeviceMap {
+            snapshots: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, S> React<P> for DeviceMap<P, S>
+where
+    P: PlatformBinding,
+    S: Default + Snapshot<P>,
+{
+    fn react(&mut self, event: &Event<P>) {
+        match *event {
+            Event::Input {
+                device,
+                event: InputEvent::Disconnected,
+                ..
+            } => {
+                self.snapshots.remove(&device);
+            }
+            Event::Input { device, .. } => {
+                self.snapshots.entry(device).or_default().react(event);
+            }
+            _ => {
+                for snapshot in self.snapshots.values_mut() {
+                    snapshot.react(event);
+                }
+            }
+        }
+    }
+}
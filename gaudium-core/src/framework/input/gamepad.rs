@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use crate::device::DeviceHandle;
+use crate::event::{Event, GameControllerAxis, InputEvent};
+use crate::framework::React;
+use crate::platform::PlatformBinding;
+
+/// Calibration of a game controller axis.
+///
+/// Raw axis values are mapped piecewise such that `min`, `center`, and `max`
+/// map to `-1.0`, `0.0`, and `1.0`, respectively. Raw values beyond `min` and
+/// `max` are clamped. The default calibration maps `[-1, 1]` onto itself.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisCalibration {
+    pub min: f64,
+    pub center: f64,
+    pub max: f64,
+}
+
+impl AxisCalibration {
+    /// Maps a raw axis value into `[-1, 1]`.
+    pub fn normalize(&self, value: f64) -> f64 {
+        let normal = if value >= self.center {
+            ratio(value - self.center, self.max - self.center)
+        }
+        else {
+            -ratio(self.center - value, self.center - self.min)
+        };
+        normal.clamp(-1.0, 1.0)
+    }
+}
+
+impl Default for AxisCalibration {
+    fn default() -> Self {
+        AxisCalibration {
+            min: -1.0,
+            center: 0.0,
+            max: 1.0,
+        }
+    }
+}
+
+/// Normalized game controller axes.
+///
+/// `GamepadAxes` reacts to raw `GameControllerAxisChanged` events and exposes
+/// axis values that have been calibrated, normalized into `[-1, 1]`, and had
+/// deadzones applied. Values are tracked per device and are forgotten when a
+/// device is disconnected.
+pub struct GamepadAxes<P>
+where
+    P: PlatformBinding,
+{
+    calibrations: HashMap<GameControllerAxis, AxisCalibration>,
+    deadzones: HashMap<GameControllerAxis, f64>,
+    sticks: HashMap<(GameControllerAxis, GameControllerAxis), f64>,
+    values: HashMap<(DeviceHandle<P>, GameControllerAxis), f64>,
+}
+
+impl<P> GamepadAxes<P>
+where
+    P: PlatformBinding,
+{
+    pub fn new() -> Self {
+        GamepadAxes::default()
+    }
+
+    /// Sets the calibration of an axis for all devices.
+    pub fn set_calibration(&mut self, axis: GameControllerAxis, calibration: AxisCalibration) {
+        self.calibrations.insert(axis, calibration);
+    }
+
+    /// Sets the axial deadzone of an axis.
+    ///
+    /// Values within the deadzone are reported as zero and values outside of
+    /// it are rescaled so that output remains continuous and spans `[-1, 1]`.
+    /// The deadzone is clamped to `[0, 1)`.
+    pub fn set_axial_deadzone(&mut self, axis: GameControllerAxis, deadzone: f64) {
+        self.deadzones.insert(axis, clamp_deadzone(deadzone));
+    }
+
+    /// Sets the radial deadzone of a pair of axes, such as the axes of an
+    /// analog stick.
+    ///
+    /// Radial deadzones apply to the magnitude of both axes and preserve
+    /// direction. They only apply when querying the axes together via `stick`.
+    /// The deadzone is clamped to `[0, 1)`.
+    pub fn set_radial_deadzone(
+        &mut self,
+        axes: (GameControllerAxis, GameControllerAxis),
+        deadzone: f64,
+    ) {
+        self.sticks.insert(axes, clamp_deadzone(deadzone));
+    }
+
+    /// Normalizes a raw value of an axis, applying its calibration and axial
+    /// deadzone.
+    pub fn normalize(&self, axis: GameControllerAxis, value: f64) -> f64 {
+        let value = self.calibrate(axis, value);
+        match self.deadzones.get(&axis) {
+            Some(&deadzone) => axial(value, deadzone),
+            None => value,
+        }
+    }
+
+    /// Gets the normalized value of an axis of a device.
+    ///
+    /// Returns zero if no value has been observed.
+    pub fn axis(&self, device: DeviceHandle<P>, axis: GameControllerAxis) -> f64 {
+        self.values
+            .get(&(device, axis))
+            .map_or(0.0, |&value| self.normalize(axis, value))
+    }
+
+    /// Gets the normalized values of a pair of axes of a device, such as the
+    /// axes of an analog stick.
+    ///
+    /// If a radial deadzone has been set for the pair of axes, then it is
+    /// applied instead of any axial deadzones.
+    pub fn stick(
+        &self,
+        device: DeviceHandle<P>,
+        axes: (GameControllerAxis, GameControllerAxis),
+    ) -> (f64, f64) {
+        match self.sticks.get(&axes) {
+            Some(&deadzone) => {
+                let value = |axis| {
+                    self.values
+                        .get(&(device, axis))
+                        .map_or(0.0, |&value| self.calibrate(axis, value))
+                };
+                radial((value(axes.0), value(axes.1)), deadzone)
+            }
+            None => (self.axis(device, axes.0), self.axis(device, axes.1)),
+        }
+    }
+
+    fn calibrate(&self, axis: GameControllerAxis, value: f64) -> f64 {
+        self.calibrations
+            .get(&axis)
+            .cloned()
+            .unwrap_or_default()
+            .normalize(value)
+    }
+}
+
+impl<P> Default for GamepadAxes<P>
+where
+    P: PlatformBinding,
+{
+    fn default() -> Self {
+        GamepadAxes {
+            calibrations: HashMap::new(),
+            deadzones: HashMap::new(),
+            sticks: HashMap::new(),
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<P> React<P> for GamepadAxes<P>
+where
+    P: PlatformBinding,
+{
+    fn react(&mut self, event: &Event<P>) {
+        match *event {
+            Event::Input {
+                device,
+                event: InputEvent::GameControllerAxisChanged { axis, value },
+                ..
+            } => {
+                self.values.insert((device, axis), value);
+            }
+            Event::Input {
+                device,
+                event: InputEvent::Disconnected,
+                ..
+            } => {
+                self.values.retain(|&(other, _), _| other != device);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator > 0.0 {
+        numerator / denominator
+    }
+    else {
+        0.0
+    }
+}
+
+fn clamp_deadzone(deadzone: f64) -> f64 {
+    if deadzone.is_nan() {
+        0.0
+    }
+    else {
+        deadzone.clamp(0.0, 1.0 - f64::EPSILON)
+    }
+}
+
+fn axial(value: f64, deadzone: f64) -> f64 {
+    let magnitude = value.abs();
+    if magnitude <= deadzone {
+        0.0
+    }
+    else {
+        value.signum() * ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0)
+    }
+}
+
+fn radial((x, y): (f64, f64), deadzone: f64) -> (f64, f64) {
+    let magnitude = x.hypot(y);
+    if magnitude <= deadzone {
+        (0.0, 0.0)
+    }
+    else {
+        let scale = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0) / magnitude;
+        (x * scale, y * scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::framework::input::gamepad::{axial, radial, AxisCalibration};
+
+    #[test]
+    fn normalize_and_deadzones() {
+        let calibration = AxisCalibration {
+            min: 0.0,
+            center: 128.0,
+            max: 255.0,
+        };
+        assert_eq!(-1.0, calibration.normalize(0.0));
+        assert_eq!(0.0, calibration.normalize(128.0));
+        assert_eq!(1.0, calibration.normalize(255.0));
+        assert_eq!(1.0, calibration.normalize(300.0));
+
+        assert_eq!(0.0, axial(0.25, 0.5));
+        assert_eq!(-0.5, axial(-0.75, 0.5));
+        assert_eq!((0.0, 0.0), radial((0.25, 0.25), 0.5));
+        let (x, y) = radial((0.0, 0.75), 0.5);
+        assert!(x.abs() < 1e-9 && (y - 0.5).abs() < 1e-9);
+    }
+}
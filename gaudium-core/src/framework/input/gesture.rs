@@ -0,0 +1,267 @@
+use std::time::{Duration, Instant};
+
+use crate::display::LogicalPosition;
+use crate::event::{ElementState, Event, InputEvent, MouseButton, MouseMovement};
+use crate::framework::React;
+use crate::platform::PlatformBinding;
+use crate::window::WindowHandle;
+
+/// A gesture recognized from mouse input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture<P>
+where
+    P: PlatformBinding,
+{
+    /// A button was pressed and released without dragging.
+    ///
+    /// `count` is the number of consecutive clicks of the same button within
+    /// the click interval and distance thresholds. A `count` of two is a
+    /// double-click.
+    Click {
+        window: Option<WindowHandle<P>>,
+        button: MouseButton,
+        count: u32,
+        position: LogicalPosition,
+        timestamp: Instant,
+    },
+    /// The pointer moved beyond the drag threshold while a button was held.
+    DragStarted {
+        window: Option<WindowHandle<P>>,
+        button: MouseButton,
+        origin: LogicalPosition,
+        timestamp: Instant,
+    },
+    /// The pointer moved during a drag.
+    Dragged {
+        window: Option<WindowHandle<P>>,
+        button: MouseButton,
+        origin: LogicalPosition,
+        position: LogicalPosition,
+        timestamp: Instant,
+    },
+    /// The button of a drag was released.
+    DragEnded {
+        window: Option<WindowHandle<P>>,
+        button: MouseButton,
+        origin: LogicalPosition,
+        position: LogicalPosition,
+        timestamp: Instant,
+    },
+}
+
+impl<P> Gesture<P>
+where
+    P: PlatformBinding,
+{
+    /// Determines if the gesture is a double-click.
+    pub fn is_double_click(&self) -> bool {
+        matches!(*self, Gesture::Click { count: 2, .. })
+    }
+}
+
+struct Press<P>
+where
+    P: PlatformBinding,
+{
+    window: Option<WindowHandle<P>>,
+    button: MouseButton,
+    origin: LogicalPosition,
+    timestamp: Instant,
+    is_dragging: bool,
+}
+
+struct Click<P>
+where
+    P: PlatformBinding,
+{
+    window: Option<WindowHandle<P>>,
+    button: MouseButton,
+    position: LogicalPosition,
+    // The time at which the button was pressed.
+    timestamp: Instant,
+    count: u32,
+}
+
+/// Recognizes clicks, multi-clicks, and drags from mouse input.
+///
+/// `ClickGestures` observes mouse button and movement events and synthesizes
+/// `Gesture`s. Presses of a button in the same window are counted as
+/// consecutive clicks if each press occurs within the click interval of the
+/// press of the previous click and within the click distance of it. A press
+/// becomes a drag if the pointer moves beyond the drag distance before the
+/// button is released; drags never produce clicks.
+///
+/// Only the most recently pressed button is tracked. Pointer positions are
+/// taken from absolute mouse movement, so gestures are only recognized for
+/// platforms and mouse modes that report absolute positions.
+///
+/// `ClickGestures` does not dispatch gestures itself. After reacting to
+/// events, gestures are retrieved via `drain`.
+pub struct ClickGestures<P>
+where
+    P: PlatformBinding,
+{
+    interval: Duration,
+    distance: f64,
+    drag_distance: f64,
+    position: LogicalPosition,
+    press: Option<Press<P>>,
+    click: Option<Click<P>>,
+    gestures: Vec<Gesture<P>>,
+}
+
+impl<P> ClickGestures<P>
+where
+    P: PlatformBinding,
+{
+    /// Creates a gesture recognizer with the given maximum interval and
+    /// distance between consecutive clicks and the minimum distance that
+    /// begins a drag.
+    ///
+    /// Distances are in logical units.
+    pub fn new(interval: Duration, distance: f64, drag_distance: f64) -> Self {
+        ClickGestures {
+            interval,
+            distance,
+            drag_distance,
+            position: LogicalPosition::default(),
+            press: None,
+            click: None,
+            gestures: vec![],
+        }
+    }
+
+    /// Removes and returns all recognized gestures in the order in which they
+    /// were recognized.
+    pub fn drain(&mut self) -> impl '_ + Iterator<Item = Gesture<P>> {
+        self.gestures.drain(..)
+    }
+
+    fn pressed(
+        &mut self,
+        window: Option<WindowHandle<P>>,
+        button: MouseButton,
+        timestamp: Instant,
+    ) {
+        self.press = Some(Press {
+            window,
+            button,
+            origin: self.position,
+            timestamp,
+            is_dragging: false,
+        });
+    }
+
+    fn released(&mut self, button: MouseButton, timestamp: Instant) {
+        let press = match self.press.take() {
+            Some(press) if press.button == button => press,
+            press => {
+                self.press = press;
+                return;
+            }
+        };
+        if press.is_dragging {
+            self.click = None;
+            self.gestures.push(Gesture::DragEnded {
+                window: press.window,
+                button,
+                origin: press.origin,
+                position: self.position,
+                timestamp,
+            });
+            return;
+        }
+        let count = match self.click {
+            Some(ref click)
+                if click.button == button
+                    && click.window == press.window
+                    && press.timestamp.saturating_duration_since(click.timestamp)
+                        <= self.interval
+                    && (click.position - self.position).length() <= self.distance =>
+            {
+                click.count + 1
+            }
+            _ => 1,
+        };
+        self.click = Some(Click {
+            window: press.window,
+            button,
+            position: self.position,
+            timestamp: press.timestamp,
+            count,
+        });
+        self.gestures.push(Gesture::Click {
+            window: press.window,
+            button,
+            count,
+            position: self.position,
+            timestamp,
+        });
+    }
+
+    fn moved(&mut self, position: LogicalPosition, timestamp: Instant) {
+        self.position = position;
+        if let Some(ref mut press) = self.press {
+            if !press.is_dragging && (press.origin - position).length() >= self.drag_distance {
+                press.is_dragging = true;
+                self.gestures.push(Gesture::DragStarted {
+                    window: press.window,
+                    button: press.button,
+                    origin: press.origin,
+                    timestamp,
+                });
+            }
+            if press.is_dragging {
+                self.gestures.push(Gesture::Dragged {
+                    window: press.window,
+                    button: press.button,
+                    origin: press.origin,
+                    position,
+                    timestamp,
+                });
+            }
+        }
+    }
+}
+
+impl<P> Default for ClickGestures<P>
+where
+    P: PlatformBinding,
+{
+    fn default() -> Self {
+        ClickGestures::new(Duration::from_millis(500), 4.0, 4.0)
+    }
+}
+
+impl<P> React<P> for ClickGestures<P>
+where
+    P: PlatformBinding,
+{
+    fn react(&mut self, event: &Event<P>) {
+        match *event {
+            Event::Input {
+                window,
+                event: InputEvent::MouseButtonChanged { button, state, .. },
+                timestamp,
+                ..
+            } => match state {
+                ElementState::Pressed => self.pressed(window, button, timestamp),
+                ElementState::Released => self.released(button, timestamp),
+            },
+            Event::Input {
+                event:
+                    InputEvent::MouseMoved {
+                        movement:
+                            MouseMovement {
+                                absolute: Some(position),
+                                ..
+                            },
+                        ..
+                    },
+                timestamp,
+                ..
+            } => self.moved(position, timestamp),
+            _ => {}
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use std::ops::Deref;
 
-use crate::event::{ElementState, Event, InputEvent, KeyCode};
+use crate::event::{ElementState, Event, InputEvent, KeyCode, ToggleState};
 use crate::framework::input::state::{AsRawState, Element, Snapshot, SnapshotState};
 use crate::framework::React;
 use crate::platform::PlatformBinding;
@@ -66,19 +66,25 @@ where
 {
     fn react(&mut self, event: &Event<P>) {
         if let Event::Input {
-            event: InputEvent::KeyboardKeyChanged { keycode, state, .. },
+            event:
+                InputEvent::KeyboardKeyChanged {
+                    keycode,
+                    state,
+                    modifier,
+                    ..
+                },
             ..
         } = *event
         {
-            if let Some(keycode) = keycode {
-                match state {
-                    ElementState::Pressed => {
-                        self.new.keys.insert(keycode);
-                    }
-                    ElementState::Released => {
-                        self.new.keys.remove(&keycode);
-                    }
+            self.new.toggles = modifier.toggles;
+            match (keycode, state) {
+                (Some(keycode), ElementState::Pressed) => {
+                    self.new.keys.insert(keycode);
                 }
+                (Some(keycode), ElementState::Released) => {
+                    self.new.keys.remove(&keycode);
+                }
+                _ => {}
             }
         }
     }
@@ -87,14 +93,25 @@ where
 #[derive(Clone)]
 pub struct KeyboardState {
     keys: HashSet<KeyCode>,
+    toggles: ToggleState,
 }
 
 impl KeyboardState {
     fn new() -> Self {
         KeyboardState {
             keys: HashSet::new(),
+            toggles: ToggleState::default(),
         }
     }
+
+    /// Gets the state of the toggle keys as of the most recent keyboard
+    /// event.
+    ///
+    /// Toggles are assumed to be off until a keyboard event is received. Use
+    /// `ThreadContext::toggles` to query the keyboard directly.
+    pub fn toggles(&self) -> ToggleState {
+        self.toggles
+    }
 }
 
 impl AsRawState<KeyCode> for KeyboardState {
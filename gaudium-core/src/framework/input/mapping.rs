@@ -0,0 +1,655 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::device::{DeviceHandle, DeviceInfo};
+use crate::error::{Error, ErrorKind};
+use crate::event::{ElementState, Event, GameControllerAxis, GameControllerButton, InputEvent};
+use crate::platform::PlatformBinding;
+
+/// The bus type of USB devices in SDL device GUIDs.
+const BUS_USB: u16 = 0x0003;
+
+/// The threshold beyond which an axis that is mapped to a button is pressed.
+const AXIS_THRESHOLD: f64 = 0.5;
+
+/// Identifies a model of game controller.
+///
+/// This is compatible with the GUIDs used by SDL and its community database of
+/// game controller mappings (`gamecontrollerdb.txt`), and is formatted as 32
+/// hexadecimal digits.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeviceGuid([u8; 16]);
+
+impl DeviceGuid {
+    /// Computes the GUID of a USB device from its vendor, product, and
+    /// version.
+    pub fn from_usb(vendor: u16, product: u16, version: u16) -> Self {
+        let mut guid = [0u8; 16];
+        for (index, &word) in [BUS_USB, 0, vendor, 0, product, 0, version, 0]
+            .iter()
+            .enumerate()
+        {
+            guid[index * 2..index * 2 + 2].copy_from_slice(&word.to_le_bytes());
+        }
+        DeviceGuid(guid)
+    }
+
+    /// Computes the GUID of a device from its metadata.
+    ///
+    /// Returns `None` if the platform does not report the vendor and product
+    /// of the device. A version that is not reported is zero.
+    pub fn from_device_info(info: &DeviceInfo) -> Option<Self> {
+        Some(DeviceGuid::from_usb(
+            info.vendor_id?,
+            info.product_id?,
+            info.version.unwrap_or(0),
+        ))
+    }
+
+    /// Gets the GUID with its version cleared.
+    ///
+    /// Many mappings in the SDL database do not specify a version, so this is
+    /// used as a fallback when no mapping matches a device exactly.
+    pub fn without_version(self) -> Self {
+        let DeviceGuid(mut guid) = self;
+        guid[12] = 0;
+        guid[13] = 0;
+        DeviceGuid(guid)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl Display for DeviceGuid {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for byte in self.0.iter() {
+            write!(formatter, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DeviceGuid {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || Error::new(ErrorKind::InvalidInput, "invalid device GUID");
+        if text.len() != 32 || !text.is_ascii() {
+            return Err(error());
+        }
+        let mut guid = [0u8; 16];
+        for (index, byte) in guid.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[index * 2..index * 2 + 2], 16).map_err(|_| error())?;
+        }
+        Ok(DeviceGuid(guid))
+    }
+}
+
+/// Buttons of the standard game controller layout.
+///
+/// The standard layout resembles an Xbox controller. Buttons are reported as
+/// the `GameControllerButton` given by their discriminants, which agree with
+/// platforms that report standard layouts, such as XInput on Windows.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(u8)]
+pub enum StandardButton {
+    A = 0,
+    B = 1,
+    X = 2,
+    Y = 3,
+    LeftShoulder = 4,
+    RightShoulder = 5,
+    Back = 6,
+    Start = 7,
+    LeftStick = 8,
+    RightStick = 9,
+    DPadUp = 10,
+    DPadDown = 11,
+    DPadLeft = 12,
+    DPadRight = 13,
+    Guide = 14,
+    Misc1 = 15,
+    Paddle1 = 16,
+    Paddle2 = 17,
+    Paddle3 = 18,
+    Paddle4 = 19,
+    Touchpad = 20,
+}
+
+impl StandardButton {
+    fn from_sdl_name(name: &str) -> Option<Self> {
+        use StandardButton::*;
+
+        Some(match name {
+            "a" => A,
+            "b" => B,
+            "x" => X,
+            "y" => Y,
+            "leftshoulder" => LeftShoulder,
+            "rightshoulder" => RightShoulder,
+            "back" => Back,
+            "start" => Start,
+            "leftstick" => LeftStick,
+            "rightstick" => RightStick,
+            "dpup" => DPadUp,
+            "dpdown" => DPadDown,
+            "dpleft" => DPadLeft,
+            "dpright" => DPadRight,
+            "guide" => Guide,
+            "misc1" => Misc1,
+            "paddle1" => Paddle1,
+            "paddle2" => Paddle2,
+            "paddle3" => Paddle3,
+            "paddle4" => Paddle4,
+            "touchpad" => Touchpad,
+            _ => return None,
+        })
+    }
+}
+
+/// Axes of the standard game controller layout.
+///
+/// Stick axes are in the range `[-1.0, 1.0]`, where negative values are left
+/// and up, and trigger axes are in the range `[0.0, 1.0]`. Axes are reported
+/// as the `GameControllerAxis` given by their discriminants.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(u8)]
+pub enum StandardAxis {
+    LeftX = 0,
+    LeftY = 1,
+    RightX = 2,
+    RightY = 3,
+    LeftTrigger = 4,
+    RightTrigger = 5,
+}
+
+impl StandardAxis {
+    fn from_sdl_name(name: &str) -> Option<Self> {
+        use StandardAxis::*;
+
+        Some(match name {
+            "leftx" => LeftX,
+            "lefty" => LeftY,
+            "rightx" => RightX,
+            "righty" => RightY,
+            "lefttrigger" => LeftTrigger,
+            "righttrigger" => RightTrigger,
+            _ => return None,
+        })
+    }
+
+    fn is_trigger(self) -> bool {
+        matches!(self, StandardAxis::LeftTrigger | StandardAxis::RightTrigger)
+    }
+}
+
+/// The portion of an axis that is bound, given by a `+` or `-` prefix in SDL
+/// mappings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Range {
+    Full,
+    Positive,
+    Negative,
+}
+
+impl Range {
+    fn split(text: &str) -> (Self, &str) {
+        if let Some(text) = text.strip_prefix('+') {
+            (Range::Positive, text)
+        }
+        else if let Some(text) = text.strip_prefix('-') {
+            (Range::Negative, text)
+        }
+        else {
+            (Range::Full, text)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Source {
+    Button(GameControllerButton),
+    Axis {
+        axis: GameControllerAxis,
+        range: Range,
+        is_inverted: bool,
+    },
+}
+
+impl Source {
+    fn parse(text: &str) -> Option<Self> {
+        let (range, text) = Range::split(text);
+        let (text, is_inverted) = match text.strip_suffix('~') {
+            Some(text) => (text, true),
+            None => (text, false),
+        };
+        if let Some(button) = text.strip_prefix('b') {
+            Some(Source::Button(button.parse().ok()?))
+        }
+        else if let Some(axis) = text.strip_prefix('a') {
+            Some(Source::Axis {
+                axis: axis.parse().ok()?,
+                range,
+                is_inverted,
+            })
+        }
+        else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    Button(StandardButton),
+    Axis { axis: StandardAxis, range: Range },
+}
+
+impl Target {
+    fn parse(text: &str) -> Option<Self> {
+        let (range, text) = Range::split(text);
+        StandardAxis::from_sdl_name(text)
+            .map(|axis| Target::Axis { axis, range })
+            .or_else(|| StandardButton::from_sdl_name(text).map(Target::Button))
+    }
+}
+
+/// A mapping from the raw buttons and axes of a model of game controller to
+/// the standard layout.
+///
+/// Mappings are parsed from lines in the format of SDL's game controller
+/// database, such as
+/// `03000000de280000ff11000000000000,Steam Virtual Gamepad,a:b0,b:b1,...`.
+/// Bindings to hats are ignored, because hats are not reported by platforms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GamepadMapping {
+    guid: DeviceGuid,
+    name: String,
+    platform: Option<String>,
+    bindings: Vec<(Source, Target)>,
+}
+
+impl GamepadMapping {
+    pub fn guid(&self) -> DeviceGuid {
+        self.guid
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the platform of the mapping, such as "Windows", if any.
+    pub fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
+    }
+
+    /// Determines if the mapping applies to the target platform.
+    ///
+    /// Mappings that do not specify a platform apply to all platforms.
+    pub fn is_native(&self) -> bool {
+        let native = if cfg!(target_os = "windows") {
+            "Windows"
+        }
+        else if cfg!(target_os = "macos") {
+            "Mac OS X"
+        }
+        else if cfg!(target_os = "android") {
+            "Android"
+        }
+        else if cfg!(target_os = "ios") {
+            "iOS"
+        }
+        else {
+            "Linux"
+        };
+        self.platform
+            .as_ref()
+            .is_none_or(|platform| platform == native)
+    }
+
+    /// Maps a raw input event into events of the standard layout.
+    ///
+    /// `previous` is the previous raw value of the axis of an
+    /// `GameControllerAxisChanged` event, which is used to detect when a
+    /// button bound to the axis changes state. Events other than game
+    /// controller events are not mapped and produce no events.
+    fn map(&self, event: &InputEvent, previous: f64) -> Vec<InputEvent> {
+        let mut events = vec![];
+        for &(source, target) in self.bindings.iter() {
+            match (source, *event) {
+                (
+                    Source::Button(button),
+                    InputEvent::GameControllerButtonChanged {
+                        button: other,
+                        state,
+                    },
+                ) if button == other => {
+                    let is_pressed = state == ElementState::Pressed;
+                    events.push(match target {
+                        Target::Button(button) => InputEvent::GameControllerButtonChanged {
+                            button: button as GameControllerButton,
+                            state,
+                        },
+                        Target::Axis { axis, range } => InputEvent::GameControllerAxisChanged {
+                            axis: axis as GameControllerAxis,
+                            value: match (is_pressed, range) {
+                                (false, _) => 0.0,
+                                (true, Range::Negative) => -1.0,
+                                (true, _) => 1.0,
+                            },
+                        },
+                    });
+                }
+                (
+                    Source::Axis {
+                        axis,
+                        range,
+                        is_inverted,
+                    },
+                    InputEvent::GameControllerAxisChanged { axis: other, value },
+                ) if axis == other => {
+                    let (full, unit) = normalize(value, range, is_inverted);
+                    match target {
+                        Target::Button(button) => {
+                            let (_, previous) = normalize(previous, range, is_inverted);
+                            let is_pressed = axis_to_button(full, unit, range);
+                            if is_pressed != axis_to_button(full, previous, range) {
+                                events.push(InputEvent::GameControllerButtonChanged {
+                                    button: button as GameControllerButton,
+                                    state: if is_pressed {
+                                        ElementState::Pressed
+                                    }
+                                    else {
+                                        ElementState::Released
+                                    },
+                                });
+                            }
+                        }
+                        Target::Axis {
+                            axis,
+                            range: target,
+                        } => {
+                            events.push(InputEvent::GameControllerAxisChanged {
+                                axis: axis as GameControllerAxis,
+                                value: match target {
+                                    Range::Full if axis.is_trigger() => unit,
+                                    Range::Full => full,
+                                    Range::Positive => unit,
+                                    Range::Negative => -unit,
+                                },
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+impl FromStr for GamepadMapping {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = |context| Error::new(ErrorKind::InvalidInput, context);
+        let mut fields = line.trim().split(',');
+        let guid = fields
+            .next()
+            .ok_or_else(|| error("missing device GUID"))?
+            .parse()?;
+        let name = fields
+            .next()
+            .ok_or_else(|| error("missing controller name"))?
+            .to_owned();
+        let mut platform = None;
+        let mut bindings = vec![];
+        for field in fields.filter(|field| !field.is_empty()) {
+            let (key, value) = field
+                .split_once(':')
+                .ok_or_else(|| error("invalid controller binding"))?;
+            if key == "platform" {
+                platform = Some(value.to_owned());
+            }
+            else if let Some(target) = Target::parse(key) {
+                // Hats and unrecognized sources are ignored.
+                if let Some(source) = Source::parse(value) {
+                    bindings.push((source, target));
+                }
+            }
+        }
+        Ok(GamepadMapping {
+            guid,
+            name,
+            platform,
+            bindings,
+        })
+    }
+}
+
+/// A set of `GamepadMapping`s that translates events from game controllers
+/// into the standard layout.
+///
+/// Mappings are matched against devices by their `DeviceGuid`, which is
+/// computed from the metadata of the device when it is first observed. If no
+/// mapping matches a device exactly, a mapping that matches all versions of
+/// the device is used.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate gaudium_core;
+/// # extern crate gaudium_platform_empty;
+/// #
+/// use gaudium_core::framework::input::GamepadMappings;
+/// use gaudium_platform_empty::Binding;
+///
+/// # fn main() {
+/// let mut mappings = GamepadMappings::<Binding>::new();
+/// let count = mappings.import(
+///     "# Game Controller DB\n\
+///      03000000de280000ff11000000000000,Steam Virtual Gamepad,a:b0,b:b1,leftx:a0,platform:Windows,\n",
+/// );
+/// assert!(count <= 1);
+/// # }
+/// ```
+pub struct GamepadMappings<P>
+where
+    P: PlatformBinding,
+{
+    mappings: HashMap<DeviceGuid, GamepadMapping>,
+    devices: HashMap<DeviceHandle<P>, MappedDevice>,
+}
+
+struct MappedDevice {
+    guid: Option<DeviceGuid>,
+    axes: HashMap<GameControllerAxis, f64>,
+}
+
+impl<P> GamepadMappings<P>
+where
+    P: PlatformBinding,
+{
+    pub fn new() -> Self {
+        GamepadMappings::default()
+    }
+
+    /// Imports mappings from text in the format of SDL's game controller
+    /// database, such as the contents of `gamecontrollerdb.txt`.
+    ///
+    /// Blank lines, comments, lines that cannot be parsed, and mappings for
+    /// other platforms are ignored. Returns the number of mappings imported.
+    pub fn import(&mut self, text: &str) -> usize {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.parse::<GamepadMapping>().ok())
+            .filter(|mapping| mapping.is_native())
+            .map(|mapping| self.insert(mapping))
+            .count()
+    }
+
+    /// Inserts a mapping, replacing and returning any mapping with the same
+    /// GUID.
+    pub fn insert(&mut self, mapping: GamepadMapping) -> Option<GamepadMapping> {
+        self.mappings.insert(mapping.guid, mapping)
+    }
+
+    pub fn get(&self, guid: DeviceGuid) -> Option<&GamepadMapping> {
+        self.mappings
+            .get(&guid)
+            .or_else(|| self.mappings.get(&guid.without_version()))
+    }
+
+    /// Gets the mapping applied to a device, if any.
+    pub fn device_mapping(&mut self, device: DeviceHandle<P>) -> Option<&GamepadMapping> {
+        let guid = self.device(device).guid?;
+        self.get(guid)
+    }
+
+    /// Translates an event from a game controller into the standard layout.
+    ///
+    /// Events from devices without a mapping, and events other than game
+    /// controller buttons and axes, are returned unchanged. Events from mapped
+    /// devices may be translated into any number of events, including none
+    /// for buttons and axes that are not bound.
+    pub fn map(&mut self, event: Event<P>) -> Vec<Event<P>> {
+        match event {
+            Event::Input {
+                device,
+                window,
+                event: input,
+                focus,
+                timestamp,
+            } => match input {
+                InputEvent::Disconnected => {
+                    self.devices.remove(&device);
+                    vec![event]
+                }
+                InputEvent::GameControllerButtonChanged { .. }
+                | InputEvent::GameControllerAxisChanged { .. } => {
+                    let guid = self.device(device).guid;
+                    let mapping = match guid.and_then(|guid| self.get(guid)) {
+                        Some(mapping) => mapping.clone(),
+                        None => return vec![event],
+                    };
+                    let previous = match input {
+                        InputEvent::GameControllerAxisChanged { axis, value } => {
+                            self.device(device).axes.insert(axis, value).unwrap_or(0.0)
+                        }
+                        _ => 0.0,
+                    };
+                    mapping
+                        .map(&input, previous)
+                        .into_iter()
+                        .map(|event| Event::Input {
+                            device,
+                            window,
+                            event,
+                            focus,
+                            timestamp,
+                        })
+                        .collect()
+                }
+                _ => vec![event],
+            },
+            _ => vec![event],
+        }
+    }
+
+    fn device(&mut self, device: DeviceHandle<P>) -> &mut MappedDevice {
+        self.devices.entry(device).or_insert_with(|| MappedDevice {
+            guid: device
+                .info()
+                .and_then(|info| DeviceGuid::from_device_info(&info)),
+            axes: HashMap::new(),
+        })
+    }
+}
+
+impl<P> Default for GamepadMappings<P>
+where
+    P: PlatformBinding,
+{
+    fn default() -> Self {
+        GamepadMappings {
+            mappings: HashMap::new(),
+            devices: HashMap::new(),
+        }
+    }
+}
+
+/// Normalizes a raw axis value with respect to the bound range of the axis.
+///
+/// Returns the value in `[-1, 1]` and in `[0, 1]`. For half axes, the unit
+/// value is the magnitude of the bound half.
+fn normalize(value: f64, range: Range, is_inverted: bool) -> (f64, f64) {
+    let value = if is_inverted { -value } else { value };
+    match range {
+        Range::Full => (value, (value + 1.0) / 2.0),
+        Range::Positive => {
+            let unit = value.clamp(0.0, 1.0);
+            (unit * 2.0 - 1.0, unit)
+        }
+        Range::Negative => {
+            let unit = (-value).clamp(0.0, 1.0);
+            (unit * 2.0 - 1.0, unit)
+        }
+    }
+}
+
+fn axis_to_button(full: f64, unit: f64, range: Range) -> bool {
+    match range {
+        Range::Full => full > AXIS_THRESHOLD,
+        _ => unit > AXIS_THRESHOLD,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{ElementState, InputEvent};
+    use crate::framework::input::mapping::{
+        DeviceGuid, GamepadMapping, StandardAxis, StandardButton,
+    };
+
+    #[test]
+    fn parse_sdl_mapping() {
+        let guid = DeviceGuid::from_usb(0x045E, 0x028E, 0);
+        assert_eq!("030000005e0400008e02000000000000", guid.to_string());
+        assert_eq!(guid, guid.to_string().parse().unwrap());
+
+        let mapping: GamepadMapping =
+            "030000005e0400008e02000000000000,Pad,a:b1,dpup:h0.1,-leftx:b4,lefttrigger:a2,righttrigger:-a3~,platform:Windows,"
+                .parse()
+                .unwrap();
+        assert_eq!(guid, mapping.guid());
+        assert_eq!("Pad", mapping.name());
+        assert_eq!(Some("Windows"), mapping.platform());
+
+        let pressed = |button| InputEvent::GameControllerButtonChanged {
+            button,
+            state: ElementState::Pressed,
+        };
+        let axis = |axis, value| InputEvent::GameControllerAxisChanged { axis, value };
+        assert_eq!(
+            vec![pressed(StandardButton::A as u8)],
+            mapping.map(&pressed(1), 0.0)
+        );
+        assert_eq!(
+            vec![axis(StandardAxis::LeftX as u8, -1.0)],
+            mapping.map(&pressed(4), 0.0)
+        );
+        assert_eq!(
+            vec![axis(StandardAxis::LeftTrigger as u8, 0.5)],
+            mapping.map(&axis(2, 0.0), 0.0)
+        );
+        // The negative half of an inverted axis is its positive half.
+        assert_eq!(
+            vec![axis(StandardAxis::RightTrigger as u8, 0.75)],
+            mapping.map(&axis(3, 0.75), 0.0)
+        );
+    }
+}
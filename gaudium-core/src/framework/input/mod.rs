@@ -1,7 +1,19 @@
+mod device;
+mod gamepad;
+mod gesture;
 mod keyboard;
+mod mapping;
 mod mouse;
+mod repeat;
 mod state;
 
+pub use self::device::DeviceMap;
+pub use self::gamepad::{AxisCalibration, GamepadAxes};
+pub use self::gesture::{ClickGestures, Gesture};
 pub use self::keyboard::{KeyboardSnapshot, KeyboardState};
+pub use self::mapping::{
+    DeviceGuid, GamepadMapping, GamepadMappings, StandardAxis, StandardButton,
+};
 pub use self::mouse::{MousePosition, MouseProximity, MouseSnapshot, MouseState};
+pub use self::repeat::KeyRepeat;
 pub use self::state::{CompositeState, Snapshot, SnapshotDifference, SnapshotTransition};
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use std::ops::Deref;
 
-use crate::event::{ElementState, Event, InputEvent, MouseButton, MouseMovement};
+use crate::event::{ElementState, Event, InputEvent, MouseButton, MouseMovement, WindowEvent};
 use crate::framework::input::state::{
     AsRawState, CompositeState, Element, Snapshot, SnapshotDifference, SnapshotState,
     SnapshotTransition, State,
@@ -141,7 +141,7 @@ where
                     InputEvent::MouseMoved {
                         movement:
                             MouseMovement {
-                                absolute: Some((x, y)),
+                                absolute: Some(position),
                                 ..
                             },
                         ..
@@ -149,7 +149,19 @@ where
                 ..
             } => {
                 // TODO: Reconcile these types.
-                self.new.position = (x.into(), y.into());
+                self.new.position = (position.x.into(), position.y.into());
+            }
+            Event::Window {
+                event: WindowEvent::MouseEntered,
+                ..
+            } => {
+                self.new.proximity = true;
+            }
+            Event::Window {
+                event: WindowEvent::MouseLeft,
+                ..
+            } => {
+                self.new.proximity = false;
             }
             _ => {}
         }
@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+use crate::event::{ElementState, Event, InputEvent, ScanCode};
+use crate::framework::React;
+use crate::platform::PlatformBinding;
+
+/// Synthesizes repeated key presses while a key is held.
+///
+/// Platforms may not report auto-repeat or may report it at a rate that is
+/// not configurable. `KeyRepeat` observes keyboard events and produces
+/// `KeyboardKeyChanged` events with `repeat` set to `true` after the most
+/// recently pressed key has been held for a delay, and then at a fixed
+/// interval until it is released. Repeats reported by the platform are
+/// ignored.
+///
+/// `KeyRepeat` does not dispatch events itself. A reactor should poll with
+/// `Poll::WaitUntil` using `deadline` and then react to the events returned by
+/// `repeats`.
+pub struct KeyRepeat<P>
+where
+    P: PlatformBinding,
+{
+    delay: Duration,
+    interval: Duration,
+    held: Option<(Event<P>, ScanCode, Instant)>,
+}
+
+impl<P> KeyRepeat<P>
+where
+    P: PlatformBinding,
+{
+    /// Creates a key repeat synthesizer with the given delay before the first
+    /// repeat and interval between subsequent repeats.
+    ///
+    /// The interval is at least one millisecond.
+    pub fn new(delay: Duration, interval: Duration) -> Self {
+        KeyRepeat {
+            delay,
+            interval: interval.max(Duration::from_millis(1)),
+            held: None,
+        }
+    }
+
+    /// Gets the instant at which the next repeat is synthesized, if any key is
+    /// held.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.held.as_ref().map(|&(_, _, next)| next)
+    }
+
+    /// Synthesizes all repeats that are due at the given instant.
+    ///
+    /// Repeats are timestamped with the instant at which they were due.
+    pub fn repeats(&mut self, now: Instant) -> Vec<Event<P>> {
+        let mut events = vec![];
+        if let Some((ref event, _, ref mut next)) = self.held {
+            while *next <= now {
+                if let Event::Input {
+                    device,
+                    window,
+                    event:
+                        InputEvent::KeyboardKeyChanged {
+                            scancode,
+                            keycode,
+                            state,
+                            modifier,
+                            ..
+                        },
+                    focus,
+                    ..
+                } = *event
+                {
+                    events.push(Event::Input {
+                        device,
+                        window,
+                        event: InputEvent::KeyboardKeyChanged {
+                            scancode,
+                            keycode,
+                            state,
+                            modifier,
+                            repeat: true,
+                        },
+                        focus,
+                        timestamp: *next,
+                    });
+                }
+                *next += self.interval;
+            }
+        }
+        events
+    }
+}
+
+impl<P> Default for KeyRepeat<P>
+where
+    P: PlatformBinding,
+{
+    fn default() -> Self {
+        KeyRepeat::new(Duration::from_millis(500), Duration::from_millis(33))
+    }
+}
+
+impl<P> React<P> for KeyRepeat<P>
+where
+    P: PlatformBinding,
+{
+    fn react(&mut self, event: &Event<P>) {
+        if let Event::Input {
+            event:
+                InputEvent::KeyboardKeyChanged {
+                    scancode,
+                    state,
+                    repeat: false,
+                    ..
+                },
+            timestamp,
+            ..
+        } = *event
+        {
+            match state {
+                ElementState::Pressed => {
+                    self.held = Some((*event, scancode, timestamp + self.delay));
+                }
+                ElementState::Released => {
+                    if let Some((_, held, _)) = self.held {
+                        if held == scancode {
+                            self.held = None;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
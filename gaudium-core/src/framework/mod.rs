@@ -2,7 +2,12 @@ use crate::event::Event;
 use crate::platform::PlatformBinding;
 
 // TODO: Rework types and traits around `Platform`.
-//pub mod input;
+pub mod action;
+pub mod diagnostics;
+pub mod filter;
+pub mod input;
+pub mod record;
+pub mod shortcut;
 
 pub trait React<P>
 where
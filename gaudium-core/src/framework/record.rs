@@ -0,0 +1,880 @@
+//! Event recording and playback.
+//!
+//! `EventRecorder` is a reactor adapter that writes the events it receives to
+//! a writer before forwarding them to another reactor. `EventPlayer` is a
+//! reactor adapter that ignores the events dispatched by its event thread and
+//! instead dispatches recorded events to another reactor. Together, these
+//! types can be used to reproduce a sequence of events deterministically,
+//! such as when testing or reproducing bugs.
+//!
+//! Recordings are written as text, with one event per line. Each event is
+//! prefixed by its offset in microseconds from the first recorded event.
+//! Platform handles are not meaningful outside of the event thread in which
+//! they were observed, so handles are replaced by integer identifiers that are
+//! assigned in the order that handles are first recorded.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::event::{ApplicationEvent, Event};
+//! use gaudium_core::framework::record::{self, Recorder};
+//! use gaudium_platform_empty::Binding;
+//! use std::time::Instant;
+//!
+//! # fn main() {
+//! let mut recorder = Recorder::<Binding>::default();
+//! let mut buffer = vec![];
+//! recorder
+//!     .write(
+//!         &mut buffer,
+//!         &Event::Application {
+//!             event: ApplicationEvent::Flushed,
+//!             timestamp: Instant::now(),
+//!         },
+//!     )
+//!     .unwrap();
+//!
+//! let events = record::read::<Binding, _>(buffer.as_slice()).unwrap();
+//! assert_eq!(events.len(), 1);
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::marker::PhantomData;
+use std::str::{FromStr, SplitWhitespace};
+use std::time::{Duration, Instant};
+
+use crate::device::{DeviceHandle, SensorType, Usage};
+use crate::display::{DisplayHandle, LogicalUnit, PhysicalUnit};
+use crate::event::{
+    ApplicationEvent, DisplayEvent, ElementState, Event, InputEvent, InputFocus, KeyCode,
+    ModifierState, MouseButton, MouseMovement, MouseWheelDelta, PenInfo, Resumption, ToggleState,
+    WindowCloseState, WindowEvent, WindowPosition, WindowState,
+};
+use crate::keyboard::KeyLayoutId;
+use crate::platform::{self, PlatformBinding};
+use crate::reactor::{EventBatch, Poll, Reaction, Reactor, ThreadContext, TimerId};
+use crate::shell::hotkey::HotkeyId;
+use crate::window::WindowHandle;
+use crate::{FromRawHandle, IntoRawHandle};
+
+/// A recorded event and its offset from the beginning of a recording.
+pub type Record<P> = (Duration, Event<P>);
+
+/// Determines the timing of events dispatched by an `EventPlayer`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Timing {
+    /// Dispatches events with the same relative timing as the recording.
+    Relative,
+    /// Dispatches events as fast as possible.
+    Immediate,
+}
+
+/// Identifies handles in a recording.
+struct Identifiers<T>
+where
+    T: PartialEq,
+{
+    handles: Vec<T>,
+}
+
+impl<T> Identifiers<T>
+where
+    T: PartialEq,
+{
+    fn id(&mut self, handle: T) -> u64 {
+        (match self.handles.iter().position(|other| *other == handle) {
+            Some(index) => index,
+            None => {
+                self.handles.push(handle);
+                self.handles.len() - 1
+            }
+        }) as u64
+    }
+}
+
+impl<T> Default for Identifiers<T>
+where
+    T: PartialEq,
+{
+    fn default() -> Self {
+        Identifiers { handles: vec![] }
+    }
+}
+
+/// Encodes events into a recording.
+pub struct Recorder<P>
+where
+    P: PlatformBinding,
+{
+    start: Option<Instant>,
+    devices: Identifiers<platform::DeviceHandle<P>>,
+    displays: Identifiers<platform::DisplayHandle<P>>,
+    windows: Identifiers<platform::WindowHandle<P>>,
+}
+
+impl<P> Recorder<P>
+where
+    P: PlatformBinding,
+{
+    /// Writes an event to a recording as a single line.
+    ///
+    /// The first event written establishes the beginning of the recording.
+    /// Platform events are opaque and are not written.
+    pub fn write<W>(&mut self, writer: &mut W, event: &Event<P>) -> io::Result<()>
+    where
+        W: Write,
+    {
+        if let Event::Platform { .. } = *event {
+            return Ok(());
+        }
+        writeln!(writer, "{}", self.encode(event))
+    }
+
+    fn encode(&mut self, event: &Event<P>) -> String {
+        let start = *self.start.get_or_insert_with(|| event.timestamp());
+        let offset = |instant: Instant| -> i128 {
+            if instant >= start {
+                (instant - start).as_micros() as i128
+            }
+            else {
+                -((start - instant).as_micros() as i128)
+            }
+        };
+        let mut line = format!("{}", offset(event.timestamp()));
+        // Writing to a `String` cannot fail.
+        let _ = match *event {
+            Event::Application { event, .. } => match event {
+                ApplicationEvent::Resumed(Resumption::Poll) => write!(line, " A resumed poll"),
+                ApplicationEvent::Resumed(Resumption::Timeout(instant)) => {
+                    write!(line, " A resumed timeout {}", offset(instant))
+                }
+                ApplicationEvent::Resumed(Resumption::Interrupt(instant)) => {
+                    write!(line, " A resumed interrupt {}", offset(instant))
+                }
+                ApplicationEvent::Flushed => write!(line, " A flushed"),
+                ApplicationEvent::Suspended => write!(line, " A suspended"),
+                ApplicationEvent::Awakened => write!(line, " A awakened"),
+                ApplicationEvent::LowMemory => write!(line, " A low-memory"),
+                ApplicationEvent::Terminating => write!(line, " A terminating"),
+                ApplicationEvent::AbortRequested => write!(line, " A abort-requested"),
+                ApplicationEvent::KeyboardLayoutChanged(layout) => {
+                    write!(
+                        line,
+                        " A keyboard-layout-changed {}",
+                        layout.into_raw_handle()
+                    )
+                }
+                ApplicationEvent::QueueOverflowed { discarded } => {
+                    write!(line, " A queue-overflowed {}", discarded)
+                }
+                ApplicationEvent::Idle { deadline } => {
+                    write!(line, " A idle {}", offset(deadline))
+                }
+                ApplicationEvent::HotkeyPressed(id) => {
+                    write!(line, " A hotkey-pressed {}", id.into_raw_handle())
+                }
+                ApplicationEvent::Timer(TimerId(id)) => write!(line, " A timer {}", id),
+                ApplicationEvent::InstanceLaunched => write!(line, " A instance-launched"),
+            },
+            Event::Display { display, event, .. } => write!(
+                line,
+                " D {} {}",
+                self.displays.id(display.into_raw_handle()),
+                match event {
+                    DisplayEvent::Connected => "connected",
+                    DisplayEvent::Disconnected => "disconnected",
+                    DisplayEvent::ModeChanged => "mode-changed",
+                },
+            ),
+            Event::Input {
+                device,
+                window,
+                event,
+                focus,
+                ..
+            } => {
+                let _ = write!(line, " I {}", self.devices.id(device.into_raw_handle()));
+                let _ = match window {
+                    Some(window) => {
+                        write!(line, " {}", self.windows.id(window.into_raw_handle()))
+                    }
+                    None => write!(line, " -"),
+                };
+                let _ = match focus {
+                    InputFocus::Foreground => write!(line, " foreground"),
+                    InputFocus::Background => write!(line, " background"),
+                };
+                encode_input_event(&mut line, event)
+            }
+            Event::Window { window, event, .. } => {
+                let _ = write!(line, " W {}", self.windows.id(window.into_raw_handle()));
+                match event {
+                    WindowEvent::Closed(WindowCloseState::Requested) => {
+                        write!(line, " closed requested")
+                    }
+                    WindowEvent::Closed(WindowCloseState::Committed) => {
+                        write!(line, " closed committed")
+                    }
+                    WindowEvent::Activated => write!(line, " activated"),
+                    WindowEvent::Deactivated => write!(line, " deactivated"),
+                    WindowEvent::Focused(is_focused) => write!(line, " focused {}", is_focused),
+                    WindowEvent::Moved { position, dpi } => {
+                        write!(line, " moved {} {} {}", *position.x, *position.y, dpi)
+                    }
+                    WindowEvent::Resized { dimensions, dpi } => write!(
+                        line,
+                        " resized {} {} {}",
+                        *dimensions.width, *dimensions.height, dpi
+                    ),
+                    WindowEvent::ResizeStarted => write!(line, " resize-started"),
+                    WindowEvent::ResizeEnded => write!(line, " resize-ended"),
+                    WindowEvent::MouseEntered => write!(line, " mouse-entered"),
+                    WindowEvent::MouseLeft => write!(line, " mouse-left"),
+                    WindowEvent::Disabled => write!(line, " disabled"),
+                    WindowEvent::Enabled => write!(line, " enabled"),
+                    WindowEvent::FrameReady => write!(line, " frame-ready"),
+                    WindowEvent::StateChanged(state) => write!(
+                        line,
+                        " state-changed {}",
+                        match state {
+                            WindowState::Normal => "normal",
+                            WindowState::Minimized => "minimized",
+                            WindowState::Maximized => "maximized",
+                            WindowState::Fullscreen => "fullscreen",
+                        }
+                    ),
+                    // Characters are written as code points so that whitespace
+                    // is not ambiguous.
+                    WindowEvent::ReceivedCharacter(character) => {
+                        write!(line, " received-character {}", u32::from(character))
+                    }
+                }
+            }
+            // Platform events are not written. See `write`.
+            Event::Platform { .. } => Ok(()),
+        };
+        line
+    }
+}
+
+impl<P> Default for Recorder<P>
+where
+    P: PlatformBinding,
+{
+    fn default() -> Self {
+        Recorder {
+            start: None,
+            devices: Default::default(),
+            displays: Default::default(),
+            windows: Default::default(),
+        }
+    }
+}
+
+fn encode_input_event(line: &mut String, event: InputEvent) -> std::fmt::Result {
+    let state = |state: ElementState| match state {
+        ElementState::Pressed => "pressed",
+        ElementState::Released => "released",
+    };
+    match event {
+        InputEvent::Connected { usage } => write!(
+            line,
+            " connected {}",
+            match usage {
+                Some(Usage::Keyboard) => "keyboard",
+                Some(Usage::Mouse) => "mouse",
+                Some(Usage::GameController) => "game-controller",
+                None => "-",
+            }
+        ),
+        InputEvent::Disconnected => write!(line, " disconnected"),
+        InputEvent::GameControllerButtonChanged { button, state: s } => {
+            write!(line, " controller-button {} {}", button, state(s))
+        }
+        InputEvent::GameControllerAxisChanged { axis, value } => {
+            write!(line, " controller-axis {} {}", axis, value)
+        }
+        InputEvent::KeyboardKeyChanged {
+            scancode,
+            keycode,
+            state: s,
+            modifier,
+            repeat,
+        } => {
+            let toggles = [
+                ("caps", modifier.toggles.caps_lock),
+                ("num", modifier.toggles.num_lock),
+                ("scroll", modifier.toggles.scroll_lock),
+            ]
+            .iter()
+            .filter(|(_, is_on)| *is_on)
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join("+");
+            write!(
+                line,
+                " key {} {} {} {} {}",
+                scancode,
+                keycode.as_ref().map_or("-", KeyCode::name),
+                state(s),
+                if repeat { "repeat" } else { "-" },
+                if toggles.is_empty() { "-" } else { &toggles },
+            )
+        }
+        InputEvent::MouseButtonChanged {
+            button, state: s, ..
+        } => match button {
+            MouseButton::Left => write!(line, " mouse-button left {}", state(s)),
+            MouseButton::Right => write!(line, " mouse-button right {}", state(s)),
+            MouseButton::Center => write!(line, " mouse-button center {}", state(s)),
+            MouseButton::Other(button) => {
+                write!(line, " mouse-button {} {}", button, state(s))
+            }
+        },
+        InputEvent::MouseWheelRotated { delta, .. } => match delta {
+            MouseWheelDelta::Rotational(x, y) => {
+                write!(line, " mouse-wheel rotational {} {}", x, y)
+            }
+            MouseWheelDelta::Positional(x, y) => {
+                write!(line, " mouse-wheel positional {} {}", *x, *y)
+            }
+        },
+        InputEvent::MouseMoved { movement, .. } => {
+            let _ = write!(line, " mouse-move");
+            let _ = match movement.absolute {
+                Some(position) => write!(line, " {} {}", *position.x, *position.y),
+                None => write!(line, " - -"),
+            };
+            match movement.relative {
+                Some(motion) => write!(line, " {} {}", *motion.x, *motion.y),
+                None => write!(line, " - -"),
+            }
+        }
+        InputEvent::MotionSensor {
+            sensor,
+            values: [x, y, z],
+        } => write!(
+            line,
+            " motion-sensor {} {} {} {}",
+            match sensor {
+                SensorType::Accelerometer => "accelerometer",
+                SensorType::Gyroscope => "gyroscope",
+            },
+            x,
+            y,
+            z
+        ),
+        InputEvent::PenMoved { position, info } => write!(
+            line,
+            " pen-move {} {} {} {} {} {} {} {} {}",
+            *position.x,
+            *position.y,
+            info.pressure,
+            info.raw_pressure,
+            info.tilt.0,
+            info.tilt.1,
+            info.barrel_buttons,
+            info.eraser,
+            info.hover,
+        ),
+        InputEvent::PenProximityChanged { in_range } => {
+            write!(line, " pen-proximity {}", in_range)
+        }
+    }
+}
+
+/// Reads a recording.
+///
+/// Handle identifiers in the recording are converted into raw handles of the
+/// platform, so this is typically used with a platform that does not
+/// interpret its handles, such as the empty platform.
+pub fn read<P, R>(reader: R) -> io::Result<Vec<Record<P>>>
+where
+    P: PlatformBinding,
+    R: BufRead,
+    platform::DeviceHandle<P>: From<u64>,
+    platform::DisplayHandle<P>: From<u64>,
+    platform::WindowHandle<P>: From<u64>,
+{
+    let start = Instant::now();
+    let mut records = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        records.push(decode(&line, start).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed event record: {}", line),
+            )
+        })?);
+    }
+    Ok(records)
+}
+
+fn decode<P>(line: &str, start: Instant) -> Option<Record<P>>
+where
+    P: PlatformBinding,
+    platform::DeviceHandle<P>: From<u64>,
+    platform::DisplayHandle<P>: From<u64>,
+    platform::WindowHandle<P>: From<u64>,
+{
+    let mut tokens = line.split_whitespace();
+    // Events may precede the first recorded event, because platforms take
+    // timestamps from different sources. Such events are played back at the
+    // beginning of the recording.
+    let offset: i128 = parse(&mut tokens)?;
+    let offset = Duration::from_micros(offset.max(0) as u64);
+    let instant = |offset: i128| {
+        if offset >= 0 {
+            start + Duration::from_micros(offset as u64)
+        }
+        else {
+            start
+                .checked_sub(Duration::from_micros((-offset) as u64))
+                .unwrap_or(start)
+        }
+    };
+    let timestamp = start + offset;
+    let event = match tokens.next()? {
+        "A" => Event::Application {
+            event: match tokens.next()? {
+                "resumed" => ApplicationEvent::Resumed(match tokens.next()? {
+                    "poll" => Resumption::Poll,
+                    "timeout" => Resumption::Timeout(instant(parse(&mut tokens)?)),
+                    "interrupt" => Resumption::Interrupt(instant(parse(&mut tokens)?)),
+                    _ => return None,
+                }),
+                "flushed" => ApplicationEvent::Flushed,
+                "suspended" => ApplicationEvent::Suspended,
+                "awakened" => ApplicationEvent::Awakened,
+                "low-memory" => ApplicationEvent::LowMemory,
+                "terminating" => ApplicationEvent::Terminating,
+                "abort-requested" => ApplicationEvent::AbortRequested,
+                "keyboard-layout-changed" => ApplicationEvent::KeyboardLayoutChanged(
+                    KeyLayoutId::from_raw_handle(parse(&mut tokens)?),
+                ),
+                "queue-overflowed" => ApplicationEvent::QueueOverflowed {
+                    discarded: parse(&mut tokens)?,
+                },
+                "idle" => ApplicationEvent::Idle {
+                    deadline: instant(parse(&mut tokens)?),
+                },
+                "hotkey-pressed" => {
+                    ApplicationEvent::HotkeyPressed(HotkeyId::from_raw_handle(parse(&mut tokens)?))
+                }
+                "timer" => ApplicationEvent::Timer(TimerId(parse(&mut tokens)?)),
+                "instance-launched" => ApplicationEvent::InstanceLaunched,
+                _ => return None,
+            },
+            timestamp,
+        },
+        "D" => Event::Display {
+            display: DisplayHandle::from_raw_handle(parse::<u64>(&mut tokens)?.into()),
+            event: match tokens.next()? {
+                "connected" => DisplayEvent::Connected,
+                "disconnected" => DisplayEvent::Disconnected,
+                "mode-changed" => DisplayEvent::ModeChanged,
+                _ => return None,
+            },
+            timestamp,
+        },
+        "I" => Event::Input {
+            device: DeviceHandle::from_raw_handle(parse::<u64>(&mut tokens)?.into()),
+            window: match tokens.next()? {
+                "-" => None,
+                window => Some(WindowHandle::from_raw_handle(
+                    window.parse::<u64>().ok()?.into(),
+                )),
+            },
+            focus: match tokens.next()? {
+                "foreground" => InputFocus::Foreground,
+                "background" => InputFocus::Background,
+                _ => return None,
+            },
+            event: decode_input_event(&mut tokens)?,
+            timestamp,
+        },
+        "W" => Event::Window {
+            window: WindowHandle::from_raw_handle(parse::<u64>(&mut tokens)?.into()),
+            event: match tokens.next()? {
+                "closed" => WindowEvent::Closed(match tokens.next()? {
+                    "requested" => WindowCloseState::Requested,
+                    "committed" => WindowCloseState::Committed,
+                    _ => return None,
+                }),
+                "activated" => WindowEvent::Activated,
+                "deactivated" => WindowEvent::Deactivated,
+                "focused" => WindowEvent::Focused(tokens.next()?.parse().ok()?),
+                "moved" => WindowEvent::Moved {
+                    position: (parse::<f64>(&mut tokens)?, parse::<f64>(&mut tokens)?).into(),
+                    dpi: parse(&mut tokens)?,
+                },
+                "resized" => WindowEvent::Resized {
+                    dimensions: (parse::<f64>(&mut tokens)?, parse::<f64>(&mut tokens)?).into(),
+                    dpi: parse(&mut tokens)?,
+                },
+                "resize-started" => WindowEvent::ResizeStarted,
+                "resize-ended" => WindowEvent::ResizeEnded,
+                "mouse-entered" => WindowEvent::MouseEntered,
+                "mouse-left" => WindowEvent::MouseLeft,
+                "disabled" => WindowEvent::Disabled,
+                "enabled" => WindowEvent::Enabled,
+                "frame-ready" => WindowEvent::FrameReady,
+                "state-changed" => WindowEvent::StateChanged(match tokens.next()? {
+                    "normal" => WindowState::Normal,
+                    "minimized" => WindowState::Minimized,
+                    "maximized" => WindowState::Maximized,
+                    "fullscreen" => WindowState::Fullscreen,
+                    _ => return None,
+                }),
+                "received-character" => {
+                    WindowEvent::ReceivedCharacter(char::from_u32(parse(&mut tokens)?)?)
+                }
+                _ => return None,
+            },
+            timestamp,
+        },
+        _ => return None,
+    };
+    Some((offset, event))
+}
+
+fn decode_input_event(tokens: &mut SplitWhitespace) -> Option<InputEvent> {
+    fn state(tokens: &mut SplitWhitespace) -> Option<ElementState> {
+        match tokens.next()? {
+            "pressed" => Some(ElementState::Pressed),
+            "released" => Some(ElementState::Released),
+            _ => None,
+        }
+    }
+
+    fn pair<T>(tokens: &mut SplitWhitespace) -> Option<Option<(T, T)>>
+    where
+        T: From<f64>,
+    {
+        match (tokens.next()?, tokens.next()?) {
+            ("-", "-") => Some(None),
+            (x, y) => Some(Some((
+                x.parse::<f64>().ok()?.into(),
+                y.parse::<f64>().ok()?.into(),
+            ))),
+        }
+    }
+
+    let modifier = ModifierState::default();
+    Some(match tokens.next()? {
+        "connected" => InputEvent::Connected {
+            usage: match tokens.next()? {
+                "keyboard" => Some(Usage::Keyboard),
+                "mouse" => Some(Usage::Mouse),
+                "game-controller" => Some(Usage::GameController),
+                "-" => None,
+                _ => return None,
+            },
+        },
+        "disconnected" => InputEvent::Disconnected,
+        "controller-button" => InputEvent::GameControllerButtonChanged {
+            button: parse(tokens)?,
+            state: state(tokens)?,
+        },
+        "controller-axis" => InputEvent::GameControllerAxisChanged {
+            axis: parse(tokens)?,
+            value: parse(tokens)?,
+        },
+        "key" => InputEvent::KeyboardKeyChanged {
+            scancode: parse(tokens)?,
+            keycode: match tokens.next()? {
+                "-" => None,
+                keycode => Some(KeyCode::from_name(keycode)?),
+            },
+            state: state(tokens)?,
+            repeat: match tokens.next()? {
+                "repeat" => true,
+                "-" => false,
+                _ => return None,
+            },
+            modifier: {
+                let mut toggles = ToggleState::default();
+                match tokens.next()? {
+                    "-" => {}
+                    names => {
+                        for name in names.split('+') {
+                            match name {
+                                "caps" => toggles.caps_lock = true,
+                                "num" => toggles.num_lock = true,
+                                "scroll" => toggles.scroll_lock = true,
+                                _ => return None,
+                            }
+                        }
+                    }
+                }
+                ModifierState { toggles }
+            },
+        },
+        "mouse-button" => InputEvent::MouseButtonChanged {
+            button: match tokens.next()? {
+                "left" => MouseButton::Left,
+                "right" => MouseButton::Right,
+                "center" => MouseButton::Center,
+                button => MouseButton::Other(button.parse().ok()?),
+            },
+            state: state(tokens)?,
+            modifier,
+        },
+        "mouse-wheel" => InputEvent::MouseWheelRotated {
+            delta: match tokens.next()? {
+                "rotational" => MouseWheelDelta::Rotational(parse(tokens)?, parse(tokens)?),
+                "positional" => {
+                    let (x, y) = pair(tokens)??;
+                    MouseWheelDelta::Positional(x, y)
+                }
+                _ => return None,
+            },
+            modifier,
+        },
+        "mouse-move" => InputEvent::MouseMoved {
+            movement: MouseMovement {
+                absolute: pair::<LogicalUnit>(tokens)?.map(From::from),
+                relative: pair::<PhysicalUnit>(tokens)?.map(From::from),
+            },
+            modifier,
+        },
+        "motion-sensor" => InputEvent::MotionSensor {
+            sensor: match tokens.next()? {
+                "accelerometer" => SensorType::Accelerometer,
+                "gyroscope" => SensorType::Gyroscope,
+                _ => return None,
+            },
+            values: [parse(tokens)?, parse(tokens)?, parse(tokens)?],
+        },
+        "pen-move" => InputEvent::PenMoved {
+            position: WindowPosition::new(parse::<f64>(tokens)?, parse::<f64>(tokens)?),
+            info: PenInfo {
+                pressure: parse(tokens)?,
+                raw_pressure: parse(tokens)?,
+                tilt: (parse(tokens)?, parse(tokens)?),
+                barrel_buttons: parse(tokens)?,
+                eraser: parse(tokens)?,
+                hover: parse(tokens)?,
+            },
+        },
+        "pen-proximity" => InputEvent::PenProximityChanged {
+            in_range: parse(tokens)?,
+        },
+        _ => return None,
+    })
+}
+
+fn parse<T>(tokens: &mut SplitWhitespace) -> Option<T>
+where
+    T: FromStr,
+{
+    tokens.next()?.parse().ok()
+}
+
+/// A reactor that records events.
+///
+/// Writes each event it receives to a writer and then forwards the event to
+/// another reactor. If writing fails, then the event loop is aborted.
+pub struct EventRecorder<P, R, W>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+    W: Write,
+{
+    reactor: R,
+    writer: W,
+    recorder: Recorder<P>,
+}
+
+impl<P, R, W> EventRecorder<P, R, W>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+    W: Write,
+{
+    pub fn new(reactor: R, writer: W) -> Self {
+        EventRecorder {
+            reactor,
+            writer,
+            recorder: Default::default(),
+        }
+    }
+}
+
+impl<P, R, W> Reactor<P> for EventRecorder<P, R, W>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+    W: Write,
+{
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        match self.recorder.write(&mut self.writer, &event) {
+            Ok(_) => self.reactor.react(context, event),
+            Err(_) => Reaction::Abort,
+        }
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        for event in events.iter() {
+            if self.recorder.write(&mut self.writer, event).is_err() {
+                return Reaction::Abort;
+            }
+        }
+        self.reactor.react_all(context, events)
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        let EventRecorder {
+            reactor,
+            mut writer,
+            ..
+        } = self;
+        let _ = writer.flush();
+        reactor.abort();
+    }
+}
+
+/// A reactor that plays recorded events.
+///
+/// Events dispatched by the event thread are ignored, except that
+/// `ApplicationEvent`s are used to drive playback. Recorded events are
+/// dispatched to another reactor instead. When all recorded events have been
+/// dispatched, the event loop is aborted.
+pub struct EventPlayer<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    records: VecDeque<Record<P>>,
+    timing: Timing,
+    start: Option<Instant>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> EventPlayer<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new<I>(reactor: R, records: I, timing: Timing) -> Self
+    where
+        I: IntoIterator<Item = Record<P>>,
+    {
+        EventPlayer {
+            reactor,
+            records: records.into_iter().collect(),
+            timing,
+            start: None,
+            phantom: PhantomData,
+        }
+    }
+
+    fn start(&mut self) -> Instant {
+        *self.start.get_or_insert_with(Instant::now)
+    }
+}
+
+impl<P, R> Reactor<P> for EventPlayer<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        if let Event::Application { .. } = event {
+            let start = self.start();
+            let now = Instant::now();
+            while let Some((offset, _)) = self.records.front() {
+                if self.timing == Timing::Relative && start + *offset > now {
+                    break;
+                }
+                let (offset, event) = self.records.pop_front().unwrap();
+                // Replace the recorded timestamp with the time of playback.
+                let event = match event {
+                    Event::Application { event, .. } => Event::Application {
+                        event,
+                        timestamp: start + offset,
+                    },
+                    Event::Display { display, event, .. } => Event::Display {
+                        display,
+                        event,
+                        timestamp: start + offset,
+                    },
+                    Event::Input {
+                        device,
+                        window,
+                        event,
+                        focus,
+                        ..
+                    } => Event::Input {
+                        device,
+                        window,
+                        event,
+                        focus,
+                        timestamp: start + offset,
+                    },
+                    Event::Window { window, event, .. } => Event::Window {
+                        window,
+                        event,
+                        timestamp: start + offset,
+                    },
+                    Event::Platform { data, .. } => Event::Platform {
+                        data,
+                        timestamp: start + offset,
+                    },
+                };
+                if let Reaction::Abort = self.reactor.react(context, event) {
+                    return Reaction::Abort;
+                }
+            }
+        }
+        Reaction::Continue(())
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        let start = self.start();
+        let playback = match self.records.front() {
+            Some((offset, _)) => match self.timing {
+                Timing::Relative => Poll::WaitUntil(start + *offset),
+                Timing::Immediate => Poll::Ready,
+            },
+            None => return Reaction::Abort,
+        };
+        // Resume for whichever of playback or the reactor is more urgent.
+        self.reactor
+            .poll(context)
+            .map(|poll| match (playback, poll) {
+                (Poll::Ready, _) | (_, Poll::Ready) => Poll::Ready,
+                (Poll::ReadyThrottled(duration), Poll::ReadyThrottled(other)) => {
+                    Poll::ReadyThrottled(duration.min(other))
+                }
+                (Poll::ReadyThrottled(duration), _) | (_, Poll::ReadyThrottled(duration)) => {
+                    Poll::ReadyThrottled(duration)
+                }
+                (Poll::WaitUntil(instant), Poll::WaitUntil(other)) => {
+                    Poll::WaitUntil(instant.min(other))
+                }
+                (Poll::WaitUntil(instant), Poll::Wait) | (Poll::Wait, Poll::WaitUntil(instant)) => {
+                    Poll::WaitUntil(instant)
+                }
+                (Poll::Wait, Poll::Wait) => Poll::Wait,
+            })
+    }
+
+    fn abort(self) {
+        self.reactor.abort();
+    }
+}
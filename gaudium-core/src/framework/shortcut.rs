@@ -0,0 +1,444 @@
+//! Keyboard shortcuts.
+//!
+//! A `ShortcutMap` binds keyboard shortcuts to user-defined commands and
+//! reacts to keyboard events to determine when shortcuts are entered. A
+//! `Shortcut` is a sequence of one or more `Chord`s, each of which is a key
+//! pressed while holding modifiers, such as `Ctrl+Shift+P` or `Ctrl+K,
+//! Ctrl+C`.
+//!
+//! Chords match key codes rather than scan codes, so shortcuts respect the
+//! active keyboard layout. For example, `Ctrl+Z` matches the key that is
+//! labeled Z on both QWERTY and AZERTY layouts.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::event::Event;
+//! use gaudium_core::framework::shortcut::ShortcutMap;
+//! use gaudium_core::framework::React;
+//! use gaudium_platform_empty::Binding;
+//!
+//! # fn main() {
+//! #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+//! enum Command {
+//!     ShowPalette,
+//!     Comment,
+//! }
+//!
+//! let mut shortcuts = ShortcutMap::new();
+//! shortcuts
+//!     .bind("Ctrl+Shift+P".parse().unwrap(), Command::ShowPalette)
+//!     .unwrap();
+//! shortcuts
+//!     .bind("Ctrl+K, Ctrl+C".parse().unwrap(), Command::Comment)
+//!     .unwrap();
+//!
+//! fn update(shortcuts: &mut ShortcutMap<Command>, events: &[Event<Binding>]) {
+//!     for event in events {
+//!         shortcuts.react(event);
+//!     }
+//!     for command in shortcuts.drain() {
+//!         println!("{:?}", command);
+//!     }
+//! }
+//! # update(&mut shortcuts, &[]);
+//! # }
+//! ```
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorKind};
+use crate::event::{ElementState, Event, InputEvent, KeyCode, WindowEvent};
+use crate::framework::React;
+use crate::platform::PlatformBinding;
+use crate::shell::hotkey::Modifiers;
+
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+fn is_modifier(keycode: KeyCode) -> bool {
+    matches!(
+        keycode,
+        KeyCode::LeftShift
+            | KeyCode::RightShift
+            | KeyCode::LeftControl
+            | KeyCode::RightControl
+            | KeyCode::LeftAlt
+            | KeyCode::RightAlt
+            | KeyCode::LeftSuper
+            | KeyCode::RightSuper
+    )
+}
+
+/// A key pressed while holding modifiers.
+///
+/// Chords are parsed from and displayed as modifiers and a key separated by
+/// `+`, such as `Ctrl+Shift+P`. Modifiers are `Ctrl`, `Alt`, `Shift`, and
+/// `Super` (or `Win`, `Cmd`, or `Meta`) and are case-insensitive. Keys are the
+/// names of `KeyCode`s, except that digits may be written as `0` through `9`.
+/// A chord matches only if exactly its modifiers are held.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Chord {
+    pub modifiers: Modifiers,
+    pub keycode: KeyCode,
+}
+
+impl Display for Chord {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for &(name, is_held) in &[
+            ("Ctrl", self.modifiers.control),
+            ("Alt", self.modifiers.alt),
+            ("Shift", self.modifiers.shift),
+            ("Super", self.modifiers.super_key),
+        ] {
+            if is_held {
+                write!(formatter, "{}+", name)?;
+            }
+        }
+        match DIGITS.iter().position(|&digit| digit == self.keycode) {
+            Some(digit) => write!(formatter, "{}", digit),
+            None => formatter.write_str(self.keycode.name()),
+        }
+    }
+}
+
+impl FromStr for Chord {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |context| Error::new(ErrorKind::InvalidInput, context);
+        let mut tokens = text.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = tokens.pop().filter(|key| !key.is_empty());
+        let key = key.ok_or_else(|| error("missing shortcut key"))?;
+        let mut modifiers = Modifiers::default();
+        for token in tokens {
+            let modifier = match token.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut modifiers.control,
+                "alt" => &mut modifiers.alt,
+                "shift" => &mut modifiers.shift,
+                "super" | "win" | "cmd" | "meta" => &mut modifiers.super_key,
+                _ => return Err(error("unknown shortcut modifier")),
+            };
+            *modifier = true;
+        }
+        let keycode = key
+            .parse::<usize>()
+            .ok()
+            .and_then(|digit| DIGITS.get(digit).cloned())
+            .or_else(|| {
+                KeyCode::ALL
+                    .iter()
+                    .cloned()
+                    .find(|keycode| keycode.name().eq_ignore_ascii_case(key))
+            })
+            .ok_or_else(|| error("unknown shortcut key"))?;
+        if is_modifier(keycode) {
+            return Err(error("shortcut key cannot be a modifier"));
+        }
+        Ok(Chord { modifiers, keycode })
+    }
+}
+
+/// A sequence of chords.
+///
+/// Shortcuts are parsed from and displayed as chords separated by `,`, such
+/// as `Ctrl+K, Ctrl+C`. See `Chord`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Shortcut {
+    chords: Vec<Chord>,
+}
+
+impl Shortcut {
+    /// Creates a shortcut from a non-empty sequence of chords.
+    pub fn new<I>(chords: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Chord>,
+    {
+        let chords: Vec<_> = chords.into_iter().collect();
+        if chords.is_empty() {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "shortcut has no chords",
+            ))
+        }
+        else {
+            Ok(Shortcut { chords })
+        }
+    }
+
+    pub fn chords(&self) -> &[Chord] {
+        &self.chords
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for (index, chord) in self.chords.iter().enumerate() {
+            if index > 0 {
+                formatter.write_str(", ")?;
+            }
+            write!(formatter, "{}", chord)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Chord> for Shortcut {
+    fn from(chord: Chord) -> Self {
+        Shortcut {
+            chords: vec![chord],
+        }
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Shortcut::new(
+            text.split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    }
+}
+
+/// Maps keyboard shortcuts to user-defined commands.
+///
+/// `ShortcutMap` reacts to keyboard events and queues the command of each
+/// shortcut that is entered, which can be taken via `drain`. While the chords
+/// entered so far are a prefix of a multi-chord shortcut, the map waits for
+/// the next chord. If the next chord does not continue any shortcut, then the
+/// sequence is abandoned and the chord is matched on its own. Sequences are
+/// also abandoned when a window loses focus.
+pub struct ShortcutMap<C>
+where
+    C: Clone,
+{
+    bindings: Vec<(Shortcut, C)>,
+    held: HashSet<KeyCode>,
+    pending: Vec<Chord>,
+    commands: VecDeque<C>,
+}
+
+impl<C> ShortcutMap<C>
+where
+    C: Clone,
+{
+    pub fn new() -> Self {
+        ShortcutMap::default()
+    }
+
+    /// Gets all bindings.
+    pub fn bindings(&self) -> &[(Shortcut, C)] {
+        &self.bindings
+    }
+
+    /// Binds a shortcut to a command.
+    ///
+    /// Fails if the shortcut conflicts with a bound shortcut, which occurs if
+    /// either shortcut is the same as or a prefix of the other, because such
+    /// shortcuts cannot be distinguished when entered. For example, `Ctrl+K`
+    /// conflicts with `Ctrl+K, Ctrl+C`.
+    pub fn bind(&mut self, shortcut: Shortcut, command: C) -> Result<(), Error> {
+        if self.bindings.iter().any(|(other, _)| {
+            other.chords.starts_with(&shortcut.chords) || shortcut.chords.starts_with(&other.chords)
+        }) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "shortcut conflicts with a bound shortcut",
+            ));
+        }
+        self.bindings.push((shortcut, command));
+        self.pending.clear();
+        Ok(())
+    }
+
+    /// Removes the binding of a shortcut, if any.
+    pub fn unbind(&mut self, shortcut: &Shortcut) {
+        self.bindings.retain(|(other, _)| other != shortcut);
+        self.pending.clear();
+    }
+
+    /// Gets the chords of a multi-chord shortcut that have been entered so
+    /// far, such as to display them in a status bar.
+    pub fn pending(&self) -> &[Chord] {
+        &self.pending
+    }
+
+    /// Takes the commands of the shortcuts that have been entered in the order
+    /// in which they were entered.
+    pub fn drain(&mut self) -> impl '_ + Iterator<Item = C> {
+        self.commands.drain(..)
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        let is_held = |keys: &[KeyCode]| keys.iter().any(|key| self.held.contains(key));
+        Modifiers {
+            alt: is_held(&[KeyCode::LeftAlt, KeyCode::RightAlt]),
+            control: is_held(&[KeyCode::LeftControl, KeyCode::RightControl]),
+            shift: is_held(&[KeyCode::LeftShift, KeyCode::RightShift]),
+            super_key: is_held(&[KeyCode::LeftSuper, KeyCode::RightSuper]),
+        }
+    }
+
+    fn enter(&mut self, chord: Chord) {
+        let mut sequence = vec![];
+        sequence.append(&mut self.pending);
+        sequence.push(chord);
+        // Abandon the pending sequence if the chord does not continue it.
+        if sequence.len() > 1 && !self.is_prefix(&sequence) {
+            sequence = vec![chord];
+        }
+        if let Some(command) = self
+            .bindings
+            .iter()
+            .find(|(shortcut, _)| shortcut.chords == sequence)
+            .map(|(_, command)| command.clone())
+        {
+            self.commands.push_back(command);
+        }
+        else if self.is_prefix(&sequence) {
+            self.pending = sequence;
+        }
+    }
+
+    fn is_prefix(&self, sequence: &[Chord]) -> bool {
+        self.bindings
+            .iter()
+            .any(|(shortcut, _)| shortcut.chords.starts_with(sequence))
+    }
+}
+
+impl<C> Default for ShortcutMap<C>
+where
+    C: Clone,
+{
+    fn default() -> Self {
+        ShortcutMap {
+            bindings: vec![],
+            held: HashSet::new(),
+            pending: vec![],
+            commands: VecDeque::new(),
+        }
+    }
+}
+
+impl<P, C> React<P> for ShortcutMap<C>
+where
+    P: PlatformBinding,
+    C: Clone,
+{
+    fn react(&mut self, event: &Event<P>) {
+        match *event {
+            Event::Input {
+                event:
+                    InputEvent::KeyboardKeyChanged {
+                        keycode: Some(keycode),
+                        state,
+                        repeat,
+                        ..
+                    },
+                ..
+            } => match state {
+                ElementState::Pressed => {
+                    if is_modifier(keycode) {
+                        self.held.insert(keycode);
+                    }
+                    // Auto-repeat does not enter chords.
+                    else if !repeat {
+                        let modifiers = self.modifiers();
+                        self.enter(Chord { modifiers, keycode });
+                    }
+                }
+                ElementState::Released => {
+                    self.held.remove(&keycode);
+                }
+            },
+            // Keys may be released while no window is focused, so held
+            // modifiers are forgotten.
+            Event::Window {
+                event: WindowEvent::Focused(false),
+                ..
+            } => {
+                self.held.clear();
+                self.pending.clear();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::KeyCode;
+    use crate::framework::shortcut::{Chord, Shortcut, ShortcutMap};
+    use crate::shell::hotkey::Modifiers;
+
+    #[test]
+    fn parse_shortcut() {
+        let shortcut: Shortcut = "ctrl+shift+p, Alt+1".parse().unwrap();
+        assert_eq!(
+            &[
+                Chord {
+                    modifiers: Modifiers {
+                        control: true,
+                        shift: true,
+                        ..Default::default()
+                    },
+                    keycode: KeyCode::P,
+                },
+                Chord {
+                    modifiers: Modifiers {
+                        alt: true,
+                        ..Default::default()
+                    },
+                    keycode: KeyCode::Digit1,
+                },
+            ],
+            shortcut.chords()
+        );
+        assert_eq!("Ctrl+Shift+P, Alt+1", shortcut.to_string());
+        assert!("Ctrl+".parse::<Shortcut>().is_err());
+        assert!("Ctrl+Shift".parse::<Shortcut>().is_err());
+        assert!("Hyper+P".parse::<Shortcut>().is_err());
+    }
+
+    #[test]
+    fn match_chords() {
+        let chord = |text: &str| text.parse::<Chord>().unwrap();
+        let mut shortcuts = ShortcutMap::new();
+        shortcuts
+            .bind("Ctrl+K, Ctrl+C".parse().unwrap(), 0)
+            .unwrap();
+        shortcuts.bind("Ctrl+P".parse().unwrap(), 1).unwrap();
+        // Shortcuts that are prefixes of one another conflict.
+        assert!(shortcuts.bind("Ctrl+K".parse().unwrap(), 2).is_err());
+
+        shortcuts.enter(chord("Ctrl+K"));
+        assert_eq!(&[chord("Ctrl+K")], shortcuts.pending());
+        shortcuts.enter(chord("Ctrl+C"));
+        assert!(shortcuts.pending().is_empty());
+        // A chord that does not continue the pending sequence is matched on
+        // its own.
+        shortcuts.enter(chord("Ctrl+K"));
+        shortcuts.enter(chord("Ctrl+P"));
+        shortcuts.enter(chord("Ctrl+C"));
+        assert_eq!(vec![0, 1], shortcuts.drain().collect::<Vec<_>>());
+    }
+}
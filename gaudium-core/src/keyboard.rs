@@ -0,0 +1,108 @@
+//! Keyboard layouts.
+//!
+//! Keyboard events report both a `ScanCode`, which identifies a physical key,
+//! and a `KeyCode`, which identifies the meaning of that key in the active
+//! layout. `KeyLayout` translates scan codes using the active layout, such as
+//! when displaying key bindings. When the active layout changes, an
+//! `ApplicationEvent::KeyboardLayoutChanged` event is dispatched with the
+//! `KeyLayoutId` of the new layout.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::event::{Event, InputEvent};
+//! use gaudium_core::keyboard::KeyLayout;
+//! use gaudium_core::reactor::{Reaction, ThreadContext};
+//! use gaudium_platform_empty::Binding;
+//!
+//! fn react(context: &ThreadContext, event: Event<Binding>) -> Reaction {
+//!     if let Event::Input {
+//!         event: InputEvent::KeyboardKeyChanged { scancode, .. },
+//!         ..
+//!     } = event
+//!     {
+//!         let layout = KeyLayout::<Binding>::from_context(context);
+//!         println!("{:?}", layout.label(scancode));
+//!     }
+//!     Reaction::Continue(())
+//! }
+//! # fn main() {}
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::{KeyCode, ScanCode};
+use crate::platform::{self, PlatformBinding};
+use crate::reactor::ThreadContext;
+use crate::{FromRawHandle, IntoRawHandle};
+
+/// An opaque identifier of a keyboard layout.
+///
+/// Identifiers are assigned by the platform and are only meaningful within a
+/// process.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct KeyLayoutId(u64);
+
+impl FromRawHandle<u64> for KeyLayoutId {
+    fn from_raw_handle(handle: u64) -> Self {
+        KeyLayoutId(handle)
+    }
+}
+
+impl IntoRawHandle<u64> for KeyLayoutId {
+    fn into_raw_handle(self) -> u64 {
+        self.0
+    }
+}
+
+/// The active keyboard layout of an event thread.
+pub struct KeyLayout<P>
+where
+    P: PlatformBinding,
+{
+    inner: P::KeyLayout,
+}
+
+impl<P> KeyLayout<P>
+where
+    P: PlatformBinding,
+{
+    /// Gets the active keyboard layout of the event thread.
+    pub fn from_context(context: &ThreadContext) -> Self {
+        KeyLayout {
+            inner: <P::KeyLayout as platform::KeyLayout>::from_context(context),
+        }
+    }
+
+    /// Gets the identifier of the layout.
+    ///
+    /// This is the same identifier that is reported by
+    /// `ApplicationEvent::KeyboardLayoutChanged`.
+    pub fn id(&self) -> KeyLayoutId {
+        KeyLayoutId(platform::KeyLayout::id(&self.inner))
+    }
+
+    /// Gets the language of the layout as an IETF language tag, such as
+    /// "en-US" or "fr-CA".
+    pub fn language(&self) -> Option<String> {
+        platform::KeyLayout::language(&self.inner)
+    }
+
+    /// Translates a scan code into a key code using the layout.
+    pub fn keycode(&self, scancode: ScanCode) -> Option<KeyCode> {
+        platform::KeyLayout::keycode(&self.inner, scancode)
+    }
+
+    /// Gets a human-readable label for the key with the given scan code, such
+    /// as "W" or "Shift".
+    ///
+    /// Labels are localized by the platform and depend on the layout.
+    pub fn label(&self, scancode: ScanCode) -> Option<String> {
+        platform::KeyLayout::label(&self.inner, scancode)
+    }
+}
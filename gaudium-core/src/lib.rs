@@ -36,8 +36,8 @@
 //! use std::thread::{self, JoinHandle};
 //!
 //! use gaudium_core::prelude::*;
-//! use gaudium_core::reactor::{EventThread, FromContext, Reactor, ThreadContext};
-//! use gaudium_core::window::{Window, WindowBuilder, WindowHandle};
+//! use gaudium_core::reactor::{EventThread, FromContext, Reactor, Sinks, ThreadContext};
+//! use gaudium_core::window::{Window, WindowBuilder};
 //! use gaudium_platform_empty::{Binding, WindowBuilderExt};
 //!
 //! # fn main() {
@@ -48,7 +48,7 @@
 //! }
 //!
 //! impl FromContext<Binding> for TestReactor {
-//!     fn from_context(context: &ThreadContext) -> (WindowHandle<Binding>, Self) {
+//!     fn from_context(context: &ThreadContext) -> (Sinks<Binding>, Self) {
 //!         let window = WindowBuilder::<Binding>::default()
 //!             .with_title("Gaudium")
 //!             .build(context)
@@ -59,7 +59,7 @@
 //!                 println!("{:?}", event);
 //!             }
 //!         });
-//!         (window.handle(), TestReactor { window, tx, handle })
+//!         (window.handle().into(), TestReactor { window, tx, handle })
 //!     }
 //! }
 //!
@@ -87,18 +87,27 @@
 
 #![allow(unknown_lints)] // Allow clippy lints.
 
+pub mod accessibility;
 pub mod device;
 pub mod display;
+pub mod error;
 pub mod event;
 pub mod framework;
+pub mod keyboard;
 pub mod platform;
 pub mod reactor;
+pub mod shell;
 pub mod window;
 
+mod registry;
+#[cfg(feature = "serde")]
+mod serial;
+
 pub mod prelude {
     pub use crate::event::*;
     pub use crate::reactor::Poll;
     pub use crate::reactor::Poll::Ready;
+    pub use crate::reactor::Poll::ReadyThrottled;
     pub use crate::reactor::Poll::Wait;
     pub use crate::reactor::Poll::WaitUntil;
     pub use crate::reactor::Reaction;
@@ -121,8 +130,8 @@ mod tests {
 
     use crate::platform::PlatformBinding;
     use crate::prelude::*;
-    use crate::reactor::{FromContext, Reactor, ThreadContext};
-    use crate::window::{Window, WindowBuilder, WindowHandle};
+    use crate::reactor::{FromContext, Reactor, Sinks, ThreadContext};
+    use crate::window::{Window, WindowBuilder};
 
     // For sanity.
     #[test]
@@ -141,7 +150,7 @@ mod tests {
         where
             P: PlatformBinding,
         {
-            fn from_context(context: &ThreadContext) -> (WindowHandle<P>, Self) {
+            fn from_context(context: &ThreadContext) -> (Sinks<P>, Self) {
                 let window = WindowBuilder::<P>::default().build(context).expect("");
                 let (tx, rx) = mpsc::channel();
                 let handle = thread::spawn(move || {
@@ -149,7 +158,7 @@ mod tests {
                         println!("{:?}", event);
                     }
                 });
-                (window.handle(), TestReactor { window, tx, handle })
+                (window.handle().into(), TestReactor { window, tx, handle })
             }
         }
 
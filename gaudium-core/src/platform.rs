@@ -9,11 +9,30 @@
 //! functions provided by these traits are expected to be compatible with
 //! traits defined elsewhere.
 
+use std::any::Any;
+use std::ffi::OsString;
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::reactor::{Reactor, ThreadContext};
-use crate::window;
+use crate::accessibility::{AccessibleElement, Role};
+use crate::device::{Battery, DeviceInfo, Led, SensorType};
+use crate::display::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, VideoMode};
+use crate::error::Error;
+#[cfg(any(feature = "capture", feature = "test"))]
+use crate::error::ErrorKind;
+#[cfg(feature = "test")]
+use crate::event::InputEvent;
+use crate::event::{KeyCode, ScanCode, ToggleState, WheelScroll};
+use crate::reactor::{Reactor, Recurrence, Sinks, ThreadContext, TimerId};
+use crate::shell::dialog::{FileDialog, MessageDialog, MessageResponse};
+use crate::shell::hotkey::{HotkeyId, Modifiers};
+#[cfg(feature = "capture")]
+use crate::window::Image;
+use crate::window::{
+    CursorIcon, Fullscreen, Geometry, HitTest, Icon, MouseMode, WindowKind, ZOrder,
+};
 
 pub type Window<P> = <<P as PlatformBinding>::WindowBuilder as WindowBuilder>::Window;
 
@@ -25,15 +44,33 @@ pub type WindowHandle<P> = <Window<P> as Handle>::Handle;
 pub trait PlatformBinding: 'static + Copy + Clone + Debug + PartialEq + Sized {
     type EventThread: Abort<Self>;
     type WindowBuilder: WindowBuilder;
-    type Device: Device;
+    type Device: Device + Output;
     type Display: Display;
+    type Waker: Waker;
+    type KeyLayout: KeyLayout;
+    type Dialog: Dialog<Self>;
+    type Hotkey: Hotkey;
+    type Timer: Timer;
+    type SingleInstance: SingleInstance;
+    /// Data for platform messages that are not otherwise modeled by events.
+    type RawEvent: Copy + Clone + Debug + PartialEq + Send;
+
+    /// Gets the distance that content scrolls per detent of a mouse wheel.
+    fn wheel_scroll() -> WheelScroll {
+        WheelScroll::default()
+    }
+
+    /// Gets the state of the toggle keys of the keyboard.
+    fn toggles() -> ToggleState {
+        ToggleState::default()
+    }
 }
 
 pub trait Abort<P>
 where
     P: PlatformBinding,
 {
-    fn run_and_abort<R>(context: ThreadContext, sink: window::WindowHandle<P>, reactor: R) -> !
+    fn run_and_abort<R>(context: ThreadContext, sinks: Sinks<P>, reactor: R) -> !
     where
         R: Reactor<P>;
 }
@@ -42,31 +79,307 @@ pub trait Join<P>
 where
     P: PlatformBinding,
 {
-    fn run_and_join<R>(context: ThreadContext, sink: window::WindowHandle<P>, reactor: R)
+    fn run_and_join<R>(context: ThreadContext, sinks: Sinks<P>, reactor: R) -> Result<(), Error>
     where
         R: Reactor<P>;
 }
 
+/// Resumes an event thread from any thread.
+pub trait Waker: 'static + Send + Sync + Sized {
+    /// Gets a waker for the event thread executing on the calling thread.
+    fn from_context(context: &ThreadContext) -> Self;
+
+    /// Resumes the event loop if it is waiting for events.
+    fn wake(&self);
+}
+
+/// Translates keys using the active keyboard layout.
+pub trait KeyLayout: Sized {
+    fn from_context(context: &ThreadContext) -> Self;
+
+    /// Gets an identifier of the layout that is reported by
+    /// `ApplicationEvent::KeyboardLayoutChanged`.
+    fn id(&self) -> u64;
+
+    /// Gets the language of the layout as an IETF language tag.
+    fn language(&self) -> Option<String>;
+
+    fn keycode(&self, scancode: ScanCode) -> Option<KeyCode>;
+
+    fn label(&self, scancode: ScanCode) -> Option<String>;
+}
+
+/// Shows native modal dialogs.
+pub trait Dialog<P>
+where
+    P: PlatformBinding,
+{
+    fn show_message(
+        context: &ThreadContext,
+        dialog: &MessageDialog<P>,
+    ) -> Result<MessageResponse, Error>;
+
+    /// Shows a dialog for opening files.
+    ///
+    /// Returns no paths if the dialog is cancelled.
+    fn open_files(
+        context: &ThreadContext,
+        dialog: &FileDialog<P>,
+        multiple: bool,
+    ) -> Result<Vec<PathBuf>, Error>;
+
+    fn save_file(context: &ThreadContext, dialog: &FileDialog<P>)
+        -> Result<Option<PathBuf>, Error>;
+}
+
+/// Registers system-wide hotkeys for an event thread.
+///
+/// Hotkeys are registered and unregistered on the event thread. When a
+/// registered hotkey is pressed, the event thread dispatches
+/// `ApplicationEvent::HotkeyPressed` with its identifier.
+pub trait Hotkey {
+    fn register(
+        context: &ThreadContext,
+        id: HotkeyId,
+        modifiers: Modifiers,
+        keycode: KeyCode,
+    ) -> Result<(), Error>;
+
+    fn unregister(id: HotkeyId);
+}
+
+/// Sets timers for an event thread.
+///
+/// Timers are set and cancelled on the event thread. When a timer elapses,
+/// the event thread dispatches `ApplicationEvent::Timer` with its identifier.
+/// Timers must be cancelled when the event loop stops.
+pub trait Timer {
+    fn set(
+        context: &ThreadContext,
+        id: TimerId,
+        duration: Duration,
+        recurrence: Recurrence,
+    ) -> Result<(), Error>;
+
+    fn cancel(context: &ThreadContext, id: TimerId);
+}
+
+/// Owns a name shared by all instances of an application.
+///
+/// When another process forwards arguments to the owner, the event thread that
+/// acquired the name dispatches `ApplicationEvent::InstanceLaunched`.
+pub trait SingleInstance: Sized {
+    /// Acquires the name or forwards the arguments to its owner, in which case
+    /// `None` is returned.
+    fn acquire(
+        context: &ThreadContext,
+        name: &str,
+        arguments: Vec<OsString>,
+    ) -> Result<Option<Self>, Error>;
+
+    fn take_arguments(&mut self) -> Option<Vec<OsString>>;
+}
+
 pub trait WindowBuilder: Default + Sized {
-    type Window: Eq + Handle + Hash + Sized;
+    type Window: Accessible + Surface;
+
+    fn build(self, context: &ThreadContext) -> Result<Self::Window, Error>;
+
+    fn with_icon(self, icon: Icon) -> Self;
+
+    fn with_always_on_top(self, always_on_top: bool) -> Self;
+
+    fn with_active(self, active: bool) -> Self;
+
+    fn with_decorations(self, decorations: bool) -> Self;
+
+    fn with_transparent(self, transparent: bool) -> Self;
+
+    fn with_resizable(self, resizable: bool) -> Self;
+
+    fn with_min_dimensions(self, dimensions: LogicalSize) -> Self;
+
+    fn with_max_dimensions(self, dimensions: LogicalSize) -> Self;
 
-    fn build(self, context: &ThreadContext) -> Result<Self::Window, ()>;
+    fn with_aspect_ratio(self, ratio: f64) -> Self;
+
+    fn with_fullscreen(self, fullscreen: Fullscreen) -> Self;
+
+    fn with_opacity(self, opacity: f32) -> Self;
+
+    fn with_kind(self, kind: WindowKind) -> Self;
+
+    fn with_owner(self, owner: <Self::Window as Handle>::Handle) -> Self;
+
+    fn with_parent(self, parent: <Self::Window as Handle>::Handle) -> Self;
+
+    fn with_position(self, position: PhysicalPosition) -> Self;
+
+    /// Centers the window in the given area, which is typically the work area
+    /// of a display. See `display::center`.
+    fn with_centered_in(self, position: PhysicalPosition, area: PhysicalSize) -> Self;
+
+    /// Sets the outer geometry of the window. The geometry has already been
+    /// clamped to the connected displays.
+    fn with_geometry(self, geometry: Geometry) -> Self;
+}
+
+pub trait Surface: Eq + Handle + Hash + Sized {
+    type Token: SurfaceToken;
+
+    fn set_icon(&mut self, icon: Option<Icon>) -> Result<(), Error>;
+
+    fn set_mouse_mode(&mut self, mode: MouseMode) -> Result<(), Error>;
+
+    fn set_cursor_icon(&mut self, icon: CursorIcon) -> Result<(), Error>;
+
+    fn set_pointer_capture(&mut self, capture: bool) -> Result<(), Error>;
+
+    fn focus(&mut self) -> Result<(), Error>;
+
+    fn set_focus(&mut self) -> Result<(), Error>;
+
+    fn set_always_on_top(&mut self, always_on_top: bool) -> Result<(), Error>;
+
+    fn set_z_order(&mut self, order: ZOrder) -> Result<(), Error>;
+
+    fn set_hit_test(&mut self, hit_test: Option<HitTest>) -> Result<(), Error>;
+
+    fn set_min_dimensions(&mut self, dimensions: Option<LogicalSize>) -> Result<(), Error>;
+
+    fn set_max_dimensions(&mut self, dimensions: Option<LogicalSize>) -> Result<(), Error>;
+
+    fn set_aspect_ratio(&mut self, ratio: Option<f64>) -> Result<(), Error>;
+
+    fn set_fullscreen(&mut self, fullscreen: Option<Fullscreen>) -> Result<(), Error>;
+
+    fn set_maximized(&mut self, maximized: bool) -> Result<(), Error>;
+
+    fn set_minimized(&mut self, minimized: bool) -> Result<(), Error>;
+
+    fn restore(&mut self) -> Result<(), Error>;
+
+    fn is_opacity_supported() -> bool;
+
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Error>;
+
+    fn set_modal(&mut self, modal: bool) -> Result<(), Error>;
+
+    fn request_frame(&mut self) -> Result<(), Error>;
+
+    fn outer_position(&self) -> Result<LogicalPosition, Error>;
+
+    fn inner_size(&self) -> Result<LogicalSize, Error>;
+
+    fn outer_size(&self) -> Result<LogicalSize, Error>;
+
+    fn is_focused(&self) -> bool;
+
+    fn is_minimized(&self) -> bool;
+
+    fn geometry(&self) -> Result<Geometry, Error>;
+
+    fn surface_token(&self) -> Self::Token;
+
+    /// Captures the pixels of the client area of the window.
+    ///
+    /// Platforms that do not support capture need not implement this.
+    #[cfg(feature = "capture")]
+    fn capture(&self) -> Result<Image, Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "window capture is not supported",
+        ))
+    }
+
+    /// Injects an input event into the window as if it were produced by a
+    /// device.
+    ///
+    /// Platforms that do not support injection need not implement this.
+    #[cfg(feature = "test")]
+    fn inject_input(&self, _: InputEvent) -> Result<(), Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "input injection is not supported",
+        ))
+    }
+
+    fn set_user_data(&mut self, data: Option<Box<dyn Any + Send>>) -> Result<(), Error>;
+
+    /// Executes a function against the user data of the window with the
+    /// given handle, if any.
+    ///
+    /// This is only called on the event thread.
+    fn with_user_data(handle: Self::Handle, f: &mut dyn FnMut(&mut dyn Any));
+}
+
+/// Exposes a window and its custom elements to assistive technologies.
+///
+/// Platforms that do not support accessibility should succeed and ignore these
+/// functions.
+pub trait Accessible {
+    fn set_accessible_name(&mut self, name: String) -> Result<(), Error>;
+
+    fn set_accessible_role(&mut self, role: Role) -> Result<(), Error>;
+
+    /// Sets the focused custom element and notifies assistive technologies.
+    ///
+    /// If `element` is `None`, then the window itself has focus.
+    fn set_accessible_focus(&mut self, element: Option<AccessibleElement>) -> Result<(), Error>;
+
+    /// Notifies assistive technologies that a custom element has changed.
+    fn notify_accessible_change(&mut self, element: AccessibleElement) -> Result<(), Error>;
+}
+
+/// Exposes the surface of a window to other threads.
+///
+/// Tokens expose the raw handles needed for rendering via the implicit
+/// interface.
+pub trait SurfaceToken: 'static + Clone + Send + Sync + Sized {
+    /// Determines if the window of the surface has not been destroyed.
+    fn is_alive(&self) -> bool;
 }
 
 pub trait Display: Handle + Sized {
     type Query: IntoIterator<Item = Self>;
 
     fn connected() -> Self::Query;
+
+    fn modes(handle: Self::Handle) -> Vec<VideoMode>;
+
+    fn mode(handle: Self::Handle) -> Option<VideoMode>;
+
+    /// Changes the video mode of a display or restores its default mode if
+    /// `mode` is `None`.
+    fn set_mode(handle: Self::Handle, mode: Option<VideoMode>) -> Result<(), Error>;
+
+    /// Gets the position and dimensions of the work area of a display in
+    /// physical units.
+    fn work_area(handle: Self::Handle) -> Option<(PhysicalPosition, PhysicalSize)>;
 }
 
 pub trait Device: Handle + Sized {
     type Query: IntoIterator<Item = Self>;
 
     fn connected() -> Self::Query;
+
+    fn info(handle: Self::Handle) -> Option<DeviceInfo>;
+
+    fn battery(handle: Self::Handle) -> Option<Battery>;
+
+    fn has_sensor(handle: Self::Handle, sensor: SensorType) -> bool;
+}
+
+pub trait Output: Device {
+    fn has_led(handle: Self::Handle, led: Led) -> bool;
+
+    fn set_led(handle: Self::Handle, led: Led, on: bool) -> Result<(), Error>;
+
+    fn send_output_report(handle: Self::Handle, report: &[u8]) -> Result<(), Error>;
 }
 
 pub trait Handle {
-    type Handle: Copy + Debug + Hash + PartialEq + Sized;
+    type Handle: Copy + Debug + Eq + Hash + Ord + Sized;
 
     fn handle(&self) -> Self::Handle;
 }
@@ -103,9 +103,9 @@
 //! #
 //! # use gaudium_core::event::*;
 //! # use gaudium_core::reactor::{
-//! #     EventThread, FromContext, Poll, Reaction, Reactor, StatefulReactor, ThreadContext
+//! #     EventThread, FromContext, Poll, Reaction, Reactor, Sinks, StatefulReactor, ThreadContext
 //! # };
-//! # use gaudium_core::window::{Window, WindowBuilder, WindowHandle};
+//! # use gaudium_core::window::{Window, WindowBuilder};
 //! # use gaudium_platform_empty::Binding;
 //! #
 //! # use Poll::Wait;
@@ -118,9 +118,9 @@
 //! }
 //!
 //! impl FromContext<Binding> for TestReactor {
-//!     fn from_context(context: &ThreadContext) -> (WindowHandle<Binding>, Self) {
+//!     fn from_context(context: &ThreadContext) -> (Sinks<Binding>, Self) {
 //!         let window = WindowBuilder::<Binding>::default().build(context).unwrap();
-//!         (window.handle(), TestReactor { window })
+//!         (window.handle().into(), TestReactor { window })
 //!     }
 //! }
 //!
@@ -146,16 +146,82 @@
 //! # }
 //! ```
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::iter::{self, FromIterator};
 use std::marker::PhantomData;
-use std::time::Instant;
+use std::slice;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, ErrorKind};
+use crate::event::{Event, ToggleState, WheelScroll};
+use crate::platform::{self, Abort, Join, PlatformBinding};
+use crate::reactor::abort::AbortRequestReactor;
+use crate::reactor::attribute::AttributeReactor;
+use crate::reactor::command::CommandReactor;
+use crate::reactor::focus::FocusReactor;
+use crate::reactor::instrument::Instrumentation;
+use crate::reactor::lifetime::LifetimeReactor;
+use crate::reactor::prefilter::{PreFilter, PreFilterReactor};
+use crate::reactor::stream::{EventSender, EventStream};
+use crate::reactor::swap::SwapReactor;
+use crate::reactor::task::TaskReactor;
+use crate::reactor::unwind::UnwindReactor;
+use crate::reactor::watchdog::{Watchdog, WatchdogReactor};
+use crate::window::{Window, WindowBuilder, WindowHandle, WindowId};
 
-use crate::event::Event;
-use crate::platform::{Abort, Join, PlatformBinding};
-use crate::window::WindowHandle;
+mod abort;
+mod attribute;
+mod command;
+mod focus;
+pub mod instrument;
+mod lifetime;
+pub mod prefilter;
+pub mod stream;
+mod swap;
+pub mod task;
+mod unwind;
+pub mod watchdog;
 
 /// `PhantomData` that prevents auto-implementation of `Send` and `Sync`.
 type ThreadStatic = PhantomData<*mut isize>;
 
+/// The poll function of a `StatefulReactor`.
+type PollFn<T> = Box<dyn FnMut(&mut T, &ThreadContext) -> Reaction<Poll>>;
+
+const DEFAULT_IDLE_BUDGET: Duration = Duration::from_millis(4);
+
+thread_local! {
+    static IS_RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the calling thread as hosting an event loop until dropped.
+///
+/// Thread-local state, such as the executor, command queue, and pre-filters,
+/// is shared by everything that runs on a thread, so a second event loop on
+/// the same thread would corrupt the state of the first.
+struct RunGuard;
+
+impl RunGuard {
+    fn acquire() -> Self {
+        if IS_RUNNING.with(|cell| cell.replace(true)) {
+            panic!(
+                "an event thread is already running on this thread: each thread may host at most \
+                 one event loop at a time"
+            );
+        }
+        RunGuard
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        IS_RUNNING.with(|cell| cell.set(false));
+    }
+}
+
 /// Thread-static context.
 ///
 /// A thread context provides state for its event thread and notably does not
@@ -165,14 +231,385 @@ type ThreadStatic = PhantomData<*mut isize>;
 /// A thread context is used to create `Reactor`s and `Window`s, which are both
 /// operations that must execute on the event thread.
 pub struct ThreadContext {
+    thread_priority: Option<ThreadPriority>,
+    thread_affinity: Option<u64>,
+    is_platform_events_enabled: bool,
+    sink: Cell<Option<WindowId>>,
+    idle_budget: Cell<Option<Duration>>,
+    instrumentation: RefCell<Option<Box<dyn Instrumentation>>>,
+    is_abort_requested: Cell<bool>,
+    is_root_attribution_enabled: Cell<bool>,
+    focused_window: Cell<Option<WindowId>>,
     phantom: ThreadStatic,
 }
 
+impl ThreadContext {
+    /// Spawns a future onto the executor of the event thread.
+    ///
+    /// The future is polled on the event thread between event dispatches and
+    /// is dropped if the event loop stops before it completes. See the `task`
+    /// module.
+    pub fn spawn_local<F>(&self, future: F)
+    where
+        F: 'static + Future<Output = ()>,
+    {
+        task::spawn(future)
+    }
+
+    /// Installs instrumentation for the event thread, replacing any previous
+    /// instrumentation.
+    ///
+    /// The instrumentation is dropped when the event loop stops. See the
+    /// `instrument` module.
+    pub fn set_instrumentation<I>(&self, instrumentation: I)
+    where
+        I: Instrumentation,
+    {
+        let instrumentation = self
+            .instrumentation
+            .replace(Some(Box::new(instrumentation)));
+        // Drop any previous instrumentation outside of the borrow.
+        drop(instrumentation);
+    }
+
+    /// Removes any instrumentation from the event thread.
+    pub fn clear_instrumentation(&self) {
+        let instrumentation = self.instrumentation.take();
+        drop(instrumentation);
+    }
+
+    /// Adds a pre-filter to the event thread.
+    ///
+    /// Pre-filters execute against each event before it is dispatched to the
+    /// reactor, in the order in which they are added. See the `prefilter`
+    /// module.
+    pub fn add_pre_filter<P, F>(&self, filter: F)
+    where
+        P: PlatformBinding,
+        F: PreFilter<P>,
+    {
+        prefilter::add(Box::new(filter));
+    }
+
+    /// Removes all pre-filters from the event thread.
+    pub fn clear_pre_filters(&self) {
+        prefilter::clear();
+    }
+
+    /// Creates a window on the event thread.
+    ///
+    /// This is equivalent to `WindowBuilder::build`, but reads naturally where
+    /// windows are created lazily after the event thread has started, such as
+    /// when a reactor reacts to an event.
+    pub fn create_window<P>(&self, builder: WindowBuilder<P>) -> Result<Window<P>, Error>
+    where
+        P: PlatformBinding,
+    {
+        builder.build(self)
+    }
+
+    /// Gets the sink window of the event thread.
+    ///
+    /// The sink is initially the window handle produced when the event thread
+    /// starts (see `FromContext`) and can be replaced via `set_sink`. Returns
+    /// `None` if the sink window has been dropped.
+    pub fn sink<P>(&self) -> Option<WindowHandle<P>>
+    where
+        P: PlatformBinding,
+    {
+        self.sink.get().and_then(WindowHandle::from_id)
+    }
+
+    /// Replaces the sink window of the event thread.
+    ///
+    /// This allows the window produced when the event thread starts to be
+    /// closed and dropped without special casing, for example, when an
+    /// application replaces a splash window with its main window.
+    pub fn set_sink<P>(&self, window: WindowHandle<P>)
+    where
+        P: PlatformBinding,
+    {
+        self.sink.set(Some(window.id()));
+    }
+
+    /// Gets the window of the event thread that has keyboard focus, if any.
+    ///
+    /// This is updated as `WindowEvent::Focused` events are dispatched, so
+    /// within a reaction to such an event it reflects that event.
+    pub fn focused_window<P>(&self) -> Option<WindowHandle<P>>
+    where
+        P: PlatformBinding,
+    {
+        self.focused_window.get().and_then(WindowHandle::from_id)
+    }
+
+    /// Replaces the reactor of the event thread.
+    ///
+    /// The replacement takes effect when the current reaction or poll
+    /// returns. The current reactor is then aborted (see `Reactor::abort`)
+    /// and all subsequent events, including events that are already queued,
+    /// are dispatched to the replacement. Events in a batch that is being
+    /// dispatched are still dispatched to the current reactor. If this is
+    /// called more than once before the replacement takes effect, then the
+    /// most recent replacement is used.
+    ///
+    /// The event loop and its windows are not torn down. Windows owned by the
+    /// current reactor are dropped when it is aborted, so they must be moved
+    /// into the replacement to keep them open. The panic policy of the event
+    /// thread is that of its initial reactor and does not change.
+    pub fn swap_reactor<P, R>(&self, reactor: R)
+    where
+        P: PlatformBinding,
+        R: 'static + Reactor<P>,
+    {
+        swap::install::<P>(Box::new(reactor));
+    }
+
+    /// Gets the distance that content scrolls per detent of a mouse wheel,
+    /// as configured by the user.
+    ///
+    /// The setting is queried each time this is called, so it reflects any
+    /// changes made while the event thread is running. Platforms without such
+    /// a setting scroll three lines. See `MouseWheelDelta::to_pixels`.
+    pub fn wheel_scroll<P>(&self) -> WheelScroll
+    where
+        P: PlatformBinding,
+    {
+        P::wheel_scroll()
+    }
+
+    /// Gets the state of the toggle keys of the keyboard, such as Caps Lock.
+    ///
+    /// The state is queried each time this is called. Keyboard events also
+    /// carry the toggle state in `ModifierState`, which should be preferred
+    /// when reacting to input. Platforms that cannot query the keyboard report
+    /// that all toggles are off.
+    pub fn toggles<P>(&self) -> ToggleState
+    where
+        P: PlatformBinding,
+    {
+        P::toggles()
+    }
+
+    /// Sets a timer that dispatches `ApplicationEvent::Timer` with the given
+    /// identifier when the duration elapses.
+    ///
+    /// Identifiers are chosen by the application. Setting a timer with the
+    /// identifier of a timer that is already set replaces that timer. Timers
+    /// are cancelled when the event loop stops. Platforms may round the
+    /// duration to the resolution of their timers.
+    ///
+    /// Fails if the platform cannot create the timer.
+    pub fn set_timer<P>(
+        &self,
+        id: TimerId,
+        duration: Duration,
+        recurrence: Recurrence,
+    ) -> Result<(), Error>
+    where
+        P: PlatformBinding,
+    {
+        <P::Timer as platform::Timer>::set(self, id, duration, recurrence)
+    }
+
+    /// Cancels a timer set via `set_timer`.
+    ///
+    /// Does nothing if no timer with the given identifier is set, such as
+    /// when a timer that does not recur has already elapsed.
+    pub fn cancel_timer<P>(&self, id: TimerId)
+    where
+        P: PlatformBinding,
+    {
+        <P::Timer as platform::Timer>::cancel(self, id)
+    }
+
+    /// Gets a proxy that executes commands on the event thread.
+    ///
+    /// See `EventThreadProxy`.
+    pub fn proxy(&self) -> EventThreadProxy {
+        EventThreadProxy {
+            commands: command::queue(),
+            wake: task::wake(),
+        }
+    }
+
+    /// Sets the idle budget of the event thread.
+    ///
+    /// The budget is the duration that a reactor is expected to spend on
+    /// low-priority work when it receives `ApplicationEvent::Idle`. If `None`,
+    /// then `Idle` is not dispatched. Defaults to 4 ms.
+    pub fn set_idle_budget(&self, budget: Option<Duration>) {
+        self.idle_budget.set(budget);
+    }
+
+    /// Gets the idle budget of the event thread.
+    ///
+    /// See `set_idle_budget`.
+    pub fn idle_budget(&self) -> Option<Duration> {
+        self.idle_budget.get()
+    }
+
+    /// Returns `true` if platform events are enabled.
+    ///
+    /// See `EventThreadBuilder::with_platform_events`.
+    pub fn is_platform_events_enabled(&self) -> bool {
+        self.is_platform_events_enabled
+    }
+
+    /// Enables or disables the attribution of input to root windows.
+    ///
+    /// When enabled, input events that are received by a child window are
+    /// attributed to the top-level window that contains it (see
+    /// `WindowHandle::root`), so reactors need not be aware of child windows.
+    /// Window events, such as `Resized` and `Closed`, are always attributed to
+    /// the child window. This is disabled by default.
+    pub fn set_root_attribution_enabled(&self, is_enabled: bool) {
+        self.is_root_attribution_enabled.set(is_enabled);
+    }
+
+    /// Returns `true` if input is attributed to root windows.
+    ///
+    /// See `set_root_attribution_enabled`.
+    pub fn is_root_attribution_enabled(&self) -> bool {
+        self.is_root_attribution_enabled.get()
+    }
+
+    /// Gets the scheduling priority of the event thread, if any.
+    ///
+    /// See `EventThreadBuilder::with_thread_priority`.
+    pub fn thread_priority(&self) -> Option<ThreadPriority> {
+        self.thread_priority
+    }
+
+    /// Gets the processor affinity mask of the event thread, if any.
+    ///
+    /// See `EventThreadBuilder::with_thread_affinity`.
+    pub fn thread_affinity(&self) -> Option<u64> {
+        self.thread_affinity
+    }
+}
+
+/// Executes commands on an event thread from other threads.
+///
+/// Some operations, such as creating windows or changing their titles, must
+/// execute on the event thread. A proxy marshals closures onto the event
+/// thread, which executes them during the flush phase of its event loop,
+/// before `ApplicationEvent::Flushed` is dispatched. Queuing a command
+/// resumes the event loop, even if the reactor has requested the `Wait` poll
+/// mode.
+///
+/// Proxies are obtained via `ThreadContext::proxy` and implement `Send` and
+/// `Sync`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # extern crate gaudium_core;
+/// # extern crate gaudium_platform_empty;
+/// #
+/// use gaudium_core::prelude::*;
+/// use gaudium_core::reactor::{EventThread, StatefulReactor, ThreadContext};
+/// use gaudium_core::window::{Window, WindowBuilder};
+/// use gaudium_platform_empty::Binding;
+/// use std::thread;
+///
+/// # fn main() {
+/// EventThread::<Binding, _>::run_and_abort_with(|context| {
+///     let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+///     let proxy = context.proxy();
+///     thread::spawn(move || {
+///         let _ = proxy.execute(|_| println!("Hello from the event thread!"));
+///     });
+///     (window.handle(), StatefulReactor::from((
+///         window,
+///         |_: &mut Window<Binding>, _: &ThreadContext, event| match event {
+///             Event::Window {
+///                 event: WindowEvent::Closed(..),
+///                 ..
+///             } => Abort,
+///             _ => Continue(()),
+///         },
+///     )))
+/// })
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct EventThreadProxy {
+    commands: command::CommandQueue,
+    wake: Option<task::WakeFn>,
+}
+
+impl EventThreadProxy {
+    /// Queues a command for execution on the event thread.
+    ///
+    /// Commands are executed in the order in which they are queued. Fails if
+    /// the event loop has stopped, in which case the command is never
+    /// executed.
+    pub fn execute<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: 'static + FnOnce(&ThreadContext) + Send,
+    {
+        self.commands
+            .lock()
+            .unwrap()
+            .as_mut()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidState, "event thread has stopped"))?
+            .push(Box::new(f));
+        if let Some(ref wake) = self.wake {
+            wake();
+        }
+        Ok(())
+    }
+
+    /// Requests that the event loop stop.
+    ///
+    /// `ApplicationEvent::AbortRequested` is dispatched to the reactor, which
+    /// may clean up and abort or ignore the request. This allows threads other
+    /// than the event thread, such as a control thread or signal handler, to
+    /// stop the event loop cleanly. Fails if the event loop has stopped.
+    pub fn request_abort(&self) -> Result<(), Error> {
+        self.execute(|context| context.is_abort_requested.set(true))
+    }
+}
+
+/// Scheduling priority of an event thread.
+///
+/// See `EventThreadBuilder::with_thread_priority`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ThreadPriority {
+    Lowest,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    Highest,
+    /// The highest priority available to threads, which may starve other
+    /// threads of the process.
+    TimeCritical,
+}
+
+/// Identifier of a timer chosen by the application.
+///
+/// See `ThreadContext::set_timer`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TimerId(pub u64);
+
+/// Determines whether or not a timer elapses repeatedly.
+///
+/// See `ThreadContext::set_timer`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Recurrence {
+    /// The timer elapses once and is then cancelled.
+    Once,
+    /// The timer elapses repeatedly at its interval until it is cancelled.
+    Repeat,
+}
+
 /// Poll mode.
 ///
 /// Specifies how an event thread should poll events in the event loop. A poll
 /// mode is returned as part of a `Reaction` by a reactor's `poll` function.
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Poll {
     /// Resumes immediately.
     ///
@@ -182,6 +619,17 @@ pub enum Poll {
     ///
     /// Resumes with `Resumption::Poll`.
     Ready,
+    /// Resumes after at most the given duration has elapsed since the
+    /// previous resumption.
+    ///
+    /// This poll mode behaves like `Ready`, but limits how often the event
+    /// loop resumes. If the reactor reacts quickly, then the event thread
+    /// sleeps for the remainder of the duration, but wakes early to dispatch
+    /// any events that occur. This is best suited for applications like games
+    /// that render continuously but need not consume an entire CPU.
+    ///
+    /// Resumes with `Resumption::Poll`.
+    ReadyThrottled(Duration),
     /// Blocks until an event occurs.
     ///
     /// This poll mode puts the event thread to sleep until an event can be
@@ -204,11 +652,32 @@ impl Default for Poll {
     }
 }
 
+/// Determines how an event thread handles panics in its reactor.
+///
+/// See `Reactor::PANIC_POLICY`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum PanicPolicy {
+    /// Panics are caught and abort the event loop.
+    ///
+    /// Once a reactor panics, no further events are dispatched to it. The
+    /// event thread stops the event loop, attempts to `abort` the reactor, and
+    /// then resumes the panic on the thread that started the event thread.
+    /// For `EventThread::run_and_join`, the panic propagates to the caller.
+    #[default]
+    Propagate,
+    /// The reactor never panics, so panics are not caught.
+    ///
+    /// This avoids the (small) cost of catching panics. If the reactor does
+    /// panic, then the panic may unwind into platform code that cannot
+    /// propagate it, which typically aborts the process.
+    NoPanic,
+}
+
 /// Reaction to an event or poll mode query.
 ///
 /// Reactions control the behavior of event loops. Ignoring the payload,
 /// `Reaction` either continues or aborts execution.
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Reaction<T = ()> {
     /// Continues execution of an event loop.
     ///
@@ -282,6 +751,142 @@ impl<T> Into<Option<T>> for Reaction<T> {
     }
 }
 
+/// A batch of events in the order in which they occurred.
+///
+/// Batches borrow the events queued by the event thread, so dispatching a
+/// batch does not allocate. Events are yielded by value when iterating a
+/// batch. See `Reactor::react_all`.
+#[derive(Clone, Copy, Debug)]
+pub struct EventBatch<'a, P>
+where
+    P: PlatformBinding,
+{
+    events: &'a [Event<P>],
+}
+
+impl<'a, P> EventBatch<'a, P>
+where
+    P: PlatformBinding,
+{
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'a, Event<P>> {
+        self.events.iter()
+    }
+
+    pub fn as_slice(&self) -> &'a [Event<P>] {
+        self.events
+    }
+
+    /// Splits the batch at events that match a predicate.
+    ///
+    /// Matching events are yielded on their own and the events between them
+    /// are yielded as batches, so that reactors can dispatch events that
+    /// change their state individually without allocating.
+    pub(crate) fn split<F>(self, f: F) -> Split<'a, P, F>
+    where
+        F: FnMut(&Event<P>) -> bool,
+    {
+        Split {
+            events: self.events,
+            f,
+        }
+    }
+}
+
+impl<'a, P> From<&'a [Event<P>]> for EventBatch<'a, P>
+where
+    P: PlatformBinding,
+{
+    fn from(events: &'a [Event<P>]) -> Self {
+        EventBatch { events }
+    }
+}
+
+impl<'a, P> IntoIterator for EventBatch<'a, P>
+where
+    P: PlatformBinding,
+{
+    type Item = Event<P>;
+    type IntoIter = iter::Copied<slice::Iter<'a, Event<P>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.iter().copied()
+    }
+}
+
+impl<'a, P> IntoIterator for &EventBatch<'a, P>
+where
+    P: PlatformBinding,
+{
+    type Item = &'a Event<P>;
+    type IntoIter = slice::Iter<'a, Event<P>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.iter()
+    }
+}
+
+/// A part of a split `EventBatch`.
+///
+/// See `EventBatch::split`.
+pub(crate) enum Part<'a, P>
+where
+    P: PlatformBinding,
+{
+    Batch(EventBatch<'a, P>),
+    Event(Event<P>),
+}
+
+/// An iterator over the parts of a split `EventBatch`.
+pub(crate) struct Split<'a, P, F>
+where
+    P: PlatformBinding,
+{
+    events: &'a [Event<P>],
+    f: F,
+}
+
+impl<'a, P, F> Iterator for Split<'a, P, F>
+where
+    P: PlatformBinding,
+    F: FnMut(&Event<P>) -> bool,
+{
+    type Item = Part<'a, P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Split {
+            ref mut events,
+            ref mut f,
+        } = *self;
+        let (event, rest) = events.split_first()?;
+        if f(event) {
+            *events = rest;
+            Some(Part::Event(*event))
+        }
+        else {
+            let index = events.iter().position(f).unwrap_or(events.len());
+            let (batch, rest) = events.split_at(index);
+            *events = rest;
+            Some(Part::Batch(batch.into()))
+        }
+    }
+}
+
+/// Merges reactions such that any `Abort` is retained.
+pub(crate) fn merge(reaction: Reaction, other: Reaction) -> Reaction {
+    match other {
+        Reaction::Abort => Reaction::Abort,
+        _ => reaction,
+    }
+}
+
 /// Event thread reactor.
 ///
 /// Reacts to events and controls the poll mode of its event thread. Provides
@@ -290,11 +895,37 @@ pub trait Reactor<P>: Sized
 where
     P: PlatformBinding,
 {
+    /// Determines how the event thread handles panics in the reactor.
+    ///
+    /// See `PanicPolicy`.
+    const PANIC_POLICY: PanicPolicy = PanicPolicy::Propagate;
+
     /// Reacts to an event.
     ///
     /// The output of this function causes the event loop to continue or abort.
     fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction;
 
+    /// Reacts to a batch of events.
+    ///
+    /// Platforms may dispatch events that are queued together in batches,
+    /// which allows reactors to amortize their handling, such as by
+    /// processing input in bulk. By default, this calls `react` with each
+    /// event in order.
+    ///
+    /// Every event in the batch is dispatched regardless of the reactions to
+    /// previous events. If any reaction is `Abort`, then the event loop
+    /// aborts.
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        events
+            .into_iter()
+            .fold(Reaction::Continue(()), |reaction, event| {
+                match self.react(context, event) {
+                    Reaction::Abort => Reaction::Abort,
+                    _ => reaction,
+                }
+            })
+    }
+
     /// Gets the poll mode that is used when the event loop next resumes.
     ///
     /// The output of this function causes the event loop to continue with the
@@ -323,17 +954,264 @@ where
     }
 }
 
+/// Object-safe event thread reactor.
+///
+/// `Reactor` cannot be used as a trait object, because `abort` consumes the
+/// reactor. `DynReactor` is implemented for all reactors and
+/// `Box<dyn DynReactor<P>>` implements `Reactor`, so reactors of different
+/// types can be stored together and selected at runtime, such as reactors
+/// provided by plugins.
+///
+/// The panic policy of a boxed reactor is always `PanicPolicy::Propagate`,
+/// because `Reactor::PANIC_POLICY` is not available through a trait object.
+///
+/// # Examples
+///
+/// ```rust
+/// use gaudium_core::event::Event;
+/// use gaudium_core::reactor::{DynReactor, Reaction, ThreadContext};
+/// use gaudium_platform_empty::Binding;
+///
+/// let reactors: Vec<Box<dyn DynReactor<Binding>>> = vec![
+///     Box::new(|_: &ThreadContext, _: Event<Binding>| Reaction::Continue(())),
+///     Box::new(|_: &ThreadContext, _: Event<Binding>| Reaction::Abort),
+/// ];
+/// ```
+pub trait DynReactor<P>
+where
+    P: PlatformBinding,
+{
+    /// See `Reactor::react`.
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction;
+
+    /// See `Reactor::react_all`.
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction;
+
+    /// See `Reactor::poll`.
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll>;
+
+    /// See `Reactor::abort`.
+    fn abort(self: Box<Self>);
+}
+
+impl<P, R> DynReactor<P> for R
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        Reactor::react(self, context, event)
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        Reactor::react_all(self, context, events)
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        Reactor::poll(self, context)
+    }
+
+    fn abort(self: Box<Self>) {
+        Reactor::abort(*self)
+    }
+}
+
+impl<P> Reactor<P> for Box<dyn DynReactor<P>>
+where
+    P: PlatformBinding,
+{
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        DynReactor::react(&mut **self, context, event)
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        DynReactor::react_all(&mut **self, context, events)
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        DynReactor::poll(&mut **self, context)
+    }
+
+    fn abort(self) {
+        DynReactor::abort(self)
+    }
+}
+
+/// Event sink of an event thread.
+///
+/// Most event threads are anchored to a window that receives platform events
+/// on their behalf. Headless event threads, such as tray utilities and tools
+/// that observe global input, have no visible window and instead receive
+/// device events via a hidden platform-specific sink.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sink<P>
+where
+    P: PlatformBinding,
+{
+    /// A window that receives events.
+    Window(WindowHandle<P>),
+    /// No window.
+    ///
+    /// Platforms create a hidden sink that receives device events. On
+    /// Windows, this is a message-only window, which receives raw input in the
+    /// background but does not receive broadcast messages, such as display
+    /// changes.
+    Headless,
+}
+
+impl<P> Sink<P>
+where
+    P: PlatformBinding,
+{
+    /// Gets the window of the sink, if any.
+    pub fn window(self) -> Option<WindowHandle<P>> {
+        match self {
+            Sink::Window(window) => Some(window),
+            Sink::Headless => None,
+        }
+    }
+}
+
+impl<P> Eq for Sink<P> where P: PlatformBinding {}
+
+impl<P> From<WindowHandle<P>> for Sink<P>
+where
+    P: PlatformBinding,
+{
+    fn from(window: WindowHandle<P>) -> Self {
+        Sink::Window(window)
+    }
+}
+
+/// Event sinks of an event thread.
+///
+/// Applications that start with more than one top-level window, such as an
+/// editor with tool palettes or a window on each display, can produce each of
+/// these windows as a sink when the event thread starts. The first window is
+/// the primary sink (see `ThreadContext::sink`). If there are no windows, then
+/// the event thread is headless (see `Sink::Headless`).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # extern crate gaudium_core;
+/// # extern crate gaudium_platform_empty;
+/// #
+/// use gaudium_core::prelude::*;
+/// use gaudium_core::reactor::{EventThread, Sinks, StatefulReactor, ThreadContext};
+/// use gaudium_core::window::{Window, WindowBuilder};
+/// use gaudium_platform_empty::Binding;
+///
+/// # fn main() {
+/// EventThread::<Binding, _>::run_and_abort_with(|context| {
+///     let canvas = WindowBuilder::<Binding>::default().build(context).unwrap();
+///     let palette = WindowBuilder::<Binding>::default().build(context).unwrap();
+///     let sinks: Sinks<Binding> = vec![canvas.handle(), palette.handle()].into();
+///     (sinks, StatefulReactor::from((
+///         (canvas, palette),
+///         |_: &mut (Window<Binding>, Window<Binding>), _: &ThreadContext, event| match event {
+///             Event::Window {
+///                 event: WindowEvent::Closed(..),
+///                 ..
+///             } => Abort,
+///             _ => Continue(()),
+///         },
+///     )))
+/// })
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sinks<P>
+where
+    P: PlatformBinding,
+{
+    windows: Vec<WindowHandle<P>>,
+}
+
+impl<P> Sinks<P>
+where
+    P: PlatformBinding,
+{
+    /// Gets sinks with no windows.
+    pub fn headless() -> Self {
+        Sinks { windows: vec![] }
+    }
+
+    /// Gets the primary window, if any.
+    pub fn primary(&self) -> Option<WindowHandle<P>> {
+        self.windows.first().copied()
+    }
+
+    pub fn windows(&self) -> &[WindowHandle<P>] {
+        self.windows.as_slice()
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.windows.is_empty()
+    }
+}
+
+impl<P> Eq for Sinks<P> where P: PlatformBinding {}
+
+impl<P> From<Sink<P>> for Sinks<P>
+where
+    P: PlatformBinding,
+{
+    fn from(sink: Sink<P>) -> Self {
+        sink.window().into_iter().collect()
+    }
+}
+
+impl<P> From<Vec<WindowHandle<P>>> for Sinks<P>
+where
+    P: PlatformBinding,
+{
+    fn from(windows: Vec<WindowHandle<P>>) -> Self {
+        Sinks { windows }
+    }
+}
+
+impl<P> From<WindowHandle<P>> for Sinks<P>
+where
+    P: PlatformBinding,
+{
+    fn from(window: WindowHandle<P>) -> Self {
+        Sinks {
+            windows: vec![window],
+        }
+    }
+}
+
+impl<P> FromIterator<WindowHandle<P>> for Sinks<P>
+where
+    P: PlatformBinding,
+{
+    fn from_iter<I>(windows: I) -> Self
+    where
+        I: IntoIterator<Item = WindowHandle<P>>,
+    {
+        Sinks {
+            windows: windows.into_iter().collect(),
+        }
+    }
+}
+
 /// Conversion from a thread context into a sink and reactor.
 ///
 /// This trait is typically implemented by reactors. A reactor that implements
 /// `FromContext` can be used with `EventThread::run_and_abort` and similar
 /// functions.
+///
+/// Sinks are only special when the event thread starts and may include more
+/// than one window (see `Sinks`). Reactors may create more windows later via
+/// `ThreadContext::create_window` and replace the primary sink via
+/// `ThreadContext::set_sink`.
 pub trait FromContext<P>: Sized
 where
     P: PlatformBinding,
 {
-    /// Creates an event sink (window handle) and an instance of `Self`.
-    fn from_context(context: &ThreadContext) -> (WindowHandle<P>, Self);
+    /// Creates event sinks and an instance of `Self`.
+    fn from_context(context: &ThreadContext) -> (Sinks<P>, Self);
 }
 
 pub trait IntoReactor<P, R>
@@ -341,7 +1219,7 @@ where
     P: PlatformBinding,
     R: Reactor<P>,
 {
-    fn into_reactor(self) -> (WindowHandle<P>, R);
+    fn into_reactor(self) -> (Sinks<P>, R);
 }
 
 impl<'a, P, R> IntoReactor<P, R> for &'a ThreadContext
@@ -349,7 +1227,7 @@ where
     P: PlatformBinding,
     R: FromContext<P> + Reactor<P>,
 {
-    fn into_reactor(self) -> (WindowHandle<P>, R) {
+    fn into_reactor(self) -> (Sinks<P>, R) {
         R::from_context(self)
     }
 }
@@ -364,8 +1242,47 @@ where
 /// events. This is useful in simple applications, but for most applications it
 /// is preferable to implement `FromContext` and `Reactor` instead.
 ///
-/// `StatefulReactor` does nothing when it `abort`s and always uses the `Wait`
-/// poll mode.
+/// By default, `StatefulReactor` always uses the `Wait` poll mode and drops
+/// its state when it `abort`s. Functions that poll and abort can be provided
+/// via `with_poll` and `with_abort`.
+///
+/// # Examples
+///
+/// Rendering at a fixed rate and saving state when the event loop stops:
+///
+/// ```rust,no_run
+/// # extern crate gaudium_core;
+/// # extern crate gaudium_platform_empty;
+/// #
+/// use gaudium_core::prelude::*;
+/// use gaudium_core::reactor::{EventThread, StatefulReactor, ThreadContext};
+/// use gaudium_core::window::{Window, WindowBuilder};
+/// use gaudium_platform_empty::Binding;
+/// use std::time::Duration;
+///
+/// # fn main() {
+/// EventThread::<Binding, _>::run_and_abort_with(|context| {
+///     let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+///     (window.handle(), StatefulReactor::from((
+///         (window, 0usize),
+///         |_: &mut (Window<Binding>, usize), _: &ThreadContext, event| match event {
+///             Event::Window {
+///                 event: WindowEvent::Closed(..),
+///                 ..
+///             } => Abort,
+///             _ => Continue(()),
+///         },
+///     ))
+///     .with_poll(|(_, frames): &mut (Window<Binding>, usize), _: &ThreadContext| {
+///         *frames += 1;
+///         Poll::ReadyThrottled(Duration::from_millis(16)).into()
+///     })
+///     .with_abort(|(_, frames): (Window<Binding>, usize)| {
+///         println!("Rendered {} frames.", frames);
+///     }))
+/// })
+/// # }
+/// ```
 pub struct StatefulReactor<P, T, F>
 where
     P: PlatformBinding,
@@ -373,9 +1290,40 @@ where
 {
     state: T,
     f: F,
+    poll: Option<PollFn<T>>,
+    abort: Option<Box<dyn FnOnce(T)>>,
     phantom: PhantomData<P>,
 }
 
+impl<P, T, F> StatefulReactor<P, T, F>
+where
+    P: PlatformBinding,
+    F: 'static + FnMut(&mut T, &ThreadContext, Event<P>) -> Reaction,
+{
+    /// Sets the function that determines the poll mode of the event loop.
+    ///
+    /// The function is called whenever the reactor is polled. See
+    /// `Reactor::poll`.
+    pub fn with_poll<G>(mut self, poll: G) -> Self
+    where
+        G: 'static + FnMut(&mut T, &ThreadContext) -> Reaction<Poll>,
+    {
+        self.poll = Some(Box::new(poll));
+        self
+    }
+
+    /// Sets the function that consumes the state when the event loop stops.
+    ///
+    /// See `Reactor::abort`.
+    pub fn with_abort<H>(mut self, abort: H) -> Self
+    where
+        H: 'static + FnOnce(T),
+    {
+        self.abort = Some(Box::new(abort));
+        self
+    }
+}
+
 impl<P, T, F> Reactor<P> for StatefulReactor<P, T, F>
 where
     P: PlatformBinding,
@@ -385,8 +1333,18 @@ where
         (self.f)(&mut self.state, context, event)
     }
 
-    fn poll(&mut self, _: &ThreadContext) -> Reaction<Poll> {
-        Poll::Wait.into()
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        match self.poll {
+            Some(ref mut poll) => poll(&mut self.state, context),
+            None => Poll::Wait.into(),
+        }
+    }
+
+    fn abort(self) {
+        let StatefulReactor { state, abort, .. } = self;
+        if let Some(abort) = abort {
+            abort(state);
+        }
     }
 }
 
@@ -402,11 +1360,64 @@ where
         StatefulReactor {
             state,
             f,
+            poll: None,
+            abort: None,
             phantom: PhantomData,
         }
     }
 }
 
+/// Creates a sink and `StatefulReactor` from functions.
+///
+/// `init` creates the sink and state of the reactor from the thread context
+/// and `f` reacts to events. The output is accepted by
+/// `EventThread::run_and_abort_with` and `EventThread::run_and_join_with`, so
+/// applications can be written entirely with closures. Unlike
+/// `StatefulReactor::from`, the parameters of `f` need not be annotated.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # extern crate gaudium_core;
+/// # extern crate gaudium_platform_empty;
+/// #
+/// use gaudium_core::prelude::*;
+/// use gaudium_core::reactor::{self, EventThread};
+/// use gaudium_core::window::WindowBuilder;
+/// use gaudium_platform_empty::Binding;
+///
+/// # fn main() {
+/// EventThread::<Binding, _>::run_and_abort_with(reactor::from_fn_with(
+///     |context| {
+///         let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+///         (window.handle(), window)
+///     },
+///     |_, _, event| match event {
+///         Event::Window {
+///             event: WindowEvent::Closed(..),
+///             ..
+///         } => Abort,
+///         _ => Continue(()),
+///     },
+/// ))
+/// # }
+/// ```
+pub fn from_fn_with<P, T, S, I, F>(
+    init: I,
+    f: F,
+) -> impl 'static + FnOnce(&ThreadContext) -> (S, StatefulReactor<P, T, F>)
+where
+    P: PlatformBinding,
+    S: Into<Sinks<P>>,
+    I: 'static + FnOnce(&ThreadContext) -> (S, T),
+    F: 'static + FnMut(&mut T, &ThreadContext, Event<P>) -> Reaction,
+{
+    move |context| {
+        let (sink, state) = init(context);
+        (sink, StatefulReactor::from((state, f)))
+    }
+}
+
 /// Event thread.
 ///
 /// An event thread executes an event loop that polls and dispatches events.
@@ -417,7 +1428,15 @@ where
 /// event thread. The reactor processes each event it receives and determines
 /// if the event loop continues or aborts.
 ///
-/// `EventThread` takes control of the thread on which it is started.
+/// `EventThread` takes control of the thread on which it is started. See
+/// `EventThreadBuilder` to configure an event thread before it starts.
+///
+/// A process may run any number of event threads concurrently, but each
+/// thread hosts at most one event loop at a time. Event threads share no
+/// state, though some platform resources are process-wide: on Windows, raw
+/// input for a given kind of device is delivered to only the most recently
+/// registered window. Starting an event thread from a thread that is already
+/// running an event loop, such as from within a reactor, panics.
 pub struct EventThread<P, R>
 where
     P: PlatformBinding,
@@ -440,9 +1459,9 @@ where
     /// #
     /// # use gaudium_core::event::*;
     /// # use gaudium_core::reactor::{
-    /// #     EventThread, FromContext, Poll, Reaction, Reactor, StatefulReactor, ThreadContext
+    /// #     EventThread, FromContext, Poll, Reaction, Reactor, Sinks, StatefulReactor, ThreadContext
     /// # };
-    /// # use gaudium_core::window::{Window, WindowBuilder, WindowHandle};
+    /// # use gaudium_core::window::{Window, WindowBuilder};
     /// # use gaudium_platform_empty::Binding;
     /// #
     /// # use Poll::Wait;
@@ -455,9 +1474,9 @@ where
     /// }
     ///
     /// impl FromContext<Binding> for TestReactor {
-    ///     fn from_context(context: &ThreadContext) -> (WindowHandle<Binding>, Self) {
+    ///     fn from_context(context: &ThreadContext) -> (Sinks<Binding>, Self) {
     ///         let window = WindowBuilder::<Binding>::default().build(context).unwrap();
-    ///         (window.handle(), TestReactor { window })
+    ///         (window.handle().into(), TestReactor { window })
     ///     }
     /// }
     ///
@@ -485,7 +1504,9 @@ where
     /// Starts a divergent event thread that aborts when its event loop
     /// terminates.
     ///
-    /// Accepts a function that produces a reactor from a thread context.
+    /// Accepts a function that produces sinks and a reactor from a thread
+    /// context. The sinks are typically a window handle, `Sink::Headless`, or
+    /// `Sinks`. Panics if the calling thread is already running an event loop.
     ///
     /// # Examples
     ///
@@ -521,20 +1542,19 @@ where
     /// })
     /// # }
     /// ```
-    pub fn run_and_abort_with<F>(f: F) -> !
+    pub fn run_and_abort_with<F, S>(f: F) -> !
     where
-        F: 'static + FnOnce(&ThreadContext) -> (WindowHandle<P>, R),
+        F: 'static + FnOnce(&ThreadContext) -> (S, R),
+        S: Into<Sinks<P>>,
     {
-        let context = ThreadContext {
-            phantom: PhantomData,
-        };
-        let (sink, reactor) = f(&context);
-        <P::EventThread as Abort<P>>::run_and_abort(context, sink, reactor)
+        EventThreadBuilder::default().run_and_abort_with(f)
     }
 
     /// Starts an event thread that returns control to the caller when its
     /// event loop terminates.
-    pub fn run_and_join()
+    ///
+    /// Fails if the platform's event loop fails.
+    pub fn run_and_join() -> Result<(), Error>
     where
         R: FromContext<P>,
         P::EventThread: Join<P>,
@@ -545,16 +1565,248 @@ where
     /// Starts an event thread that returns control to the caller when its
     /// event loop terminates.
     ///
-    /// Accepts a function that produces a reactor from a thread context.
-    pub fn run_and_join_with<F>(f: F)
+    /// Accepts a function that produces sinks and a reactor from a thread
+    /// context. Fails if the platform's event loop fails.
+    ///
+    /// Panics if the calling thread is already running an event loop.
+    pub fn run_and_join_with<F, S>(f: F) -> Result<(), Error>
     where
-        F: 'static + FnOnce(&ThreadContext) -> (WindowHandle<P>, R),
+        F: 'static + FnOnce(&ThreadContext) -> (S, R),
+        S: Into<Sinks<P>>,
         P::EventThread: Join<P>,
     {
-        let context = ThreadContext {
+        EventThreadBuilder::default().run_and_join_with(f)
+    }
+}
+
+/// Configures and starts an `EventThread`.
+///
+/// An `EventThreadBuilder` provides a default configuration that can be
+/// customized using a fluent interface. The configuration is fixed when the
+/// event thread starts and applies until its event loop stops.
+/// `EventThread::run_and_abort_with` and similar functions start an event
+/// thread with the default configuration.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # extern crate gaudium_core;
+/// # extern crate gaudium_platform_empty;
+/// #
+/// use gaudium_core::prelude::*;
+/// use gaudium_core::reactor::watchdog::Watchdog;
+/// use gaudium_core::reactor::{EventThreadBuilder, StatefulReactor, ThreadContext};
+/// use gaudium_core::window::{Window, WindowBuilder};
+/// use gaudium_platform_empty::Binding;
+/// use std::time::Duration;
+///
+/// # fn main() {
+/// EventThreadBuilder::<Binding>::default()
+///     .with_watchdog(Watchdog::new(Duration::from_millis(250), |_| {}))
+///     .run_and_abort_with(|context| {
+///         let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+///         (window.handle(), StatefulReactor::from((
+///             window,
+///             |_: &mut Window<Binding>, _: &ThreadContext, event| match event {
+///                 Event::Window {
+///                     event: WindowEvent::Closed(..),
+///                     ..
+///                 } => Abort,
+///                 _ => Continue(()),
+///             },
+///         )))
+///     })
+/// # }
+/// ```
+pub struct EventThreadBuilder<P>
+where
+    P: PlatformBinding,
+{
+    watchdog: Option<Watchdog>,
+    thread_priority: Option<ThreadPriority>,
+    thread_affinity: Option<u64>,
+    is_platform_events_enabled: bool,
+    phantom: PhantomData<P>,
+}
+
+impl<P> EventThreadBuilder<P>
+where
+    P: PlatformBinding,
+{
+    /// Sets the watchdog of the event thread.
+    ///
+    /// The watchdog is dropped when the event loop stops. See the `watchdog`
+    /// module.
+    pub fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Sets whether or not platform events are dispatched.
+    ///
+    /// When enabled, platform messages that are not otherwise modeled by
+    /// events are dispatched to the reactor as `Event::Platform`. Defaults to
+    /// `false`.
+    pub fn with_platform_events(mut self, enabled: bool) -> Self {
+        self.is_platform_events_enabled = enabled;
+        self
+    }
+
+    /// Sets the scheduling priority of the event thread.
+    ///
+    /// The priority is applied when the event loop starts and the previous
+    /// priority of the thread is restored when it stops. If no priority is
+    /// set, then the priority of the thread is not changed. Platforms that
+    /// cannot prioritize threads ignore the priority.
+    pub fn with_thread_priority(mut self, priority: ThreadPriority) -> Self {
+        self.thread_priority = Some(priority);
+        self
+    }
+
+    /// Sets the processors on which the event thread may execute.
+    ///
+    /// Each bit of the mask represents a logical processor. For example, a
+    /// mask of `1` pins the event thread to the first processor. The affinity
+    /// is applied when the event loop starts and the previous affinity of the
+    /// thread is restored when it stops. If the mask is zero or includes no
+    /// processors of the system, then the event thread fails to start.
+    /// Platforms that cannot constrain the affinity of threads ignore the
+    /// mask.
+    pub fn with_thread_affinity(mut self, mask: u64) -> Self {
+        self.thread_affinity = Some(mask);
+        self
+    }
+
+    /// Starts a divergent event thread that aborts when its event loop
+    /// terminates.
+    ///
+    /// See `EventThread::run_and_abort`.
+    pub fn run_and_abort<R>(self) -> !
+    where
+        R: FromContext<P> + Reactor<P>,
+    {
+        self.run_and_abort_with(|context| R::from_context(context))
+    }
+
+    /// Starts a divergent event thread that aborts when its event loop
+    /// terminates.
+    ///
+    /// See `EventThread::run_and_abort_with`.
+    pub fn run_and_abort_with<R, F, S>(self, f: F) -> !
+    where
+        R: Reactor<P>,
+        F: 'static + FnOnce(&ThreadContext) -> (S, R),
+        S: Into<Sinks<P>>,
+    {
+        let _guard = RunGuard::acquire();
+        let context = self.context();
+        let (sinks, reactor) = self.start(&context, f);
+        <P::EventThread as Abort<P>>::run_and_abort(context, sinks, reactor)
+    }
+
+    /// Starts an event thread that returns control to the caller when its
+    /// event loop terminates.
+    ///
+    /// See `EventThread::run_and_join`.
+    pub fn run_and_join<R>(self) -> Result<(), Error>
+    where
+        R: FromContext<P> + Reactor<P>,
+        P::EventThread: Join<P>,
+    {
+        self.run_and_join_with(|context| R::from_context(context))
+    }
+
+    /// Starts an event thread that returns control to the caller when its
+    /// event loop terminates.
+    ///
+    /// See `EventThread::run_and_join_with`.
+    pub fn run_and_join_with<R, F, S>(self, f: F) -> Result<(), Error>
+    where
+        R: Reactor<P>,
+        F: 'static + FnOnce(&ThreadContext) -> (S, R),
+        S: Into<Sinks<P>>,
+        P::EventThread: Join<P>,
+    {
+        let _guard = RunGuard::acquire();
+        let context = self.context();
+        let (sinks, reactor) = self.start(&context, f);
+        <P::EventThread as Join<P>>::run_and_join(context, sinks, reactor)
+    }
+
+    fn context(&self) -> ThreadContext {
+        ThreadContext {
+            thread_priority: self.thread_priority,
+            thread_affinity: self.thread_affinity,
+            is_platform_events_enabled: self.is_platform_events_enabled,
+            sink: Cell::new(None),
+            idle_budget: Cell::new(Some(DEFAULT_IDLE_BUDGET)),
+            instrumentation: RefCell::new(None),
+            is_abort_requested: Cell::new(false),
+            is_root_attribution_enabled: Cell::new(false),
+            focused_window: Cell::new(None),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates the sinks and reactor of the event thread.
+    fn start<R, F, S>(self, context: &ThreadContext, f: F) -> (Sinks<P>, impl Reactor<P>)
+    where
+        R: Reactor<P>,
+        F: 'static + FnOnce(&ThreadContext) -> (S, R),
+        S: Into<Sinks<P>>,
+    {
+        task::install::<P>(context);
+        command::install();
+        let (sinks, reactor) = f(context);
+        let sinks = sinks.into();
+        context.sink.set(sinks.primary().map(WindowHandle::id));
+        let reactor = SwapReactor::new(reactor);
+        let reactor = WatchdogReactor::new(reactor, self.watchdog);
+        let reactor = PreFilterReactor::new(reactor);
+        let reactor = AbortRequestReactor::new(reactor);
+        let reactor = CommandReactor::new(reactor);
+        let reactor = FocusReactor::new(reactor);
+        let reactor = AttributeReactor::new(reactor);
+        let reactor = LifetimeReactor::new(reactor);
+        let reactor = TaskReactor::new(reactor);
+        (sinks, UnwindReactor::new(reactor))
+    }
+}
+
+impl<P> Default for EventThreadBuilder<P>
+where
+    P: PlatformBinding,
+{
+    fn default() -> Self {
+        EventThreadBuilder {
+            watchdog: None,
+            thread_priority: None,
+            thread_affinity: None,
+            is_platform_events_enabled: false,
             phantom: PhantomData,
-        };
-        let (sink, reactor) = f(&context);
-        <P::EventThread as Join<P>>::run_and_join(context, sink, reactor)
+        }
+    }
+}
+
+impl<P> EventThread<P, EventSender<P>>
+where
+    P: PlatformBinding,
+{
+    /// Starts a divergent event thread that sends events to an
+    /// `EventStream`.
+    ///
+    /// Accepts a function that receives the stream and produces a sink
+    /// window. The function typically moves the stream to another thread
+    /// that executes an async runtime. The sink is kept alive until the event
+    /// loop stops. See the `stream` module.
+    pub fn run_with_stream<F>(capacity: usize, f: F) -> !
+    where
+        F: 'static + FnOnce(&ThreadContext, EventStream<P>) -> Window<P>,
+    {
+        Self::run_and_abort_with(move |context| {
+            let (sender, stream) = stream::channel(capacity);
+            let sink = f(context, stream);
+            (sink.handle(), sender.with_sink(sink))
+        })
     }
 }
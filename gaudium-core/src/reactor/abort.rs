@@ -0,0 +1,90 @@
+//! Requests to stop the event loop.
+//!
+//! Abort requests are made via `EventThreadProxy::request_abort` and are
+//! dispatched to the reactor as `ApplicationEvent::AbortRequested` in the
+//! flush phase of the event loop.
+
+use std::marker::PhantomData;
+
+use crate::event::{ApplicationEvent, Event};
+use crate::platform::PlatformBinding;
+use crate::reactor::command::is_flushed;
+use crate::reactor::{
+    merge, EventBatch, PanicPolicy, Part, Poll, Reaction, Reactor, ThreadContext,
+};
+
+/// A reactor that dispatches `ApplicationEvent::AbortRequested` before the
+/// `Flushed` event that follows an abort request.
+pub(crate) struct AbortRequestReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> AbortRequestReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        AbortRequestReactor {
+            reactor,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for AbortRequestReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        match event {
+            Event::Application {
+                event: ApplicationEvent::Flushed,
+                timestamp,
+            } if context.is_abort_requested.replace(false) => {
+                let reaction = self.reactor.react(
+                    context,
+                    Event::Application {
+                        event: ApplicationEvent::AbortRequested,
+                        timestamp,
+                    },
+                );
+                merge(reaction, self.reactor.react(context, event))
+            }
+            _ => self.reactor.react(context, event),
+        }
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        if !context.is_abort_requested.get() {
+            return self.reactor.react_all(context, events);
+        }
+        events
+            .split(is_flushed)
+            .fold(Reaction::Continue(()), |reaction, part| {
+                merge(
+                    reaction,
+                    match part {
+                        Part::Batch(events) => self.reactor.react_all(context, events),
+                        Part::Event(event) => self.react(context, event),
+                    },
+                )
+            })
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        self.reactor.abort();
+    }
+}
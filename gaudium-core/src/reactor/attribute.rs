@@ -0,0 +1,96 @@
+//! Attribution of input to root windows.
+//!
+//! See `ThreadContext::set_root_attribution_enabled`.
+
+use std::marker::PhantomData;
+
+use crate::event::Event;
+use crate::platform::PlatformBinding;
+use crate::reactor::{EventBatch, PanicPolicy, Poll, Reaction, Reactor, ThreadContext};
+
+/// A reactor that attributes input received by child windows to their root
+/// windows, if enabled, before dispatching events to another reactor.
+pub(crate) struct AttributeReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    // Events of the batch being dispatched that have been attributed. The
+    // buffer is reused by each batch.
+    batch: Vec<Event<P>>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> AttributeReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        AttributeReactor {
+            reactor,
+            batch: vec![],
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for AttributeReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        self.reactor.react(context, attribute(context, event))
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        if !context.is_root_attribution_enabled() {
+            return self.reactor.react_all(context, events);
+        }
+        let AttributeReactor {
+            ref mut reactor,
+            ref mut batch,
+            ..
+        } = *self;
+        batch.extend(events.into_iter().map(|event| attribute(context, event)));
+        let reaction = reactor.react_all(context, batch.as_slice().into());
+        batch.clear();
+        reaction
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        self.reactor.abort();
+    }
+}
+
+/// Attributes input received by a child window to its root window if
+/// enabled.
+fn attribute<P>(context: &ThreadContext, event: Event<P>) -> Event<P>
+where
+    P: PlatformBinding,
+{
+    match event {
+        Event::Input {
+            device,
+            window: Some(window),
+            event,
+            focus,
+            timestamp,
+        } if context.is_root_attribution_enabled() => Event::Input {
+            device,
+            window: Some(window.root()),
+            event,
+            focus,
+            timestamp,
+        },
+        event => event,
+    }
+}
@@ -0,0 +1,137 @@
+//! Commands executed on the event thread.
+//!
+//! Commands are queued from any thread via an `EventThreadProxy` and are
+//! executed in the flush phase of the event loop, before the reactor receives
+//! `ApplicationEvent::Flushed`. The queue is closed when the event loop stops,
+//! after which commands are rejected.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use crate::event::{ApplicationEvent, Event};
+use crate::platform::PlatformBinding;
+use crate::reactor::{
+    merge, EventBatch, PanicPolicy, Part, Poll, Reaction, Reactor, ThreadContext,
+};
+
+type Command = Box<dyn FnOnce(&ThreadContext) + Send>;
+/// Commands queued by `EventThreadProxy`s.
+///
+/// The queue is `None` once the event loop has stopped.
+pub(crate) type CommandQueue = Arc<Mutex<Option<Vec<Command>>>>;
+
+thread_local! {
+    static COMMANDS: RefCell<CommandQueue> = RefCell::new(CommandQueue::default());
+}
+
+/// Opens the command queue of the event thread.
+pub(crate) fn install() {
+    COMMANDS.with(|queue| *queue.borrow_mut() = Arc::new(Mutex::new(Some(vec![]))));
+}
+
+/// Gets the command queue of the event thread.
+pub(crate) fn queue() -> CommandQueue {
+    COMMANDS.with(|queue| queue.borrow().clone())
+}
+
+/// Executes all queued commands.
+///
+/// Commands are removed from the queue before they are executed, so commands
+/// may queue other commands, which are executed in the next flush.
+fn run(context: &ThreadContext) {
+    let commands = COMMANDS.with(|queue| {
+        queue
+            .borrow()
+            .lock()
+            .unwrap()
+            .as_mut()
+            .map(mem::take)
+            .unwrap_or_default()
+    });
+    for command in commands {
+        command(context);
+    }
+}
+
+/// A reactor that executes queued commands before dispatching
+/// `ApplicationEvent::Flushed` to another reactor.
+///
+/// The command queue is closed when the reactor is aborted.
+pub(crate) struct CommandReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> CommandReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        CommandReactor {
+            reactor,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for CommandReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        if is_flushed(&event) {
+            run(context);
+        }
+        self.reactor.react(context, event)
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        events
+            .split(is_flushed)
+            .fold(Reaction::Continue(()), |reaction, part| {
+                merge(
+                    reaction,
+                    match part {
+                        Part::Batch(events) => self.reactor.react_all(context, events),
+                        Part::Event(event) => self.react(context, event),
+                    },
+                )
+            })
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        // Pending commands are dropped and any further commands are rejected.
+        let queue = COMMANDS.with(|queue| mem::take(&mut *queue.borrow_mut()));
+        let commands = queue.lock().unwrap().take();
+        drop(commands);
+        self.reactor.abort();
+    }
+}
+
+/// Determines if an event is `ApplicationEvent::Flushed`.
+pub(crate) fn is_flushed<P>(event: &Event<P>) -> bool
+where
+    P: PlatformBinding,
+{
+    matches!(
+        event,
+        Event::Application {
+            event: ApplicationEvent::Flushed,
+            ..
+        }
+    )
+}
@@ -0,0 +1,113 @@
+//! Keyboard focus of the windows of an event thread.
+//!
+//! See `ThreadContext::focused_window`.
+
+use std::marker::PhantomData;
+
+use crate::event::{Event, WindowCloseState, WindowEvent};
+use crate::platform::PlatformBinding;
+use crate::reactor::{
+    merge, EventBatch, PanicPolicy, Part, Poll, Reaction, Reactor, ThreadContext,
+};
+
+/// A reactor that tracks the window that has keyboard focus as events are
+/// dispatched to another reactor.
+pub(crate) struct FocusReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> FocusReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        FocusReactor {
+            reactor,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for FocusReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        let focused = &context.focused_window;
+        match event {
+            // Track focus before the reactor observes the change. Focus may
+            // be lost by a window after another has gained it.
+            Event::Window {
+                window,
+                event: WindowEvent::Focused(is_focused),
+                ..
+            } => {
+                if is_focused {
+                    focused.set(Some(window.id()));
+                }
+                else if focused.get() == Some(window.id()) {
+                    focused.set(None);
+                }
+                self.reactor.react(context, event)
+            }
+            // Closed windows lose focus after the reactor observes the close.
+            Event::Window {
+                window,
+                event: WindowEvent::Closed(WindowCloseState::Committed),
+                ..
+            } => {
+                let reaction = self.reactor.react(context, event);
+                if focused.get() == Some(window.id()) {
+                    focused.set(None);
+                }
+                reaction
+            }
+            _ => self.reactor.react(context, event),
+        }
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        events
+            .split(is_focus_changed)
+            .fold(Reaction::Continue(()), |reaction, part| {
+                merge(
+                    reaction,
+                    match part {
+                        Part::Batch(events) => self.reactor.react_all(context, events),
+                        Part::Event(event) => self.react(context, event),
+                    },
+                )
+            })
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        self.reactor.abort();
+    }
+}
+
+/// Determines if an event may change the window that has focus.
+fn is_focus_changed<P>(event: &Event<P>) -> bool
+where
+    P: PlatformBinding,
+{
+    matches!(
+        event,
+        Event::Window {
+            event: WindowEvent::Closed(WindowCloseState::Committed) | WindowEvent::Focused(_),
+            ..
+        }
+    )
+}
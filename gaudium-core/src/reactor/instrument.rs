@@ -0,0 +1,302 @@
+//! Event loop instrumentation.
+//!
+//! An `Instrumentation` observes the phases of an event loop, such as flushing
+//! platform events, dispatching events to the reactor, polling the reactor, and
+//! resuming after waiting. Instrumentation is installed for an event thread
+//! via `ThreadContext::set_instrumentation` and is invoked by the platform's
+//! event loop at phase boundaries. When no instrumentation is installed, these
+//! hooks have negligible cost.
+//!
+//! `Collector` is an instrumentation that exposes rolling statistics, such as
+//! events dispatched per second and the maximum time spent dispatching a
+//! single event.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::prelude::*;
+//! use gaudium_core::reactor::instrument::Collector;
+//! use gaudium_core::reactor::{EventThread, StatefulReactor, ThreadContext};
+//! use gaudium_core::window::{Window, WindowBuilder};
+//! use gaudium_platform_empty::Binding;
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! # fn main() {
+//! EventThread::<Binding, _>::run_and_abort_with(|context| {
+//!     let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+//!     let collector = Rc::new(RefCell::new(Collector::default()));
+//!     context.set_instrumentation(collector.clone());
+//!     (window.handle(), StatefulReactor::from((
+//!         (window, collector),
+//!         |(_, collector): &mut (Window<Binding>, Rc<RefCell<Collector>>),
+//!          _: &ThreadContext,
+//!          event| match event {
+//!             Event::Application {
+//!                 event: ApplicationEvent::Flushed,
+//!                 ..
+//!             } => {
+//!                 println!("{:?}", collector.borrow().statistics());
+//!                 Continue(())
+//!             }
+//!             Event::Window {
+//!                 event: WindowEvent::Closed(..),
+//!                 ..
+//!             } => Abort,
+//!             _ => Continue(()),
+//!         },
+//!     )))
+//! })
+//! # }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::event::Resumption;
+use crate::reactor::{Poll, Reaction, ThreadContext};
+
+/// Observes the phases of an event loop.
+///
+/// All functions have empty default implementations, so implementations need
+/// only observe the phases in which they are interested. Functions are always
+/// called on the event thread and never while the reactor is reacting or
+/// polling.
+pub trait Instrumentation: 'static {
+    /// Called before the event loop dispatches pending platform events.
+    fn flush_started(&mut self, _timestamp: Instant) {}
+
+    /// Called after the event loop has dispatched pending platform events,
+    /// including the `Flushed` event.
+    fn flush_completed(&mut self, _timestamp: Instant) {}
+
+    /// Called before an event is dispatched to the reactor.
+    ///
+    /// `depth` is the number of events that are queued by the event thread
+    /// behind the dispatched event. Not all platforms queue events.
+    fn dispatch_started(&mut self, _timestamp: Instant, _depth: usize) {}
+
+    /// Called after the reactor has reacted to an event.
+    fn dispatch_completed(&mut self, _timestamp: Instant) {}
+
+    /// Called after the reactor has been polled.
+    fn polled(&mut self, _timestamp: Instant, _reaction: Reaction<Poll>) {}
+
+    /// Called when the event loop resumes, before the `Resumed` event is
+    /// dispatched.
+    fn resumed(&mut self, _timestamp: Instant, _resumption: Resumption) {}
+}
+
+impl<I> Instrumentation for Rc<RefCell<I>>
+where
+    I: Instrumentation,
+{
+    fn flush_started(&mut self, timestamp: Instant) {
+        self.borrow_mut().flush_started(timestamp)
+    }
+
+    fn flush_completed(&mut self, timestamp: Instant) {
+        self.borrow_mut().flush_completed(timestamp)
+    }
+
+    fn dispatch_started(&mut self, timestamp: Instant, depth: usize) {
+        self.borrow_mut().dispatch_started(timestamp, depth)
+    }
+
+    fn dispatch_completed(&mut self, timestamp: Instant) {
+        self.borrow_mut().dispatch_completed(timestamp)
+    }
+
+    fn polled(&mut self, timestamp: Instant, reaction: Reaction<Poll>) {
+        self.borrow_mut().polled(timestamp, reaction)
+    }
+
+    fn resumed(&mut self, timestamp: Instant, resumption: Resumption) {
+        self.borrow_mut().resumed(timestamp, resumption)
+    }
+}
+
+/// Invokes the instrumentation of an event thread, if any.
+///
+/// This is used by platforms to report the phases of their event loops. The
+/// function is not called if no instrumentation is installed, so arguments
+/// like timestamps should be computed within it.
+pub fn instrument<F>(context: &ThreadContext, f: F)
+where
+    F: FnOnce(&mut dyn Instrumentation),
+{
+    if let Ok(mut instrumentation) = context.instrumentation.try_borrow_mut() {
+        if let Some(ref mut instrumentation) = *instrumentation {
+            f(instrumentation.as_mut());
+        }
+    }
+}
+
+/// Rolling statistics collected by a `Collector`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Statistics {
+    /// The rate at which events are dispatched to the reactor.
+    pub events_per_second: f64,
+    /// The maximum time spent dispatching a single event.
+    pub max_dispatch: Duration,
+    /// The maximum time spent flushing platform events.
+    pub max_flush: Duration,
+    /// The maximum number of events queued behind a dispatched event.
+    pub max_queue_depth: usize,
+    /// The mean delay between a `WaitUntil` instant and the resumption of the
+    /// event loop, if any `WaitUntil` poll has timed out.
+    pub mean_wake_latency: Option<Duration>,
+    /// The maximum delay between a `WaitUntil` instant and the resumption of
+    /// the event loop, if any `WaitUntil` poll has timed out.
+    pub max_wake_latency: Option<Duration>,
+}
+
+/// An instrumentation that collects rolling statistics.
+///
+/// Statistics are computed over samples collected within a window of time
+/// preceding the query, which is one second by default.
+pub struct Collector {
+    window: Duration,
+    dispatches: VecDeque<(Instant, Duration, usize)>,
+    flushes: VecDeque<(Instant, Duration)>,
+    wakes: VecDeque<(Instant, Duration)>,
+    dispatch: Option<(Instant, usize)>,
+    flush: Option<Instant>,
+    deadline: Option<Instant>,
+}
+
+impl Collector {
+    /// Creates a collector that computes statistics over the given window of
+    /// time.
+    pub fn new(window: Duration) -> Self {
+        Collector {
+            window,
+            dispatches: VecDeque::new(),
+            flushes: VecDeque::new(),
+            wakes: VecDeque::new(),
+            dispatch: None,
+            flush: None,
+            deadline: None,
+        }
+    }
+
+    /// Gets statistics for the samples collected within the window preceding
+    /// the current instant.
+    pub fn statistics(&self) -> Statistics {
+        let now = Instant::now();
+        let is_recent =
+            |timestamp: Instant| now.saturating_duration_since(timestamp) <= self.window;
+        let mut statistics = Statistics::default();
+        let mut count = 0usize;
+        for &(_, duration, depth) in self
+            .dispatches
+            .iter()
+            .filter(|&&(timestamp, ..)| is_recent(timestamp))
+        {
+            count += 1;
+            statistics.max_dispatch = statistics.max_dispatch.max(duration);
+            statistics.max_queue_depth = statistics.max_queue_depth.max(depth);
+        }
+        if !self.window.is_zero() {
+            statistics.events_per_second = count as f64 / self.window.as_secs_f64();
+        }
+        statistics.max_flush = self
+            .flushes
+            .iter()
+            .filter(|&&(timestamp, _)| is_recent(timestamp))
+            .map(|&(_, duration)| duration)
+            .max()
+            .unwrap_or_default();
+        let wakes: Vec<_> = self
+            .wakes
+            .iter()
+            .filter(|&&(timestamp, _)| is_recent(timestamp))
+            .map(|&(_, latency)| latency)
+            .collect();
+        if !wakes.is_empty() {
+            statistics.mean_wake_latency =
+                Some(wakes.iter().sum::<Duration>() / wakes.len() as u32);
+            statistics.max_wake_latency = wakes.iter().max().cloned();
+        }
+        statistics
+    }
+
+    fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        let is_stale = |timestamp: Instant| now.saturating_duration_since(timestamp) > window;
+        while self
+            .dispatches
+            .front()
+            .is_some_and(|&(timestamp, ..)| is_stale(timestamp))
+        {
+            self.dispatches.pop_front();
+        }
+        while self
+            .flushes
+            .front()
+            .is_some_and(|&(timestamp, _)| is_stale(timestamp))
+        {
+            self.flushes.pop_front();
+        }
+        while self
+            .wakes
+            .front()
+            .is_some_and(|&(timestamp, _)| is_stale(timestamp))
+        {
+            self.wakes.pop_front();
+        }
+    }
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Collector::new(Duration::from_secs(1))
+    }
+}
+
+impl Instrumentation for Collector {
+    fn flush_started(&mut self, timestamp: Instant) {
+        self.flush = Some(timestamp);
+    }
+
+    fn flush_completed(&mut self, timestamp: Instant) {
+        if let Some(started) = self.flush.take() {
+            self.flushes
+                .push_back((timestamp, timestamp.saturating_duration_since(started)));
+        }
+        self.prune(timestamp);
+    }
+
+    fn dispatch_started(&mut self, timestamp: Instant, depth: usize) {
+        self.dispatch = Some((timestamp, depth));
+    }
+
+    fn dispatch_completed(&mut self, timestamp: Instant) {
+        if let Some((started, depth)) = self.dispatch.take() {
+            self.dispatches.push_back((
+                timestamp,
+                timestamp.saturating_duration_since(started),
+                depth,
+            ));
+        }
+    }
+
+    fn polled(&mut self, _: Instant, reaction: Reaction<Poll>) {
+        self.deadline = match reaction {
+            Reaction::Continue(Poll::WaitUntil(until)) => Some(until),
+            _ => None,
+        };
+    }
+
+    fn resumed(&mut self, timestamp: Instant, resumption: Resumption) {
+        if let (Some(deadline), Resumption::Timeout(_)) = (self.deadline.take(), resumption) {
+            self.wakes
+                .push_back((timestamp, timestamp.saturating_duration_since(deadline)));
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Lifetimes of the devices and windows of an event thread.
+//!
+//! See the `registry` module.
+
+use std::marker::PhantomData;
+
+use crate::event::{Event, InputEvent, WindowCloseState, WindowEvent};
+use crate::platform::PlatformBinding;
+use crate::reactor::{
+    merge, EventBatch, PanicPolicy, Part, Poll, Reaction, Reactor, ThreadContext,
+};
+use crate::registry;
+use crate::window::WindowHandle;
+
+/// A reactor that maintains the stable identifiers of devices and windows as
+/// events are dispatched to another reactor.
+///
+/// Events for windows that have been closed are discarded. Closed windows are
+/// forgotten when the reactor is aborted.
+pub(crate) struct LifetimeReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    // Events of the batch being dispatched that have been detached. The
+    // buffer is reused by each batch.
+    batch: Vec<Event<P>>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> LifetimeReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        LifetimeReactor {
+            reactor,
+            batch: vec![],
+            phantom: PhantomData,
+        }
+    }
+
+    /// Dispatches a batch of events that do not change the registry.
+    fn react_batch(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        let LifetimeReactor {
+            ref mut reactor,
+            ref mut batch,
+            ..
+        } = *self;
+        batch.extend(events.into_iter().filter_map(detach));
+        let reaction = if batch.is_empty() {
+            Reaction::Continue(())
+        }
+        else {
+            reactor.react_all(context, batch.as_slice().into())
+        };
+        batch.clear();
+        reaction
+    }
+}
+
+impl<P, R> Reactor<P> for LifetimeReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        let event = match detach(event) {
+            Some(event) => event,
+            None => return Reaction::Continue(()),
+        };
+        match event {
+            // Assign a new identifier, because the platform may have recycled
+            // the handle of a disconnected device.
+            Event::Input {
+                device,
+                event: InputEvent::Connected { .. },
+                ..
+            } => {
+                registry::register(device);
+                self.reactor.react(context, event)
+            }
+            // Forget the identifier only after the reactor has observed the
+            // disconnection so that it can query the identifier.
+            Event::Input {
+                device,
+                event: InputEvent::Disconnected,
+                ..
+            } => {
+                let reaction = self.reactor.react(context, event);
+                registry::unregister(device);
+                reaction
+            }
+            // No further events are dispatched for the window until its
+            // handle is recycled. The identifier of the window is retained
+            // until this event has been dispatched, even if the window has
+            // already been dropped.
+            Event::Window {
+                window,
+                event: WindowEvent::Closed(WindowCloseState::Committed),
+                ..
+            } => {
+                let reaction = self.reactor.react(context, event);
+                registry::close(window);
+                reaction
+            }
+            _ => self.reactor.react(context, event),
+        }
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        // Events that change the registry are dispatched on their own so that
+        // it is consistent with the events that precede and follow them.
+        events
+            .split(is_registry_changed)
+            .fold(Reaction::Continue(()), |reaction, part| {
+                merge(
+                    reaction,
+                    match part {
+                        Part::Batch(events) => self.react_batch(context, events),
+                        Part::Event(event) => self.react(context, event),
+                    },
+                )
+            })
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        self.reactor.abort();
+        // Windows dropped by the reactor may never be reported as closed, and
+        // no further events are dispatched for windows that have been closed.
+        registry::release_retired::<WindowHandle<P>>();
+        registry::clear_closed();
+    }
+}
+
+/// Determines if an event changes the registry of devices and windows.
+fn is_registry_changed<P>(event: &Event<P>) -> bool
+where
+    P: PlatformBinding,
+{
+    matches!(
+        event,
+        Event::Input {
+            event: InputEvent::Connected { .. } | InputEvent::Disconnected,
+            ..
+        } | Event::Window {
+            event: WindowEvent::Closed(WindowCloseState::Committed),
+            ..
+        }
+    )
+}
+
+/// Discards events for windows that have been destroyed and detaches input
+/// from them.
+///
+/// Platforms purge pending events when a window is destroyed, but input may
+/// still be attributed to its handle.
+fn detach<P>(event: Event<P>) -> Option<Event<P>>
+where
+    P: PlatformBinding,
+{
+    match event {
+        Event::Window { window, .. } if registry::is_closed(window) => None,
+        Event::Input {
+            device,
+            window: Some(window),
+            event,
+            focus,
+            timestamp,
+        } if registry::is_closed(window) => Some(Event::Input {
+            device,
+            window: None,
+            event,
+            focus,
+            timestamp,
+        }),
+        event => Some(event),
+    }
+}
@@ -0,0 +1,248 @@
+//! Event filters executed before the reactor.
+//!
+//! A `PreFilter` observes each event before it is dispatched to the reactor
+//! of an event thread. Pre-filters can pass events unchanged, modify them, or
+//! consume them, in which case the reactor never receives them. This is
+//! useful for code that is independent of the reactor, such as global hotkey
+//! handlers and debug overlays.
+//!
+//! Pre-filters are installed via `ThreadContext::add_pre_filter` and execute
+//! in the order in which they are added. Pre-filters are dropped when the
+//! event loop stops and the reactor is aborted.
+//!
+//! # Examples
+//!
+//! Consuming a key before it reaches the reactor:
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::prelude::*;
+//! use gaudium_core::reactor::{EventThread, StatefulReactor, ThreadContext};
+//! use gaudium_core::window::{Window, WindowBuilder};
+//! use gaudium_platform_empty::Binding;
+//!
+//! # fn main() {
+//! EventThread::<Binding, _>::run_and_abort_with(|context| {
+//!     let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+//!     context.add_pre_filter(|_: &ThreadContext, event: Event<Binding>| match event {
+//!         Event::Input {
+//!             event:
+//!                 InputEvent::KeyboardKeyChanged {
+//!                     keycode: Some(KeyCode::F12),
+//!                     state: ElementState::Pressed,
+//!                     ..
+//!                 },
+//!             ..
+//!         } => {
+//!             println!("Toggled the debug overlay.");
+//!             None
+//!         }
+//!         _ => Some(event),
+//!     });
+//!     (window.handle(), StatefulReactor::from((
+//!         window,
+//!         |_: &mut Window<Binding>, _: &ThreadContext, event| match event {
+//!             Event::Window {
+//!                 event: WindowEvent::Closed(..),
+//!                 ..
+//!             } => Abort,
+//!             _ => Continue(()),
+//!         },
+//!     )))
+//! })
+//! # }
+//! ```
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem;
+
+use crate::event::Event;
+use crate::platform::PlatformBinding;
+use crate::reactor::{EventBatch, PanicPolicy, Poll, Reaction, Reactor, ThreadContext};
+
+thread_local! {
+    static PRE_FILTERS: RefCell<PreFilters> = RefCell::new(PreFilters::default());
+}
+
+/// Pre-filters of an event thread.
+///
+/// Pre-filters are stored as `Box<dyn PreFilter<P>>`, but thread-local state
+/// cannot be generic, so they are erased.
+#[derive(Default)]
+struct PreFilters {
+    filters: Vec<Box<dyn Any>>,
+    // Whether or not the pre-filters have been cleared while executing.
+    is_cleared: bool,
+}
+
+/// Filters events before they are dispatched to the reactor.
+///
+/// This is implemented for functions and closures of the form
+/// `FnMut(&ThreadContext, Event<P>) -> Option<Event<P>>`.
+pub trait PreFilter<P>: 'static
+where
+    P: PlatformBinding,
+{
+    /// Filters an event.
+    ///
+    /// Returns the event, which may be modified, to pass it to subsequent
+    /// pre-filters and the reactor or `None` to consume it.
+    fn filter(&mut self, context: &ThreadContext, event: Event<P>) -> Option<Event<P>>;
+}
+
+impl<P, F> PreFilter<P> for F
+where
+    P: PlatformBinding,
+    F: 'static + FnMut(&ThreadContext, Event<P>) -> Option<Event<P>>,
+{
+    fn filter(&mut self, context: &ThreadContext, event: Event<P>) -> Option<Event<P>> {
+        (self)(context, event)
+    }
+}
+
+pub(crate) fn add<P>(filter: Box<dyn PreFilter<P>>)
+where
+    P: PlatformBinding,
+{
+    PRE_FILTERS.with(|installed| installed.borrow_mut().filters.push(Box::new(filter)));
+}
+
+pub(crate) fn clear() {
+    let filters = PRE_FILTERS.with(|installed| {
+        let mut installed = installed.borrow_mut();
+        installed.is_cleared = true;
+        mem::take(&mut installed.filters)
+    });
+    // Drop the pre-filters outside of the borrow.
+    drop(filters);
+}
+
+fn is_empty() -> bool {
+    PRE_FILTERS.with(|installed| installed.borrow().filters.is_empty())
+}
+
+/// Executes the pre-filters of the event thread against an event.
+///
+/// Returns `None` if a pre-filter consumed the event. Pre-filters are removed
+/// while they execute, so they may add or clear pre-filters.
+fn filter<P>(context: &ThreadContext, event: Event<P>) -> Option<Event<P>>
+where
+    P: PlatformBinding,
+{
+    let mut filters = PRE_FILTERS.with(|installed| {
+        let mut installed = installed.borrow_mut();
+        installed.is_cleared = false;
+        mem::take(&mut installed.filters)
+    });
+    if filters.is_empty() {
+        return Some(event);
+    }
+    let mut event = Some(event);
+    for filter in filters.iter_mut() {
+        if let Some(filter) = filter.downcast_mut::<Box<dyn PreFilter<P>>>() {
+            event = match event {
+                Some(event) => filter.filter(context, event),
+                None => break,
+            };
+        }
+    }
+    // Restore the pre-filters before any that were added while executing,
+    // unless they were cleared.
+    let filters = PRE_FILTERS.with(move |installed| {
+        let mut installed = installed.borrow_mut();
+        if installed.is_cleared {
+            Some(filters)
+        }
+        else {
+            let added = mem::replace(&mut installed.filters, filters);
+            installed.filters.extend(added);
+            None
+        }
+    });
+    drop(filters);
+    event
+}
+
+/// A reactor that executes the pre-filters of the event thread before
+/// dispatching events to another reactor.
+///
+/// The pre-filters are cleared when the reactor is aborted.
+pub(crate) struct PreFilterReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    // Events of the batch being dispatched that have passed the pre-filters.
+    // The buffer is reused by each batch.
+    batch: Vec<Event<P>>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> PreFilterReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        PreFilterReactor {
+            reactor,
+            batch: vec![],
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for PreFilterReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        match filter(context, event) {
+            Some(event) => self.reactor.react(context, event),
+            None => Reaction::Continue(()),
+        }
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        // Pre-filters added while the batch is dispatched apply to the next
+        // batch.
+        if is_empty() {
+            return self.reactor.react_all(context, events);
+        }
+        let PreFilterReactor {
+            ref mut reactor,
+            ref mut batch,
+            ..
+        } = *self;
+        batch.extend(
+            events
+                .into_iter()
+                .filter_map(|event| filter(context, event)),
+        );
+        let reaction = if batch.is_empty() {
+            Reaction::Continue(())
+        }
+        else {
+            reactor.react_all(context, batch.as_slice().into())
+        };
+        batch.clear();
+        reaction
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.reactor.poll(context)
+    }
+
+    fn abort(self) {
+        clear();
+        self.reactor.abort();
+    }
+}
@@ -0,0 +1,192 @@
+//! Pull-based event consumption.
+//!
+//! An `EventStream` is an asynchronous `Stream` of the events dispatched by an
+//! event thread. Events are sent to the stream by an `EventSender`, which is a
+//! reactor. Streams are typically consumed by an async runtime executing on
+//! another thread, while the event thread executes the platform's event loop.
+//!
+//! Streams are bounded. When a stream is full, the event thread blocks until
+//! the stream is polled, so slow consumers apply back pressure to the event
+//! loop. If the stream is dropped, then the event loop is aborted. Likewise,
+//! the stream terminates when the event loop stops.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::reactor::EventThread;
+//! use gaudium_core::window::WindowBuilder;
+//! use gaudium_platform_empty::Binding;
+//! use std::thread;
+//!
+//! # fn main() {
+//! EventThread::<Binding, _>::run_with_stream(64, |context, stream| {
+//!     thread::spawn(move || {
+//!         // Consume the stream using an async runtime.
+//!         # drop(stream);
+//!     });
+//!     WindowBuilder::<Binding>::default().build(context).unwrap()
+//! })
+//! # }
+//! ```
+
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{self, Context, Waker};
+
+use crate::event::Event;
+use crate::platform::PlatformBinding;
+use crate::reactor::{Poll, Reaction, Reactor, ThreadContext};
+use crate::window::Window;
+
+struct State<P>
+where
+    P: PlatformBinding,
+{
+    events: VecDeque<Event<P>>,
+    waker: Option<Waker>,
+    is_sender_dropped: bool,
+    is_stream_dropped: bool,
+}
+
+struct Shared<P>
+where
+    P: PlatformBinding,
+{
+    state: Mutex<State<P>>,
+    capacity: usize,
+    not_full: Condvar,
+}
+
+/// Creates a bounded event stream and a reactor that sends events to it.
+///
+/// The capacity is at least one.
+pub fn channel<P>(capacity: usize) -> (EventSender<P>, EventStream<P>)
+where
+    P: PlatformBinding,
+{
+    let capacity = capacity.max(1);
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            events: VecDeque::with_capacity(capacity),
+            waker: None,
+            is_sender_dropped: false,
+            is_stream_dropped: false,
+        }),
+        capacity,
+        not_full: Condvar::new(),
+    });
+    (
+        EventSender {
+            shared: shared.clone(),
+            sink: None,
+        },
+        EventStream { shared },
+    )
+}
+
+/// A reactor that sends events to an `EventStream`.
+///
+/// Aborts if the stream has been dropped and uses the `Wait` poll mode.
+pub struct EventSender<P>
+where
+    P: PlatformBinding,
+{
+    shared: Arc<Shared<P>>,
+    // Keeps the sink alive for the duration of the event loop when used with
+    // `EventThread::run_with_stream`.
+    sink: Option<Window<P>>,
+}
+
+impl<P> EventSender<P>
+where
+    P: PlatformBinding,
+{
+    pub(crate) fn with_sink(mut self, sink: Window<P>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+}
+
+impl<P> Drop for EventSender<P>
+where
+    P: PlatformBinding,
+{
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.is_sender_dropped = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<P> Reactor<P> for EventSender<P>
+where
+    P: PlatformBinding,
+{
+    fn react(&mut self, _: &ThreadContext, event: Event<P>) -> Reaction {
+        let mut state = self.shared.state.lock().unwrap();
+        while state.events.len() >= self.shared.capacity && !state.is_stream_dropped {
+            state = self.shared.not_full.wait(state).unwrap();
+        }
+        if state.is_stream_dropped {
+            return Reaction::Abort;
+        }
+        state.events.push_back(event);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        Reaction::Continue(())
+    }
+
+    fn poll(&mut self, _: &ThreadContext) -> Reaction<Poll> {
+        Poll::Wait.into()
+    }
+}
+
+/// A stream of the events dispatched by an event thread.
+///
+/// See the module documentation.
+pub struct EventStream<P>
+where
+    P: PlatformBinding,
+{
+    shared: Arc<Shared<P>>,
+}
+
+impl<P> Drop for EventStream<P>
+where
+    P: PlatformBinding,
+{
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().is_stream_dropped = true;
+        self.shared.not_full.notify_all();
+    }
+}
+
+impl<P> Stream for EventStream<P>
+where
+    P: PlatformBinding,
+{
+    type Item = Event<P>;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> task::Poll<Option<Self::Item>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if let Some(event) = state.events.pop_front() {
+            self.shared.not_full.notify_all();
+            task::Poll::Ready(Some(event))
+        }
+        else if state.is_sender_dropped {
+            task::Poll::Ready(None)
+        }
+        else {
+            state.waker = Some(context.waker().clone());
+            task::Poll::Pending
+        }
+    }
+}
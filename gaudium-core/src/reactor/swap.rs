@@ -0,0 +1,124 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::mem;
+
+use crate::event::Event;
+use crate::platform::PlatformBinding;
+use crate::reactor::{DynReactor, EventBatch, PanicPolicy, Poll, Reaction, Reactor, ThreadContext};
+
+thread_local! {
+    // The reactor that replaces the active reactor when the current reaction
+    // returns. This is a `Box<dyn DynReactor<P>>`, which is erased because
+    // thread-local storage cannot be generic.
+    static REPLACEMENT: RefCell<Option<Box<dyn Any>>> = const { RefCell::new(None) };
+}
+
+/// Installs a reactor that replaces the active reactor of the event thread.
+///
+/// Any previously installed replacement is dropped.
+pub(crate) fn install<P>(reactor: Box<dyn DynReactor<P>>)
+where
+    P: PlatformBinding,
+{
+    let previous = REPLACEMENT.with(|replacement| {
+        replacement
+            .borrow_mut()
+            .replace(Box::new(reactor) as Box<dyn Any>)
+    });
+    drop(previous);
+}
+
+/// Drops any installed replacement.
+pub(crate) fn clear() {
+    let replacement = REPLACEMENT.with(|replacement| replacement.borrow_mut().take());
+    drop(replacement);
+}
+
+fn take<P>() -> Option<Box<dyn DynReactor<P>>>
+where
+    P: PlatformBinding,
+{
+    REPLACEMENT
+        .with(|replacement| replacement.borrow_mut().take())
+        .and_then(|replacement| replacement.downcast::<Box<dyn DynReactor<P>>>().ok())
+        .map(|replacement| *replacement)
+}
+
+/// A reactor that can be replaced by another reactor while the event loop
+/// is running. See `ThreadContext::swap_reactor`.
+///
+/// The replacement takes effect after each reaction and poll, at which point
+/// the replaced reactor is aborted.
+pub(crate) enum SwapReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    Initial(R),
+    Swapped(Box<dyn DynReactor<P>>),
+}
+
+impl<P, R> SwapReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        SwapReactor::Initial(reactor)
+    }
+
+    /// Replaces the reactor if a replacement has been installed.
+    fn swap(&mut self) {
+        if let Some(reactor) = take::<P>() {
+            match mem::replace(self, SwapReactor::Swapped(reactor)) {
+                SwapReactor::Initial(reactor) => reactor.abort(),
+                SwapReactor::Swapped(reactor) => reactor.abort(),
+            }
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for SwapReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    // The panic policy cannot change while the event loop is running, so the
+    // policy of the initial reactor is used.
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        let reaction = match self {
+            SwapReactor::Initial(reactor) => Reactor::react(reactor, context, event),
+            SwapReactor::Swapped(reactor) => Reactor::react(reactor, context, event),
+        };
+        self.swap();
+        reaction
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        let reaction = match self {
+            SwapReactor::Initial(reactor) => Reactor::react_all(reactor, context, events),
+            SwapReactor::Swapped(reactor) => Reactor::react_all(reactor, context, events),
+        };
+        self.swap();
+        reaction
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        let reaction = match self {
+            SwapReactor::Initial(reactor) => Reactor::poll(reactor, context),
+            SwapReactor::Swapped(reactor) => Reactor::poll(reactor, context),
+        };
+        self.swap();
+        reaction
+    }
+
+    fn abort(self) {
+        clear();
+        match self {
+            SwapReactor::Initial(reactor) => reactor.abort(),
+            SwapReactor::Swapped(reactor) => Reactor::abort(reactor),
+        }
+    }
+}
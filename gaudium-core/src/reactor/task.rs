@@ -0,0 +1,260 @@
+//! Futures executed on the event thread.
+//!
+//! Each event thread embeds a single-threaded executor. Futures are spawned
+//! onto the executor via `ThreadContext::spawn_local` and are polled by the
+//! event thread between event dispatches, so they may freely access
+//! thread-local state and need not implement `Send`.
+//!
+//! When a spawned future is woken, the executor uses the platform's wake-up
+//! primitive to resume the event loop, even if the reactor has requested the
+//! `Wait` poll mode. Futures can therefore be woken from any thread, such as
+//! by a channel or timer running elsewhere.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::prelude::*;
+//! use gaudium_core::reactor::{EventThread, StatefulReactor, ThreadContext};
+//! use gaudium_core::window::{Window, WindowBuilder};
+//! use gaudium_platform_empty::Binding;
+//!
+//! # fn main() {
+//! EventThread::<Binding, _>::run_and_abort_with(|context| {
+//!     let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+//!     context.spawn_local(async {
+//!         println!("Hello from the event thread!");
+//!     });
+//!     (window.handle(), StatefulReactor::from((
+//!         window,
+//!         |_: &mut Window<Binding>, _: &ThreadContext, event| match event {
+//!             Event::Window {
+//!                 event: WindowEvent::Closed(..),
+//!                 ..
+//!             } => Abort,
+//!             _ => Continue(()),
+//!         },
+//!     )))
+//! })
+//! # }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Wake, Waker};
+
+use crate::event::Event;
+use crate::platform::PlatformBinding;
+use crate::reactor::{EventBatch, PanicPolicy, Poll, Reaction, Reactor, ThreadContext};
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+type ReadyQueue = Arc<Mutex<VecDeque<usize>>>;
+pub(crate) type WakeFn = Arc<dyn Fn() + Send + Sync>;
+
+thread_local! {
+    static EXECUTOR: RefCell<Executor> = RefCell::new(Executor::default());
+}
+
+struct Task {
+    future: Option<LocalFuture>,
+    waker: Waker,
+}
+
+#[derive(Default)]
+struct Executor {
+    tasks: Vec<Option<Task>>,
+    ready: ReadyQueue,
+    wake: Option<WakeFn>,
+}
+
+/// Wakes a task by queuing it and resuming the event loop.
+struct TaskWaker {
+    id: usize,
+    ready: ReadyQueue,
+    wake: Option<WakeFn>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.ready.lock().unwrap().push_back(self.id);
+        if let Some(ref wake) = self.wake {
+            wake();
+        }
+    }
+}
+
+pub(crate) fn spawn<F>(future: F)
+where
+    F: 'static + Future<Output = ()>,
+{
+    EXECUTOR.with(|executor| {
+        let mut executor = executor.borrow_mut();
+        let id = executor
+            .tasks
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or(executor.tasks.len());
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            ready: executor.ready.clone(),
+            wake: executor.wake.clone(),
+        }));
+        let task = Some(Task {
+            future: Some(Box::pin(future)),
+            waker,
+        });
+        if id == executor.tasks.len() {
+            executor.tasks.push(task);
+        }
+        else {
+            executor.tasks[id] = task;
+        }
+        executor.ready.lock().unwrap().push_back(id);
+    });
+}
+
+/// Polls all tasks that have been woken.
+///
+/// Tasks are removed from the executor while they are polled, so futures may
+/// spawn other futures.
+fn run_ready() {
+    let ready = EXECUTOR.with(|executor| mem::take(&mut *executor.borrow().ready.lock().unwrap()));
+    for id in ready {
+        let task = EXECUTOR.with(|executor| {
+            executor
+                .borrow_mut()
+                .tasks
+                .get_mut(id)
+                .and_then(Option::as_mut)
+                .and_then(|task| {
+                    task.future
+                        .take()
+                        .map(|future| (future, task.waker.clone()))
+                })
+        });
+        // Tasks may be woken more than once before they are polled, in which
+        // case the future has already been taken or has completed.
+        if let Some((mut future, waker)) = task {
+            let is_pending = future
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_pending();
+            EXECUTOR.with(|executor| {
+                let mut executor = executor.borrow_mut();
+                if is_pending {
+                    if let Some(Some(task)) = executor.tasks.get_mut(id) {
+                        task.future = Some(future);
+                    }
+                }
+                else {
+                    executor.tasks[id] = None;
+                }
+            });
+        }
+    }
+}
+
+/// Installs the platform's wake-up primitive for the executor of the
+/// event thread.
+///
+/// This must be called before any tasks are spawned.
+pub(crate) fn install<P>(context: &ThreadContext)
+where
+    P: PlatformBinding,
+{
+    use crate::platform::Waker as _;
+
+    let waker = P::Waker::from_context(context);
+    EXECUTOR.with(|executor| {
+        let executor = mem::replace(
+            &mut *executor.borrow_mut(),
+            Executor {
+                wake: Some(Arc::new(move || waker.wake())),
+                ..Default::default()
+            },
+        );
+        drop(executor);
+    });
+}
+
+/// Gets the function that resumes the event loop of the event thread.
+pub(crate) fn wake() -> Option<WakeFn> {
+    EXECUTOR.with(|executor| executor.borrow().wake.clone())
+}
+
+fn is_ready() -> bool {
+    EXECUTOR.with(|executor| !executor.borrow().ready.lock().unwrap().is_empty())
+}
+
+/// A reactor that executes tasks in the poll phase of the event loop before
+/// querying another reactor.
+pub(crate) struct TaskReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> TaskReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        TaskReactor {
+            reactor,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for TaskReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        self.reactor.react(context, event)
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        self.reactor.react_all(context, events)
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        run_ready();
+        let reaction = self.reactor.poll(context);
+        // Resume immediately if tasks were woken while polling.
+        if is_ready() {
+            reaction.map(|_| Poll::Ready)
+        }
+        else {
+            reaction
+        }
+    }
+
+    fn abort(self) {
+        // Drop any remaining tasks before the reactor, because futures may
+        // reference state shared with the reactor.
+        EXECUTOR.with(|executor| {
+            let tasks = mem::take(&mut *executor.borrow_mut());
+            drop(tasks);
+        });
+        self.reactor.abort();
+    }
+}
@@ -0,0 +1,97 @@
+use std::any::Any;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::event::Event;
+use crate::platform::PlatformBinding;
+use crate::reactor::{EventBatch, PanicPolicy, Poll, Reaction, Reactor, ThreadContext};
+
+/// A reactor that catches panics in another reactor according to its
+/// `PanicPolicy`.
+///
+/// When a panic is caught, the payload is captured and the event loop is
+/// aborted. Events dispatched after the panic are not forwarded to the
+/// reactor. The panic resumes when the event thread aborts the reactor, which
+/// occurs on the thread that started the event thread and outside of any
+/// platform callbacks.
+pub(crate) struct UnwindReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    payload: Option<Box<dyn Any + Send>>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> UnwindReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R) -> Self {
+        UnwindReactor {
+            reactor,
+            payload: None,
+            phantom: PhantomData,
+        }
+    }
+
+    fn catch<T, F>(&mut self, f: F) -> Reaction<T>
+    where
+        F: FnOnce(&mut R) -> Reaction<T>,
+    {
+        if self.payload.is_some() {
+            return Reaction::Abort;
+        }
+        match R::PANIC_POLICY {
+            PanicPolicy::Propagate => {
+                let reactor = &mut self.reactor;
+                match panic::catch_unwind(AssertUnwindSafe(move || f(reactor))) {
+                    Ok(reaction) => reaction,
+                    Err(payload) => {
+                        self.payload = Some(payload);
+                        Reaction::Abort
+                    }
+                }
+            }
+            PanicPolicy::NoPanic => f(&mut self.reactor),
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for UnwindReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        self.catch(move |reactor| reactor.react(context, event))
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        self.catch(move |reactor| reactor.react_all(context, events))
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.catch(move |reactor| reactor.poll(context))
+    }
+
+    fn abort(self) {
+        let UnwindReactor {
+            reactor, payload, ..
+        } = self;
+        if let Some(payload) = payload {
+            // The state of the reactor may be inconsistent after a panic, so
+            // any panic while aborting is discarded in favor of the original
+            // panic.
+            let _ = panic::catch_unwind(AssertUnwindSafe(move || reactor.abort()));
+            panic::resume_unwind(payload);
+        }
+        else {
+            reactor.abort();
+        }
+    }
+}
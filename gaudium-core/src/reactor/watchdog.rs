@@ -0,0 +1,282 @@
+//! Detection of unresponsive reactors.
+//!
+//! While a reactor reacts to an event, its event thread cannot dispatch
+//! platform messages, so its windows do not paint and cannot be closed. A
+//! `Watchdog` measures each call into the reactor from another thread and
+//! invokes a callback on that thread when a call exceeds its budget. Because
+//! the callback executes while the reactor is still blocked, it can report
+//! stalls as they occur, such as by logging or capturing a backtrace of the
+//! process with a debugger.
+//!
+//! The callback is invoked at most once per call into the reactor. Watchdogs
+//! are configured via `EventThreadBuilder::with_watchdog` and are dropped when
+//! the event loop stops.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::prelude::*;
+//! use gaudium_core::reactor::watchdog::Watchdog;
+//! use gaudium_core::reactor::{EventThreadBuilder, StatefulReactor, ThreadContext};
+//! use gaudium_core::window::{Window, WindowBuilder};
+//! use gaudium_platform_empty::Binding;
+//! use std::time::Duration;
+//!
+//! # fn main() {
+//! EventThreadBuilder::<Binding>::default()
+//!     .with_watchdog(Watchdog::new(Duration::from_millis(250), |stall| {
+//!         eprintln!("reactor unresponsive for {:?}", stall.started.elapsed());
+//!     }))
+//!     .run_and_abort_with(|context| {
+//!         let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+//!         (window.handle(), StatefulReactor::from((
+//!             window,
+//!             |_: &mut Window<Binding>, _: &ThreadContext, event| match event {
+//!                 Event::Window {
+//!                     event: WindowEvent::Closed(..),
+//!                     ..
+//!                 } => Abort,
+//!                 _ => Continue(()),
+//!             },
+//!         )))
+//!     })
+//! # }
+//! ```
+
+use std::marker::PhantomData;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::event::Event;
+use crate::platform::PlatformBinding;
+use crate::reactor::{EventBatch, PanicPolicy, Poll, Reaction, Reactor, ThreadContext};
+
+/// A call into a reactor that exceeded the budget of a `Watchdog`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stall {
+    /// The instant at which the call into the reactor started.
+    pub started: Instant,
+    pub budget: Duration,
+}
+
+/// State shared between an event thread and its watchdog thread.
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    condition: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    // The instant at which the current call into the reactor started, if any.
+    started: Option<Instant>,
+    // Changes whenever a call into the reactor starts or completes.
+    serial: u64,
+    is_stopped: bool,
+}
+
+/// Monitors the duration of calls into a reactor.
+///
+/// A watchdog owns a thread that invokes its callback when a call into the
+/// reactor exceeds its budget. The thread is stopped when the watchdog is
+/// dropped.
+pub struct Watchdog {
+    budget: Duration,
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Creates a watchdog with the given budget and callback.
+    ///
+    /// The callback is invoked on the thread of the watchdog, not the event
+    /// thread.
+    pub fn new<F>(budget: Duration, mut f: F) -> Self
+    where
+        F: 'static + FnMut(Stall) + Send,
+    {
+        let shared = Arc::new(Shared::default());
+        let thread = {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("gaudium-watchdog".into())
+                .spawn(move || {
+                    let mut state = shared.state.lock().unwrap();
+                    while !state.is_stopped {
+                        let started = match state.started {
+                            Some(started) => started,
+                            None => {
+                                state = shared.condition.wait(state).unwrap();
+                                continue;
+                            }
+                        };
+                        let deadline = started + budget;
+                        let now = Instant::now();
+                        if now < deadline {
+                            state = shared
+                                .condition
+                                .wait_timeout(state, deadline - now)
+                                .unwrap()
+                                .0;
+                            continue;
+                        }
+                        // The call has exceeded its budget. Report the stall
+                        // without holding the lock and then wait for the call
+                        // to complete.
+                        let serial = state.serial;
+                        drop(state);
+                        f(Stall { started, budget });
+                        state = shared.state.lock().unwrap();
+                        while !state.is_stopped && state.serial == serial {
+                            state = shared.condition.wait(state).unwrap();
+                        }
+                    }
+                })
+                .ok()
+        };
+        Watchdog {
+            budget,
+            shared,
+            thread,
+        }
+    }
+
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Executes a call into the reactor under the watchdog.
+    fn watch<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        /// Notifies the watchdog that the call completed, even if it panics.
+        struct Completion<'a>(&'a Watchdog);
+
+        impl Drop for Completion<'_> {
+            fn drop(&mut self) {
+                self.0.update(None);
+            }
+        }
+
+        self.update(Some(Instant::now()));
+        let _completion = Completion(self);
+        f()
+    }
+
+    fn update(&self, started: Option<Instant>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.started = started;
+        state.serial = state.serial.wrapping_add(1);
+        self.shared.condition.notify_one();
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            state.is_stopped = true;
+            self.shared.condition.notify_one();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A reactor that executes calls into another reactor under a watchdog, if
+/// any.
+pub(crate) struct WatchdogReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    reactor: R,
+    watchdog: Option<Watchdog>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, R> WatchdogReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    pub fn new(reactor: R, watchdog: Option<Watchdog>) -> Self {
+        WatchdogReactor {
+            reactor,
+            watchdog,
+            phantom: PhantomData,
+        }
+    }
+
+    fn watch<T, F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut R) -> T,
+    {
+        let WatchdogReactor {
+            ref mut reactor,
+            ref watchdog,
+            ..
+        } = *self;
+        match *watchdog {
+            Some(ref watchdog) => watchdog.watch(move || f(reactor)),
+            None => f(reactor),
+        }
+    }
+}
+
+impl<P, R> Reactor<P> for WatchdogReactor<P, R>
+where
+    P: PlatformBinding,
+    R: Reactor<P>,
+{
+    const PANIC_POLICY: PanicPolicy = R::PANIC_POLICY;
+
+    fn react(&mut self, context: &ThreadContext, event: Event<P>) -> Reaction {
+        self.watch(move |reactor| reactor.react(context, event))
+    }
+
+    fn react_all(&mut self, context: &ThreadContext, events: EventBatch<'_, P>) -> Reaction {
+        self.watch(move |reactor| reactor.react_all(context, events))
+    }
+
+    fn poll(&mut self, context: &ThreadContext) -> Reaction<Poll> {
+        self.watch(move |reactor| reactor.poll(context))
+    }
+
+    fn abort(self) {
+        let WatchdogReactor {
+            reactor, watchdog, ..
+        } = self;
+        // Aborting the reactor is not observed by the watchdog.
+        drop(watchdog);
+        reactor.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::reactor::watchdog::Watchdog;
+
+    #[test]
+    fn report_stall() {
+        let (sender, receiver) = mpsc::channel();
+        let watchdog = Watchdog::new(Duration::from_millis(10), move |stall| {
+            let _ = sender.send(stall);
+        });
+        watchdog.watch(|| {});
+        watchdog.watch(|| thread::sleep(Duration::from_millis(100)));
+        drop(watchdog);
+        // Exactly one stall is reported for the call that exceeded the budget.
+        assert_eq!(1, receiver.iter().count());
+    }
+}
@@ -0,0 +1,374 @@
+//! Stable identifiers for handles.
+//!
+//! Platforms may recycle raw handles, such as window and device handles, once
+//! the objects that they refer to are destroyed. Registries assign identifiers
+//! that are never reused within a process to handles and map between the two.
+//! A handle that is registered again is assigned a new identifier, so an
+//! identifier observed before a handle is recycled never refers to the object
+//! that later receives that handle.
+//!
+//! Registries also track handles whose objects have been destroyed but that
+//! have not yet been recycled, so that late events for them can be discarded.
+//! Platforms report the destruction of an object after its owner has been
+//! dropped, so the identifier of a handle is retained until its destruction
+//! is observed and is never reassigned while the handle is closed.
+//!
+//! Events for an object are only dispatched by the event thread that owns it,
+//! so closed handles are tracked per thread and are forgotten when the event
+//! loop of the thread stops. This also allows event threads to query closed
+//! handles without synchronization.
+
+use lazy_static::lazy_static;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+
+lazy_static! {
+    static ref REGISTRIES: Mutex<HashMap<TypeId, Box<dyn Any + Send>>> = Mutex::new(HashMap::new());
+}
+
+thread_local! {
+    // Handles closed on the calling thread and their most recent identifiers.
+    static CLOSED: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+type Closed<K> = HashMap<K, Option<NonZeroU64>>;
+
+pub trait Key: 'static + Copy + Eq + Hash + Send {}
+
+impl<K> Key for K where K: 'static + Copy + Eq + Hash + Send {}
+
+struct Registry<K>
+where
+    K: Key,
+{
+    ids: HashMap<K, NonZeroU64>,
+    handles: HashMap<NonZeroU64, K>,
+    // Handles whose registrations have been dropped but that have not yet
+    // been closed and the threads that dropped them.
+    retired: HashMap<K, ThreadId>,
+    parents: HashMap<K, K>,
+    next: NonZeroU64,
+}
+
+impl<K> Registry<K>
+where
+    K: Key,
+{
+    fn new() -> Self {
+        Registry {
+            ids: HashMap::new(),
+            handles: HashMap::new(),
+            retired: HashMap::new(),
+            parents: HashMap::new(),
+            next: NonZeroU64::MIN,
+        }
+    }
+
+    fn register(&mut self, handle: K) -> NonZeroU64 {
+        self.unregister(handle);
+        let id = self.next;
+        self.next = id.checked_add(1).expect("identifiers exhausted");
+        self.ids.insert(handle, id);
+        self.handles.insert(id, handle);
+        id
+    }
+
+    fn unregister(&mut self, handle: K) {
+        if let Some(id) = self.ids.remove(&handle) {
+            self.handles.remove(&id);
+        }
+        self.retired.remove(&handle);
+        self.parents.remove(&handle);
+        self.parents.retain(|_, parent| *parent != handle);
+    }
+}
+
+fn with_registry<K, T, F>(f: F) -> T
+where
+    K: Key,
+    F: FnOnce(&mut Registry<K>) -> T,
+{
+    let mut registries = REGISTRIES.lock().unwrap();
+    let registry = registries
+        .entry(TypeId::of::<K>())
+        .or_insert_with(|| Box::new(Registry::<K>::new()));
+    f(registry.downcast_mut::<Registry<K>>().unwrap())
+}
+
+fn with_closed<K, T, F>(f: F) -> T
+where
+    K: Key,
+    F: FnOnce(&mut Closed<K>) -> T,
+{
+    CLOSED.with(|closed| {
+        let mut closed = closed.borrow_mut();
+        let closed = closed
+            .entry(TypeId::of::<K>())
+            .or_insert_with(|| Box::new(Closed::<K>::new()));
+        f(closed.downcast_mut::<Closed<K>>().unwrap())
+    })
+}
+
+/// Assigns a new identifier to a handle, replacing any identifier previously
+/// assigned to it.
+pub fn register<K>(handle: K) -> NonZeroU64
+where
+    K: Key,
+{
+    with_registry(|registry| registry.register(handle))
+}
+
+/// Forgets the identifier of a handle, if any.
+pub fn unregister<K>(handle: K)
+where
+    K: Key,
+{
+    with_registry(|registry: &mut Registry<K>| registry.unregister(handle))
+}
+
+/// Gets the identifier of a handle, assigning one if the handle has not been
+/// registered.
+///
+/// Closed handles retain their most recent identifier and are never assigned
+/// another until they are recycled.
+pub fn id<K>(handle: K) -> NonZeroU64
+where
+    K: Key,
+{
+    with_registry(|registry| match registry.ids.get(&handle) {
+        Some(id) => *id,
+        None => with_closed(|closed: &mut Closed<K>| match closed.get(&handle) {
+            Some(Some(id)) => *id,
+            _ => {
+                let id = registry.register(handle);
+                if let Some(closed) = closed.get_mut(&handle) {
+                    // Do not retain the handle, since it is closed.
+                    *closed = Some(id);
+                    registry.unregister(handle);
+                }
+                id
+            }
+        }),
+    })
+}
+
+/// Marks a handle as closed on the calling thread, because the object that it
+/// refers to has been destroyed.
+///
+/// The handle remains closed until it is registered again via
+/// `Registration`, which indicates that it has been recycled, or until closed
+/// handles are cleared.
+pub fn close<K>(handle: K)
+where
+    K: Key,
+{
+    with_registry(|registry: &mut Registry<K>| {
+        let id = registry.ids.get(&handle).cloned();
+        with_closed(|closed| closed.insert(handle, id));
+        // The owner of the handle has already been dropped.
+        if registry.retired.contains_key(&handle) {
+            registry.unregister(handle);
+        }
+    });
+}
+
+/// Forgets the identifiers of handles whose registrations were dropped on the
+/// calling thread but that have not been closed.
+///
+/// This is called when the event loop stops, after which no further events
+/// close these handles.
+pub fn release_retired<K>()
+where
+    K: Key,
+{
+    let thread = thread::current().id();
+    with_registry(|registry: &mut Registry<K>| {
+        let handles: Vec<_> = registry
+            .retired
+            .iter()
+            .filter(|(_, other)| **other == thread)
+            .map(|(handle, _)| *handle)
+            .collect();
+        for handle in handles {
+            registry.unregister(handle);
+        }
+    });
+}
+
+/// Determines if a handle has been closed on the calling thread and not yet
+/// recycled.
+pub fn is_closed<K>(handle: K) -> bool
+where
+    K: Key,
+{
+    with_closed(|closed: &mut Closed<K>| closed.contains_key(&handle))
+}
+
+/// Forgets the handles closed on the calling thread.
+///
+/// This is called when the event loop stops, after which no further events
+/// are dispatched for these handles.
+pub fn clear_closed() {
+    let closed = CLOSED.with(|closed| closed.take());
+    // Drop the closed handles outside of the borrow.
+    drop(closed);
+}
+
+/// Records the parent of a handle, such as the parent of a child window.
+///
+/// The relationship is forgotten when either handle is unregistered.
+pub fn set_parent<K>(handle: K, parent: K)
+where
+    K: Key,
+{
+    with_registry(|registry: &mut Registry<K>| registry.parents.insert(handle, parent));
+}
+
+/// Gets the parent of a handle, if any.
+pub fn parent<K>(handle: K) -> Option<K>
+where
+    K: Key,
+{
+    with_registry(|registry: &mut Registry<K>| registry.parents.get(&handle).cloned())
+}
+
+/// Gets the handle assigned an identifier, if it is still registered.
+pub fn handle<K>(id: NonZeroU64) -> Option<K>
+where
+    K: Key,
+{
+    with_registry(|registry: &mut Registry<K>| registry.handles.get(&id).cloned())
+}
+
+/// Unregisters a handle when dropped.
+#[derive(Debug)]
+pub struct Registration<K>
+where
+    K: Key,
+{
+    handle: K,
+    id: NonZeroU64,
+}
+
+impl<K> Registration<K>
+where
+    K: Key,
+{
+    /// Registers a handle that refers to a new object.
+    ///
+    /// The handle is no longer closed if it has been recycled.
+    pub fn new(handle: K) -> Self {
+        with_closed(|closed: &mut Closed<K>| closed.remove(&handle));
+        let id = with_registry(|registry| registry.register(handle));
+        Registration { handle, id }
+    }
+
+    pub fn id(&self) -> NonZeroU64 {
+        self.id
+    }
+}
+
+impl<K> Eq for Registration<K> where K: Key {}
+
+impl<K> Hash for Registration<K>
+where
+    K: Key,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.id.hash(state)
+    }
+}
+
+impl<K> PartialEq for Registration<K>
+where
+    K: Key,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<K> Drop for Registration<K>
+where
+    K: Key,
+{
+    fn drop(&mut self) {
+        // Do not forget the handle if it has since been registered again. If
+        // the handle has not yet been closed, then retain its identifier until
+        // it is, so that events that report its destruction can be attributed
+        // to it.
+        with_registry(|registry: &mut Registry<K>| {
+            if registry.ids.get(&self.handle) == Some(&self.id) {
+                if is_closed(self.handle) {
+                    registry.unregister(self.handle);
+                }
+                else {
+                    registry.retired.insert(self.handle, thread::current().id());
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::{self, Registration};
+
+    #[test]
+    fn recycled_handle_is_assigned_new_id() {
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        struct Handle(u32);
+
+        let first = Registration::new(Handle(1));
+        let id = first.id();
+        assert_eq!(Some(Handle(1)), registry::handle::<Handle>(id));
+        assert_eq!(id, registry::id(Handle(1)));
+        drop(first);
+        registry::release_retired::<Handle>();
+        assert_eq!(None, registry::handle::<Handle>(id));
+
+        let second = Registration::new(Handle(1));
+        assert!(second.id() > id);
+        assert_eq!(None, registry::handle::<Handle>(id));
+        assert_eq!(Some(Handle(1)), registry::handle::<Handle>(second.id()));
+    }
+
+    #[test]
+    fn recycled_handle_is_not_closed() {
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        struct Handle(u32);
+
+        let first = Registration::new(Handle(1));
+        registry::close(Handle(1));
+        drop(first);
+        assert!(registry::is_closed(Handle(1)));
+
+        let _second = Registration::new(Handle(1));
+        assert!(!registry::is_closed(Handle(1)));
+    }
+
+    #[test]
+    fn dropped_handle_is_retained_until_closed() {
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        struct Handle(u32);
+
+        let registration = Registration::new(Handle(1));
+        let id = registration.id();
+        drop(registration);
+        assert_eq!(id, registry::id(Handle(1)));
+        assert_eq!(Some(Handle(1)), registry::handle::<Handle>(id));
+
+        registry::close(Handle(1));
+        assert_eq!(None, registry::handle::<Handle>(id));
+        assert_eq!(id, registry::id(Handle(1)));
+        assert_eq!(None, registry::handle::<Handle>(id));
+    }
+}
@@ -0,0 +1,112 @@
+//! Serialization of handles and timestamps.
+//!
+//! Platform handles are not meaningful outside of the process in which they
+//! were observed, so handles are serialized as opaque identifiers. Identifiers
+//! are assigned in the order that handles are first serialized and are stable
+//! for the lifetime of the process. Deserialization only resolves identifiers
+//! that have been assigned by the same process.
+//!
+//! `Instant`s are opaque, so timestamps are serialized as signed offsets in
+//! microseconds from an epoch that is established when the first timestamp is
+//! serialized or deserialized. This preserves the relative timing of events.
+
+use lazy_static::lazy_static;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    static ref EPOCH: Instant = Instant::now();
+    static ref REGISTRIES: Mutex<HashMap<TypeId, Box<dyn Any + Send>>> = Mutex::new(HashMap::new());
+}
+
+struct Registry<K> {
+    handles: Vec<K>,
+}
+
+impl<K> Registry<K>
+where
+    K: Copy + PartialEq,
+{
+    fn id(&mut self, handle: K) -> u64 {
+        (match self.handles.iter().position(|other| *other == handle) {
+            Some(index) => index,
+            None => {
+                self.handles.push(handle);
+                self.handles.len() - 1
+            }
+        }) as u64
+    }
+
+    fn handle(&self, id: u64) -> Option<K> {
+        self.handles.get(id as usize).cloned()
+    }
+}
+
+fn with_registry<K, T, F>(f: F) -> T
+where
+    K: 'static + Copy + PartialEq + Send,
+    F: FnOnce(&mut Registry<K>) -> T,
+{
+    let mut registries = REGISTRIES.lock().unwrap();
+    let registry = registries
+        .entry(TypeId::of::<K>())
+        .or_insert_with(|| Box::new(Registry::<K> { handles: vec![] }));
+    f(registry.downcast_mut::<Registry<K>>().unwrap())
+}
+
+pub fn serialize_handle<K, S>(handle: K, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: 'static + Copy + PartialEq + Send,
+    S: Serializer,
+{
+    with_registry(|registry| registry.id(handle)).serialize(serializer)
+}
+
+pub fn deserialize_handle<'de, K, D>(deserializer: D, name: &str) -> Result<K, D::Error>
+where
+    K: 'static + Copy + PartialEq + Send,
+    D: Deserializer<'de>,
+{
+    let id = u64::deserialize(deserializer)?;
+    with_registry(|registry| registry.handle(id))
+        .ok_or_else(|| D::Error::custom(format!("unknown {} identifier {}", name, id)))
+}
+
+/// Serializes `Instant`s. Use with `#[serde(with = "crate::serial::instant")]`.
+pub mod instant {
+    use super::*;
+
+    pub fn serialize<S>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let epoch = *EPOCH;
+        let offset = if *instant >= epoch {
+            (*instant - epoch).as_micros() as i64
+        }
+        else {
+            -((epoch - *instant).as_micros() as i64)
+        };
+        offset.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Instant, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let epoch = *EPOCH;
+        let offset = i64::deserialize(deserializer)?;
+        let magnitude = Duration::from_micros(offset.unsigned_abs());
+        if offset >= 0 {
+            epoch.checked_add(magnitude)
+        }
+        else {
+            epoch.checked_sub(magnitude)
+        }
+        .ok_or_else(|| D::Error::custom("timestamp out of range"))
+    }
+}
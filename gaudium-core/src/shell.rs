@@ -0,0 +1,5 @@
+//! Integration with the platform shell.
+
+pub mod dialog;
+pub mod hotkey;
+pub mod instance;
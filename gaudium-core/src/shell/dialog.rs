@@ -0,0 +1,309 @@
+//! Native message boxes and file dialogs.
+//!
+//! Dialogs are modal: showing a dialog blocks the event thread until the
+//! dialog is dismissed. For this reason, dialogs can only be shown with a
+//! `ThreadContext`, typically from within a reactor. Events that occur while a
+//! dialog is shown are dispatched after it is dismissed.
+//!
+//! # Examples
+//!
+//! Confirming that a window should be closed:
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::event::{Event, WindowCloseState, WindowEvent};
+//! use gaudium_core::reactor::{Reaction, ThreadContext};
+//! use gaudium_core::shell::dialog::{MessageButtons, MessageDialog, MessageResponse};
+//! use gaudium_platform_empty::Binding;
+//!
+//! fn react(context: &ThreadContext, event: Event<Binding>) -> Reaction {
+//!     match event {
+//!         Event::Window {
+//!             window,
+//!             event: WindowEvent::Closed(WindowCloseState::Requested),
+//!             ..
+//!         } => {
+//!             let response = MessageDialog::new("Are you sure?")
+//!                 .with_buttons(MessageButtons::YesNo)
+//!                 .with_owner(window)
+//!                 .show(context);
+//!             if let Ok(MessageResponse::Yes) = response {
+//!                 Reaction::Abort
+//!             }
+//!             else {
+//!                 Reaction::Continue(())
+//!             }
+//!         }
+//!         _ => Reaction::Continue(()),
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::platform::{Dialog, PlatformBinding};
+use crate::reactor::ThreadContext;
+use crate::window::WindowHandle;
+
+/// Severity of a message dialog, which determines its icon.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MessageLevel {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+/// Buttons presented by a message dialog.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MessageButtons {
+    #[default]
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+}
+
+/// The button with which a message dialog was dismissed.
+///
+/// Dismissing a dialog without choosing a button, such as by pressing escape,
+/// produces `Cancel` if the dialog has a cancel button and otherwise produces
+/// the response of its only (or negative) button.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MessageResponse {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+}
+
+/// A modal message box.
+#[derive(Clone, Debug)]
+pub struct MessageDialog<P>
+where
+    P: PlatformBinding,
+{
+    text: String,
+    title: String,
+    level: MessageLevel,
+    buttons: MessageButtons,
+    owner: Option<WindowHandle<P>>,
+}
+
+impl<P> MessageDialog<P>
+where
+    P: PlatformBinding,
+{
+    pub fn new<T>(text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        MessageDialog {
+            text: text.into(),
+            title: String::new(),
+            level: MessageLevel::default(),
+            buttons: MessageButtons::default(),
+            owner: None,
+        }
+    }
+
+    pub fn with_title<T>(mut self, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_level(mut self, level: MessageLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_buttons(mut self, buttons: MessageButtons) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    /// Sets the window that owns the dialog.
+    ///
+    /// The owner is disabled while the dialog is shown and the dialog is
+    /// positioned relative to it.
+    pub fn with_owner(mut self, owner: WindowHandle<P>) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn level(&self) -> MessageLevel {
+        self.level
+    }
+
+    pub fn buttons(&self) -> MessageButtons {
+        self.buttons
+    }
+
+    pub fn owner(&self) -> Option<WindowHandle<P>> {
+        self.owner
+    }
+
+    /// Shows the dialog and blocks until it is dismissed.
+    pub fn show(&self, context: &ThreadContext) -> Result<MessageResponse, Error> {
+        P::Dialog::show_message(context, self)
+    }
+}
+
+/// A named set of file extensions that a file dialog displays.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FileFilter {
+    pub name: String,
+    /// Extensions without a leading period, such as `"png"`.
+    pub extensions: Vec<String>,
+}
+
+/// A modal dialog for choosing files to open or a path to save to.
+#[derive(Clone, Debug)]
+pub struct FileDialog<P>
+where
+    P: PlatformBinding,
+{
+    title: String,
+    directory: Option<PathBuf>,
+    file_name: String,
+    filters: Vec<FileFilter>,
+    owner: Option<WindowHandle<P>>,
+}
+
+impl<P> FileDialog<P>
+where
+    P: PlatformBinding,
+{
+    pub fn new() -> Self {
+        FileDialog::default()
+    }
+
+    pub fn with_title<T>(mut self, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the directory that is initially displayed.
+    pub fn with_directory<T>(mut self, directory: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Sets the file name that is initially entered.
+    pub fn with_file_name<T>(mut self, file_name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.file_name = file_name.into();
+        self
+    }
+
+    /// Adds a filter of file extensions.
+    ///
+    /// The first filter is selected initially. If no filters are added, then
+    /// all files are displayed.
+    pub fn with_filter<T, I>(mut self, name: T, extensions: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.filters.push(FileFilter {
+            name: name.into(),
+            extensions: extensions
+                .into_iter()
+                .map(|extension| extension.into())
+                .collect(),
+        });
+        self
+    }
+
+    /// Sets the window that owns the dialog.
+    ///
+    /// The owner is disabled while the dialog is shown and the dialog is
+    /// positioned relative to it.
+    pub fn with_owner(mut self, owner: WindowHandle<P>) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn filters(&self) -> &[FileFilter] {
+        &self.filters
+    }
+
+    pub fn owner(&self) -> Option<WindowHandle<P>> {
+        self.owner
+    }
+
+    /// Shows a dialog for choosing an existing file and blocks until it is
+    /// dismissed.
+    ///
+    /// Returns `None` if the dialog is cancelled.
+    pub fn open(&self, context: &ThreadContext) -> Result<Option<PathBuf>, Error> {
+        P::Dialog::open_files(context, self, false).map(|paths| paths.into_iter().next())
+    }
+
+    /// Shows a dialog for choosing any number of existing files and blocks
+    /// until it is dismissed.
+    ///
+    /// Returns no paths if the dialog is cancelled.
+    pub fn open_multiple(&self, context: &ThreadContext) -> Result<Vec<PathBuf>, Error> {
+        P::Dialog::open_files(context, self, true)
+    }
+
+    /// Shows a dialog for choosing a path to save to and blocks until it is
+    /// dismissed.
+    ///
+    /// The path need not exist. Returns `None` if the dialog is cancelled.
+    pub fn save(&self, context: &ThreadContext) -> Result<Option<PathBuf>, Error> {
+        P::Dialog::save_file(context, self)
+    }
+}
+
+impl<P> Default for FileDialog<P>
+where
+    P: PlatformBinding,
+{
+    fn default() -> Self {
+        FileDialog {
+            title: String::new(),
+            directory: None,
+            file_name: String::new(),
+            filters: vec![],
+            owner: None,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! System-wide hotkeys.
+//!
+//! A hotkey is a combination of modifiers and a key that is reported to the
+//! event thread that registered it, even if none of its windows have input
+//! focus. When a hotkey is pressed, `ApplicationEvent::HotkeyPressed` is
+//! dispatched with the identifier of the hotkey. Hotkeys are unregistered when
+//! dropped.
+//!
+//! Hotkeys are system resources: registration fails if another application
+//! has already registered the same combination.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::prelude::*;
+//! use gaudium_core::reactor::{EventThread, Sink, StatefulReactor, ThreadContext};
+//! use gaudium_core::shell::hotkey::{Hotkey, Modifiers};
+//! use gaudium_platform_empty::Binding;
+//!
+//! # fn main() {
+//! EventThread::<Binding, _>::run_and_abort_with(|context| {
+//!     let modifiers = Modifiers {
+//!         control: true,
+//!         shift: true,
+//!         ..Default::default()
+//!     };
+//!     let hotkey = Hotkey::<Binding>::register(context, modifiers, KeyCode::Q).unwrap();
+//!     (Sink::Headless, StatefulReactor::from((
+//!         hotkey,
+//!         |hotkey: &mut Hotkey<Binding>, _: &ThreadContext, event| match event {
+//!             Event::Application {
+//!                 event: ApplicationEvent::HotkeyPressed(id),
+//!                 ..
+//!             } if id == hotkey.id() => Abort,
+//!             _ => Continue(()),
+//!         },
+//!     )))
+//! })
+//! # }
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::error::{Error, ErrorKind};
+use crate::event::KeyCode;
+use crate::platform::{self, PlatformBinding};
+use crate::reactor::ThreadContext;
+use crate::{FromRawHandle, IntoRawHandle};
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// An opaque identifier of a `Hotkey`.
+///
+/// Identifiers are never zero and are never reused within a process.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct HotkeyId(u32);
+
+impl FromRawHandle<u32> for HotkeyId {
+    fn from_raw_handle(handle: u32) -> Self {
+        HotkeyId(handle)
+    }
+}
+
+impl IntoRawHandle<u32> for HotkeyId {
+    fn into_raw_handle(self) -> u32 {
+        self.0
+    }
+}
+
+/// Modifier keys that must be held for a hotkey.
+///
+/// Modifiers do not distinguish between their left and right keys.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Modifiers {
+    pub alt: bool,
+    pub control: bool,
+    pub shift: bool,
+    /// The super key, such as the Windows or Command key.
+    pub super_key: bool,
+}
+
+/// A system-wide hotkey registered for an event thread.
+///
+/// The hotkey is unregistered when dropped. Hotkeys must be dropped on the
+/// event thread and so do not implement `Send`.
+#[derive(Debug)]
+pub struct Hotkey<P>
+where
+    P: PlatformBinding,
+{
+    id: HotkeyId,
+    modifiers: Modifiers,
+    keycode: KeyCode,
+    phantom: PhantomData<(P, *mut ())>,
+}
+
+impl<P> Hotkey<P>
+where
+    P: PlatformBinding,
+{
+    /// Registers a hotkey for the event thread.
+    ///
+    /// Fails if the combination of modifiers and key cannot be registered,
+    /// such as when another application has registered it.
+    pub fn register(
+        context: &ThreadContext,
+        modifiers: Modifiers,
+        keycode: KeyCode,
+    ) -> Result<Self, Error> {
+        let id = NEXT_ID
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .map(HotkeyId)
+            .map_err(|_| Error::new(ErrorKind::Unsupported, "hotkey identifiers exhausted"))?;
+        <P::Hotkey as platform::Hotkey>::register(context, id, modifiers, keycode)?;
+        Ok(Hotkey {
+            id,
+            modifiers,
+            keycode,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn id(&self) -> HotkeyId {
+        self.id
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn keycode(&self) -> KeyCode {
+        self.keycode
+    }
+}
+
+impl<P> Drop for Hotkey<P>
+where
+    P: PlatformBinding,
+{
+    fn drop(&mut self) {
+        <P::Hotkey as platform::Hotkey>::unregister(self.id);
+    }
+}
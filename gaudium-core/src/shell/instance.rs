@@ -0,0 +1,111 @@
+//! Single-instance applications.
+//!
+//! A `SingleInstance` is a named, process-wide guard. The first process that
+//! acquires a name owns it until the guard is dropped. Later processes that
+//! attempt to acquire the name instead forward their command line arguments
+//! to the owner and are expected to exit.
+//!
+//! When arguments are forwarded, `ApplicationEvent::InstanceLaunched` is
+//! dispatched on the event thread of the owner and the arguments can be taken
+//! via `SingleInstance::take_arguments`. Events cannot own allocations, so the
+//! arguments are not carried by the event.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate gaudium_core;
+//! # extern crate gaudium_platform_empty;
+//! #
+//! use gaudium_core::prelude::*;
+//! use gaudium_core::reactor::{EventThread, Sink, StatefulReactor, ThreadContext};
+//! use gaudium_core::shell::instance::SingleInstance;
+//! use gaudium_platform_empty::Binding;
+//! use std::process;
+//!
+//! # fn main() {
+//! EventThread::<Binding, _>::run_and_abort_with(|context| {
+//!     let instance = match SingleInstance::<Binding>::acquire(context, "com.example.editor") {
+//!         Ok(Some(instance)) => instance,
+//!         // The arguments have been forwarded to the running instance.
+//!         _ => process::exit(0),
+//!     };
+//!     (Sink::Headless, StatefulReactor::from((
+//!         instance,
+//!         |instance: &mut SingleInstance<Binding>, _: &ThreadContext, event| {
+//!             if let Event::Application {
+//!                 event: ApplicationEvent::InstanceLaunched,
+//!                 ..
+//!             } = event
+//!             {
+//!                 while let Some(arguments) = instance.take_arguments() {
+//!                     println!("{:?}", arguments);
+//!                 }
+//!             }
+//!             Continue(())
+//!         },
+//!     )))
+//! })
+//! # }
+//! ```
+
+use std::env;
+use std::ffi::OsString;
+
+use crate::error::{Error, ErrorKind};
+use crate::platform::{self, PlatformBinding};
+use crate::reactor::ThreadContext;
+
+/// Ownership of a name shared by all instances of an application.
+///
+/// The name is released when the guard is dropped. Guards must be dropped on
+/// the event thread that acquired them.
+pub struct SingleInstance<P>
+where
+    P: PlatformBinding,
+{
+    inner: P::SingleInstance,
+}
+
+impl<P> SingleInstance<P>
+where
+    P: PlatformBinding,
+{
+    /// Acquires the given name or forwards the command line arguments of the
+    /// process to the instance that owns it.
+    ///
+    /// Returns `None` if another instance owns the name, in which case the
+    /// arguments (excluding the name of the executable) have been forwarded
+    /// to it.
+    pub fn acquire(context: &ThreadContext, name: &str) -> Result<Option<Self>, Error> {
+        SingleInstance::acquire_with(context, name, env::args_os().skip(1).collect())
+    }
+
+    /// Acquires the given name or forwards the given arguments to the instance
+    /// that owns it.
+    ///
+    /// See `acquire`.
+    pub fn acquire_with(
+        context: &ThreadContext,
+        name: &str,
+        arguments: Vec<OsString>,
+    ) -> Result<Option<Self>, Error> {
+        if name.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "instance name is empty",
+            ));
+        }
+        Ok(
+            <P::SingleInstance as platform::SingleInstance>::acquire(context, name, arguments)?
+                .map(|inner| SingleInstance { inner }),
+        )
+    }
+
+    /// Takes the arguments forwarded by the earliest launch of another
+    /// instance that have not yet been taken.
+    ///
+    /// Each launch dispatches `ApplicationEvent::InstanceLaunched`.
+    pub fn take_arguments(&mut self) -> Option<Vec<OsString>> {
+        platform::SingleInstance::take_arguments(&mut self.inner)
+    }
+}
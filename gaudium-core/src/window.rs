@@ -1,13 +1,93 @@
-use crate::platform::{self, Handle, PlatformBinding, Proxy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::Any;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+
+use crate::accessibility::{AccessibleElement, Role};
+use crate::display::{
+    DisplayHandle, LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, VideoMode,
+};
+use crate::error::{Error, ErrorKind};
+#[cfg(feature = "test")]
+use crate::event::InputEvent;
+use crate::platform::{
+    self, Accessible, Handle, PlatformBinding, Proxy, Surface, SurfaceToken as _,
+};
 use crate::reactor::ThreadContext;
+use crate::registry::{self, Registration};
+#[cfg(feature = "serde")]
+use crate::serial;
 use crate::{FromRawHandle, IntoRawHandle};
 
 /// An opaque type that identifies a `Window`.
-#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct WindowHandle<P>(platform::WindowHandle<P>)
 where
     P: PlatformBinding;
 
+impl<P> Eq for WindowHandle<P> where P: PlatformBinding {}
+
+impl<P> Hash for WindowHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.0.hash(state)
+    }
+}
+
+/// Handles are ordered by their raw platform handles. This order is arbitrary
+/// but consistent for the lifetime of the handles, so handles can be used as
+/// keys in ordered collections like `BTreeMap`.
+impl<P> Ord for WindowHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<P> PartialOrd for WindowHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<P> Serialize for WindowHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serial::serialize_handle(*self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P> Deserialize<'de> for WindowHandle<P>
+where
+    P: PlatformBinding,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serial::deserialize_handle(deserializer, "window")
+    }
+}
+
 impl<P> FromRawHandle<platform::WindowHandle<P>> for WindowHandle<P>
 where
     P: PlatformBinding,
@@ -26,9 +106,85 @@ where
     }
 }
 
+impl<P> WindowHandle<P>
+where
+    P: PlatformBinding,
+{
+    /// Gets the handle of the window with the given identifier.
+    ///
+    /// Returns `None` if the window has been dropped.
+    pub fn from_id(id: WindowId) -> Option<Self> {
+        registry::handle(id.0)
+    }
+
+    /// Gets the stable identifier of the window.
+    ///
+    /// See `WindowId`.
+    pub fn id(self) -> WindowId {
+        WindowId(registry::id(self))
+    }
+
+    /// Gets the parent of a child window.
+    ///
+    /// Returns `None` if the window is not a child window or if either window
+    /// has been dropped. See `WindowBuilder::with_parent`.
+    pub fn parent(self) -> Option<Self> {
+        registry::parent(self)
+    }
+
+    /// Gets the top-level window that contains a child window.
+    ///
+    /// Returns the window itself if it is not a child window.
+    pub fn root(self) -> Self {
+        let mut window = self;
+        while let Some(parent) = window.parent() {
+            window = parent;
+        }
+        window
+    }
+
+    /// Executes a function against the user data of the window.
+    ///
+    /// Returns `None` if the window does not exist, has no user data, or its
+    /// user data is not of type `T`. Because a `ThreadContext` is required,
+    /// this can only be used on the event thread, such as from a reactor. See
+    /// `Window::set_user_data`.
+    pub fn with_user_data<T, U, F>(self, _: &ThreadContext, f: F) -> Option<U>
+    where
+        T: Any,
+        F: FnOnce(&mut T) -> U,
+    {
+        let mut f = Some(f);
+        let mut output = None;
+        <platform::Window<P> as platform::Surface>::with_user_data(self.0, &mut |data| {
+            if let (Some(data), Some(f)) = (data.downcast_mut::<T>(), f.take()) {
+                output = Some(f(data));
+            }
+        });
+        output
+    }
+}
+
 unsafe impl<P> Send for WindowHandle<P> where P: PlatformBinding {}
 unsafe impl<P> Sync for WindowHandle<P> where P: PlatformBinding {}
 
+/// A stable identifier for a window.
+///
+/// Platforms may reuse the raw handles of windows that have been destroyed.
+/// Unlike `WindowHandle`, identifiers are never reused within a process, so
+/// they can be used as long-lived keys. Identifiers are ordered by the creation
+/// of their windows.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct WindowId(NonZeroU64);
+
+impl WindowId {
+    /// Gets the identifier as an integer.
+    pub fn get(self) -> u64 {
+        self.0.get()
+    }
+}
+
 /// Configures and builds a `Window`.
 ///
 /// A `WindowBuilder` is used to create `Window`s. It provides a default
@@ -42,13 +198,219 @@ where
     P: PlatformBinding,
 {
     inner: <P as PlatformBinding>::WindowBuilder,
+    parent: Option<WindowHandle<P>>,
 }
 
 impl<P> WindowBuilder<P>
 where
     P: PlatformBinding,
 {
-    pub fn build(self, context: &ThreadContext) -> Result<Window<P>, ()> {
+    /// Sets the icon of the window.
+    ///
+    /// On platforms with a taskbar or similar, this icon is also used there.
+    pub fn with_icon(self, icon: Icon) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_icon(icon))
+    }
+
+    /// Sets whether or not the window is kept above other windows that are
+    /// not also always on top. Defaults to `false`.
+    pub fn with_always_on_top(self, always_on_top: bool) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_always_on_top(always_on_top))
+    }
+
+    /// Sets whether or not the window is activated (focused) when it is
+    /// created. Defaults to `true`.
+    pub fn with_active(self, active: bool) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_active(active))
+    }
+
+    /// Sets whether or not the window has decorations, such as a title bar
+    /// and borders. Defaults to `true`.
+    pub fn with_decorations(self, decorations: bool) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_decorations(decorations))
+    }
+
+    /// Sets whether or not the window is composited with transparency.
+    /// Defaults to `false`.
+    ///
+    /// How transparency is determined depends on the platform and the
+    /// renderer, so transparent windows may require platform-specific
+    /// rendering to appear transparent.
+    pub fn with_transparent(self, transparent: bool) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_transparent(transparent))
+    }
+
+    /// Sets the opacity of the window, including its decorations, in the
+    /// range `[0.0, 1.0]`. Defaults to `1.0`.
+    ///
+    /// Opacities outside of this range are ignored. Not all platforms support
+    /// opacity. See `Window::is_opacity_supported`.
+    pub fn with_opacity(self, opacity: f32) -> Self {
+        use crate::platform::WindowBuilder;
+
+        if is_opacity(opacity) {
+            self.map(move |inner| inner.with_opacity(opacity))
+        }
+        else {
+            self
+        }
+    }
+
+    /// Sets whether or not the window can be resized by the user. Defaults to
+    /// `true`.
+    pub fn with_resizable(self, resizable: bool) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_resizable(resizable))
+    }
+
+    /// Sets the minimum dimensions of the client area of the window.
+    pub fn with_min_dimensions<T>(self, dimensions: T) -> Self
+    where
+        T: Into<LogicalSize>,
+    {
+        use crate::platform::WindowBuilder;
+
+        let dimensions = dimensions.into();
+        self.map(move |inner| inner.with_min_dimensions(dimensions))
+    }
+
+    /// Sets the maximum dimensions of the client area of the window.
+    pub fn with_max_dimensions<T>(self, dimensions: T) -> Self
+    where
+        T: Into<LogicalSize>,
+    {
+        use crate::platform::WindowBuilder;
+
+        let dimensions = dimensions.into();
+        self.map(move |inner| inner.with_max_dimensions(dimensions))
+    }
+
+    /// Locks the ratio of the width to the height of the client area of the
+    /// window while it is resized.
+    ///
+    /// Ratios that are not positive and finite are ignored.
+    pub fn with_aspect_ratio(self, ratio: f64) -> Self {
+        use crate::platform::WindowBuilder;
+
+        if is_aspect_ratio(ratio) {
+            self.map(move |inner| inner.with_aspect_ratio(ratio))
+        }
+        else {
+            self
+        }
+    }
+
+    /// Sets the kind of the window. Defaults to `WindowKind::Normal`.
+    ///
+    /// See `WindowKind`.
+    pub fn with_kind(self, kind: WindowKind) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_kind(kind))
+    }
+
+    /// Sets the window that owns the window.
+    ///
+    /// An owned window is kept above its owner and is hidden when its owner
+    /// is minimized. Platforms may also close owned windows when their owner
+    /// is closed. Popups, tooltips, and utility windows are typically owned.
+    pub fn with_owner(self, owner: WindowHandle<P>) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_owner(owner.0))
+    }
+
+    /// Sets the parent of the window, which becomes a child window.
+    ///
+    /// Child windows are embedded in the client area of their parent, such as
+    /// to compose a video panel with other content. They are positioned
+    /// relative to and clipped by the client area of the parent and are
+    /// destroyed with it. Options that only apply to top-level windows, such
+    /// as the kind, owner, and fullscreen mode, are ignored. Platforms without
+    /// child windows create a top-level window.
+    pub fn with_parent(self, parent: &Window<P>) -> Self {
+        use crate::platform::WindowBuilder;
+
+        let parent = parent.handle();
+        let mut builder = self.map(move |inner| inner.with_parent(parent.0));
+        builder.parent = Some(parent);
+        builder
+    }
+
+    /// Sets the position of the outer top-left corner of the window in
+    /// physical units.
+    ///
+    /// Positions are in the coordinate space shared by all displays (see
+    /// `DisplayHandle::work_area`). By default, the platform chooses the
+    /// position of the window.
+    pub fn with_position<T>(self, position: T) -> Self
+    where
+        T: Into<PhysicalPosition>,
+    {
+        use crate::platform::WindowBuilder;
+
+        let position = position.into();
+        self.map(move |inner| inner.with_position(position))
+    }
+
+    /// Centers the window in the work area of the given display.
+    ///
+    /// If the window is larger than the work area, then it is aligned with the
+    /// top-left of the work area instead. If the display is no longer
+    /// connected, then this has no effect.
+    pub fn with_centered_on(self, display: DisplayHandle<P>) -> Self {
+        use crate::platform::WindowBuilder;
+
+        match display.work_area() {
+            Some((position, area)) => self.map(move |inner| inner.with_centered_in(position, area)),
+            None => self,
+        }
+    }
+
+    /// Restores the geometry of the window, such as geometry saved by a
+    /// previous session via `Window::geometry`.
+    ///
+    /// The geometry is clamped to the displays that are connected (see
+    /// `Geometry::clamp`). This overrides the position and dimensions of the
+    /// window.
+    pub fn with_geometry(self, geometry: Geometry) -> Self {
+        use crate::platform::WindowBuilder;
+
+        let areas = DisplayHandle::<P>::connected()
+            .iter()
+            .filter_map(DisplayHandle::work_area)
+            .collect::<Vec<_>>();
+        let geometry = geometry.clamp(&areas);
+        self.map(move |inner| inner.with_geometry(geometry))
+    }
+
+    /// Makes the window fullscreen when it is created.
+    ///
+    /// See `Fullscreen`.
+    pub fn with_fullscreen(self, fullscreen: Fullscreen) -> Self {
+        use crate::platform::WindowBuilder;
+
+        self.map(move |inner| inner.with_fullscreen(fullscreen))
+    }
+
+    /// Creates the window.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if the configuration is invalid,
+    /// such as when the minimum dimensions of the window exceed its maximum
+    /// dimensions, or with `ErrorKind::Platform` if the platform cannot create
+    /// the window.
+    pub fn build(self, context: &ThreadContext) -> Result<Window<P>, Error> {
         Window::new(self, context)
     }
 }
@@ -60,6 +422,7 @@ where
     fn default() -> Self {
         WindowBuilder {
             inner: Default::default(),
+            parent: None,
         }
     }
 }
@@ -82,8 +445,11 @@ where
     where
         F: FnOnce(Self::Inner) -> Self::Inner,
     {
-        let WindowBuilder { inner } = self;
-        WindowBuilder { inner: f(inner) }
+        let WindowBuilder { inner, parent } = self;
+        WindowBuilder {
+            inner: f(inner),
+            parent,
+        }
     }
 }
 
@@ -102,6 +468,8 @@ where
 ///
 /// `Window` can be moved across threads but must be created on the event
 /// thread using a `WindowBuilder`. When a `Window` is dropped, it is closed.
+/// Windows are always destroyed on the event thread, and no events for a
+/// window are dispatched after `WindowCloseState::Committed`.
 ///
 /// Because windows are fairly abstract and manifest differently, `Window`
 /// provides very limited functionality. See the `WindowExt` extension traits
@@ -112,23 +480,843 @@ where
     P: PlatformBinding,
 {
     inner: platform::Window<P>,
+    registration: Registration<WindowHandle<P>>,
 }
 
 impl<P> Window<P>
 where
     P: PlatformBinding,
 {
-    fn new(builder: WindowBuilder<P>, context: &ThreadContext) -> Result<Self, ()> {
+    fn new(builder: WindowBuilder<P>, context: &ThreadContext) -> Result<Self, Error> {
         use crate::platform::WindowBuilder;
 
-        let window = Window {
-            inner: builder.inner.build(context)?,
-        };
-        Ok(window)
+        let parent = builder.parent;
+        let inner = builder.inner.build(context)?;
+        // Always assign a new identifier, because the platform may have
+        // recycled the handle of a destroyed window.
+        let handle = WindowHandle(inner.handle());
+        let registration = Registration::new(handle);
+        if let Some(parent) = parent {
+            registry::set_parent(handle, parent);
+        }
+        Ok(Window {
+            inner,
+            registration,
+        })
+    }
+
+    /// Determines if the platform supports the opacity of windows.
+    ///
+    /// If opacity is not supported, then setting it has no effect.
+    pub fn is_opacity_supported() -> bool {
+        platform::Window::<P>::is_opacity_supported()
     }
 
     /// Gets the handle of the window.
     pub fn handle(&self) -> WindowHandle<P> {
         WindowHandle(self.inner.handle())
     }
+
+    /// Gets the stable identifier of the window.
+    pub fn id(&self) -> WindowId {
+        WindowId(self.registration.id())
+    }
+
+    /// Sets or clears the icon of the window.
+    pub fn set_icon(&mut self, icon: Option<Icon>) -> Result<(), Error> {
+        self.inner.set_icon(icon)
+    }
+
+    /// Sets the mouse mode of the window.
+    ///
+    /// See `MouseMode`.
+    pub fn set_mouse_mode(&mut self, mode: MouseMode) -> Result<(), Error> {
+        self.inner.set_mouse_mode(mode)
+    }
+
+    /// Sets the icon of the mouse pointer while it is within the window.
+    ///
+    /// Fails if the hotspot of a custom cursor is outside of its image.
+    pub fn set_cursor_icon(&mut self, icon: CursorIcon) -> Result<(), Error> {
+        if let CursorIcon::Custom {
+            ref image,
+            hotspot: (x, y),
+        } = icon
+        {
+            if x >= image.width() || y >= image.height() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "cursor hotspot is outside of image",
+                ));
+            }
+        }
+        self.inner.set_cursor_icon(icon)
+    }
+
+    /// Captures or releases the pointer.
+    ///
+    /// While the pointer is captured, mouse input is delivered to the window
+    /// even if the pointer leaves it. Some platforms also capture the pointer
+    /// automatically while mouse buttons are held; manual capture persists
+    /// until it is released or the platform revokes it, such as when another
+    /// window is activated. Not all platforms support pointer capture.
+    pub fn set_pointer_capture(&mut self, capture: bool) -> Result<(), Error> {
+        self.inner.set_pointer_capture(capture)
+    }
+
+    /// Activates the window and brings it to the foreground.
+    ///
+    /// Platforms may refuse to steal focus from other applications, in which
+    /// case the window may only be highlighted.
+    pub fn focus(&mut self) -> Result<(), Error> {
+        self.inner.focus()
+    }
+
+    /// Moves keyboard focus to the window without activating it.
+    ///
+    /// Focus can only be moved among windows of the event thread, such as
+    /// between a window and its children. `WindowEvent::Focused` is
+    /// dispatched when focus changes.
+    pub fn set_focus(&mut self) -> Result<(), Error> {
+        self.inner.set_focus()
+    }
+
+    /// Sets whether or not the window is kept above other windows that are
+    /// not also always on top.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) -> Result<(), Error> {
+        self.inner.set_always_on_top(always_on_top)
+    }
+
+    /// Sets a function that determines the region of the window at a
+    /// position.
+    ///
+    /// The function receives positions relative to the client area and
+    /// determines whether the pointer there moves or resizes the window. This
+    /// allows windows without decorations (see `WindowBuilder::with_decorations`)
+    /// to draw their own title bar and borders. The function must be set on
+    /// the event thread that owns the window.
+    pub fn set_hit_test<F>(&mut self, hit_test: F) -> Result<(), Error>
+    where
+        F: 'static + Fn(LogicalPosition) -> HitTestResult,
+    {
+        self.inner.set_hit_test(Some(Box::new(hit_test)))
+    }
+
+    /// Clears any function set via `set_hit_test`.
+    pub fn clear_hit_test(&mut self) -> Result<(), Error> {
+        self.inner.set_hit_test(None)
+    }
+
+    /// Raises or lowers the window without activating it.
+    pub fn set_z_order(&mut self, order: ZOrder) -> Result<(), Error> {
+        self.inner.set_z_order(order)
+    }
+
+    /// Sets or clears the minimum dimensions of the client area of the
+    /// window.
+    ///
+    /// If the window is smaller than the given dimensions, then it is resized.
+    pub fn set_min_dimensions(&mut self, dimensions: Option<LogicalSize>) -> Result<(), Error> {
+        self.inner.set_min_dimensions(dimensions)
+    }
+
+    /// Sets or clears the maximum dimensions of the client area of the
+    /// window.
+    ///
+    /// If the window is larger than the given dimensions, then it is resized.
+    pub fn set_max_dimensions(&mut self, dimensions: Option<LogicalSize>) -> Result<(), Error> {
+        self.inner.set_max_dimensions(dimensions)
+    }
+
+    /// Locks or unlocks the ratio of the width to the height of the client
+    /// area of the window.
+    ///
+    /// Returns an error if the ratio is not positive and finite.
+    pub fn set_aspect_ratio(&mut self, ratio: Option<f64>) -> Result<(), Error> {
+        match ratio {
+            Some(ratio) if !is_aspect_ratio(ratio) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "aspect ratio must be positive and finite",
+            )),
+            _ => self.inner.set_aspect_ratio(ratio),
+        }
+    }
+
+    /// Sets the opacity of the window, including its decorations, in the
+    /// range `[0.0, 1.0]`.
+    ///
+    /// Returns an error if the opacity is outside of this range. See
+    /// `is_opacity_supported`.
+    pub fn set_opacity(&mut self, opacity: f32) -> Result<(), Error> {
+        if is_opacity(opacity) {
+            self.inner.set_opacity(opacity)
+        }
+        else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "opacity must be in the range [0.0, 1.0]",
+            ))
+        }
+    }
+
+    /// Sets whether or not the window is modal.
+    ///
+    /// While a window is modal, its owner is disabled and does not receive
+    /// input. The owner is enabled again when the window is no longer modal
+    /// or is closed. The owner receives `WindowEvent::Disabled` and
+    /// `WindowEvent::Enabled` events as this occurs.
+    ///
+    /// Returns an error if the window has no owner. See
+    /// `WindowBuilder::with_owner`.
+    pub fn set_modal(&mut self, modal: bool) -> Result<(), Error> {
+        self.inner.set_modal(modal)
+    }
+
+    /// Requests permission to present a frame.
+    ///
+    /// `WindowEvent::FrameReady` is dispatched for the window when the
+    /// platform is ready to present a frame, such as after the next vertical
+    /// blank. Requests made before the event is dispatched are coalesced, so
+    /// reactors typically request the next frame when rendering a frame.
+    pub fn request_frame(&mut self) -> Result<(), Error> {
+        self.inner.request_frame()
+    }
+
+    /// Sets the name of the window as presented by assistive technologies.
+    ///
+    /// By default, the title of the window is used.
+    pub fn set_accessible_name<T>(&mut self, name: T) -> Result<(), Error>
+    where
+        T: Into<String>,
+    {
+        self.inner.set_accessible_name(name.into())
+    }
+
+    /// Sets the role of the window as presented by assistive technologies.
+    ///
+    /// Defaults to `Role::Window`.
+    pub fn set_accessible_role(&mut self, role: Role) -> Result<(), Error> {
+        self.inner.set_accessible_role(role)
+    }
+
+    /// Sets the custom element that has focus within the window and announces
+    /// it to assistive technologies.
+    ///
+    /// This should be called whenever focus moves between the custom elements
+    /// of the window. If `element` is `None`, then focus returns to the window
+    /// itself.
+    pub fn set_accessible_focus(
+        &mut self,
+        element: Option<AccessibleElement>,
+    ) -> Result<(), Error> {
+        self.inner.set_accessible_focus(element)
+    }
+
+    /// Notifies assistive technologies that a custom element has changed, such
+    /// as when its name changes.
+    ///
+    /// Only changes to the focused element are announced.
+    pub fn notify_accessible_change(&mut self, element: AccessibleElement) -> Result<(), Error> {
+        self.inner.notify_accessible_change(element)
+    }
+
+    /// Enters or leaves fullscreen.
+    ///
+    /// When the window leaves fullscreen, its previous position, dimensions,
+    /// and decorations are restored. See `Fullscreen`.
+    pub fn set_fullscreen(&mut self, fullscreen: Option<Fullscreen>) -> Result<(), Error> {
+        self.inner.set_fullscreen(fullscreen)
+    }
+
+    /// Maximizes the window or restores it from being maximized.
+    ///
+    /// `WindowEvent::StateChanged` is dispatched when the state of the window
+    /// changes.
+    pub fn set_maximized(&mut self, maximized: bool) -> Result<(), Error> {
+        self.inner.set_maximized(maximized)
+    }
+
+    /// Minimizes the window or restores it from being minimized.
+    pub fn set_minimized(&mut self, minimized: bool) -> Result<(), Error> {
+        self.inner.set_minimized(minimized)
+    }
+
+    /// Restores the window to its normal state, leaving fullscreen and
+    /// restoring it from being minimized or maximized.
+    pub fn restore(&mut self) -> Result<(), Error> {
+        self.inner.restore()
+    }
+
+    /// Gets the position of the window on the desktop, including any frame
+    /// and decorations.
+    pub fn outer_position(&self) -> Result<LogicalPosition, Error> {
+        self.inner.outer_position()
+    }
+
+    /// Gets the dimensions of the client area of the window.
+    pub fn inner_size(&self) -> Result<LogicalSize, Error> {
+        self.inner.inner_size()
+    }
+
+    /// Gets the dimensions of the window, including any frame and
+    /// decorations.
+    pub fn outer_size(&self) -> Result<LogicalSize, Error> {
+        self.inner.outer_size()
+    }
+
+    /// Gets whether or not the window has input focus.
+    pub fn is_focused(&self) -> bool {
+        self.inner.is_focused()
+    }
+
+    /// Gets the geometry of the window, which can be saved and restored via
+    /// `WindowBuilder::with_geometry`.
+    pub fn geometry(&self) -> Result<Geometry, Error> {
+        self.inner.geometry()
+    }
+
+    /// Gets whether or not the window is minimized.
+    pub fn is_minimized(&self) -> bool {
+        self.inner.is_minimized()
+    }
+
+    /// Gets a token that exposes the surface of the window to other threads.
+    ///
+    /// See `SurfaceToken`.
+    pub fn surface_token(&self) -> SurfaceToken<P> {
+        SurfaceToken {
+            inner: self.inner.surface_token(),
+        }
+    }
+
+    /// Captures the pixels of the client area of the window.
+    ///
+    /// This reads back what has been presented to the window, so it is
+    /// intended for tests and thumbnails rather than rendering. Fails if the
+    /// window has no visible client area, such as when it is minimized, or if
+    /// the platform does not support capture.
+    #[cfg(feature = "capture")]
+    pub fn capture(&self) -> Result<Image, Error> {
+        self.inner.capture()
+    }
+
+    /// Injects an input event into the window as if it were produced by a
+    /// device.
+    ///
+    /// This generates real platform input, so the event is dispatched by the
+    /// event thread like any other input and reactors can be tested end to
+    /// end. Injected input may be delivered to the focused window of the
+    /// system, so the window is brought to the foreground first. Only keyboard
+    /// keys, mouse buttons, mouse motion, and mouse wheels can be injected.
+    ///
+    /// Fails if the event cannot be injected or if the platform does not
+    /// support injection.
+    #[cfg(feature = "test")]
+    pub fn inject_input(&self, event: InputEvent) -> Result<(), Error> {
+        self.inner.inject_input(event)
+    }
+
+    /// Attaches arbitrary data to the window, replacing any previous data.
+    ///
+    /// User data can be accessed from a reactor via the window's handle (see
+    /// `WindowHandle::with_user_data`) and is dropped when the window is
+    /// destroyed.
+    pub fn set_user_data<T>(&mut self, data: T) -> Result<(), Error>
+    where
+        T: Any + Send,
+    {
+        self.inner.set_user_data(Some(Box::new(data)))
+    }
+
+    /// Drops any data attached to the window.
+    pub fn clear_user_data(&mut self) -> Result<(), Error> {
+        self.inner.set_user_data(None)
+    }
+}
+
+impl<P> Proxy for Window<P>
+where
+    P: PlatformBinding,
+{
+    type Inner = platform::Window<P>;
+
+    fn as_inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    fn as_inner_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(Self::Inner) -> Self::Inner,
+    {
+        let Window {
+            inner,
+            registration,
+        } = self;
+        Window {
+            inner: f(inner),
+            registration,
+        }
+    }
+}
+
+/// Exposes the surface of a `Window` to other threads.
+///
+/// Renderers often execute on a dedicated thread while windows and their
+/// events remain on the event thread. Unlike `Window` and `WindowHandle`,
+/// surface tokens are `Send` and `Sync`, and platform extension traits expose
+/// the raw handles needed to create a rendering surface from them.
+///
+/// A window may be destroyed at any time on the event thread, after which its
+/// raw handles are invalid and may be reused. `is_alive` is cleared as the
+/// window is destroyed, so render threads can detect stale surfaces, but this
+/// is inherently racy: reactors should stop rendering to a window, such as
+/// when its closure is requested, before destroying it.
+#[derive(Clone)]
+pub struct SurfaceToken<P>
+where
+    P: PlatformBinding,
+{
+    inner: <platform::Window<P> as Surface>::Token,
+}
+
+impl<P> SurfaceToken<P>
+where
+    P: PlatformBinding,
+{
+    /// Determines if the window of the surface has not been destroyed.
+    pub fn is_alive(&self) -> bool {
+        self.inner.is_alive()
+    }
+}
+
+impl<P> Proxy for SurfaceToken<P>
+where
+    P: PlatformBinding,
+{
+    type Inner = <platform::Window<P> as Surface>::Token;
+
+    fn as_inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    fn as_inner_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(Self::Inner) -> Self::Inner,
+    {
+        let SurfaceToken { inner } = self;
+        SurfaceToken { inner: f(inner) }
+    }
+}
+
+fn is_aspect_ratio(ratio: f64) -> bool {
+    ratio.is_finite() && ratio > 0.0
+}
+
+fn is_opacity(opacity: f32) -> bool {
+    (0.0..=1.0).contains(&opacity)
+}
+
+/// The position of a `Window` in the stack of windows on a display.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ZOrder {
+    /// Above all other windows, except those that are always on top.
+    Top,
+    /// Below all other windows.
+    Bottom,
+}
+
+/// The region of a `Window` at a position, which determines how the platform
+/// handles the pointer there.
+///
+/// See `Window::set_hit_test`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum HitTestResult {
+    /// The client area, which receives input as usual.
+    #[default]
+    Client,
+    /// A title bar, which moves the window when dragged and maximizes or
+    /// restores the window when double-clicked.
+    Caption,
+    /// A resizing border on the left edge.
+    Left,
+    /// A resizing border on the right edge.
+    Right,
+    /// A resizing border on the top edge.
+    Top,
+    /// A resizing border on the bottom edge.
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// A function that determines the region of a window at a position in the
+/// client area. See `Window::set_hit_test`.
+pub type HitTest = Box<dyn Fn(LogicalPosition) -> HitTestResult>;
+
+/// The role of a `Window`, which determines its decorations and how it
+/// relates to other windows.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum WindowKind {
+    /// An application window with decorations that appears in the taskbar or
+    /// similar.
+    #[default]
+    Normal,
+    /// An undecorated window for transient content, such as a menu or a
+    /// drop-down list.
+    ///
+    /// Popups do not appear in the taskbar.
+    Popup,
+    /// An undecorated window that describes content near the pointer.
+    ///
+    /// Tooltips do not appear in the taskbar, are never activated, and are
+    /// kept above other windows.
+    Tooltip,
+    /// An auxiliary window with minimal decorations, such as a tool palette.
+    ///
+    /// Utility windows do not appear in the taskbar.
+    Utility,
+}
+
+/// Fullscreen mode of a `Window`.
+///
+/// A fullscreen window covers the display that contains it. To make a window
+/// fullscreen on a particular display, move the window onto that display
+/// first.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Fullscreen {
+    /// The window is undecorated and covers the display without changing its
+    /// video mode.
+    Borderless,
+    /// The video mode of the display is changed while the window is
+    /// fullscreen.
+    ///
+    /// The mode should be one of the modes returned by `DisplayHandle::modes`.
+    /// The previous mode is restored when the window leaves fullscreen or is
+    /// dropped.
+    Exclusive(VideoMode),
+}
+
+/// The geometry of a `Window`, which can be saved and later restored.
+///
+/// Geometry describes the normal (restored) bounds of a window, even if the
+/// window is maximized or minimized, so that a window that is restored as
+/// maximized returns to its previous bounds when it is later restored by the
+/// user. Positions and dimensions are in physical units in the coordinate
+/// space shared by all displays (see `DisplayHandle::work_area`).
+///
+/// Displays may be disconnected or rearranged between sessions, so restored
+/// geometry is clamped to the displays that are connected. See `clamp`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Geometry {
+    /// The position of the outer top-left corner of the window.
+    pub position: PhysicalPosition,
+    /// The outer dimensions of the window.
+    pub dimensions: PhysicalSize,
+    /// The work area of the display that contains the window, if any.
+    pub display: Option<(PhysicalPosition, PhysicalSize)>,
+    pub is_maximized: bool,
+}
+
+impl Geometry {
+    /// Moves and shrinks the geometry such that it fits within one of the
+    /// given work areas.
+    ///
+    /// The area that overlaps the geometry the most is used. If no area
+    /// overlaps the geometry, then the area that matches `display` is used or
+    /// else the first area. If there are no areas, then the geometry is not
+    /// changed.
+    pub fn clamp(self, areas: &[(PhysicalPosition, PhysicalSize)]) -> Self {
+        let overlap = |(position, area): (PhysicalPosition, PhysicalSize)| {
+            let overlap = |start: f64, length: f64, area_start: f64, area_length: f64| {
+                ((start + length).min(area_start + area_length) - start.max(area_start)).max(0.0)
+            };
+            overlap(
+                *self.position.x,
+                *self.dimensions.width,
+                *position.x,
+                *area.width,
+            ) * overlap(
+                *self.position.y,
+                *self.dimensions.height,
+                *position.y,
+                *area.height,
+            )
+        };
+        let display = areas
+            .iter()
+            .copied()
+            .map(|area| (overlap(area), area))
+            .filter(|(overlap, _)| *overlap > 0.0)
+            .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .map(|(_, area)| area)
+            .or_else(|| self.display.filter(|display| areas.contains(display)))
+            .or_else(|| areas.first().copied());
+        let (position, area) = match display {
+            Some(display) => display,
+            None => return self,
+        };
+        let fit = |start: f64, length: f64, area_start: f64, area_length: f64| {
+            let length = length.min(area_length);
+            (
+                start.max(area_start).min(area_start + area_length - length),
+                length,
+            )
+        };
+        let (x, width) = fit(
+            *self.position.x,
+            *self.dimensions.width,
+            *position.x,
+            *area.width,
+        );
+        let (y, height) = fit(
+            *self.position.y,
+            *self.dimensions.height,
+            *position.y,
+            *area.height,
+        );
+        Geometry {
+            position: PhysicalPosition::new(x, y),
+            dimensions: PhysicalSize::new(width, height),
+            display: Some((position, area)),
+            is_maximized: self.is_maximized,
+        }
+    }
+}
+
+/// Determines how the mouse (pointer) behaves within a `Window`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MouseMode {
+    /// The pointer is visible and moves freely. Mouse movement includes
+    /// absolute positions.
+    #[default]
+    Absolute,
+    /// The pointer is hidden and confined to the window while it is active.
+    /// Mouse movement only includes relative motion.
+    ///
+    /// This is best suited for applications like games that use the mouse to
+    /// control a camera.
+    Relative,
+}
+
+/// The icon of the mouse pointer (cursor) within a `Window`.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CursorIcon {
+    /// The platform's default pointer.
+    #[default]
+    Arrow,
+    /// A text selection caret (I-beam).
+    Text,
+    /// A pointing hand, typically used for links.
+    Hand,
+    Crosshair,
+    /// Indicates that the application is busy and cannot accept input.
+    Wait,
+    /// Indicates that the application is busy but still accepts input.
+    Progress,
+    Help,
+    /// Indicates that an action is not allowed.
+    NotAllowed,
+    /// Indicates movement in any direction.
+    Move,
+    /// Indicates resizing to the left or right.
+    ResizeHorizontal,
+    /// Indicates resizing up or down.
+    ResizeVertical,
+    /// Indicates resizing along the diagonal from the upper left to the lower
+    /// right.
+    ResizeNorthwestSoutheast,
+    /// Indicates resizing along the diagonal from the upper right to the
+    /// lower left.
+    ResizeNortheastSouthwest,
+    /// A custom RGBA image. The hotspot is the position within the image that
+    /// corresponds to the position of the pointer.
+    Custom {
+        image: Icon,
+        hotspot: (u32, u32),
+    },
+}
+
+/// An RGBA image used as the icon of a `Window` or a custom `CursorIcon`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Icon {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl Icon {
+    /// Creates an icon from 32-bit RGBA pixels in row-major order.
+    ///
+    /// Fails if either dimension is zero or if the length of `rgba` does not
+    /// match the dimensions.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, Error> {
+        if width == 0 || height == 0 {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "icon dimensions must be non-zero",
+            ))
+        }
+        else if rgba.len() != (width as usize * height as usize * 4) {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "icon pixels do not match dimensions",
+            ))
+        }
+        else {
+            Ok(Icon {
+                rgba,
+                width,
+                height,
+            })
+        }
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        self.rgba.as_slice()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// An RGBA image captured from a `Window`.
+///
+/// Pixels are stored in row-major order from the top of the image. Rows may be
+/// padded, so each row begins `stride` bytes after the previous row.
+#[cfg(feature = "capture")]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Image {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+    stride: usize,
+}
+
+#[cfg(feature = "capture")]
+impl Image {
+    /// Creates an image from 32-bit RGBA pixels with the given stride in
+    /// bytes.
+    ///
+    /// Fails if the stride is less than the length of a row or if the length
+    /// of `rgba` does not match the dimensions and stride.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32, stride: usize) -> Result<Self, Error> {
+        if stride < width as usize * 4 {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "image stride is less than row length",
+            ))
+        }
+        else if rgba.len() != stride * height as usize {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "image pixels do not match dimensions",
+            ))
+        }
+        else {
+            Ok(Image {
+                rgba,
+                width,
+                height,
+                stride,
+            })
+        }
+    }
+
+    /// Gets the pixels of the image, including any padding.
+    pub fn rgba(&self) -> &[u8] {
+        self.rgba.as_slice()
+    }
+
+    /// Gets the pixels of a row of the image without padding.
+    ///
+    /// Returns `None` if the row is out of bounds.
+    pub fn row(&self, y: u32) -> Option<&[u8]> {
+        if y < self.height {
+            let start = y as usize * self.stride;
+            Some(&self.rgba[start..start + self.width as usize * 4])
+        }
+        else {
+            None
+        }
+    }
+
+    /// Gets the RGBA components of a pixel.
+    ///
+    /// Returns `None` if the pixel is out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x < self.width {
+            self.row(y).map(|row| {
+                let start = x as usize * 4;
+                [row[start], row[start + 1], row[start + 2], row[start + 3]]
+            })
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Gets the length of a row in bytes, including any padding.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::display::{PhysicalPosition, PhysicalSize};
+    use crate::window::Geometry;
+
+    #[test]
+    fn clamp_geometry_to_connected_display() {
+        let primary = (PhysicalPosition::new(0, 0), PhysicalSize::new(1920, 1040));
+        let secondary = (PhysicalPosition::new(1920, 0), PhysicalSize::new(1280, 984));
+        // The window was saved on a display that has since been disconnected.
+        let geometry = Geometry {
+            position: PhysicalPosition::new(-1500, 100),
+            dimensions: PhysicalSize::new(800, 600),
+            display: Some((
+                PhysicalPosition::new(-1920, 0),
+                PhysicalSize::new(1920, 1040),
+            )),
+            is_maximized: false,
+        };
+        let geometry = geometry.clamp(&[primary, secondary]);
+        assert_eq!(PhysicalPosition::new(0, 100), geometry.position);
+        assert_eq!(Some(primary), geometry.display);
+        // The window mostly overlaps the secondary display and is larger than
+        // it.
+        let geometry = Geometry {
+            position: PhysicalPosition::new(1800, 0),
+            dimensions: PhysicalSize::new(1400, 1000),
+            display: None,
+            is_maximized: true,
+        }
+        .clamp(&[primary, secondary]);
+        assert_eq!(PhysicalPosition::new(1920, 0), geometry.position);
+        assert_eq!(PhysicalSize::new(1280, 984), geometry.dimensions);
+    }
 }
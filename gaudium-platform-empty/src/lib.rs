@@ -1,6 +1,10 @@
 use gaudium_core::platform::{PlatformBinding, Proxy};
 use gaudium_core::window::WindowBuilder;
 
+mod queue;
+
+pub use crate::queue::{injector, record_reactions, take_reactions, Injector};
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Binding {}
 
@@ -9,6 +13,13 @@ impl PlatformBinding for Binding {
     type WindowBuilder = empty::WindowBuilder;
     type Device = empty::Device;
     type Display = empty::Display;
+    type Waker = queue::Waker;
+    type KeyLayout = empty::KeyLayout;
+    type Dialog = empty::Dialog;
+    type Hotkey = empty::Hotkey;
+    type Timer = empty::Timer;
+    type SingleInstance = empty::SingleInstance;
+    type RawEvent = empty::RawEvent;
 }
 
 pub trait WindowBuilderExt: Sized {
@@ -27,28 +38,212 @@ impl WindowBuilderExt for WindowBuilder<Binding> {
 }
 
 mod empty {
+    use gaudium_core::accessibility::{AccessibleElement, Role};
+    use gaudium_core::device::{Battery, DeviceInfo, Led, SensorType};
+    use gaudium_core::display::{
+        LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, VideoMode,
+    };
+    use gaudium_core::error::{Error, ErrorKind};
+    use gaudium_core::event::{ApplicationEvent, Event, KeyCode, Resumption, ScanCode};
     use gaudium_core::platform;
-    use gaudium_core::reactor::Reactor;
-    use gaudium_core::reactor::ThreadContext;
-    use gaudium_core::window::WindowHandle;
+    use gaudium_core::reactor::{
+        instrument, Poll, Reaction, Reactor, Recurrence, Sinks, ThreadContext, TimerId,
+    };
+    use gaudium_core::shell::dialog::{FileDialog, MessageButtons, MessageDialog, MessageResponse};
+    use gaudium_core::shell::hotkey::{HotkeyId, Modifiers};
+    use gaudium_core::window::{
+        CursorIcon, Fullscreen, Geometry, HitTest, Icon, MouseMode, WindowKind, ZOrder,
+    };
+    use std::any::Any;
+    use std::ffi::OsString;
+    use std::path::PathBuf;
     use std::process;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
 
+    use crate::queue::{self, Queue};
     use crate::Binding;
 
+    type UserData = Box<dyn Any + Send>;
+
+    static WINDOW_COUNT: AtomicU64 = AtomicU64::new(0);
+    static USER_DATA: Mutex<Vec<(u64, UserData)>> = Mutex::new(Vec::new());
+    static SURFACES: Mutex<Vec<(u64, Arc<AtomicBool>)>> = Mutex::new(Vec::new());
+
+    fn take_user_data(window: u64) -> Option<UserData> {
+        let mut entries = USER_DATA.lock().unwrap();
+        entries
+            .iter()
+            .position(|(id, _)| *id == window)
+            .map(|index| entries.swap_remove(index).1)
+    }
+
     pub struct EventThread;
 
-    impl platform::Abort<Binding> for EventThread {
-        fn run_and_abort<R>(_: ThreadContext, _: WindowHandle<Binding>, reactor: R) -> !
+    impl EventThread {
+        /// Runs an event loop over the in-memory event queue of the current
+        /// thread.
+        ///
+        /// The event loop stops when the reactor aborts or when the reactor
+        /// waits for events and no events can be injected.
+        fn run<R>(context: ThreadContext, mut reactor: R)
         where
             R: Reactor<Binding>,
         {
+            let queue = Queue::current();
+            let react = |reactor: &mut R, event: Event<Binding>, depth: usize| {
+                instrument::instrument(&context, |instrumentation| {
+                    instrumentation.dispatch_started(Instant::now(), depth)
+                });
+                let reaction = reactor.react(&context, event);
+                instrument::instrument(&context, |instrumentation| {
+                    instrumentation.dispatch_completed(Instant::now())
+                });
+                queue::record(event, reaction);
+                reaction == Reaction::Abort
+            };
+            // Events that are drained from the queue together are dispatched
+            // in a batch. Each event is recorded with the reaction to its
+            // batch.
+            let react_all = |reactor: &mut R, events: &[Event<Binding>]| {
+                let depth = events.len();
+                instrument::instrument(&context, |instrumentation| {
+                    instrumentation.dispatch_started(Instant::now(), depth)
+                });
+                let reaction = reactor.react_all(&context, events.into());
+                instrument::instrument(&context, |instrumentation| {
+                    instrumentation.dispatch_completed(Instant::now())
+                });
+                for event in events {
+                    queue::record(*event, reaction);
+                }
+                reaction == Reaction::Abort
+            };
+            // The buffer into which events are drained is reused by each
+            // flush.
+            let mut events = vec![];
+            let mut is_aborted = false;
+            let mut resumed = Instant::now();
+            loop {
+                instrument::instrument(&context, |instrumentation| {
+                    instrumentation.flush_started(Instant::now())
+                });
+                queue.drain_into(&mut events);
+                let is_idle = events.is_empty();
+                if !is_idle {
+                    is_aborted |= react_all(&mut reactor, &events);
+                    events.clear();
+                }
+                is_aborted |= react(
+                    &mut reactor,
+                    Event::Application {
+                        event: ApplicationEvent::Flushed,
+                        timestamp: Instant::now(),
+                    },
+                    0,
+                );
+                instrument::instrument(&context, |instrumentation| {
+                    instrumentation.flush_completed(Instant::now())
+                });
+                let poll = |reactor: &mut R| {
+                    let reaction = reactor.poll(&context);
+                    instrument::instrument(&context, |instrumentation| {
+                        instrumentation.polled(Instant::now(), reaction)
+                    });
+                    reaction
+                };
+                let mut reaction = poll(&mut reactor);
+                // Dispatch `Idle` before waiting if no events were dispatched
+                // and poll the reactor again.
+                if is_idle && !is_aborted {
+                    if let Some(deadline) = idle_deadline(&context, reaction) {
+                        is_aborted |= react(
+                            &mut reactor,
+                            Event::Application {
+                                event: ApplicationEvent::Idle { deadline },
+                                timestamp: Instant::now(),
+                            },
+                            0,
+                        );
+                        reaction = poll(&mut reactor);
+                    }
+                }
+                let resumption = match if is_aborted {
+                    Reaction::Abort
+                }
+                else {
+                    reaction
+                } {
+                    Reaction::Continue(Poll::Ready) => Resumption::Poll,
+                    Reaction::Continue(Poll::ReadyThrottled(duration)) => {
+                        queue.wait_until(resumed + duration);
+                        Resumption::Poll
+                    }
+                    Reaction::Continue(Poll::Wait) => {
+                        if queue.wait() {
+                            Resumption::Poll
+                        }
+                        else {
+                            break;
+                        }
+                    }
+                    Reaction::Continue(Poll::WaitUntil(until)) => queue.wait_until(until),
+                    Reaction::Abort => break,
+                };
+                resumed = Instant::now();
+                instrument::instrument(&context, |instrumentation| {
+                    instrumentation.resumed(resumed, resumption)
+                });
+                is_aborted |= react(
+                    &mut reactor,
+                    Event::Application {
+                        event: ApplicationEvent::Resumed(resumption),
+                        timestamp: resumed,
+                    },
+                    0,
+                );
+            }
             reactor.abort();
-            process::abort()
+        }
+    }
+
+    /// Gets the deadline of `Idle` if the reactor is about to wait.
+    fn idle_deadline(context: &ThreadContext, reaction: Reaction<Poll>) -> Option<Instant> {
+        let deadline = Instant::now() + context.idle_budget()?;
+        match reaction {
+            Reaction::Continue(Poll::Wait) => Some(deadline),
+            Reaction::Continue(Poll::WaitUntil(until)) => Some(deadline.min(until)),
+            _ => None,
+        }
+    }
+
+    impl platform::Abort<Binding> for EventThread {
+        fn run_and_abort<R>(context: ThreadContext, _: Sinks<Binding>, reactor: R) -> !
+        where
+            R: Reactor<Binding>,
+        {
+            EventThread::run(context, reactor);
+            process::exit(0)
+        }
+    }
+
+    impl platform::Join<Binding> for EventThread {
+        fn run_and_join<R>(
+            context: ThreadContext,
+            _: Sinks<Binding>,
+            reactor: R,
+        ) -> Result<(), Error>
+        where
+            R: Reactor<Binding>,
+        {
+            EventThread::run(context, reactor);
+            Ok(())
         }
     }
 
     #[derive(Debug, Eq, Hash, PartialEq)]
-    pub struct Device(usize);
+    pub struct Device(u64);
 
     impl platform::Device for Device {
         type Query = Option<Self>;
@@ -56,10 +251,42 @@ mod empty {
         fn connected() -> Self::Query {
             None
         }
+
+        fn info(_: Self::Handle) -> Option<DeviceInfo> {
+            None
+        }
+
+        fn battery(_: Self::Handle) -> Option<Battery> {
+            None
+        }
+
+        fn has_sensor(_: Self::Handle, _: SensorType) -> bool {
+            false
+        }
+    }
+
+    impl platform::Output for Device {
+        fn has_led(_: Self::Handle, _: Led) -> bool {
+            false
+        }
+
+        fn set_led(_: Self::Handle, _: Led, _: bool) -> Result<(), Error> {
+            Err(Error::new(
+                ErrorKind::Unsupported,
+                "platform does not support device LEDs",
+            ))
+        }
+
+        fn send_output_report(_: Self::Handle, _: &[u8]) -> Result<(), Error> {
+            Err(Error::new(
+                ErrorKind::Unsupported,
+                "platform does not support device output reports",
+            ))
+        }
     }
 
     impl platform::Handle for Device {
-        type Handle = usize;
+        type Handle = u64;
 
         fn handle(&self) -> Self::Handle {
             self.0
@@ -67,7 +294,7 @@ mod empty {
     }
 
     #[derive(Debug, Eq, Hash, PartialEq)]
-    pub struct Display(usize);
+    pub struct Display(u64);
 
     impl platform::Display for Display {
         type Query = Option<Self>;
@@ -75,16 +302,124 @@ mod empty {
         fn connected() -> Self::Query {
             None
         }
+
+        fn modes(_: Self::Handle) -> Vec<VideoMode> {
+            vec![]
+        }
+
+        fn mode(_: Self::Handle) -> Option<VideoMode> {
+            None
+        }
+
+        fn set_mode(_: Self::Handle, _: Option<VideoMode>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn work_area(_: Self::Handle) -> Option<(PhysicalPosition, PhysicalSize)> {
+            None
+        }
     }
 
     impl platform::Handle for Display {
-        type Handle = usize;
+        type Handle = u64;
 
         fn handle(&self) -> Self::Handle {
             self.0
         }
     }
 
+    /// Dialogs that are dismissed immediately as if cancelled.
+    pub struct Dialog;
+
+    impl platform::Dialog<Binding> for Dialog {
+        fn show_message(
+            _: &ThreadContext,
+            dialog: &MessageDialog<Binding>,
+        ) -> Result<MessageResponse, Error> {
+            Ok(match dialog.buttons() {
+                MessageButtons::Ok => MessageResponse::Ok,
+                MessageButtons::YesNo => MessageResponse::No,
+                MessageButtons::OkCancel | MessageButtons::YesNoCancel => MessageResponse::Cancel,
+            })
+        }
+
+        fn open_files(
+            _: &ThreadContext,
+            _: &FileDialog<Binding>,
+            _: bool,
+        ) -> Result<Vec<PathBuf>, Error> {
+            Ok(vec![])
+        }
+
+        fn save_file(_: &ThreadContext, _: &FileDialog<Binding>) -> Result<Option<PathBuf>, Error> {
+            Ok(None)
+        }
+    }
+
+    /// Hotkeys that are registered but never pressed.
+    pub struct Hotkey;
+
+    impl platform::Hotkey for Hotkey {
+        fn register(_: &ThreadContext, _: HotkeyId, _: Modifiers, _: KeyCode) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn unregister(_: HotkeyId) {}
+    }
+
+    /// Timers that are set but never elapse.
+    pub struct Timer;
+
+    impl platform::Timer for Timer {
+        fn set(_: &ThreadContext, _: TimerId, _: Duration, _: Recurrence) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn cancel(_: &ThreadContext, _: TimerId) {}
+    }
+
+    /// Platform messages, of which there are none.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum RawEvent {}
+
+    /// Instances that always own their name and never receive arguments.
+    pub struct SingleInstance;
+
+    impl platform::SingleInstance for SingleInstance {
+        fn acquire(_: &ThreadContext, _: &str, _: Vec<OsString>) -> Result<Option<Self>, Error> {
+            Ok(Some(SingleInstance))
+        }
+
+        fn take_arguments(&mut self) -> Option<Vec<OsString>> {
+            None
+        }
+    }
+
+    /// A keyboard layout that cannot translate any keys.
+    pub struct KeyLayout;
+
+    impl platform::KeyLayout for KeyLayout {
+        fn from_context(_: &ThreadContext) -> Self {
+            KeyLayout
+        }
+
+        fn id(&self) -> u64 {
+            0
+        }
+
+        fn language(&self) -> Option<String> {
+            None
+        }
+
+        fn keycode(&self, _: ScanCode) -> Option<KeyCode> {
+            None
+        }
+
+        fn label(&self, _: ScanCode) -> Option<String> {
+            None
+        }
+    }
+
     pub struct WindowBuilder {
         title: String,
     }
@@ -110,15 +445,105 @@ mod empty {
     impl platform::WindowBuilder for WindowBuilder {
         type Window = Window;
 
-        fn build(self, _: &ThreadContext) -> Result<Self::Window, ()> {
-            // TODO: All windows will compare and hash as equal.
-            Ok(Window(0))
+        fn build(self, _: &ThreadContext) -> Result<Self::Window, Error> {
+            Ok(Window(WINDOW_COUNT.fetch_add(1, Ordering::Relaxed)))
+        }
+
+        fn with_icon(self, _: Icon) -> Self {
+            self
+        }
+
+        fn with_always_on_top(self, _: bool) -> Self {
+            self
+        }
+
+        fn with_active(self, _: bool) -> Self {
+            self
+        }
+
+        fn with_decorations(self, _: bool) -> Self {
+            self
+        }
+
+        fn with_transparent(self, _: bool) -> Self {
+            self
+        }
+
+        fn with_resizable(self, _: bool) -> Self {
+            self
+        }
+
+        fn with_min_dimensions(self, _: LogicalSize) -> Self {
+            self
+        }
+
+        fn with_max_dimensions(self, _: LogicalSize) -> Self {
+            self
+        }
+
+        fn with_aspect_ratio(self, _: f64) -> Self {
+            self
+        }
+
+        fn with_fullscreen(self, _: Fullscreen) -> Self {
+            self
+        }
+
+        fn with_opacity(self, _: f32) -> Self {
+            self
+        }
+
+        fn with_kind(self, _: WindowKind) -> Self {
+            self
+        }
+
+        fn with_owner(self, _: u64) -> Self {
+            self
+        }
+
+        fn with_parent(self, _: u64) -> Self {
+            self
+        }
+
+        fn with_position(self, _: PhysicalPosition) -> Self {
+            self
+        }
+
+        fn with_centered_in(self, _: PhysicalPosition, _: PhysicalSize) -> Self {
+            self
+        }
+
+        fn with_geometry(self, _: Geometry) -> Self {
+            self
         }
     }
 
     #[derive(Eq, Hash, PartialEq)]
     pub struct Window(u64);
 
+    impl Drop for Window {
+        fn drop(&mut self) {
+            drop(take_user_data(self.0));
+            let mut surfaces = SURFACES.lock().unwrap();
+            if let Some(index) = surfaces.iter().position(|(id, _)| *id == self.0) {
+                surfaces
+                    .swap_remove(index)
+                    .1
+                    .store(false, Ordering::Release);
+            }
+        }
+    }
+
+    /// A surface token with no raw handles.
+    #[derive(Clone, Debug)]
+    pub struct SurfaceToken(Arc<AtomicBool>);
+
+    impl platform::SurfaceToken for SurfaceToken {
+        fn is_alive(&self) -> bool {
+            self.0.load(Ordering::Acquire)
+        }
+    }
+
     impl platform::Handle for Window {
         type Handle = u64;
 
@@ -126,4 +551,1473 @@ mod empty {
             self.0
         }
     }
+
+    impl platform::Accessible for Window {
+        fn set_accessible_name(&mut self, _: String) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_accessible_role(&mut self, _: Role) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_accessible_focus(&mut self, _: Option<AccessibleElement>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn notify_accessible_change(&mut self, _: AccessibleElement) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl platform::Surface for Window {
+        type Token = SurfaceToken;
+
+        fn set_icon(&mut self, _: Option<Icon>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_mouse_mode(&mut self, _: MouseMode) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_cursor_icon(&mut self, _: CursorIcon) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_pointer_capture(&mut self, _: bool) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn focus(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_focus(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_always_on_top(&mut self, _: bool) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_z_order(&mut self, _: ZOrder) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_hit_test(&mut self, _: Option<HitTest>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_min_dimensions(&mut self, _: Option<LogicalSize>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_max_dimensions(&mut self, _: Option<LogicalSize>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_aspect_ratio(&mut self, _: Option<f64>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_fullscreen(&mut self, _: Option<Fullscreen>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_maximized(&mut self, _: bool) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_minimized(&mut self, _: bool) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn restore(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn is_opacity_supported() -> bool {
+            false
+        }
+
+        fn set_opacity(&mut self, _: f32) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn set_modal(&mut self, _: bool) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn request_frame(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn outer_position(&self) -> Result<LogicalPosition, Error> {
+            Ok(LogicalPosition::default())
+        }
+
+        fn inner_size(&self) -> Result<LogicalSize, Error> {
+            Ok(LogicalSize::default())
+        }
+
+        fn outer_size(&self) -> Result<LogicalSize, Error> {
+            Ok(LogicalSize::default())
+        }
+
+        fn is_focused(&self) -> bool {
+            false
+        }
+
+        fn is_minimized(&self) -> bool {
+            false
+        }
+
+        fn geometry(&self) -> Result<Geometry, Error> {
+            Ok(Geometry::default())
+        }
+
+        fn surface_token(&self) -> Self::Token {
+            let mut surfaces = SURFACES.lock().unwrap();
+            let is_alive = match surfaces.iter().find(|(id, _)| *id == self.0) {
+                Some((_, is_alive)) => is_alive.clone(),
+                None => {
+                    let is_alive = Arc::new(AtomicBool::new(true));
+                    surfaces.push((self.0, is_alive.clone()));
+                    is_alive
+                }
+            };
+            SurfaceToken(is_alive)
+        }
+
+        fn set_user_data(&mut self, data: Option<Box<dyn Any + Send>>) -> Result<(), Error> {
+            let previous = take_user_data(self.0);
+            if let Some(data) = data {
+                USER_DATA.lock().unwrap().push((self.0, data));
+            }
+            drop(previous);
+            Ok(())
+        }
+
+        fn with_user_data(handle: Self::Handle, f: &mut dyn FnMut(&mut dyn Any)) {
+            // The data is removed while the function executes so that the
+            // lock is not held.
+            if let Some(mut data) = take_user_data(handle) {
+                f(data.as_mut());
+                USER_DATA.lock().unwrap().push((handle, data));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_core::Stream;
+    use gaudium_core::device::DeviceHandle;
+    use gaudium_core::display::LogicalPosition;
+    use gaudium_core::event::{
+        ApplicationEvent, ElementState, Event, InputEvent, InputFocus, KeyCode, ModifierState,
+        MouseButton, MouseMovement, Resumption, ToggleState, WindowCloseState, WindowEvent,
+    };
+    use gaudium_core::framework::diagnostics::{FpsCounter, FpsMonitor};
+    use gaudium_core::framework::filter::{self, Predicate};
+    use gaudium_core::framework::input::{
+        ClickGestures, DeviceMap, Gesture, KeyRepeat, KeyboardSnapshot,
+    };
+    use gaudium_core::framework::record::{self, EventPlayer, Recorder, Timing};
+    use gaudium_core::framework::React;
+    use gaudium_core::reactor::{
+        self, EventThread, Poll, Reaction, Sink, StatefulReactor, ThreadContext,
+    };
+    use gaudium_core::reactor::{EventBatch, Reactor};
+    use gaudium_core::window::{Window, WindowBuilder, WindowHandle};
+    use gaudium_core::FromRawHandle;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::panic;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::task::{self, Context, Waker};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::Binding;
+
+    #[test]
+    fn inject_and_abort() {
+        crate::record_reactions();
+        let injector = crate::injector();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            let handle = window.handle();
+            injector.inject(Event::Window {
+                window: handle,
+                event: WindowEvent::Closed(WindowCloseState::Requested),
+                timestamp: Instant::now(),
+            });
+            (
+                handle,
+                StatefulReactor::from((
+                    (window, injector),
+                    |_: &mut (Window<Binding>, _), _: &ThreadContext, event| match event {
+                        Event::Window {
+                            event: WindowEvent::Closed(..),
+                            ..
+                        } => Reaction::Abort,
+                        _ => Reaction::Continue(()),
+                    },
+                )),
+            )
+        })
+        .unwrap();
+        let reactions = crate::take_reactions();
+        assert!(matches!(
+            reactions[0],
+            (
+                Event::Window {
+                    event: WindowEvent::Closed(WindowCloseState::Requested),
+                    ..
+                },
+                Reaction::Abort,
+            )
+        ));
+        assert!(matches!(
+            reactions[1],
+            (
+                Event::Application {
+                    event: ApplicationEvent::Flushed,
+                    ..
+                },
+                Reaction::Continue(()),
+            )
+        ));
+        assert_eq!(reactions.len(), 2);
+    }
+
+    #[test]
+    fn swap_reactor() {
+        crate::record_reactions();
+        let is_swapped = Arc::new(AtomicBool::new(false));
+        let swapped = is_swapped.clone();
+        let injector = crate::injector();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            let handle = window.handle();
+            injector.inject(Event::Window {
+                window: handle,
+                event: WindowEvent::Closed(WindowCloseState::Requested),
+                timestamp: Instant::now(),
+            });
+            (
+                handle,
+                StatefulReactor::from((
+                    (Some(window), injector),
+                    move |(window, _): &mut (Option<Window<Binding>>, _),
+                          context: &ThreadContext,
+                          _: Event<Binding>| {
+                        // Move the window into the replacement so that it is
+                        // not dropped when this reactor is aborted.
+                        let window = window.take();
+                        let swapped = swapped.clone();
+                        context.swap_reactor(StatefulReactor::from((
+                            window,
+                            move |_: &mut Option<Window<Binding>>,
+                                  _: &ThreadContext,
+                                  _: Event<Binding>| {
+                                swapped.store(true, Ordering::SeqCst);
+                                Reaction::Abort
+                            },
+                        )));
+                        Reaction::Continue(())
+                    },
+                )),
+            )
+        })
+        .unwrap();
+        assert!(is_swapped.load(Ordering::SeqCst));
+        let reactions = crate::take_reactions();
+        assert_eq!(reactions.len(), 2);
+    }
+
+    #[test]
+    fn panic_propagates() {
+        crate::record_reactions();
+        let result = panic::catch_unwind(|| {
+            let injector = crate::injector();
+            EventThread::<Binding, _>::run_and_join_with(move |context| {
+                let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+                let handle = window.handle();
+                injector.inject(Event::Window {
+                    window: handle,
+                    event: WindowEvent::Closed(WindowCloseState::Requested),
+                    timestamp: Instant::now(),
+                });
+                (
+                    handle,
+                    StatefulReactor::from((
+                        (window, injector),
+                        |_: &mut (Window<Binding>, _), _: &ThreadContext, event| match event {
+                            Event::Window {
+                                event: WindowEvent::Closed(..),
+                                ..
+                            } => panic!("closed"),
+                            _ => Reaction::Continue(()),
+                        },
+                    )),
+                )
+            })
+        });
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"closed"));
+        // All events after the panic abort the event loop.
+        let reactions = crate::take_reactions();
+        assert!(matches!(
+            reactions[0],
+            (
+                Event::Window {
+                    event: WindowEvent::Closed(WindowCloseState::Requested),
+                    ..
+                },
+                Reaction::Abort,
+            )
+        ));
+        assert!(reactions
+            .iter()
+            .all(|&(_, reaction)| reaction == Reaction::Abort));
+    }
+
+    #[test]
+    fn concurrent_event_threads() {
+        let threads = (0..2)
+            .map(|_| {
+                thread::spawn(|| {
+                    crate::record_reactions();
+                    let injector = crate::injector();
+                    EventThread::<Binding, _>::run_and_join_with(move |context| {
+                        let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+                        let handle = window.handle();
+                        injector.inject(Event::Window {
+                            window: handle,
+                            event: WindowEvent::Closed(WindowCloseState::Requested),
+                            timestamp: Instant::now(),
+                        });
+                        (
+                            handle,
+                            StatefulReactor::from((
+                                (window, injector),
+                                |_: &mut (Window<Binding>, _), _: &ThreadContext, event| match event
+                                {
+                                    Event::Window {
+                                        event: WindowEvent::Closed(..),
+                                        ..
+                                    } => Reaction::Abort,
+                                    _ => Reaction::Continue(()),
+                                },
+                            )),
+                        )
+                    })
+                    .unwrap();
+                    crate::take_reactions()
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            // Each event thread observes only the events of its own window.
+            let reactions = thread.join().unwrap();
+            let windows = reactions
+                .iter()
+                .filter_map(|(event, _)| event.window_handle())
+                .collect::<Vec<_>>();
+            assert!(!windows.is_empty());
+            assert!(windows.iter().all(|window| *window == windows[0]));
+        }
+    }
+
+    #[test]
+    fn nested_event_thread_panics() {
+        let result = panic::catch_unwind(|| {
+            EventThread::<Binding, _>::run_and_join_with(|_| {
+                let _ = EventThread::<Binding, _>::run_and_join_with(|_| {
+                    (
+                        Sink::Headless,
+                        StatefulReactor::from(((), |_: &mut (), _: &ThreadContext, _| {
+                            Reaction::Abort
+                        })),
+                    )
+                });
+                (
+                    Sink::Headless,
+                    StatefulReactor::from(((), |_: &mut (), _: &ThreadContext, _| Reaction::Abort)),
+                )
+            })
+        });
+        assert!(result.is_err());
+        // The thread may run another event loop once the first has stopped.
+        EventThread::<Binding, _>::run_and_join_with(|_| {
+            (
+                Sink::Headless,
+                StatefulReactor::from(((), |_: &mut (), _: &ThreadContext, _| Reaction::Abort)),
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn user_data() {
+        let injector = crate::injector();
+        let is_found = Arc::new(AtomicBool::new(false));
+        let found = is_found.clone();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let mut window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            window.set_user_data(1u32).unwrap();
+            let handle = window.handle();
+            injector.inject(Event::Window {
+                window: handle,
+                event: WindowEvent::Closed(WindowCloseState::Requested),
+                timestamp: Instant::now(),
+            });
+            (
+                handle,
+                StatefulReactor::from((
+                    (window, injector),
+                    move |_: &mut (Window<Binding>, _), context: &ThreadContext, event| match event
+                    {
+                        Event::Window {
+                            window,
+                            event: WindowEvent::Closed(..),
+                            ..
+                        } => {
+                            let data = window.with_user_data(context, |data: &mut u32| {
+                                *data += 1;
+                                *data
+                            });
+                            let mismatch = window.with_user_data(context, |_: &mut String| ());
+                            found.store(data == Some(2) && mismatch.is_none(), Ordering::SeqCst);
+                            Reaction::Abort
+                        }
+                        _ => Reaction::Continue(()),
+                    },
+                )),
+            )
+        })
+        .unwrap();
+        assert!(is_found.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn child_window_parent() {
+        let is_found = Arc::new(AtomicBool::new(false));
+        let found = is_found.clone();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let parent = WindowBuilder::<Binding>::default().build(context).unwrap();
+            let child = WindowBuilder::<Binding>::default()
+                .with_parent(&parent)
+                .build(context)
+                .unwrap();
+            let grandchild = WindowBuilder::<Binding>::default()
+                .with_parent(&child)
+                .build(context)
+                .unwrap();
+            found.store(
+                parent.handle().parent().is_none()
+                    && child.handle().parent() == Some(parent.handle())
+                    && grandchild.handle().root() == parent.handle(),
+                Ordering::SeqCst,
+            );
+            (
+                parent.handle(),
+                StatefulReactor::from((
+                    (parent, child, grandchild),
+                    |_: &mut _, _: &ThreadContext, _| Reaction::Abort,
+                )),
+            )
+        })
+        .unwrap();
+        assert!(is_found.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn surface_token() {
+        let injector = crate::injector();
+        let (tx, rx) = mpsc::channel();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            let token = window.surface_token();
+            tx.send(token.clone()).unwrap();
+            assert!(thread::spawn(move || token.is_alive()).join().unwrap());
+            let handle = window.handle();
+            injector.inject(Event::Window {
+                window: handle,
+                event: WindowEvent::Closed(WindowCloseState::Requested),
+                timestamp: Instant::now(),
+            });
+            (
+                handle,
+                StatefulReactor::from((
+                    (window, injector),
+                    |_: &mut (Window<Binding>, _), _: &ThreadContext, event| match event {
+                        Event::Window {
+                            event: WindowEvent::Closed(..),
+                            ..
+                        } => Reaction::Abort,
+                        _ => Reaction::Continue(()),
+                    },
+                )),
+            )
+        })
+        .unwrap();
+        assert!(!rx.recv().unwrap().is_alive());
+    }
+
+    #[test]
+    fn wait_for_injection() {
+        crate::record_reactions();
+        let injector = crate::injector();
+        let handle = thread::spawn(move || {
+            injector.inject(Event::Application {
+                event: ApplicationEvent::LowMemory,
+                timestamp: Instant::now(),
+            });
+        });
+        EventThread::<Binding, _>::run_and_join_with(|context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            (
+                window.handle(),
+                StatefulReactor::from((window, |_: &mut _, _: &ThreadContext, _| {
+                    Reaction::Continue(())
+                })),
+            )
+        })
+        .unwrap();
+        handle.join().unwrap();
+        // The event loop stops when all injectors have been dropped.
+        assert!(crate::take_reactions().iter().any(|(event, _)| matches!(
+            event,
+            Event::Application {
+                event: ApplicationEvent::LowMemory,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn run_headless() {
+        let is_headless = Arc::new(AtomicBool::new(false));
+        let headless = is_headless.clone();
+        EventThread::<Binding, _>::run_and_join_with(move |_| {
+            (
+                Sink::Headless,
+                StatefulReactor::from(((), move |_: &mut _, context: &ThreadContext, _| {
+                    headless.store(context.sink::<Binding>().is_none(), Ordering::SeqCst);
+                    Reaction::Abort
+                })),
+            )
+        })
+        .unwrap();
+        assert!(is_headless.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn pre_filter_consumes() {
+        crate::record_reactions();
+        let injector = crate::injector();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            let handle = window.handle();
+            for state in [WindowCloseState::Requested, WindowCloseState::Committed] {
+                injector.inject(Event::Window {
+                    window: handle,
+                    event: WindowEvent::Closed(state),
+                    timestamp: Instant::now(),
+                });
+            }
+            context.add_pre_filter(|_: &ThreadContext, event: Event<Binding>| match event {
+                Event::Window {
+                    event: WindowEvent::Closed(WindowCloseState::Requested),
+                    ..
+                } => None,
+                _ => Some(event),
+            });
+            (
+                handle,
+                StatefulReactor::from((
+                    (window, injector),
+                    |_: &mut (Window<Binding>, _), _: &ThreadContext, event| match event {
+                        Event::Window {
+                            event: WindowEvent::Closed(WindowCloseState::Requested),
+                            ..
+                        }
+                        | Event::Application {
+                            event: ApplicationEvent::Flushed,
+                            ..
+                        } => Reaction::Abort,
+                        _ => Reaction::Continue(()),
+                    },
+                )),
+            )
+        })
+        .unwrap();
+        // The reactor aborts if it receives `Closed(Requested)`, in which case
+        // the batch containing it would be recorded as aborting.
+        let reactions = crate::take_reactions();
+        assert!(matches!(
+            reactions[..],
+            [
+                (
+                    Event::Window {
+                        event: WindowEvent::Closed(WindowCloseState::Requested),
+                        ..
+                    },
+                    Reaction::Continue(()),
+                ),
+                (
+                    Event::Window {
+                        event: WindowEvent::Closed(WindowCloseState::Committed),
+                        ..
+                    },
+                    Reaction::Continue(()),
+                ),
+                (
+                    Event::Application {
+                        event: ApplicationEvent::Flushed,
+                        ..
+                    },
+                    Reaction::Abort,
+                ),
+            ]
+        ));
+    }
+
+    #[test]
+    fn idle_before_wait() {
+        crate::record_reactions();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            context.set_idle_budget(Some(Duration::from_millis(1)));
+            (
+                Sink::Headless,
+                StatefulReactor::from(((), |_: &mut _, _: &ThreadContext, event| match event {
+                    Event::Application {
+                        event: ApplicationEvent::Idle { .. },
+                        ..
+                    } => Reaction::Abort,
+                    _ => Reaction::Continue(()),
+                })),
+            )
+        })
+        .unwrap();
+        let reactions = crate::take_reactions();
+        assert!(matches!(
+            reactions[..],
+            [
+                (
+                    Event::Application {
+                        event: ApplicationEvent::Flushed,
+                        ..
+                    },
+                    Reaction::Continue(()),
+                ),
+                (
+                    Event::Application {
+                        event: ApplicationEvent::Idle { .. },
+                        ..
+                    },
+                    Reaction::Abort,
+                ),
+            ]
+        ));
+    }
+
+    #[test]
+    fn spawn_local_and_wake() {
+        // Completes after being woken by another thread.
+        struct Remote(Option<thread::JoinHandle<()>>);
+
+        impl Future for Remote {
+            type Output = ();
+
+            fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> task::Poll<()> {
+                match self.0.take() {
+                    Some(handle) => {
+                        handle.join().unwrap();
+                        task::Poll::Ready(())
+                    }
+                    None => {
+                        let waker = context.waker().clone();
+                        self.0 = Some(thread::spawn(move || waker.wake()));
+                        task::Poll::Pending
+                    }
+                }
+            }
+        }
+
+        let is_complete = Arc::new(AtomicBool::new(false));
+        let is_complete_task = is_complete.clone();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            // The injector keeps the event loop running until the task
+            // completes.
+            let injector = crate::injector();
+            context.spawn_local(async move {
+                Remote(None).await;
+                is_complete_task.store(true, Ordering::SeqCst);
+                drop(injector);
+            });
+            (
+                window.handle(),
+                StatefulReactor::from((window, |_: &mut _, _: &ThreadContext, _| {
+                    Reaction::Continue(())
+                })),
+            )
+        })
+        .unwrap();
+        assert!(is_complete.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn proxy_execute() {
+        let (tx, rx) = mpsc::channel();
+        let is_executed = Arc::new(AtomicBool::new(false));
+        let executed = is_executed.clone();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            // The injector keeps the event loop running until the command
+            // executes.
+            let injector = crate::injector();
+            let proxy = context.proxy();
+            tx.send(proxy.clone()).unwrap();
+            thread::spawn(move || {
+                proxy
+                    .execute(move |_| {
+                        executed.store(true, Ordering::SeqCst);
+                        drop(injector);
+                    })
+                    .unwrap();
+            });
+            (
+                window.handle(),
+                StatefulReactor::from((window, |_: &mut _, _: &ThreadContext, _| {
+                    Reaction::Continue(())
+                })),
+            )
+        })
+        .unwrap();
+        assert!(is_executed.load(Ordering::SeqCst));
+        // Commands are rejected once the event loop has stopped.
+        assert!(rx.recv().unwrap().execute(|_| {}).is_err());
+    }
+
+    #[test]
+    fn proxy_request_abort() {
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            // The injector keeps the event loop running until the reactor
+            // aborts.
+            let injector = crate::injector();
+            let proxy = context.proxy();
+            thread::spawn(move || proxy.request_abort().unwrap());
+            (
+                window.handle(),
+                StatefulReactor::from((
+                    (window, injector),
+                    |_: &mut _, _: &ThreadContext, event| match event {
+                        Event::Application {
+                            event: ApplicationEvent::AbortRequested,
+                            ..
+                        } => Reaction::Abort,
+                        _ => Reaction::Continue(()),
+                    },
+                )),
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn fps_counter() {
+        EventThread::<Binding, _>::run_and_join_with(|context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            let handle = window.handle();
+            let monitor = FpsMonitor::default();
+            // Abort once the first interval has been measured.
+            let reactor = StatefulReactor::from((
+                (window, monitor.clone()),
+                |(_, monitor): &mut (Window<Binding>, FpsMonitor), _: &ThreadContext, _| {
+                    match monitor.statistics() {
+                        Some(statistics) => {
+                            assert!(statistics.flushes_per_second > 0.0);
+                            assert!(
+                                statistics.max_flush_interval >= statistics.mean_flush_interval
+                            );
+                            Reaction::Abort
+                        }
+                        None => Reaction::Continue(()),
+                    }
+                },
+            ))
+            .with_poll(|_, _| Poll::Ready.into());
+            (
+                handle,
+                FpsCounter::new(reactor, monitor).with_interval(Duration::from_millis(5)),
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn stream() {
+        let injector = crate::injector();
+        injector.inject(Event::Application {
+            event: ApplicationEvent::LowMemory,
+            timestamp: Instant::now(),
+        });
+        drop(injector);
+        let (sender, mut stream) = reactor::stream::channel(16);
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            (window.handle(), sender)
+        })
+        .unwrap();
+        let mut context = Context::from_waker(Waker::noop());
+        let mut events = vec![];
+        while let task::Poll::Ready(Some(event)) = Pin::new(&mut stream).poll_next(&mut context) {
+            events.push(event);
+        }
+        // The stream terminates when the event loop stops.
+        assert!(Pin::new(&mut stream).poll_next(&mut context).is_ready());
+        assert!(matches!(
+            events[0],
+            Event::Application {
+                event: ApplicationEvent::LowMemory,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let start = Instant::now();
+        let window = WindowHandle::<Binding>::from_raw_handle(42);
+        let events = [
+            Event::Application {
+                event: ApplicationEvent::Flushed,
+                timestamp: start,
+            },
+            Event::Window {
+                window,
+                event: WindowEvent::Focused(true),
+                timestamp: start + Duration::from_millis(1),
+            },
+            Event::Input {
+                device: DeviceHandle::from_raw_handle(7),
+                window: Some(window),
+                event: InputEvent::KeyboardKeyChanged {
+                    scancode: 0x1e,
+                    keycode: Some(KeyCode::A),
+                    state: ElementState::Pressed,
+                    modifier: ModifierState {
+                        toggles: ToggleState {
+                            caps_lock: true,
+                            ..Default::default()
+                        },
+                    },
+                    repeat: false,
+                },
+                focus: InputFocus::Foreground,
+                timestamp: start + Duration::from_millis(2),
+            },
+            Event::Application {
+                event: ApplicationEvent::Resumed(Resumption::Timeout(
+                    start + Duration::from_millis(1),
+                )),
+                timestamp: start + Duration::from_millis(3),
+            },
+        ];
+        let write = |events: &mut dyn Iterator<Item = &Event<Binding>>| {
+            let mut recorder = Recorder::<Binding>::default();
+            let mut buffer = vec![];
+            for event in events {
+                recorder.write(&mut buffer, event).unwrap();
+            }
+            buffer
+        };
+        let buffer = write(&mut events.iter());
+        let records = record::read::<Binding, _>(buffer.as_slice()).unwrap();
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            records
+                .iter()
+                .map(|(offset, _)| offset.as_millis())
+                .collect::<Vec<_>>()
+        );
+        // Handles are replaced by identifiers, so writing the records again
+        // produces the same recording.
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            String::from_utf8(write(&mut records.iter().map(|(_, event)| event))).unwrap(),
+        );
+    }
+
+    #[test]
+    fn record_negative_offset() {
+        let start = Instant::now();
+        let mut recorder = Recorder::<Binding>::default();
+        let mut buffer = vec![];
+        for timestamp in [start, start - Duration::from_millis(2)] {
+            recorder
+                .write(
+                    &mut buffer,
+                    &Event::Application {
+                        event: ApplicationEvent::Flushed,
+                        timestamp,
+                    },
+                )
+                .unwrap();
+        }
+        // Events that precede the first recorded event are played back at the
+        // beginning of the recording.
+        let records = record::read::<Binding, _>(buffer.as_slice()).unwrap();
+        assert_eq!(2, records.len());
+        assert_eq!(Duration::ZERO, records[1].0);
+    }
+
+    #[test]
+    fn player_polls_reactor() {
+        struct Aborting {
+            #[allow(dead_code)]
+            window: Window<Binding>,
+        }
+
+        impl Reactor<Binding> for Aborting {
+            fn react(&mut self, _: &ThreadContext, _: Event<Binding>) -> Reaction {
+                Reaction::Continue(())
+            }
+
+            fn poll(&mut self, _: &ThreadContext) -> Reaction<Poll> {
+                Reaction::Abort
+            }
+        }
+
+        let records = vec![(
+            Duration::from_secs(60),
+            Event::Application {
+                event: ApplicationEvent::LowMemory,
+                timestamp: Instant::now(),
+            },
+        )];
+        let started = Instant::now();
+        EventThread::<Binding, _>::run_and_join_with(move |context| {
+            let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+            // The reactor aborts before the recorded event is played back.
+            (
+                window.handle(),
+                EventPlayer::new(Aborting { window }, records, Timing::Relative),
+            )
+        })
+        .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn key_repeat() {
+        let device = DeviceHandle::<Binding>::from_raw_handle(1);
+        let start = Instant::now();
+        let key = |scancode, state, repeat, timestamp| Event::Input {
+            device,
+            window: None,
+            event: InputEvent::KeyboardKeyChanged {
+                scancode,
+                keycode: None,
+                state,
+                modifier: ModifierState::default(),
+                repeat,
+            },
+            focus: InputFocus::Foreground,
+            timestamp,
+        };
+        let at = |milliseconds| start + Duration::from_millis(milliseconds);
+
+        let mut repeat = KeyRepeat::new(Duration::from_millis(100), Duration::from_millis(10));
+        assert_eq!(None, repeat.deadline());
+        repeat.react(&key(1, ElementState::Pressed, false, start));
+        assert_eq!(Some(at(100)), repeat.deadline());
+        assert!(repeat.repeats(at(99)).is_empty());
+        // Repeats begin after the delay and are due at each interval.
+        let events = repeat.repeats(at(125));
+        assert_eq!(
+            vec![
+                key(1, ElementState::Pressed, true, at(100)),
+                key(1, ElementState::Pressed, true, at(110)),
+                key(1, ElementState::Pressed, true, at(120)),
+            ],
+            events
+        );
+        assert_eq!(Some(at(130)), repeat.deadline());
+
+        // Repeats reported by the platform and releases of other keys are
+        // ignored.
+        repeat.react(&key(1, ElementState::Pressed, true, at(126)));
+        repeat.react(&key(2, ElementState::Released, false, at(127)));
+        assert_eq!(Some(at(130)), repeat.deadline());
+
+        // Only the most recently pressed key repeats.
+        repeat.react(&key(2, ElementState::Pressed, false, at(200)));
+        assert_eq!(Some(at(300)), repeat.deadline());
+        assert_eq!(
+            vec![key(2, ElementState::Pressed, true, at(300))],
+            repeat.repeats(at(305))
+        );
+        repeat.react(&key(2, ElementState::Released, false, at(306)));
+        assert_eq!(None, repeat.deadline());
+        assert!(repeat.repeats(at(400)).is_empty());
+    }
+
+    #[test]
+    fn filter_predicates() {
+        let window = WindowHandle::<Binding>::from_raw_handle(1);
+        let other = WindowHandle::<Binding>::from_raw_handle(2);
+        let device = DeviceHandle::<Binding>::from_raw_handle(1);
+        let input = |window, event| Event::Input {
+            device,
+            window,
+            event,
+            focus: InputFocus::Foreground,
+            timestamp: Instant::now(),
+        };
+        let key = input(
+            Some(window),
+            InputEvent::KeyboardKeyChanged {
+                scancode: 0,
+                keycode: Some(KeyCode::A),
+                state: ElementState::Pressed,
+                modifier: ModifierState::default(),
+                repeat: false,
+            },
+        );
+        let button = input(
+            None,
+            InputEvent::MouseButtonChanged {
+                button: MouseButton::Left,
+                state: ElementState::Pressed,
+                modifier: ModifierState::default(),
+            },
+        );
+        let closed = Event::<Binding>::Window {
+            window: other,
+            event: WindowEvent::Closed(WindowCloseState::Requested),
+            timestamp: Instant::now(),
+        };
+        let flushed = Event::<Binding>::Application {
+            event: ApplicationEvent::Flushed,
+            timestamp: Instant::now(),
+        };
+        let passes = |predicate: &dyn Fn(&Event<Binding>) -> bool| {
+            [key, button, closed, flushed]
+                .iter()
+                .map(predicate)
+                .collect::<Vec<_>>()
+        };
+
+        // Input events without a window are routed to all windows.
+        assert_eq!(
+            vec![true, true, false, false],
+            passes(&|event| filter::window(window).test(event))
+        );
+        assert_eq!(
+            vec![false, true, true, false],
+            passes(&|event| filter::window(other).test(event))
+        );
+        assert_eq!(
+            vec![true, true, false, false],
+            passes(&|event| filter::device(device).test(event))
+        );
+        assert_eq!(
+            vec![true, false, false, false],
+            passes(&|event| filter::keyboard().test(event))
+        );
+        assert_eq!(
+            vec![false, true, false, false],
+            passes(&|event| filter::mouse().test(event))
+        );
+        assert_eq!(
+            vec![false, false, true, false],
+            passes(&|event| filter::window_events().test(event))
+        );
+        assert_eq!(
+            vec![false, false, false, true],
+            passes(&|event| filter::application().test(event))
+        );
+        assert_eq!(
+            vec![false, true, false, false],
+            passes(&|event| filter::window(other).and(filter::input()).test(event))
+        );
+        assert_eq!(
+            vec![true, true, false, false],
+            passes(&|event| filter::keyboard().or(filter::mouse()).test(event))
+        );
+        assert_eq!(
+            vec![false, false, true, true],
+            passes(&|event| filter::input().not().test(event))
+        );
+    }
+
+    #[test]
+    fn device_map() {
+        let first = DeviceHandle::<Binding>::from_raw_handle(1);
+        let second = DeviceHandle::<Binding>::from_raw_handle(2);
+        let input = |device, event| Event::Input {
+            device,
+            window: None,
+            event,
+            focus: InputFocus::Foreground,
+            timestamp: Instant::now(),
+        };
+        let key = |device, state| {
+            input(
+                device,
+                InputEvent::KeyboardKeyChanged {
+                    scancode: 0,
+                    keycode: Some(KeyCode::A),
+                    state,
+                    modifier: ModifierState::default(),
+                    repeat: false,
+                },
+            )
+        };
+
+        let mut devices = DeviceMap::<Binding, KeyboardSnapshot>::new();
+        devices.react(&input(first, InputEvent::Connected { usage: None }));
+        assert_eq!(vec![first], devices.devices().collect::<Vec<_>>());
+        assert_eq!(ElementState::Released, devices.state(KeyCode::A));
+        // Entries are also created when a device first produces input.
+        devices.react(&key(second, ElementState::Pressed));
+        assert!(devices.get(second).is_some());
+        assert_eq!(
+            vec![
+                (first, ElementState::Released),
+                (second, ElementState::Pressed),
+            ]
+            .into_iter()
+            .collect::<HashMap<_, _>>(),
+            devices.states(KeyCode::A).collect::<HashMap<_, _>>()
+        );
+        assert_eq!(ElementState::Pressed, devices.state(KeyCode::A));
+
+        // The merged state is pressed while the element is pressed on any
+        // device.
+        devices.react(&key(first, ElementState::Pressed));
+        devices.react(&input(second, InputEvent::Disconnected));
+        assert!(devices.get(second).is_none());
+        assert_eq!(ElementState::Pressed, devices.state(KeyCode::A));
+        devices.react(&key(first, ElementState::Released));
+        assert_eq!(ElementState::Released, devices.state(KeyCode::A));
+        devices.react(&input(first, InputEvent::Disconnected));
+        assert_eq!(0, devices.devices().count());
+    }
+
+    #[test]
+    fn click_gestures() {
+        let start = Instant::now();
+        let device = DeviceHandle::<Binding>::from_raw_handle(1);
+        let input = |window: u64, event, millis| Event::Input {
+            device,
+            window: Some(WindowHandle::<Binding>::from_raw_handle(window)),
+            event,
+            focus: InputFocus::Foreground,
+            timestamp: start + Duration::from_millis(millis),
+        };
+        let button = |window, state, millis| {
+            input(
+                window,
+                InputEvent::MouseButtonChanged {
+                    button: MouseButton::Left,
+                    state,
+                    modifier: ModifierState::default(),
+                },
+                millis,
+            )
+        };
+        let moved = |x: f64, millis| {
+            input(
+                1,
+                InputEvent::MouseMoved {
+                    movement: MouseMovement {
+                        absolute: Some(LogicalPosition::new(x, 0.0)),
+                        relative: None,
+                    },
+                    modifier: ModifierState::default(),
+                },
+                millis,
+            )
+        };
+        let counts = |gestures: &mut ClickGestures<Binding>, events: &[Event<Binding>]| {
+            for event in events {
+                gestures.react(event);
+            }
+            gestures
+                .drain()
+                .map(|gesture| match gesture {
+                    Gesture::Click { count, .. } => count,
+                    _ => 0,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut gestures = ClickGestures::new(Duration::from_millis(500), 4.0, 4.0);
+        // Clicks are consecutive if their presses are within the interval, even
+        // if their releases are not.
+        assert_eq!(
+            counts(
+                &mut gestures,
+                &[
+                    button(1, ElementState::Pressed, 0),
+                    button(1, ElementState::Released, 100),
+                    button(1, ElementState::Pressed, 400),
+                    button(1, ElementState::Released, 700),
+                ],
+            ),
+            [1, 2]
+        );
+        // Clicks in another window are not consecutive.
+        assert_eq!(
+            counts(
+                &mut gestures,
+                &[
+                    button(2, ElementState::Pressed, 800),
+                    button(2, ElementState::Released, 850),
+                ],
+            ),
+            [1]
+        );
+        // Clicks are not consecutive if their presses are not within the
+        // interval.
+        assert_eq!(
+            counts(
+                &mut gestures,
+                &[
+                    button(2, ElementState::Pressed, 1400),
+                    button(2, ElementState::Released, 1450),
+                ],
+            ),
+            [1]
+        );
+        // Drags never produce clicks and end any sequence of clicks.
+        let mut gestures = ClickGestures::new(Duration::from_millis(500), 4.0, 4.0);
+        for event in [
+            button(1, ElementState::Pressed, 0),
+            button(1, ElementState::Released, 10),
+            button(1, ElementState::Pressed, 20),
+            moved(8.0, 30),
+            button(1, ElementState::Released, 40),
+        ] {
+            gestures.react(&event);
+        }
+        let drained = gestures.drain().collect::<Vec<_>>();
+        assert!(matches!(
+            drained[..],
+            [
+                Gesture::Click { count: 1, .. },
+                Gesture::DragStarted { .. },
+                Gesture::Dragged { .. },
+                Gesture::DragEnded { .. },
+            ]
+        ));
+        assert_eq!(
+            counts(
+                &mut gestures,
+                &[
+                    button(1, ElementState::Pressed, 50),
+                    button(1, ElementState::Released, 60),
+                ],
+            ),
+            [1]
+        );
+    }
+
+    #[test]
+    fn react_all_splits_batches() {
+        type Batches = Rc<RefCell<Vec<Vec<Event<Binding>>>>>;
+
+        struct BatchReactor {
+            _window: Window<Binding>,
+            _injector: crate::Injector,
+            batches: Batches,
+        }
+
+        impl Reactor<Binding> for BatchReactor {
+            fn react(&mut self, context: &ThreadContext, event: Event<Binding>) -> Reaction {
+                self.react_all(context, [event][..].into())
+            }
+
+            fn react_all(&mut self, _: &ThreadContext, events: EventBatch<Binding>) -> Reaction {
+                let is_disabled = events.iter().any(|event| {
+                    matches!(
+                        event,
+                        Event::Window {
+                            event: WindowEvent::Disabled,
+                            ..
+                        }
+                    )
+                });
+                self.batches.borrow_mut().push(events.into_iter().collect());
+                if is_disabled {
+                    Reaction::Abort
+                }
+                else {
+                    Reaction::Continue(())
+                }
+            }
+        }
+
+        crate::record_reactions();
+        let batches = Batches::default();
+        let events = Rc::new(RefCell::new(vec![]));
+        let injector = crate::injector();
+        EventThread::<Binding, _>::run_and_join_with({
+            let batches = batches.clone();
+            let events = events.clone();
+            move |context| {
+                let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+                let handle = window.handle();
+                let device = DeviceHandle::<Binding>::from_raw_handle(1);
+                let timestamp = Instant::now();
+                let window_event = |event| Event::Window {
+                    window: handle,
+                    event,
+                    timestamp,
+                };
+                let input_event = |event| Event::Input {
+                    device,
+                    window: None,
+                    event,
+                    focus: InputFocus::Foreground,
+                    timestamp,
+                };
+                *events.borrow_mut() = vec![
+                    window_event(WindowEvent::Enabled),
+                    window_event(WindowEvent::Disabled),
+                    input_event(InputEvent::Connected { usage: None }),
+                    window_event(WindowEvent::Enabled),
+                    window_event(WindowEvent::Focused(true)),
+                    Event::Application {
+                        event: ApplicationEvent::Flushed,
+                        timestamp,
+                    },
+                    window_event(WindowEvent::Disabled),
+                    input_event(InputEvent::Disconnected),
+                    window_event(WindowEvent::Closed(WindowCloseState::Committed)),
+                ];
+                injector.inject_all(events.borrow().iter().cloned());
+                (
+                    handle,
+                    BatchReactor {
+                        _window: window,
+                        _injector: injector,
+                        batches,
+                    },
+                )
+            }
+        })
+        .unwrap();
+        // Events that change the registry or focus and `Flushed` are
+        // dispatched on their own. Events are otherwise batched in order.
+        let events = events.borrow();
+        let batches = batches.borrow();
+        assert_eq!(
+            batches[..8],
+            [
+                events[0..2].to_vec(),
+                events[2..3].to_vec(),
+                events[3..4].to_vec(),
+                events[4..5].to_vec(),
+                events[5..6].to_vec(),
+                events[6..7].to_vec(),
+                events[7..8].to_vec(),
+                events[8..9].to_vec(),
+            ]
+        );
+        assert!(matches!(
+            batches[8][..],
+            [Event::Application {
+                event: ApplicationEvent::Flushed,
+                ..
+            }]
+        ));
+        assert_eq!(batches.len(), 9);
+        // Every event is dispatched, but the queued events abort the event
+        // loop, because some batches abort.
+        let reactions = crate::take_reactions();
+        assert_eq!(reactions.len(), 10);
+        assert!(reactions[..9]
+            .iter()
+            .all(|&(_, reaction)| reaction == Reaction::Abort));
+        assert_eq!(reactions[9].1, Reaction::Continue(()));
+    }
+
+    #[test]
+    fn react_all_reacts_in_order() {
+        type State = (
+            Window<Binding>,
+            crate::Injector,
+            Rc<RefCell<Vec<WindowEvent>>>,
+        );
+
+        crate::record_reactions();
+        let reacted = Rc::new(RefCell::new(vec![]));
+        let injector = crate::injector();
+        EventThread::<Binding, _>::run_and_join_with({
+            let reacted = reacted.clone();
+            move |context| {
+                let window = WindowBuilder::<Binding>::default().build(context).unwrap();
+                let handle = window.handle();
+                injector.inject_all(
+                    [
+                        WindowEvent::Enabled,
+                        WindowEvent::Disabled,
+                        WindowEvent::Focused(false),
+                    ]
+                    .map(|event| Event::Window {
+                        window: handle,
+                        event,
+                        timestamp: Instant::now(),
+                    }),
+                );
+                (
+                    handle,
+                    StatefulReactor::from((
+                        (window, injector, reacted),
+                        |(_, _, reacted): &mut State, _: &ThreadContext, event: Event<Binding>| {
+                            let reaction = match event {
+                                Event::Window {
+                                    event: WindowEvent::Enabled,
+                                    ..
+                                } => Reaction::Abort,
+                                _ => Reaction::Continue(()),
+                            };
+                            if let Event::Window { event, .. } = event {
+                                reacted.borrow_mut().push(event);
+                            }
+                            reaction
+                        },
+                    )),
+                )
+            }
+        })
+        .unwrap();
+        // Events that follow an abort are still dispatched.
+        assert_eq!(
+            *reacted.borrow(),
+            [
+                WindowEvent::Enabled,
+                WindowEvent::Disabled,
+                WindowEvent::Focused(false),
+            ]
+        );
+        let reactions = crate::take_reactions();
+        assert!(reactions[..2]
+            .iter()
+            .all(|&(_, reaction)| reaction == Reaction::Abort));
+    }
 }
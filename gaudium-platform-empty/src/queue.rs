@@ -0,0 +1,177 @@
+use gaudium_core::event::{Event, Resumption};
+use gaudium_core::platform;
+use gaudium_core::reactor::{Reaction, ThreadContext};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Instant;
+
+use crate::Binding;
+
+type Reactions = Vec<(Event<Binding>, Reaction)>;
+
+thread_local! {
+    static QUEUE: Arc<Queue> = Arc::new(Queue::default());
+    // Dispatched events and their reactions, if recording is enabled.
+    static REACTIONS: RefCell<Option<Reactions>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct State {
+    events: VecDeque<Event<Binding>>,
+    injectors: usize,
+    is_woken: bool,
+}
+
+#[derive(Default)]
+pub struct Queue {
+    state: Mutex<State>,
+    condition: Condvar,
+}
+
+impl Queue {
+    pub fn current() -> Arc<Self> {
+        QUEUE.with(Arc::clone)
+    }
+
+    /// Moves all queued events into a buffer.
+    pub fn drain_into(&self, events: &mut Vec<Event<Binding>>) {
+        events.extend(self.state.lock().unwrap().events.drain(..));
+    }
+
+    /// Blocks until an event is injected or the queue is woken.
+    ///
+    /// Returns `false` if the queue is empty and there are no injectors, in
+    /// which case no events can ever be injected.
+    pub fn wait(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        while state.events.is_empty() && !state.is_woken {
+            if state.injectors == 0 {
+                return false;
+            }
+            state = self.condition.wait(state).unwrap();
+        }
+        state.is_woken = false;
+        true
+    }
+
+    pub fn wait_until(&self, until: Instant) -> Resumption {
+        let start = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        while state.events.is_empty() && !state.is_woken {
+            let now = Instant::now();
+            if now >= until {
+                return Resumption::Timeout(start);
+            }
+            state = self.condition.wait_timeout(state, until - now).unwrap().0;
+        }
+        state.is_woken = false;
+        Resumption::Interrupt(start)
+    }
+}
+
+/// Injects events into the event queue of an event thread.
+///
+/// Injectors can be sent to other threads, so events can be injected while an
+/// event thread is running. The empty platform has no other source of events,
+/// so when a reactor waits for events and all injectors have been dropped, the
+/// event loop stops.
+pub struct Injector {
+    queue: Arc<Queue>,
+}
+
+impl Injector {
+    fn new(queue: Arc<Queue>) -> Self {
+        queue.state.lock().unwrap().injectors += 1;
+        Injector { queue }
+    }
+
+    pub fn inject(&self, event: Event<Binding>) {
+        self.inject_all(Some(event));
+    }
+
+    pub fn inject_all<I>(&self, events: I)
+    where
+        I: IntoIterator<Item = Event<Binding>>,
+    {
+        self.queue.state.lock().unwrap().events.extend(events);
+        self.queue.condition.notify_all();
+    }
+}
+
+impl Clone for Injector {
+    fn clone(&self) -> Self {
+        Injector::new(self.queue.clone())
+    }
+}
+
+impl Drop for Injector {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().injectors -= 1;
+        self.queue.condition.notify_all();
+    }
+}
+
+/// Resumes the event loop without injecting an event.
+///
+/// Unlike `Injector`, a waker does not keep the event loop running, so futures
+/// that are woken by other threads should also hold an `Injector`.
+pub struct Waker {
+    queue: Arc<Queue>,
+}
+
+impl platform::Waker for Waker {
+    fn from_context(_: &ThreadContext) -> Self {
+        Waker {
+            queue: Queue::current(),
+        }
+    }
+
+    fn wake(&self) {
+        self.queue.state.lock().unwrap().is_woken = true;
+        self.queue.condition.notify_all();
+    }
+}
+
+/// Gets an injector for the event thread of the current thread.
+///
+/// Events may be injected before the event thread is started, in which case
+/// they are dispatched when the event loop first flushes its queue.
+pub fn injector() -> Injector {
+    Injector::new(Queue::current())
+}
+
+/// Enables recording of the events dispatched by the event thread of the
+/// current thread and the reactions to those events.
+///
+/// Recording is disabled by default, because recorded events are retained
+/// until they are taken. See `take_reactions`.
+pub fn record_reactions() {
+    REACTIONS.with(|reactions| {
+        reactions.borrow_mut().get_or_insert_with(Vec::new);
+    });
+}
+
+/// Takes the events dispatched by the event thread of the current thread and
+/// the reactions to those events, in the order in which they were dispatched.
+///
+/// Returns no events unless recording has been enabled via
+/// `record_reactions`.
+pub fn take_reactions() -> Reactions {
+    REACTIONS.with(|reactions| {
+        reactions
+            .borrow_mut()
+            .as_mut()
+            .map(mem::take)
+            .unwrap_or_default()
+    })
+}
+
+pub fn record(event: Event<Binding>, reaction: Reaction) {
+    REACTIONS.with(|reactions| {
+        if let Some(ref mut reactions) = *reactions.borrow_mut() {
+            reactions.push((event, reaction));
+        }
+    });
+}
@@ -0,0 +1,427 @@
+//! UI Automation providers.
+//!
+//! Once accessibility is configured for a window, the window procedure
+//! answers `WM_GETOBJECT` with a minimal `IRawElementProviderSimple` that
+//! overrides the name and control type of the window. All other properties are
+//! provided by the default provider of the window handle. Custom elements are
+//! not exposed as a tree; instead, changes to the focused element are
+//! announced via `UiaRaiseNotificationEvent`, which requires Windows 10
+//! version 1709 or later.
+//!
+//! `winapi` does not bind UI Automation, so its interfaces are declared here
+//! and its functions are loaded from `uiautomationcore.dll` when first used.
+
+use gaudium_core::accessibility::{AccessibleElement, Role};
+use lazy_static::lazy_static;
+use std::ffi;
+use std::mem;
+use std::os::raw;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use winapi::shared::{guiddef, minwindef, ntdef, windef, winerror, wtypes};
+use winapi::um::{libloaderapi, oaidl, oleauto, unknwnbase, winuser};
+use winapi::Interface;
+
+use crate::WideNullTerminated;
+
+const UIA_ROOT_OBJECT_ID: raw::c_long = -25;
+
+const UIA_CONTROL_TYPE_PROPERTY_ID: raw::c_int = 30003;
+const UIA_NAME_PROPERTY_ID: raw::c_int = 30005;
+
+const PROVIDER_OPTIONS_SERVER_SIDE_PROVIDER: raw::c_int = 0x1;
+const PROVIDER_OPTIONS_USE_COM_THREADING: raw::c_int = 0x20;
+
+const NOTIFICATION_KIND_OTHER: raw::c_int = 4;
+const NOTIFICATION_PROCESSING_MOST_RECENT: raw::c_int = 3;
+
+const UIA_E_ELEMENTNOTAVAILABLE: ntdef::HRESULT = 0x8004_0201_u32 as ntdef::HRESULT;
+
+/// `IID_IRawElementProviderSimple`: `{D6DD68D1-86FD-4332-8666-9ABEDEA2D24C}`.
+const IID_RAW_ELEMENT_PROVIDER_SIMPLE: guiddef::IID = guiddef::GUID {
+    Data1: 0xD6DD_68D1,
+    Data2: 0x86FD,
+    Data3: 0x4332,
+    Data4: [0x86, 0x66, 0x9A, 0xBE, 0xDE, 0xA2, 0xD2, 0x4C],
+};
+
+const ACTIVITY_ID: &str = "gaudium.accessibility.focus";
+
+type ReturnRawElementProvider = unsafe extern "system" fn(
+    windef::HWND,
+    minwindef::WPARAM,
+    minwindef::LPARAM,
+    *mut Provider,
+) -> minwindef::LRESULT;
+type HostProviderFromHwnd =
+    unsafe extern "system" fn(windef::HWND, *mut *mut ffi::c_void) -> ntdef::HRESULT;
+type RaiseNotificationEvent = unsafe extern "system" fn(
+    *mut Provider,
+    raw::c_int,
+    raw::c_int,
+    wtypes::BSTR,
+    wtypes::BSTR,
+) -> ntdef::HRESULT;
+
+lazy_static! {
+    static ref UIA: Option<Uia> = unsafe { Uia::load() };
+}
+
+/// Functions loaded from `uiautomationcore.dll`.
+struct Uia {
+    return_raw_element_provider: ReturnRawElementProvider,
+    host_provider_from_hwnd: HostProviderFromHwnd,
+    raise_notification_event: Option<RaiseNotificationEvent>,
+}
+
+impl Uia {
+    unsafe fn load() -> Option<Self> {
+        let name = "uiautomationcore.dll".wide_null_terminated();
+        let module = libloaderapi::LoadLibraryW(name.as_ptr());
+        if module.is_null() {
+            return None;
+        }
+        let function = |name: &str| {
+            let function = libloaderapi::GetProcAddress(module, name.as_ptr() as ntdef::LPCSTR);
+            if function.is_null() {
+                None
+            }
+            else {
+                Some(function)
+            }
+        };
+        Some(Uia {
+            return_raw_element_provider: mem::transmute::<
+                minwindef::FARPROC,
+                ReturnRawElementProvider,
+            >(function("UiaReturnRawElementProvider\0")?),
+            host_provider_from_hwnd: mem::transmute::<minwindef::FARPROC, HostProviderFromHwnd>(
+                function("UiaHostProviderFromHwnd\0")?,
+            ),
+            raise_notification_event: function("UiaRaiseNotificationEvent\0").map(|function| {
+                mem::transmute::<minwindef::FARPROC, RaiseNotificationEvent>(function)
+            }),
+        })
+    }
+}
+
+/// Properties of a window that are shared with its provider.
+///
+/// UI Automation may query the provider on other threads, so these are
+/// synchronized.
+#[derive(Default)]
+struct Properties {
+    name: Option<String>,
+    role: Role,
+    // Whether or not the window still exists.
+    is_connected: bool,
+}
+
+/// Accessibility state of a window.
+///
+/// This is created the first time accessibility is configured for a window,
+/// so that windows that never configure accessibility use the default
+/// provider.
+pub struct Accessibility {
+    window: windef::HWND,
+    provider: *mut Provider,
+    properties: Arc<Mutex<Properties>>,
+    focus: Option<AccessibleElement>,
+}
+
+impl Accessibility {
+    pub fn new(window: windef::HWND) -> Self {
+        let properties = Arc::new(Mutex::new(Properties {
+            is_connected: true,
+            ..Default::default()
+        }));
+        let provider = Box::into_raw(Box::new(Provider {
+            vtable: &PROVIDER_VTABLE,
+            count: AtomicUsize::new(1),
+            window,
+            properties: properties.clone(),
+        }));
+        Accessibility {
+            window,
+            provider,
+            properties,
+            focus: None,
+        }
+    }
+
+    pub fn update(&mut self, update: Update) {
+        match update {
+            Update::Name(name) => {
+                self.properties.lock().unwrap().name = Some(name);
+                unsafe {
+                    winuser::NotifyWinEvent(
+                        winuser::EVENT_OBJECT_NAMECHANGE,
+                        self.window,
+                        winuser::OBJID_CLIENT,
+                        winuser::CHILDID_SELF,
+                    );
+                }
+            }
+            Update::Role(role) => {
+                self.properties.lock().unwrap().role = role;
+            }
+            Update::Focus(element) => {
+                if let Some(ref element) = element {
+                    if self.focus.as_ref() != Some(element) {
+                        self.announce(&element.name);
+                    }
+                }
+                self.focus = element;
+            }
+            Update::Change(element) => {
+                let is_focused = matches!(
+                    self.focus,
+                    Some(ref focus) if focus.id == element.id && *focus != element
+                );
+                if is_focused {
+                    self.announce(&element.name);
+                    self.focus = Some(element);
+                }
+            }
+        }
+    }
+
+    /// Answers `WM_GETOBJECT` if UI Automation requests the root provider of
+    /// the window.
+    pub unsafe fn get_object(
+        &self,
+        wparam: minwindef::WPARAM,
+        lparam: minwindef::LPARAM,
+    ) -> Option<minwindef::LRESULT> {
+        let uia = UIA.as_ref()?;
+        if lparam as raw::c_long == UIA_ROOT_OBJECT_ID {
+            Some((uia.return_raw_element_provider)(
+                self.window,
+                wparam,
+                lparam,
+                self.provider,
+            ))
+        }
+        else {
+            None
+        }
+    }
+
+    fn announce(&self, text: &str) {
+        if let Some(raise) = UIA.as_ref().and_then(|uia| uia.raise_notification_event) {
+            unsafe {
+                let text = oleauto::SysAllocString(text.wide_null_terminated().as_ptr());
+                let activity = oleauto::SysAllocString(ACTIVITY_ID.wide_null_terminated().as_ptr());
+                raise(
+                    self.provider,
+                    NOTIFICATION_KIND_OTHER,
+                    NOTIFICATION_PROCESSING_MOST_RECENT,
+                    text,
+                    activity,
+                );
+                oleauto::SysFreeString(text);
+                oleauto::SysFreeString(activity);
+            }
+        }
+    }
+}
+
+impl Drop for Accessibility {
+    fn drop(&mut self) {
+        // Disconnect the provider so that clients holding references to it
+        // observe that the element is no longer available.
+        self.properties.lock().unwrap().is_connected = false;
+        unsafe {
+            if let Some(ref uia) = *UIA {
+                (uia.return_raw_element_provider)(self.window, 0, 0, ptr::null_mut());
+            }
+            release(self.provider);
+        }
+    }
+}
+
+/// A change to the accessibility of a window.
+///
+/// Changes are sent to the window procedure, which owns the accessibility
+/// state of its window.
+pub enum Update {
+    Name(String),
+    Role(Role),
+    Focus(Option<AccessibleElement>),
+    Change(AccessibleElement),
+}
+
+/// The virtual function table of `IRawElementProviderSimple`, including
+/// `IUnknown`.
+#[repr(C)]
+struct ProviderVtable {
+    query_interface: unsafe extern "system" fn(
+        *mut Provider,
+        *const guiddef::IID,
+        *mut *mut ffi::c_void,
+    ) -> ntdef::HRESULT,
+    add_ref: unsafe extern "system" fn(*mut Provider) -> minwindef::ULONG,
+    release: unsafe extern "system" fn(*mut Provider) -> minwindef::ULONG,
+    get_provider_options:
+        unsafe extern "system" fn(*mut Provider, *mut raw::c_int) -> ntdef::HRESULT,
+    get_pattern_provider: unsafe extern "system" fn(
+        *mut Provider,
+        raw::c_int,
+        *mut *mut unknwnbase::IUnknown,
+    ) -> ntdef::HRESULT,
+    get_property_value:
+        unsafe extern "system" fn(*mut Provider, raw::c_int, *mut oaidl::VARIANT) -> ntdef::HRESULT,
+    get_host_raw_element_provider:
+        unsafe extern "system" fn(*mut Provider, *mut *mut ffi::c_void) -> ntdef::HRESULT,
+}
+
+static PROVIDER_VTABLE: ProviderVtable = ProviderVtable {
+    query_interface,
+    add_ref,
+    release,
+    get_provider_options,
+    get_pattern_provider,
+    get_property_value,
+    get_host_raw_element_provider,
+};
+
+/// A reference-counted COM object that implements
+/// `IRawElementProviderSimple` for the root of a window.
+#[repr(C)]
+struct Provider {
+    vtable: *const ProviderVtable,
+    count: AtomicUsize,
+    window: windef::HWND,
+    properties: Arc<Mutex<Properties>>,
+}
+
+unsafe extern "system" fn query_interface(
+    provider: *mut Provider,
+    iid: *const guiddef::IID,
+    object: *mut *mut ffi::c_void,
+) -> ntdef::HRESULT {
+    if object.is_null() {
+        return winerror::E_POINTER;
+    }
+    if guiddef::IsEqualGUID(&*iid, &unknwnbase::IUnknown::uuidof())
+        || guiddef::IsEqualGUID(&*iid, &IID_RAW_ELEMENT_PROVIDER_SIMPLE)
+    {
+        add_ref(provider);
+        *object = provider as *mut ffi::c_void;
+        winerror::S_OK
+    }
+    else {
+        *object = ptr::null_mut();
+        winerror::E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn add_ref(provider: *mut Provider) -> minwindef::ULONG {
+    ((*provider).count.fetch_add(1, Ordering::Relaxed) + 1) as minwindef::ULONG
+}
+
+unsafe extern "system" fn release(provider: *mut Provider) -> minwindef::ULONG {
+    let count = (*provider).count.fetch_sub(1, Ordering::Release) - 1;
+    if count == 0 {
+        std::sync::atomic::fence(Ordering::Acquire);
+        drop(Box::from_raw(provider));
+    }
+    count as minwindef::ULONG
+}
+
+unsafe extern "system" fn get_provider_options(
+    _: *mut Provider,
+    options: *mut raw::c_int,
+) -> ntdef::HRESULT {
+    if options.is_null() {
+        return winerror::E_POINTER;
+    }
+    *options = PROVIDER_OPTIONS_SERVER_SIDE_PROVIDER | PROVIDER_OPTIONS_USE_COM_THREADING;
+    winerror::S_OK
+}
+
+unsafe extern "system" fn get_pattern_provider(
+    _: *mut Provider,
+    _: raw::c_int,
+    pattern: *mut *mut unknwnbase::IUnknown,
+) -> ntdef::HRESULT {
+    if pattern.is_null() {
+        return winerror::E_POINTER;
+    }
+    // No control patterns are supported.
+    *pattern = ptr::null_mut();
+    winerror::S_OK
+}
+
+unsafe extern "system" fn get_property_value(
+    provider: *mut Provider,
+    property: raw::c_int,
+    value: *mut oaidl::VARIANT,
+) -> ntdef::HRESULT {
+    if value.is_null() {
+        return winerror::E_POINTER;
+    }
+    let properties = (*provider).properties.lock().unwrap();
+    if !properties.is_connected {
+        return UIA_E_ELEMENTNOTAVAILABLE;
+    }
+    // Properties that are empty are provided by the host provider.
+    let variant = (*value).n1.n2_mut();
+    variant.vt = wtypes::VT_EMPTY as wtypes::VARTYPE;
+    match property {
+        UIA_NAME_PROPERTY_ID => {
+            if let Some(ref name) = properties.name {
+                variant.vt = wtypes::VT_BSTR as wtypes::VARTYPE;
+                *variant.n3.bstrVal_mut() =
+                    oleauto::SysAllocString(name.as_str().wide_null_terminated().as_ptr());
+            }
+        }
+        UIA_CONTROL_TYPE_PROPERTY_ID => {
+            variant.vt = wtypes::VT_I4 as wtypes::VARTYPE;
+            *variant.n3.lVal_mut() = control_type(properties.role);
+        }
+        _ => {}
+    }
+    winerror::S_OK
+}
+
+unsafe extern "system" fn get_host_raw_element_provider(
+    provider: *mut Provider,
+    host: *mut *mut ffi::c_void,
+) -> ntdef::HRESULT {
+    if host.is_null() {
+        return winerror::E_POINTER;
+    }
+    *host = ptr::null_mut();
+    if !(*provider).properties.lock().unwrap().is_connected {
+        return UIA_E_ELEMENTNOTAVAILABLE;
+    }
+    match *UIA {
+        Some(ref uia) => (uia.host_provider_from_hwnd)((*provider).window, host),
+        None => winerror::S_OK,
+    }
+}
+
+/// Gets the UI Automation control type identifier of a role.
+fn control_type(role: Role) -> ntdef::LONG {
+    match role {
+        Role::Button => 50000,
+        Role::CheckBox => 50002,
+        Role::Edit => 50004,
+        Role::Link => 50005,
+        Role::Image => 50006,
+        Role::ListItem => 50007,
+        Role::List => 50008,
+        Role::Menu => 50009,
+        Role::MenuItem => 50011,
+        Role::Slider => 50015,
+        Role::Tab => 50018,
+        Role::Text => 50020,
+        Role::Custom => 50025,
+        Role::Group => 50026,
+        Role::Document => 50030,
+        Role::Window => 50032,
+        Role::Pane => 50033,
+    }
+}
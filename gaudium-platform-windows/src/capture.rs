@@ -0,0 +1,137 @@
+use gaudium_core::error::{Error, ErrorKind};
+use gaudium_core::window::Image;
+use std::mem;
+use std::os::raw;
+use winapi::shared::{minwindef, windef};
+use winapi::um::{wingdi, winuser};
+
+/// Captures the client area of a window as top-down RGBA pixels.
+///
+/// `PrintWindow` is used first, because it renders content that is occluded
+/// or composited by DWM. If it fails, the client area is copied from the
+/// screen via `BitBlt`, which only reflects visible content.
+pub fn capture(window: windef::HWND) -> Result<Image, Error> {
+    unsafe {
+        let mut rect = mem::zeroed::<windef::RECT>();
+        if winuser::GetClientRect(window, &mut rect) == 0 {
+            return Err(crate::last_error("failed to get window client area"));
+        }
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        if width <= 0 || height <= 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidState,
+                "window has no client area",
+            ));
+        }
+        let screen = DeviceContext::window(window)?;
+        let memory = wingdi::CreateCompatibleDC(screen.1);
+        if memory.is_null() {
+            return Err(crate::last_error("failed to create device context"));
+        }
+        let memory = MemoryContext(memory);
+        let bitmap = wingdi::CreateCompatibleBitmap(screen.1, width, height);
+        if bitmap.is_null() {
+            return Err(crate::last_error("failed to create bitmap"));
+        }
+        let bitmap = Bitmap(bitmap);
+        let previous = wingdi::SelectObject(memory.0, bitmap.0 as windef::HGDIOBJ);
+        let is_copied = winuser::PrintWindow(
+            window,
+            memory.0,
+            winuser::PW_CLIENTONLY | winuser::PW_RENDERFULLCONTENT,
+        ) != 0
+            || wingdi::BitBlt(
+                memory.0,
+                0,
+                0,
+                width,
+                height,
+                screen.1,
+                0,
+                0,
+                wingdi::SRCCOPY,
+            ) != 0;
+        // The bitmap must not be selected into a device context when its
+        // pixels are read.
+        wingdi::SelectObject(memory.0, previous);
+        if !is_copied {
+            return Err(crate::last_error("failed to copy window client area"));
+        }
+        // A negative height requests top-down rows. Rows of 32-bit pixels are
+        // always aligned, so the stride is exactly the length of a row.
+        let mut info = mem::zeroed::<wingdi::BITMAPINFO>();
+        info.bmiHeader.biSize = mem::size_of::<wingdi::BITMAPINFOHEADER>() as minwindef::DWORD;
+        info.bmiHeader.biWidth = width;
+        info.bmiHeader.biHeight = -height;
+        info.bmiHeader.biPlanes = 1;
+        info.bmiHeader.biBitCount = 32;
+        info.bmiHeader.biCompression = wingdi::BI_RGB;
+        let stride = width as usize * 4;
+        let mut pixels = vec![0u8; stride * height as usize];
+        if wingdi::GetDIBits(
+            memory.0,
+            bitmap.0,
+            0,
+            height as minwindef::UINT,
+            pixels.as_mut_ptr() as *mut _,
+            &mut info,
+            wingdi::DIB_RGB_COLORS,
+        ) != height as raw::c_int
+        {
+            return Err(crate::last_error("failed to read window pixels"));
+        }
+        // GDI produces BGRX pixels. The alpha channel is undefined, so the
+        // image is made opaque.
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            pixel[3] = u8::MAX;
+        }
+        Image::from_rgba(pixels, width as u32, height as u32, stride)
+    }
+}
+
+/// The device context of a window, which is released when dropped.
+struct DeviceContext(windef::HWND, windef::HDC);
+
+impl DeviceContext {
+    unsafe fn window(window: windef::HWND) -> Result<Self, Error> {
+        let context = winuser::GetDC(window);
+        if context.is_null() {
+            Err(crate::last_error("failed to get device context"))
+        }
+        else {
+            Ok(DeviceContext(window, context))
+        }
+    }
+}
+
+impl Drop for DeviceContext {
+    fn drop(&mut self) {
+        unsafe {
+            winuser::ReleaseDC(self.0, self.1);
+        }
+    }
+}
+
+/// A memory device context, which is deleted when dropped.
+struct MemoryContext(windef::HDC);
+
+impl Drop for MemoryContext {
+    fn drop(&mut self) {
+        unsafe {
+            wingdi::DeleteDC(self.0);
+        }
+    }
+}
+
+/// A bitmap, which is deleted when dropped.
+struct Bitmap(windef::HBITMAP);
+
+impl Drop for Bitmap {
+    fn drop(&mut self) {
+        unsafe {
+            wingdi::DeleteObject(self.0 as windef::HGDIOBJ);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use gaudium_core::error::Error;
+use lazy_static::lazy_static;
+use std::process;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use winapi::shared::{minwindef, ntdef, ntstatus};
+use winapi::um::{consoleapi, processthreadsapi, wincon, winuser};
+
+lazy_static! {
+    /// Posted to an event thread when a console control event is received.
+    pub static ref WM_CONSOLE_CONTROL: minwindef::UINT = unsafe {
+        winuser::RegisterWindowMessageA("WM_CONSOLE_CONTROL\0".as_ptr() as ntdef::LPCSTR)
+    };
+    /// Event threads that receive console control events and their grace
+    /// periods.
+    static ref THREADS: (Mutex<Vec<(minwindef::DWORD, Duration)>>, Condvar) =
+        (Mutex::new(vec![]), Condvar::new());
+}
+
+/// Enables console control events for the calling event thread with the
+/// given grace period or disables them if `None`.
+///
+/// The console control handler is installed while any event thread is
+/// registered.
+pub fn configure(grace: Option<Duration>) -> Result<(), Error> {
+    let thread = unsafe { processthreadsapi::GetCurrentThreadId() };
+    let (ref threads, ref condition) = *THREADS;
+    let mut threads = threads.lock().unwrap();
+    let was_installed = !threads.is_empty();
+    threads.retain(|&(other, _)| other != thread);
+    if let Some(grace) = grace {
+        threads.push((thread, grace));
+    }
+    condition.notify_all();
+    match (was_installed, !threads.is_empty()) {
+        (false, true) => unsafe {
+            if consoleapi::SetConsoleCtrlHandler(Some(handle), minwindef::TRUE) == 0 {
+                threads.clear();
+                return Err(crate::last_error("failed to set console control handler"));
+            }
+        },
+        (true, false) => unsafe {
+            consoleapi::SetConsoleCtrlHandler(Some(handle), minwindef::FALSE);
+        },
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Unregisters the calling event thread.
+///
+/// This is called when the event loop stops.
+pub fn unregister() {
+    let _ = configure(None);
+}
+
+/// Handles console control events on a thread created by the system.
+///
+/// Each registered event thread is notified and given its grace period to
+/// stop. If any event thread is still running after its grace period
+/// elapses, then the process exits.
+unsafe extern "system" fn handle(control: minwindef::DWORD) -> minwindef::BOOL {
+    match control {
+        wincon::CTRL_C_EVENT | wincon::CTRL_BREAK_EVENT | wincon::CTRL_CLOSE_EVENT => {}
+        _ => return minwindef::FALSE,
+    }
+    let start = Instant::now();
+    let (ref threads, ref condition) = *THREADS;
+    let mut threads = threads.lock().unwrap();
+    for &(thread, _) in threads.iter() {
+        winuser::PostThreadMessageW(thread, *WM_CONSOLE_CONTROL, control as usize, 0);
+    }
+    let notified: Vec<_> = threads.clone();
+    // Event threads unregister when they stop, so wait until the notified
+    // event threads have stopped or the earliest grace period of those that
+    // are still running elapses.
+    while let Some(deadline) = notified
+        .iter()
+        .filter(|notified| threads.contains(notified))
+        .map(|&(_, grace)| start + grace)
+        .min()
+    {
+        let now = Instant::now();
+        if now >= deadline {
+            process::exit(ntstatus::STATUS_CONTROL_C_EXIT);
+        }
+        threads = condition.wait_timeout(threads, deadline - now).unwrap().0;
+    }
+    minwindef::TRUE
+}
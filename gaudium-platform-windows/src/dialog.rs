@@ -0,0 +1,322 @@
+use gaudium_core::error::Error;
+use gaudium_core::platform;
+use gaudium_core::reactor::ThreadContext;
+use gaudium_core::shell::dialog::{
+    FileDialog, MessageButtons, MessageDialog, MessageLevel, MessageResponse,
+};
+use gaudium_core::window::WindowHandle;
+use gaudium_core::IntoRawHandle;
+use std::ffi::{self, OsString};
+use std::ops::Deref;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+use std::ptr;
+use winapi::shared::{guiddef, minwindef, ntdef, windef, winerror, wtypesbase};
+use winapi::um::{combaseapi, objbase, shobjidl, shobjidl_core, shtypes, unknwnbase, winuser};
+use winapi::Interface;
+
+use crate::{Binding, WideNullTerminated};
+
+/// Shows message boxes via `MessageBoxW` and file dialogs via the common item
+/// dialog.
+pub struct Dialog;
+
+impl platform::Dialog<Binding> for Dialog {
+    fn show_message(
+        _: &ThreadContext,
+        dialog: &MessageDialog<Binding>,
+    ) -> Result<MessageResponse, Error> {
+        let text = dialog.text().wide_null_terminated();
+        let title = dialog.title().wide_null_terminated();
+        let icon = match dialog.level() {
+            MessageLevel::Info => winuser::MB_ICONINFORMATION,
+            MessageLevel::Warning => winuser::MB_ICONWARNING,
+            MessageLevel::Error => winuser::MB_ICONERROR,
+        };
+        let buttons = match dialog.buttons() {
+            MessageButtons::Ok => winuser::MB_OK,
+            MessageButtons::OkCancel => winuser::MB_OKCANCEL,
+            MessageButtons::YesNo => winuser::MB_YESNO,
+            MessageButtons::YesNoCancel => winuser::MB_YESNOCANCEL,
+        };
+        match unsafe {
+            winuser::MessageBoxW(
+                owner(dialog.owner()),
+                text.as_ptr(),
+                title.as_ptr(),
+                icon | buttons,
+            )
+        } {
+            0 => Err(crate::last_error("failed to show message box")),
+            winuser::IDOK => Ok(MessageResponse::Ok),
+            winuser::IDYES => Ok(MessageResponse::Yes),
+            winuser::IDNO => Ok(MessageResponse::No),
+            _ => Ok(MessageResponse::Cancel),
+        }
+    }
+
+    fn open_files(
+        _: &ThreadContext,
+        dialog: &FileDialog<Binding>,
+        multiple: bool,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let _com = Com::initialize()?;
+        unsafe {
+            let file =
+                ComPtr::<shobjidl::IFileOpenDialog>::create(&shobjidl_core::CLSID_FileOpenDialog)?;
+            configure(
+                &file,
+                dialog,
+                shobjidl::FOS_FILEMUSTEXIST
+                    | if multiple {
+                        shobjidl::FOS_ALLOWMULTISELECT
+                    }
+                    else {
+                        0
+                    },
+            )?;
+            if !show(&file, dialog)? {
+                return Ok(vec![]);
+            }
+            let mut items = ptr::null_mut();
+            check(
+                file.GetResults(&mut items),
+                "failed to get file dialog results",
+            )?;
+            let items = ComPtr(items);
+            let mut count = 0;
+            check(
+                items.GetCount(&mut count),
+                "failed to get file dialog results",
+            )?;
+            (0..count)
+                .map(|index| {
+                    let mut item = ptr::null_mut();
+                    check(
+                        items.GetItemAt(index, &mut item),
+                        "failed to get file dialog result",
+                    )?;
+                    path(&ComPtr(item))
+                })
+                .collect()
+        }
+    }
+
+    fn save_file(
+        _: &ThreadContext,
+        dialog: &FileDialog<Binding>,
+    ) -> Result<Option<PathBuf>, Error> {
+        let _com = Com::initialize()?;
+        unsafe {
+            let file =
+                ComPtr::<shobjidl::IFileSaveDialog>::create(&shobjidl_core::CLSID_FileSaveDialog)?;
+            configure(&file, dialog, shobjidl::FOS_OVERWRITEPROMPT)?;
+            // Append the extension of the first filter to file names that are
+            // entered without one.
+            if let Some(extension) = dialog
+                .filters()
+                .first()
+                .and_then(|filter| filter.extensions.first())
+            {
+                let extension = extension.wide_null_terminated();
+                check(
+                    file.SetDefaultExtension(extension.as_ptr()),
+                    "failed to configure file dialog",
+                )?;
+            }
+            if !show(&file, dialog)? {
+                return Ok(None);
+            }
+            let mut item = ptr::null_mut();
+            check(
+                file.GetResult(&mut item),
+                "failed to get file dialog result",
+            )?;
+            path(&ComPtr(item)).map(Some)
+        }
+    }
+}
+
+/// Initializes COM on the event thread for the lifetime of a dialog.
+struct Com(bool);
+
+impl Com {
+    fn initialize() -> Result<Self, Error> {
+        match unsafe {
+            combaseapi::CoInitializeEx(ptr::null_mut(), objbase::COINIT_APARTMENTTHREADED)
+        } {
+            winerror::S_OK | winerror::S_FALSE => Ok(Com(true)),
+            // COM has already been initialized with a different concurrency
+            // model. Dialogs still function, but COM must not be uninitialized.
+            winerror::RPC_E_CHANGED_MODE => Ok(Com(false)),
+            code => Err(Error::with_code(code, "failed to initialize COM")),
+        }
+    }
+}
+
+impl Drop for Com {
+    fn drop(&mut self) {
+        if self.0 {
+            unsafe {
+                combaseapi::CoUninitialize();
+            }
+        }
+    }
+}
+
+/// Releases a COM interface when dropped.
+struct ComPtr<T>(*mut T)
+where
+    T: Interface;
+
+impl<T> ComPtr<T>
+where
+    T: Interface,
+{
+    unsafe fn create(class: &guiddef::CLSID) -> Result<Self, Error> {
+        let mut instance = ptr::null_mut::<T>();
+        check(
+            combaseapi::CoCreateInstance(
+                class,
+                ptr::null_mut(),
+                wtypesbase::CLSCTX_INPROC_SERVER,
+                &T::uuidof(),
+                &mut instance as *mut *mut T as *mut *mut ffi::c_void,
+            ),
+            "failed to create file dialog",
+        )?;
+        Ok(ComPtr(instance))
+    }
+}
+
+impl<T> Deref for ComPtr<T>
+where
+    T: Interface,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.0 }
+    }
+}
+
+impl<T> Drop for ComPtr<T>
+where
+    T: Interface,
+{
+    fn drop(&mut self) {
+        unsafe {
+            (*(self.0 as *mut unknwnbase::IUnknown)).Release();
+        }
+    }
+}
+
+fn check(result: ntdef::HRESULT, context: &'static str) -> Result<(), Error> {
+    if winerror::SUCCEEDED(result) {
+        Ok(())
+    }
+    else {
+        Err(Error::with_code(result, context))
+    }
+}
+
+fn owner(owner: Option<WindowHandle<Binding>>) -> windef::HWND {
+    owner.map_or(ptr::null_mut(), |owner| owner.into_raw_handle())
+}
+
+unsafe fn configure(
+    file: &shobjidl::IFileDialog,
+    dialog: &FileDialog<Binding>,
+    options: minwindef::DWORD,
+) -> Result<(), Error> {
+    let mut current = 0;
+    check(
+        file.GetOptions(&mut current),
+        "failed to configure file dialog",
+    )?;
+    check(
+        file.SetOptions(current | shobjidl::FOS_FORCEFILESYSTEM | options),
+        "failed to configure file dialog",
+    )?;
+    if !dialog.title().is_empty() {
+        let title = dialog.title().wide_null_terminated();
+        check(
+            file.SetTitle(title.as_ptr()),
+            "failed to configure file dialog",
+        )?;
+    }
+    if !dialog.file_name().is_empty() {
+        let name = dialog.file_name().wide_null_terminated();
+        check(
+            file.SetFileName(name.as_ptr()),
+            "failed to configure file dialog",
+        )?;
+    }
+    if let Some(directory) = dialog.directory() {
+        let directory = directory.wide_null_terminated();
+        let mut item = ptr::null_mut::<shobjidl_core::IShellItem>();
+        // Directories that cannot be parsed, such as directories that do not
+        // exist, are ignored and the dialog uses its default directory.
+        if winerror::SUCCEEDED(shobjidl_core::SHCreateItemFromParsingName(
+            directory.as_ptr(),
+            ptr::null_mut(),
+            &shobjidl_core::IShellItem::uuidof(),
+            &mut item as *mut *mut shobjidl_core::IShellItem as *mut *mut ffi::c_void,
+        )) {
+            let item = ComPtr(item);
+            file.SetFolder(item.0);
+        }
+    }
+    if !dialog.filters().is_empty() {
+        let filters = dialog
+            .filters()
+            .iter()
+            .map(|filter| {
+                let spec = filter
+                    .extensions
+                    .iter()
+                    .map(|extension| format!("*.{}", extension))
+                    .collect::<Vec<_>>()
+                    .join(";");
+                (
+                    filter.name.as_str().wide_null_terminated(),
+                    spec.wide_null_terminated(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let specs = filters
+            .iter()
+            .map(|(name, spec)| shtypes::COMDLG_FILTERSPEC {
+                pszName: name.as_ptr(),
+                pszSpec: spec.as_ptr(),
+            })
+            .collect::<Vec<_>>();
+        check(
+            file.SetFileTypes(specs.len() as minwindef::UINT, specs.as_ptr()),
+            "failed to configure file dialog",
+        )?;
+    }
+    Ok(())
+}
+
+/// Shows a file dialog and blocks until it is dismissed.
+///
+/// Returns `false` if the dialog is cancelled.
+unsafe fn show(file: &shobjidl::IFileDialog, dialog: &FileDialog<Binding>) -> Result<bool, Error> {
+    match file.Show(owner(dialog.owner())) {
+        result if result == winerror::HRESULT_FROM_WIN32(winerror::ERROR_CANCELLED) => Ok(false),
+        result => check(result, "failed to show file dialog").map(|_| true),
+    }
+}
+
+unsafe fn path(item: &shobjidl_core::IShellItem) -> Result<PathBuf, Error> {
+    let mut name = ptr::null_mut();
+    check(
+        item.GetDisplayName(shobjidl_core::SIGDN_FILESYSPATH, &mut name),
+        "failed to get path of file dialog result",
+    )?;
+    let length = (0..).take_while(|&index| *name.offset(index) != 0).count();
+    let path = OsString::from_wide(std::slice::from_raw_parts(name, length));
+    combaseapi::CoTaskMemFree(name as *mut ffi::c_void);
+    Ok(path.into())
+}
@@ -0,0 +1,230 @@
+use gaudium_core::display::{PhysicalPosition, PhysicalSize, VideoMode};
+use gaudium_core::error::{Error, ErrorKind};
+use gaudium_core::event::DisplayEvent;
+use gaudium_core::platform;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+use std::ptr;
+use winapi::shared::{minwindef, windef};
+use winapi::um::{wingdi, winuser};
+
+thread_local! {
+    // Modes of the displays most recently observed by the event thread. This
+    // is `None` until first queried.
+    static MODES: RefCell<Option<HashMap<windef::HMONITOR, Mode>>> =
+        const { RefCell::new(None) };
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Mode {
+    bounds: (i32, i32, i32, i32),
+    bits: u32,
+    frequency: u32,
+}
+
+#[derive(Eq, Hash, PartialEq)]
+pub struct Display(windef::HMONITOR);
+
+impl platform::Display for Display {
+    type Query = Vec<Self>;
+
+    fn connected() -> Self::Query {
+        monitors().into_iter().map(Display).collect()
+    }
+
+    fn modes(handle: Self::Handle) -> Vec<VideoMode> {
+        let info = match monitor_info(handle) {
+            Some(info) => info,
+            None => return vec![],
+        };
+        let mut modes = (0..)
+            .map_while(|index| settings(&info, index))
+            .map(|settings| VideoMode {
+                width: settings.dmPelsWidth,
+                height: settings.dmPelsHeight,
+                refresh_rate: settings.dmDisplayFrequency,
+                bit_depth: settings.dmBitsPerPel,
+            })
+            .collect::<Vec<_>>();
+        modes.sort();
+        modes.dedup();
+        modes
+    }
+
+    fn mode(handle: Self::Handle) -> Option<VideoMode> {
+        let info = monitor_info(handle)?;
+        settings(&info, winuser::ENUM_CURRENT_SETTINGS).map(|settings| VideoMode {
+            width: settings.dmPelsWidth,
+            height: settings.dmPelsHeight,
+            refresh_rate: settings.dmDisplayFrequency,
+            bit_depth: settings.dmBitsPerPel,
+        })
+    }
+
+    fn set_mode(handle: Self::Handle, mode: Option<VideoMode>) -> Result<(), Error> {
+        let info = monitor_info(handle)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "display is not connected"))?;
+        let result = unsafe {
+            match mode {
+                Some(mode) => {
+                    let mut settings = mem::zeroed::<wingdi::DEVMODEW>();
+                    settings.dmSize = mem::size_of::<wingdi::DEVMODEW>() as minwindef::WORD;
+                    settings.dmPelsWidth = mode.width;
+                    settings.dmPelsHeight = mode.height;
+                    settings.dmDisplayFrequency = mode.refresh_rate;
+                    settings.dmBitsPerPel = mode.bit_depth;
+                    settings.dmFields = wingdi::DM_PELSWIDTH
+                        | wingdi::DM_PELSHEIGHT
+                        | wingdi::DM_DISPLAYFREQUENCY
+                        | wingdi::DM_BITSPERPEL;
+                    // `CDS_FULLSCREEN` changes the mode temporarily. The
+                    // default mode is restored if the process exits.
+                    winuser::ChangeDisplaySettingsExW(
+                        info.szDevice.as_ptr(),
+                        &mut settings,
+                        ptr::null_mut(),
+                        winuser::CDS_FULLSCREEN,
+                        ptr::null_mut(),
+                    )
+                }
+                None => winuser::ChangeDisplaySettingsExW(
+                    info.szDevice.as_ptr(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    0,
+                    ptr::null_mut(),
+                ),
+            }
+        };
+        if result == winuser::DISP_CHANGE_SUCCESSFUL {
+            Ok(())
+        }
+        else {
+            Err(Error::with_code(result, "failed to change display mode"))
+        }
+    }
+
+    fn work_area(handle: Self::Handle) -> Option<(PhysicalPosition, PhysicalSize)> {
+        let windef::RECT {
+            left,
+            top,
+            right,
+            bottom,
+        } = monitor_info(handle)?.rcWork;
+        Some((
+            PhysicalPosition::new(left, top),
+            PhysicalSize::new(right - left, bottom - top),
+        ))
+    }
+}
+
+impl platform::Handle for Display {
+    type Handle = windef::HMONITOR;
+
+    fn handle(&self) -> Self::Handle {
+        self.0
+    }
+}
+
+/// Determines the changes in connected displays and their modes since the
+/// last query.
+///
+/// The first query establishes the displays that are initially connected and
+/// never reports changes.
+pub fn changes() -> Vec<(windef::HMONITOR, DisplayEvent)> {
+    let modes: HashMap<_, _> = monitors()
+        .into_iter()
+        .filter_map(|monitor| mode(monitor).map(|mode| (monitor, mode)))
+        .collect();
+    MODES.with(|previous| {
+        let previous = previous.replace(Some(modes.clone()));
+        let mut changes = vec![];
+        if let Some(previous) = previous {
+            for (monitor, mode) in &modes {
+                match previous.get(monitor) {
+                    Some(previous) if previous != mode => {
+                        changes.push((*monitor, DisplayEvent::ModeChanged));
+                    }
+                    None => {
+                        changes.push((*monitor, DisplayEvent::Connected));
+                    }
+                    _ => {}
+                }
+            }
+            for monitor in previous.keys() {
+                if !modes.contains_key(monitor) {
+                    changes.push((*monitor, DisplayEvent::Disconnected));
+                }
+            }
+        }
+        changes
+    })
+}
+
+fn monitors() -> Vec<windef::HMONITOR> {
+    unsafe extern "system" fn push(
+        monitor: windef::HMONITOR,
+        _: windef::HDC,
+        _: windef::LPRECT,
+        monitors: minwindef::LPARAM,
+    ) -> minwindef::BOOL {
+        let monitors = &mut *(monitors as *mut Vec<windef::HMONITOR>);
+        monitors.push(monitor);
+        minwindef::TRUE
+    }
+
+    let mut monitors = Vec::<windef::HMONITOR>::new();
+    unsafe {
+        winuser::EnumDisplayMonitors(
+            ptr::null_mut(),
+            ptr::null(),
+            Some(push),
+            &mut monitors as *mut Vec<windef::HMONITOR> as minwindef::LPARAM,
+        );
+    }
+    monitors
+}
+
+fn mode(monitor: windef::HMONITOR) -> Option<Mode> {
+    let info = monitor_info(monitor)?;
+    let settings = settings(&info, winuser::ENUM_CURRENT_SETTINGS)?;
+    let bounds = info.rcMonitor;
+    Some(Mode {
+        bounds: (bounds.left, bounds.top, bounds.right, bounds.bottom),
+        bits: settings.dmBitsPerPel,
+        frequency: settings.dmDisplayFrequency,
+    })
+}
+
+pub fn monitor_info(monitor: windef::HMONITOR) -> Option<winuser::MONITORINFOEXW> {
+    unsafe {
+        let mut info = mem::zeroed::<winuser::MONITORINFOEXW>();
+        info.cbSize = mem::size_of::<winuser::MONITORINFOEXW>() as minwindef::DWORD;
+        if winuser::GetMonitorInfoW(
+            monitor,
+            &mut info as *mut winuser::MONITORINFOEXW as winuser::LPMONITORINFO,
+        ) == 0
+        {
+            None
+        }
+        else {
+            Some(info)
+        }
+    }
+}
+
+/// Gets the display settings of a monitor with the given index or
+/// `ENUM_CURRENT_SETTINGS`.
+fn settings(info: &winuser::MONITORINFOEXW, index: minwindef::DWORD) -> Option<wingdi::DEVMODEW> {
+    unsafe {
+        let mut settings = mem::zeroed::<wingdi::DEVMODEW>();
+        settings.dmSize = mem::size_of::<wingdi::DEVMODEW>() as minwindef::WORD;
+        if winuser::EnumDisplaySettingsW(info.szDevice.as_ptr(), index, &mut settings) == 0 {
+            None
+        }
+        else {
+            Some(settings)
+        }
+    }
+}
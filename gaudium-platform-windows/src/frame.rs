@@ -0,0 +1,68 @@
+use lazy_static::lazy_static;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use winapi::shared::{minwindef, ntdef, windef, winerror};
+use winapi::um::{dwmapi, winuser};
+
+/// The interval between frames when composition is unavailable.
+const FALLBACK_INTERVAL: Duration = Duration::from_millis(16);
+
+lazy_static! {
+    /// Posted to a window when it may present a frame. `WPARAM` is the serial
+    /// number of the window that requested the frame.
+    pub static ref WM_FRAME_READY: minwindef::UINT =
+        unsafe { winuser::RegisterWindowMessageA("WM_FRAME_READY\0".as_ptr() as ntdef::LPCSTR) };
+    static ref CLOCK: Mutex<Sender<Request>> = {
+        let (sender, receiver) = mpsc::channel::<Request>();
+        thread::Builder::new()
+            .name("gaudium-frame-clock".into())
+            .spawn(move || run(receiver))
+            .expect("failed to spawn frame clock thread");
+        Mutex::new(sender)
+    };
+}
+
+/// A request from a window for the next frame.
+///
+/// Window handles are not `Send`, so they are sent as integers.
+struct Request {
+    window: usize,
+    serial: usize,
+}
+
+/// Requests that `WM_FRAME_READY` be posted to a window after the next
+/// composition by the desktop window manager (DWM).
+///
+/// Windows must coalesce requests, because each request posts a message.
+pub fn request(window: windef::HWND, serial: usize) {
+    let _ = CLOCK.lock().unwrap().send(Request {
+        window: window as usize,
+        serial,
+    });
+}
+
+/// Posts `WM_FRAME_READY` to windows that have requested a frame after each
+/// composition.
+fn run(receiver: Receiver<Request>) {
+    while let Ok(request) = receiver.recv() {
+        wait_for_composition();
+        // Requests received while waiting are also satisfied by this frame.
+        for Request { window, serial } in Some(request).into_iter().chain(receiver.try_iter()) {
+            unsafe {
+                winuser::PostMessageW(window as windef::HWND, *WM_FRAME_READY, serial, 0);
+            }
+        }
+    }
+}
+
+/// Blocks until the DWM presents its next frame.
+///
+/// If composition is disabled, which is possible on Windows 7, then this
+/// sleeps for the approximate period of a 60Hz display instead.
+fn wait_for_composition() {
+    if !winerror::SUCCEEDED(unsafe { dwmapi::DwmFlush() }) {
+        thread::sleep(FALLBACK_INTERVAL);
+    }
+}
@@ -0,0 +1,71 @@
+use gaudium_core::error::{Error, ErrorKind};
+use gaudium_core::event::KeyCode;
+use gaudium_core::platform;
+use gaudium_core::reactor::ThreadContext;
+use gaudium_core::shell::hotkey::{HotkeyId, Modifiers};
+use gaudium_core::IntoRawHandle;
+use std::os::raw;
+use std::ptr;
+use winapi::shared::minwindef;
+use winapi::um::winuser;
+
+use crate::keyboard;
+
+/// The largest identifier accepted by `RegisterHotKey` for applications.
+const MAX_ID: u32 = 0xBFFF;
+
+/// Registers hotkeys via `RegisterHotKey` without a window, so that
+/// `WM_HOTKEY` is posted to the event thread.
+pub struct Hotkey;
+
+impl platform::Hotkey for Hotkey {
+    fn register(
+        _: &ThreadContext,
+        id: HotkeyId,
+        modifiers: Modifiers,
+        keycode: KeyCode,
+    ) -> Result<(), Error> {
+        let id = id.into_raw_handle();
+        if id > MAX_ID {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "hotkey identifiers exhausted",
+            ));
+        }
+        let key = keyboard::virtual_key(keycode)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "key cannot be a hotkey"))?;
+        let mut flags = winuser::MOD_NOREPEAT;
+        for &(modifier, flag) in [
+            (modifiers.alt, winuser::MOD_ALT),
+            (modifiers.control, winuser::MOD_CONTROL),
+            (modifiers.shift, winuser::MOD_SHIFT),
+            (modifiers.super_key, winuser::MOD_WIN),
+        ]
+        .iter()
+        {
+            if modifier {
+                flags |= flag;
+            }
+        }
+        if unsafe {
+            winuser::RegisterHotKey(
+                ptr::null_mut(),
+                id as raw::c_int,
+                flags as minwindef::UINT,
+                key as minwindef::UINT,
+            )
+        } == 0
+        {
+            Err(crate::last_error("failed to register hotkey"))
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    fn unregister(id: HotkeyId) {
+        unsafe {
+            winuser::UnregisterHotKey(ptr::null_mut(), id.into_raw_handle() as raw::c_int);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use gaudium_core::display::{IntoPhysical, PhysicalPosition};
+use gaudium_core::error::{Error, ErrorKind};
+use gaudium_core::event::{ElementState, InputEvent, MouseButton, MouseWheelDelta};
+use std::mem;
+use winapi::shared::{minwindef, windef};
+use winapi::um::winuser;
+
+/// Injects an input event into the input stream of the system.
+///
+/// Keyboard and mouse input is injected via `SendInput`, so it is observed by
+/// the window as Raw Input just like input from a device. Such input is
+/// delivered to the foreground window, so the window is brought to the
+/// foreground first. Positional wheel deltas are not produced by Raw Input and
+/// are instead posted to the window as `WM_MOUSEWHEEL` and `WM_MOUSEHWHEEL`.
+pub fn inject(window: windef::HWND, event: InputEvent) -> Result<(), Error> {
+    let mut inputs = Vec::with_capacity(2);
+    match event {
+        InputEvent::KeyboardKeyChanged {
+            scancode, state, ..
+        } => {
+            // Scan codes prefixed with `E0` are extended.
+            let mut flags = winuser::KEYEVENTF_SCANCODE;
+            if scancode & 0xff00 == 0xe000 {
+                flags |= winuser::KEYEVENTF_EXTENDEDKEY;
+            }
+            if state == ElementState::Released {
+                flags |= winuser::KEYEVENTF_KEYUP;
+            }
+            inputs.push(keyboard_input((scancode & 0xff) as minwindef::WORD, flags));
+        }
+        InputEvent::MouseButtonChanged { button, state, .. } => {
+            let is_pressed = state == ElementState::Pressed;
+            let (flags, data) = match (button, is_pressed) {
+                (MouseButton::Left, true) => (winuser::MOUSEEVENTF_LEFTDOWN, 0),
+                (MouseButton::Left, false) => (winuser::MOUSEEVENTF_LEFTUP, 0),
+                (MouseButton::Right, true) => (winuser::MOUSEEVENTF_RIGHTDOWN, 0),
+                (MouseButton::Right, false) => (winuser::MOUSEEVENTF_RIGHTUP, 0),
+                (MouseButton::Center, true) => (winuser::MOUSEEVENTF_MIDDLEDOWN, 0),
+                (MouseButton::Center, false) => (winuser::MOUSEEVENTF_MIDDLEUP, 0),
+                (MouseButton::Other(4), true) => (winuser::MOUSEEVENTF_XDOWN, winuser::XBUTTON1),
+                (MouseButton::Other(4), false) => (winuser::MOUSEEVENTF_XUP, winuser::XBUTTON1),
+                (MouseButton::Other(5), true) => (winuser::MOUSEEVENTF_XDOWN, winuser::XBUTTON2),
+                (MouseButton::Other(5), false) => (winuser::MOUSEEVENTF_XUP, winuser::XBUTTON2),
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::Unsupported,
+                        "mouse button cannot be injected",
+                    ));
+                }
+            };
+            inputs.push(mouse_input(0, 0, data as minwindef::DWORD, flags));
+        }
+        InputEvent::MouseWheelRotated {
+            delta: MouseWheelDelta::Rotational(x, y),
+            ..
+        } => {
+            // Rotational deltas are measured in detents.
+            let detents = |delta: f64| {
+                (delta * winuser::WHEEL_DELTA as f64).round() as i32 as minwindef::DWORD
+            };
+            if y != 0.0 {
+                inputs.push(mouse_input(0, 0, detents(y), winuser::MOUSEEVENTF_WHEEL));
+            }
+            if x != 0.0 {
+                inputs.push(mouse_input(0, 0, detents(x), winuser::MOUSEEVENTF_HWHEEL));
+            }
+        }
+        InputEvent::MouseWheelRotated {
+            delta: MouseWheelDelta::Positional(x, y),
+            ..
+        } => {
+            if *y != 0.0 {
+                post_wheel_message(window, winuser::WM_MOUSEWHEEL, *y)?;
+            }
+            if *x != 0.0 {
+                post_wheel_message(window, winuser::WM_MOUSEHWHEEL, *x)?;
+            }
+        }
+        InputEvent::MouseMoved { movement, .. } => {
+            if let Some(position) = movement.absolute {
+                let dpi = 1.0; // TODO: Get the DPI factor.
+                let PhysicalPosition { x, y } = position.into_physical(dpi);
+                let mut point = windef::POINT {
+                    x: x.into(),
+                    y: y.into(),
+                };
+                unsafe {
+                    if winuser::ClientToScreen(window, &mut point) == 0 {
+                        return Err(crate::last_error("failed to transform position"));
+                    }
+                }
+                let (x, y) = normalize(point);
+                inputs.push(mouse_input(
+                    x,
+                    y,
+                    0,
+                    winuser::MOUSEEVENTF_MOVE
+                        | winuser::MOUSEEVENTF_ABSOLUTE
+                        | winuser::MOUSEEVENTF_VIRTUALDESK,
+                ));
+            }
+            // Relative motion is subject to the pointer acceleration of the
+            // system when it moves the pointer.
+            else if let Some(relative) = movement.relative {
+                inputs.push(mouse_input(
+                    (*relative.x).round() as i32,
+                    (*relative.y).round() as i32,
+                    0,
+                    winuser::MOUSEEVENTF_MOVE,
+                ));
+            }
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "input event cannot be injected",
+            ));
+        }
+    }
+    if inputs.is_empty() {
+        return Ok(());
+    }
+    unsafe {
+        winuser::SetForegroundWindow(window);
+        let count = winuser::SendInput(
+            inputs.len() as minwindef::UINT,
+            inputs.as_mut_ptr(),
+            mem::size_of::<winuser::INPUT>() as i32,
+        );
+        // Injection fails if it is blocked by User Interface Privilege
+        // Isolation (UIPI), such as when the foreground window belongs to a
+        // process with a higher integrity level.
+        if count as usize != inputs.len() {
+            return Err(crate::last_error("failed to inject input"));
+        }
+    }
+    Ok(())
+}
+
+fn keyboard_input(scancode: minwindef::WORD, flags: minwindef::DWORD) -> winuser::INPUT {
+    unsafe {
+        let mut input = mem::zeroed::<winuser::INPUT>();
+        input.type_ = winuser::INPUT_KEYBOARD;
+        *input.u.ki_mut() = winuser::KEYBDINPUT {
+            wVk: 0,
+            wScan: scancode,
+            dwFlags: flags,
+            time: 0,
+            dwExtraInfo: 0,
+        };
+        input
+    }
+}
+
+fn mouse_input(
+    dx: i32,
+    dy: i32,
+    data: minwindef::DWORD,
+    flags: minwindef::DWORD,
+) -> winuser::INPUT {
+    unsafe {
+        let mut input = mem::zeroed::<winuser::INPUT>();
+        input.type_ = winuser::INPUT_MOUSE;
+        *input.u.mi_mut() = winuser::MOUSEINPUT {
+            dx,
+            dy,
+            mouseData: data,
+            dwFlags: flags,
+            time: 0,
+            dwExtraInfo: 0,
+        };
+        input
+    }
+}
+
+/// Normalizes a point on the virtual desktop into the range used by absolute
+/// mouse input, in which `0` and `65535` are the edges of the virtual desktop.
+fn normalize(point: windef::POINT) -> (i32, i32) {
+    let (left, top, width, height) = unsafe {
+        (
+            winuser::GetSystemMetrics(winuser::SM_XVIRTUALSCREEN),
+            winuser::GetSystemMetrics(winuser::SM_YVIRTUALSCREEN),
+            winuser::GetSystemMetrics(winuser::SM_CXVIRTUALSCREEN).max(2),
+            winuser::GetSystemMetrics(winuser::SM_CYVIRTUALSCREEN).max(2),
+        )
+    };
+    let normalize = |value: i32, origin: i32, extent: i32| {
+        (((value - origin) as f64 * 65535.0) / (extent - 1) as f64).round() as i32
+    };
+    (
+        normalize(point.x, left, width),
+        normalize(point.y, top, height),
+    )
+}
+
+/// Posts a wheel message with the given delta to a window.
+///
+/// The delta is rounded to an integer. No Raw Input precedes the message, so
+/// it is reported like scrolling on a touchpad. See
+/// `mouse::parse_wheel_message`.
+fn post_wheel_message(
+    window: windef::HWND,
+    message: minwindef::UINT,
+    delta: f64,
+) -> Result<(), Error> {
+    let delta = delta.round() as i16;
+    unsafe {
+        let mut point = mem::zeroed::<windef::POINT>();
+        winuser::GetCursorPos(&mut point);
+        let wparam = minwindef::MAKELONG(0, delta as minwindef::WORD) as minwindef::DWORD
+            as minwindef::WPARAM;
+        let lparam = minwindef::MAKELONG(point.x as minwindef::WORD, point.y as minwindef::WORD)
+            as minwindef::LPARAM;
+        if winuser::PostMessageW(window, message, wparam, lparam) == 0 {
+            return Err(crate::last_error("failed to post wheel message"));
+        }
+    }
+    Ok(())
+}
@@ -1,12 +1,92 @@
-use gaudium_core::device::Usage;
+use gaudium_core::device::{Battery, BatteryState, DeviceInfo, Led, Usage};
+use gaudium_core::error::{Error, ErrorKind};
 use std::ffi;
 use std::mem::{self, MaybeUninit};
 use std::ops::{Deref, DerefMut};
+use std::os::raw;
 use std::ptr;
-use winapi::shared::{hidpi, hidusage, minwindef, ntdef, windef};
-use winapi::um::winuser;
+use winapi::shared::{hidpi, hidsdi, hidusage, minwindef, ntdef, windef};
+use winapi::um::{fileapi, handleapi, processthreadsapi, winnt, winuser};
 
-use crate::Buffer;
+use crate::{AlignedBuffer, WideNullTerminated};
+
+const HID_USAGE_PAGE_GENERIC_DEVICE: hidusage::USAGE = 0x06;
+const HID_USAGE_PAGE_BATTERY_SYSTEM: hidusage::USAGE = 0x85;
+
+const HID_USAGE_GENERIC_DEVICE_BATTERY_STRENGTH: hidusage::USAGE = 0x20;
+const HID_USAGE_BATTERY_SYSTEM_CHARGING: hidusage::USAGE = 0x44;
+const HID_USAGE_BATTERY_SYSTEM_ABSOLUTE_STATE_OF_CHARGE: hidusage::USAGE = 0x65;
+
+/// Generic desktop usages for which raw input is registered and the device
+/// classes that they represent.
+const USAGES: [(hidusage::USAGE, Usage); 4] = [
+    (hidusage::HID_USAGE_GENERIC_KEYBOARD, Usage::Keyboard),
+    (hidusage::HID_USAGE_GENERIC_MOUSE, Usage::Mouse),
+    (hidusage::HID_USAGE_GENERIC_GAMEPAD, Usage::GameController),
+    (hidusage::HID_USAGE_GENERIC_JOYSTICK, Usage::GameController),
+];
+
+/// Selects the classes of devices from which raw input is received.
+///
+/// By default, all classes of devices are included. Raw input is registered
+/// per process, so the most recently applied filter determines the devices
+/// from which input is received.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RawInputFilter {
+    keyboard: bool,
+    mouse: bool,
+    game_controller: bool,
+}
+
+impl RawInputFilter {
+    /// Gets a filter that excludes all classes of devices.
+    pub fn none() -> Self {
+        RawInputFilter {
+            keyboard: false,
+            mouse: false,
+            game_controller: false,
+        }
+    }
+
+    /// Includes devices with the given usage.
+    pub fn with_usage(mut self, usage: Usage) -> Self {
+        *self.usage_mut(usage) = true;
+        self
+    }
+
+    /// Excludes devices with the given usage.
+    pub fn without_usage(mut self, usage: Usage) -> Self {
+        *self.usage_mut(usage) = false;
+        self
+    }
+
+    /// Gets whether or not devices with the given usage are included.
+    pub fn contains(&self, usage: Usage) -> bool {
+        match usage {
+            Usage::Keyboard => self.keyboard,
+            Usage::Mouse => self.mouse,
+            Usage::GameController => self.game_controller,
+        }
+    }
+
+    fn usage_mut(&mut self, usage: Usage) -> &mut bool {
+        match usage {
+            Usage::Keyboard => &mut self.keyboard,
+            Usage::Mouse => &mut self.mouse,
+            Usage::GameController => &mut self.game_controller,
+        }
+    }
+}
+
+impl Default for RawInputFilter {
+    fn default() -> Self {
+        RawInputFilter {
+            keyboard: true,
+            mouse: true,
+            game_controller: true,
+        }
+    }
+}
 
 pub trait TryFromDeviceInfo: Sized {
     fn try_from_device_info(info: &winuser::RID_DEVICE_INFO) -> Option<Self>;
@@ -17,16 +97,14 @@ impl TryFromDeviceInfo for Usage {
         match info.dwType {
             winuser::RIM_TYPEMOUSE => Some(Usage::Mouse),
             winuser::RIM_TYPEKEYBOARD => Some(Usage::Keyboard),
-            // TODO: Usage pages should be specified in only one place. See
-            //       the `register` function.
             winuser::RIM_TYPEHID => unsafe {
                 let hid = info.u.hid();
                 if hid.usUsagePage == hidusage::HID_USAGE_PAGE_GENERIC {
-                    match hid.usUsage {
-                        hidusage::HID_USAGE_GENERIC_GAMEPAD
-                        | hidusage::HID_USAGE_GENERIC_JOYSTICK => Some(Usage::GameController),
-                        _ => None,
-                    }
+                    USAGES
+                        .iter()
+                        .find(|&&(usage, _)| usage == hid.usUsage)
+                        .map(|&(_, usage)| usage)
+                        .filter(|&usage| usage == Usage::GameController)
                 }
                 else {
                     None
@@ -39,7 +117,32 @@ impl TryFromDeviceInfo for Usage {
 
 pub enum RawInput {
     Unboxed(winuser::RAWINPUT),
-    Boxed(Box<winuser::RAWINPUT>),
+    // Raw input from HIDs includes reports that extend beyond `RAWINPUT`.
+    Buffered(AlignedBuffer<winuser::RAWINPUT>),
+}
+
+impl RawInput {
+    /// Gets a pointer to the HID reports and the size of each report, if the
+    /// input is from a HID.
+    ///
+    /// The pointer is derived from the entire allocation rather than the
+    /// `bRawData` field, which only spans the first byte of the reports.
+    pub fn hid_reports(&mut self) -> Option<(ntdef::PCHAR, minwindef::ULONG)> {
+        if self.header.dwType != winuser::RIM_TYPEHID {
+            return None;
+        }
+        let size = unsafe { self.data.hid().dwSizeHid };
+        let input: *mut winuser::RAWINPUT = match *self {
+            RawInput::Unboxed(ref mut input) => input,
+            RawInput::Buffered(ref mut buffer) => buffer.as_mut_ptr(),
+        };
+        let offset =
+            mem::offset_of!(winuser::RAWINPUT, data) + mem::offset_of!(winuser::RAWHID, bRawData);
+        Some((
+            unsafe { (input as *mut u8).add(offset) } as ntdef::PCHAR,
+            size,
+        ))
+    }
 }
 
 impl Deref for RawInput {
@@ -48,7 +151,8 @@ impl Deref for RawInput {
     fn deref(&self) -> &Self::Target {
         match *self {
             RawInput::Unboxed(ref input) => input,
-            RawInput::Boxed(ref input) => input,
+            // The buffer is zero-initialized and `RAWINPUT` is plain data.
+            RawInput::Buffered(ref buffer) => unsafe { buffer.as_ref() },
         }
     }
 }
@@ -57,50 +161,89 @@ impl DerefMut for RawInput {
     fn deref_mut(&mut self) -> &mut Self::Target {
         match *self {
             RawInput::Unboxed(ref mut input) => input,
-            RawInput::Boxed(ref mut input) => input,
+            RawInput::Buffered(ref mut buffer) => unsafe { buffer.as_mut() },
         }
     }
 }
 
-pub fn register(window: windef::HWND) -> Result<(), ()> {
-    // `RIDEV_DEVNOTIFY` enables `WM_INPUT_DEVICE_CHANGE` events. It seems
-    // that `RIDEV_INPUTSINK` would be good to use as well, but from some
-    // minimal testing it seems that these events are dispatched regardless of
-    // window focus.
-    let rids = [
-        winuser::RAWINPUTDEVICE {
-            usUsagePage: hidusage::HID_USAGE_PAGE_GENERIC,
-            usUsage: hidusage::HID_USAGE_GENERIC_KEYBOARD,
-            dwFlags: winuser::RIDEV_DEVNOTIFY,
-            hwndTarget: window,
-        },
-        winuser::RAWINPUTDEVICE {
-            usUsagePage: hidusage::HID_USAGE_PAGE_GENERIC,
-            usUsage: hidusage::HID_USAGE_GENERIC_MOUSE,
-            dwFlags: winuser::RIDEV_DEVNOTIFY,
-            hwndTarget: window,
-        },
-        winuser::RAWINPUTDEVICE {
-            usUsagePage: hidusage::HID_USAGE_PAGE_GENERIC,
-            usUsage: hidusage::HID_USAGE_GENERIC_GAMEPAD,
-            dwFlags: winuser::RIDEV_DEVNOTIFY,
-            hwndTarget: window,
-        },
-        winuser::RAWINPUTDEVICE {
+/// Preparsed data describing the reports of a HID.
+///
+/// Preparsed data is opaque and is only accessed via pointers.
+pub struct PreparsedData(AlignedBuffer<usize>);
+
+impl PreparsedData {
+    pub fn as_mut_ptr(&mut self) -> hidpi::PHIDP_PREPARSED_DATA {
+        self.0.as_mut_ptr() as hidpi::PHIDP_PREPARSED_DATA
+    }
+}
+
+/// Registers raw input for the given window.
+///
+/// Usages that are excluded by the filter are unregistered if they are
+/// received by a window of the calling thread. Raw input is registered per
+/// process, so usages received by windows of other event threads are left
+/// intact. If `background` is `true`, then the window receives input even if
+/// it is not in the foreground.
+pub fn register(
+    window: windef::HWND,
+    filter: RawInputFilter,
+    background: bool,
+) -> Result<(), Error> {
+    // `RIDEV_DEVNOTIFY` enables `WM_INPUT_DEVICE_CHANGE` events.
+    // `RIDEV_INPUTSINK` enables input while the window is in the background.
+    let flags = winuser::RIDEV_DEVNOTIFY
+        | if background {
+            winuser::RIDEV_INPUTSINK
+        }
+        else {
+            0
+        };
+    let (included, excluded): (Vec<_>, Vec<_>) = USAGES
+        .iter()
+        .partition(|&&(_, usage)| filter.contains(usage));
+    let included = included
+        .into_iter()
+        .map(|&(usage, _)| winuser::RAWINPUTDEVICE {
             usUsagePage: hidusage::HID_USAGE_PAGE_GENERIC,
-            usUsage: hidusage::HID_USAGE_GENERIC_JOYSTICK,
-            dwFlags: winuser::RIDEV_DEVNOTIFY,
+            usUsage: usage,
+            dwFlags: flags,
             hwndTarget: window,
-        },
-    ];
+        })
+        .collect::<Vec<_>>();
+    let thread = unsafe { processthreadsapi::GetCurrentThreadId() };
+    let excluded = registered_devices()
+        .into_iter()
+        .filter(|device| {
+            device.usUsagePage == hidusage::HID_USAGE_PAGE_GENERIC
+                && excluded.iter().any(|&&(usage, _)| usage == device.usUsage)
+                && (device.hwndTarget.is_null()
+                    || unsafe {
+                        winuser::GetWindowThreadProcessId(device.hwndTarget, ptr::null_mut())
+                    } == thread)
+        })
+        .map(|device| winuser::RAWINPUTDEVICE {
+            usUsagePage: device.usUsagePage,
+            usUsage: device.usUsage,
+            dwFlags: winuser::RIDEV_REMOVE,
+            hwndTarget: ptr::null_mut(),
+        })
+        .collect::<Vec<_>>();
     unsafe {
-        if winuser::RegisterRawInputDevices(
-            &rids as *const [winuser::RAWINPUTDEVICE] as *const winuser::RAWINPUTDEVICE,
-            rids.len() as u32,
-            mem::size_of::<winuser::RAWINPUTDEVICE>() as u32,
-        ) == 0
+        for rid in excluded {
+            winuser::RegisterRawInputDevices(
+                &rid,
+                1,
+                mem::size_of::<winuser::RAWINPUTDEVICE>() as u32,
+            );
+        }
+        if !included.is_empty()
+            && winuser::RegisterRawInputDevices(
+                included.as_ptr(),
+                included.len() as u32,
+                mem::size_of::<winuser::RAWINPUTDEVICE>() as u32,
+            ) == 0
         {
-            Err(())
+            Err(crate::last_error("failed to register raw input devices"))
         }
         else {
             Ok(())
@@ -108,7 +251,49 @@ pub fn register(window: windef::HWND) -> Result<(), ()> {
     }
 }
 
-pub fn raw_input_header(device: winuser::HRAWINPUT) -> Result<winuser::RAWINPUTHEADER, ()> {
+/// Unregisters raw input for usages that target destroyed windows.
+///
+/// Raw input is registered per process, so usages registered by other event
+/// threads are unaffected.
+pub fn unregister_destroyed() {
+    let size = mem::size_of::<winuser::RAWINPUTDEVICE>() as u32;
+    for device in registered_devices().into_iter().filter(|device| {
+        !device.hwndTarget.is_null() && unsafe { winuser::IsWindow(device.hwndTarget) } == 0
+    }) {
+        let rid = winuser::RAWINPUTDEVICE {
+            usUsagePage: device.usUsagePage,
+            usUsage: device.usUsage,
+            dwFlags: winuser::RIDEV_REMOVE,
+            hwndTarget: ptr::null_mut(),
+        };
+        unsafe {
+            winuser::RegisterRawInputDevices(&rid, 1, size);
+        }
+    }
+}
+
+/// Gets the raw input usages that are registered by the process.
+fn registered_devices() -> Vec<winuser::RAWINPUTDEVICE> {
+    unsafe {
+        let mut count = 0;
+        let size = mem::size_of::<winuser::RAWINPUTDEVICE>() as u32;
+        if winuser::GetRegisteredRawInputDevices(ptr::null_mut(), &mut count, size)
+            == minwindef::UINT::MAX
+            || count == 0
+        {
+            return Vec::new();
+        }
+        let mut devices = Vec::with_capacity(count as usize);
+        let count = winuser::GetRegisteredRawInputDevices(devices.as_mut_ptr(), &mut count, size);
+        if count == minwindef::UINT::MAX {
+            return Vec::new();
+        }
+        devices.set_len(count as usize);
+        devices
+    }
+}
+
+pub fn raw_input_header(device: winuser::HRAWINPUT) -> Result<winuser::RAWINPUTHEADER, Error> {
     unsafe {
         let mut header = MaybeUninit::<winuser::RAWINPUTHEADER>::uninit();
         let mut size = mem::size_of::<winuser::RAWINPUTHEADER>() as u32;
@@ -120,7 +305,7 @@ pub fn raw_input_header(device: winuser::HRAWINPUT) -> Result<winuser::RAWINPUTH
             mem::size_of::<winuser::RAWINPUTHEADER>() as u32,
         ) != size
         {
-            Err(())
+            Err(crate::last_error("failed to read raw input header"))
         }
         else {
             Ok(header.assume_init())
@@ -128,14 +313,16 @@ pub fn raw_input_header(device: winuser::HRAWINPUT) -> Result<winuser::RAWINPUTH
     }
 }
 
-pub fn raw_input(device: winuser::HRAWINPUT) -> Result<RawInput, ()> {
+pub fn raw_input(device: winuser::HRAWINPUT) -> Result<RawInput, Error> {
     // Avoid allocations by using the static size of `RAWINPUT` (48 bytes) when
     // reading generic keyboard or mouse input.
     //
     // Read the header and then determine the type of device.
     match raw_input_header(device)?.dwType {
         winuser::RIM_TYPEKEYBOARD | winuser::RIM_TYPEMOUSE => unsafe {
-            let mut input = MaybeUninit::<winuser::RAWINPUT>::uninit();
+            // Not all of the input is necessarily written, so the input is
+            // zeroed rather than uninitialized.
+            let mut input = MaybeUninit::<winuser::RAWINPUT>::zeroed();
             let mut size = mem::size_of::<winuser::RAWINPUT>() as u32;
             if winuser::GetRawInputData(
                 device,
@@ -145,7 +332,7 @@ pub fn raw_input(device: winuser::HRAWINPUT) -> Result<RawInput, ()> {
                 mem::size_of::<winuser::RAWINPUTHEADER>() as u32,
             ) > size
             {
-                Err(())
+                Err(crate::last_error("failed to read raw input"))
             }
             else {
                 Ok(RawInput::Unboxed(input.assume_init()))
@@ -161,25 +348,25 @@ pub fn raw_input(device: winuser::HRAWINPUT) -> Result<RawInput, ()> {
                 mem::size_of::<winuser::RAWINPUTHEADER>() as u32,
             ) != 0
             {
-                return Err(());
+                return Err(crate::last_error("failed to read raw input size"));
             }
-            let mut buffer = Buffer::from_size(size as usize)?;
+            let mut buffer = AlignedBuffer::<winuser::RAWINPUT>::from_size(size as usize)?;
             if winuser::GetRawInputData(
                 device,
                 winuser::RID_INPUT,
-                buffer.as_mut_ptr(),
+                buffer.as_mut_ptr() as *mut ffi::c_void,
                 &mut size,
                 mem::size_of::<winuser::RAWINPUTHEADER>() as u32,
             ) != size
             {
-                return Err(());
+                return Err(crate::last_error("failed to read raw input"));
             }
-            Ok(RawInput::Boxed(buffer.into_box()))
+            Ok(RawInput::Buffered(buffer))
         },
     }
 }
 
-pub fn preparsed_data(device: ntdef::HANDLE) -> Result<Box<hidpi::HIDP_PREPARSED_DATA>, ()> {
+pub fn preparsed_data(device: ntdef::HANDLE) -> Result<PreparsedData, Error> {
     unsafe {
         let mut size = 0;
         if winuser::GetRawInputDeviceInfoW(
@@ -190,31 +377,34 @@ pub fn preparsed_data(device: ntdef::HANDLE) -> Result<Box<hidpi::HIDP_PREPARSED
         ) == 0
         {
             if size != 0 {
-                let mut buffer = Buffer::from_size(size as usize)?;
+                let mut buffer = AlignedBuffer::<usize>::from_size(size as usize)?;
                 if winuser::GetRawInputDeviceInfoW(
                     device,
                     winuser::RIDI_PREPARSEDDATA,
-                    buffer.as_mut_ptr(),
+                    buffer.as_mut_ptr() as *mut ffi::c_void,
                     &mut size,
                 ) == size
                 {
-                    Ok(buffer.into_box())
+                    Ok(PreparsedData(buffer))
                 }
                 else {
-                    Err(())
+                    Err(crate::last_error("failed to read preparsed data"))
                 }
             }
             else {
-                Err(())
+                Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "device has no preparsed data",
+                ))
             }
         }
         else {
-            Err(())
+            Err(crate::last_error("failed to read preparsed data size"))
         }
     }
 }
 
-pub fn device_info(device: ntdef::HANDLE) -> Result<winuser::RID_DEVICE_INFO, ()> {
+pub fn device_info(device: ntdef::HANDLE) -> Result<winuser::RID_DEVICE_INFO, Error> {
     unsafe {
         let mut size = 0;
         if winuser::GetRawInputDeviceInfoW(
@@ -237,20 +427,38 @@ pub fn device_info(device: ntdef::HANDLE) -> Result<winuser::RID_DEVICE_INFO, ()
                     Ok(info.assume_init())
                 }
                 else {
-                    Err(())
+                    Err(crate::last_error("failed to read device info"))
                 }
             }
             else {
-                Err(())
+                Err(Error::new(ErrorKind::Unsupported, "device has no info"))
             }
         }
         else {
-            Err(())
+            Err(crate::last_error("failed to read device info size"))
         }
     }
 }
 
-pub fn device_name(device: ntdef::HANDLE) -> Result<String, ()> {
+/// Gets the vendor, product, and version of a HID device.
+///
+/// Other fields of the returned `DeviceInfo` are `None`.
+pub fn hid_identity(info: &winuser::RID_DEVICE_INFO) -> DeviceInfo {
+    match info.dwType {
+        winuser::RIM_TYPEHID => {
+            let hid = unsafe { info.u.hid() };
+            DeviceInfo {
+                vendor_id: Some(hid.dwVendorId as u16),
+                product_id: Some(hid.dwProductId as u16),
+                version: Some(hid.dwVersionNumber as u16),
+                ..Default::default()
+            }
+        }
+        _ => DeviceInfo::default(),
+    }
+}
+
+pub fn device_name(device: ntdef::HANDLE) -> Result<String, Error> {
     unsafe {
         let mut n = 0;
         if winuser::GetRawInputDeviceInfoW(
@@ -273,20 +481,29 @@ pub fn device_name(device: ntdef::HANDLE) -> Result<String, ()> {
                     Ok(String::from_utf16_lossy(buffer.as_slice()))
                 }
                 else {
-                    Err(())
+                    Err(crate::last_error("failed to read device name"))
                 }
             }
             else {
-                Err(())
+                Err(Error::new(ErrorKind::Unsupported, "device has no name"))
             }
         }
         else {
-            Err(())
+            Err(crate::last_error("failed to read device name length"))
         }
     }
 }
 
-pub fn devices() -> Result<Vec<winuser::RAWINPUTDEVICELIST>, ()> {
+/// Determines if a device is an XInput controller.
+///
+/// The names of XInput-capable devices contain `IG_`.
+pub fn is_xinput_device(device: ntdef::HANDLE) -> bool {
+    device_name(device)
+        .map(|name| name.to_ascii_uppercase().contains("IG_"))
+        .unwrap_or(false)
+}
+
+pub fn devices() -> Result<Vec<winuser::RAWINPUTDEVICELIST>, Error> {
     unsafe {
         let mut n = 0;
         if winuser::GetRawInputDeviceList(
@@ -306,47 +523,57 @@ pub fn devices() -> Result<Vec<winuser::RAWINPUTDEVICELIST>, ()> {
                 Ok(devices)
             }
             else {
-                Err(())
+                Err(crate::last_error("failed to enumerate devices"))
             }
         }
         else {
-            Err(())
+            Err(crate::last_error("failed to read device count"))
         }
     }
 }
 
-pub fn hid_capabilities(data: &mut hidpi::HIDP_PREPARSED_DATA) -> Result<hidpi::HIDP_CAPS, ()> {
+pub fn hid_capabilities(data: &mut PreparsedData) -> Result<hidpi::HIDP_CAPS, Error> {
     unsafe {
         let mut capabilities = MaybeUninit::<hidpi::HIDP_CAPS>::uninit();
-        if hidpi::HidP_GetCaps(data, capabilities.as_mut_ptr()) == hidpi::HIDP_STATUS_SUCCESS {
+        let status = hidpi::HidP_GetCaps(data.as_mut_ptr(), capabilities.as_mut_ptr());
+        if status == hidpi::HIDP_STATUS_SUCCESS {
             Ok(capabilities.assume_init())
         }
         else {
-            Err(())
+            Err(Error::with_code(status, "failed to read HID capabilities"))
         }
     }
 }
 
 pub fn hid_button_capabilities(
     capabilities: &hidpi::HIDP_CAPS,
-    data: &mut hidpi::HIDP_PREPARSED_DATA,
-) -> Result<Vec<hidpi::HIDP_BUTTON_CAPS>, ()> {
+    data: &mut PreparsedData,
+) -> Result<Vec<hidpi::HIDP_BUTTON_CAPS>, Error> {
     unsafe {
         let mut n = capabilities.NumberInputButtonCaps;
         let mut buttons = Vec::with_capacity(n as usize);
-        if hidpi::HidP_GetButtonCaps(hidpi::HidP_Input, buttons.as_mut_ptr(), &mut n, data)
-            == hidpi::HIDP_STATUS_SUCCESS
-        {
-            buttons.set_len(n as usize);
+        let status = hidpi::HidP_GetButtonCaps(
+            hidpi::HidP_Input,
+            buttons.as_mut_ptr(),
+            &mut n,
+            data.as_mut_ptr(),
+        );
+        if status == hidpi::HIDP_STATUS_SUCCESS {
+            // `n` is the number of capabilities written, which never exceeds
+            // the capacity.
+            buttons.set_len((n as usize).min(buttons.capacity()));
             Ok(buttons)
         }
         else {
-            Err(())
+            Err(Error::with_code(
+                status,
+                "failed to read HID button capabilities",
+            ))
         }
     }
 }
 
-pub fn hid_button_count(capabilities: &hidpi::HIDP_BUTTON_CAPS) -> Result<u16, ()> {
+pub fn hid_button_count(capabilities: &hidpi::HIDP_BUTTON_CAPS) -> Result<u16, Error> {
     if capabilities.IsRange != 0 {
         unsafe {
             let range = capabilities.u.Range();
@@ -354,58 +581,82 @@ pub fn hid_button_count(capabilities: &hidpi::HIDP_BUTTON_CAPS) -> Result<u16, (
         }
     }
     else {
-        Err(())
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "button capabilities are not a range",
+        ))
     }
 }
 
+/// Gets the total number of buttons reported by the HID capabilities of a
+/// device.
+pub fn hid_button_total(device: ntdef::HANDLE) -> Result<u16, Error> {
+    let mut data = preparsed_data(device)?;
+    let capabilities = hid_capabilities(&mut data)?;
+    Ok(hid_button_capabilities(&capabilities, &mut data)?
+        .iter()
+        .filter_map(|capabilities| hid_button_count(capabilities).ok())
+        .sum())
+}
+
 pub fn read_hid_buttons(
     capabilities: &hidpi::HIDP_BUTTON_CAPS,
     input: &mut RawInput,
-    data: &mut hidpi::HIDP_PREPARSED_DATA,
-) -> Result<Vec<hidusage::USAGE>, ()> {
+    data: &mut PreparsedData,
+) -> Result<Vec<hidusage::USAGE>, Error> {
     hid_button_count(capabilities).and_then(|n| unsafe {
         let mut n = n as minwindef::ULONG;
         let mut usages = Vec::with_capacity(n as usize);
-        if input.header.dwType == winuser::RIM_TYPEHID {
-            if hidpi::HidP_GetUsages(
+        if let Some((report, size)) = input.hid_reports() {
+            let status = hidpi::HidP_GetUsages(
                 hidpi::HidP_Input,
                 capabilities.UsagePage,
                 0,
                 usages.as_mut_ptr(),
                 &mut n,
-                data,
-                &mut input.data.hid_mut().bRawData as *mut [u8; 1] as *mut i8,
-                input.data.hid().dwSizeHid,
-            ) == hidpi::HIDP_STATUS_SUCCESS
-            {
-                usages.set_len(n as usize);
+                data.as_mut_ptr(),
+                report,
+                size,
+            );
+            if status == hidpi::HIDP_STATUS_SUCCESS {
+                usages.set_len((n as usize).min(usages.capacity()));
                 Ok(usages)
             }
             else {
-                Err(())
+                Err(Error::with_code(status, "failed to read HID buttons"))
             }
         }
         else {
-            Err(())
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "input is not from a HID",
+            ))
         }
     })
 }
 
 pub fn hid_value_capabilities(
     capabilities: &hidpi::HIDP_CAPS,
-    data: &mut hidpi::HIDP_PREPARSED_DATA,
-) -> Result<Vec<hidpi::HIDP_VALUE_CAPS>, ()> {
+    data: &mut PreparsedData,
+) -> Result<Vec<hidpi::HIDP_VALUE_CAPS>, Error> {
     unsafe {
         let mut n = capabilities.NumberInputValueCaps;
         let mut values = Vec::with_capacity(n as usize);
-        if hidpi::HidP_GetValueCaps(hidpi::HidP_Input, values.as_mut_ptr(), &mut n, data)
-            == hidpi::HIDP_STATUS_SUCCESS
-        {
-            values.set_len(n as usize);
+        let status = hidpi::HidP_GetValueCaps(
+            hidpi::HidP_Input,
+            values.as_mut_ptr(),
+            &mut n,
+            data.as_mut_ptr(),
+        );
+        if status == hidpi::HIDP_STATUS_SUCCESS {
+            values.set_len((n as usize).min(values.capacity()));
             Ok(values)
         }
         else {
-            Err(())
+            Err(Error::with_code(
+                status,
+                "failed to read HID value capabilities",
+            ))
         }
     }
 }
@@ -413,35 +664,391 @@ pub fn hid_value_capabilities(
 pub fn read_hid_value(
     capabilities: &hidpi::HIDP_VALUE_CAPS,
     input: &mut RawInput,
-    data: &mut hidpi::HIDP_PREPARSED_DATA,
-) -> Result<minwindef::ULONG, ()> {
+    data: &mut PreparsedData,
+) -> Result<minwindef::ULONG, Error> {
     if capabilities.IsRange != 0 {
         unsafe {
             let mut value = 0;
-            if input.header.dwType == winuser::RIM_TYPEHID {
-                if hidpi::HidP_GetUsageValue(
+            if let Some((report, size)) = input.hid_reports() {
+                let status = hidpi::HidP_GetUsageValue(
                     hidpi::HidP_Input,
                     capabilities.UsagePage,
                     0,
                     capabilities.u.Range().UsageMin,
                     &mut value,
-                    data,
-                    &mut input.data.hid_mut().bRawData as *mut [u8; 1] as *mut i8,
-                    input.data.hid().dwSizeHid,
-                ) == hidpi::HIDP_STATUS_SUCCESS
-                {
+                    data.as_mut_ptr(),
+                    report,
+                    size,
+                );
+                if status == hidpi::HIDP_STATUS_SUCCESS {
                     Ok(value)
                 }
                 else {
-                    Err(())
+                    Err(Error::with_code(status, "failed to read HID value"))
                 }
             }
             else {
-                Err(())
+                Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "input is not from a HID",
+                ))
             }
         }
     }
     else {
-        Err(())
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "value capabilities are not a range",
+        ))
+    }
+}
+
+/// A HID opened for reading or writing reports on demand.
+struct HidFile(ntdef::HANDLE);
+
+impl HidFile {
+    /// Opens a HID with the given access rights.
+    ///
+    /// Write access is only requested to write output reports, so that
+    /// reports can be read from devices that deny write access.
+    fn open(device: ntdef::HANDLE, access: minwindef::DWORD) -> Result<Self, Error> {
+        let name = device_name(device)?.wide_null_terminated();
+        let file = unsafe {
+            fileapi::CreateFileW(
+                name.as_ptr(),
+                access,
+                winnt::FILE_SHARE_READ | winnt::FILE_SHARE_WRITE,
+                ptr::null_mut(),
+                fileapi::OPEN_EXISTING,
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if file == handleapi::INVALID_HANDLE_VALUE {
+            Err(crate::last_error("failed to open HID"))
+        }
+        else {
+            Ok(HidFile(file))
+        }
+    }
+
+    fn write(&self, report: &[u8]) -> Result<(), Error> {
+        let mut n = 0;
+        if unsafe {
+            fileapi::WriteFile(
+                self.0,
+                report.as_ptr() as minwindef::LPCVOID,
+                report.len() as minwindef::DWORD,
+                &mut n,
+                ptr::null_mut(),
+            )
+        } == 0
+        {
+            Err(crate::last_error("failed to write HID output report"))
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    /// Reads the input report with the given identifier.
+    fn read_input_report(&self, id: u8, size: usize) -> Result<Vec<u8>, Error> {
+        let mut report = vec![0u8; size];
+        report[0] = id;
+        if unsafe {
+            hidsdi::HidD_GetInputReport(
+                self.0,
+                report.as_mut_ptr() as ntdef::PVOID,
+                size as minwindef::ULONG,
+            )
+        } == 0
+        {
+            Err(crate::last_error("failed to read HID input report"))
+        }
+        else {
+            Ok(report)
+        }
+    }
+}
+
+impl Drop for HidFile {
+    fn drop(&mut self) {
+        unsafe {
+            handleapi::CloseHandle(self.0);
+        }
+    }
+}
+
+fn value_usage(capabilities: &hidpi::HIDP_VALUE_CAPS) -> hidusage::USAGE {
+    unsafe {
+        if capabilities.IsRange != 0 {
+            capabilities.u.Range().UsageMin
+        }
+        else {
+            capabilities.u.NotRange().Usage
+        }
+    }
+}
+
+fn button_usages(
+    capabilities: &hidpi::HIDP_BUTTON_CAPS,
+) -> std::ops::RangeInclusive<hidusage::USAGE> {
+    unsafe {
+        if capabilities.IsRange != 0 {
+            let range = capabilities.u.Range();
+            range.UsageMin..=range.UsageMax
+        }
+        else {
+            let usage = capabilities.u.NotRange().Usage;
+            usage..=usage
+        }
+    }
+}
+
+/// Queries the battery of a HID.
+///
+/// The level is read from the battery strength of generic device controls or
+/// the absolute state of charge of a battery system and the state is read
+/// from the charging usage of a battery system. Reports are requested from
+/// the device, so this blocks until the device responds.
+pub fn hid_battery(device: ntdef::HANDLE) -> Result<Battery, Error> {
+    let mut data = preparsed_data(device)?;
+    let capabilities = hid_capabilities(&mut data)?;
+    let level = hid_value_capabilities(&capabilities, &mut data)?
+        .into_iter()
+        .find(|value| {
+            matches!(
+                (value.UsagePage, value_usage(value)),
+                (
+                    HID_USAGE_PAGE_GENERIC_DEVICE,
+                    HID_USAGE_GENERIC_DEVICE_BATTERY_STRENGTH
+                ) | (
+                    HID_USAGE_PAGE_BATTERY_SYSTEM,
+                    HID_USAGE_BATTERY_SYSTEM_ABSOLUTE_STATE_OF_CHARGE
+                )
+            )
+        });
+    let charging = hid_button_capabilities(&capabilities, &mut data)?
+        .into_iter()
+        .find(|button| {
+            button.UsagePage == HID_USAGE_PAGE_BATTERY_SYSTEM
+                && button_usages(button).contains(&HID_USAGE_BATTERY_SYSTEM_CHARGING)
+        });
+    if level.is_none() && charging.is_none() {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            "device does not report a battery",
+        ));
+    }
+    let file = HidFile::open(device, winnt::GENERIC_READ)?;
+    let size = capabilities.InputReportByteLength as usize;
+    let level = match level {
+        Some(value) => {
+            let mut report = file.read_input_report(value.ReportID, size)?;
+            let mut raw = 0;
+            let status = unsafe {
+                hidpi::HidP_GetUsageValue(
+                    hidpi::HidP_Input,
+                    value.UsagePage,
+                    0,
+                    value_usage(&value),
+                    &mut raw,
+                    data.as_mut_ptr(),
+                    report.as_mut_ptr() as ntdef::PCHAR,
+                    size as minwindef::ULONG,
+                )
+            };
+            if status != hidpi::HIDP_STATUS_SUCCESS {
+                return Err(Error::with_code(status, "failed to read HID battery level"));
+            }
+            if value.LogicalMax > value.LogicalMin {
+                let (min, max) = (f64::from(value.LogicalMin), f64::from(value.LogicalMax));
+                Some(((f64::from(raw) - min) / (max - min)).clamp(0.0, 1.0))
+            }
+            else {
+                None
+            }
+        }
+        None => None,
+    };
+    let state = match charging {
+        Some(button) => {
+            let mut report = file.read_input_report(button.ReportID, size)?;
+            let mut n = unsafe {
+                hidpi::HidP_MaxUsageListLength(
+                    hidpi::HidP_Input,
+                    HID_USAGE_PAGE_BATTERY_SYSTEM,
+                    data.as_mut_ptr(),
+                )
+            };
+            let mut usages = Vec::with_capacity(n as usize);
+            let status = unsafe {
+                hidpi::HidP_GetUsages(
+                    hidpi::HidP_Input,
+                    HID_USAGE_PAGE_BATTERY_SYSTEM,
+                    0,
+                    usages.as_mut_ptr(),
+                    &mut n,
+                    data.as_mut_ptr(),
+                    report.as_mut_ptr() as ntdef::PCHAR,
+                    size as minwindef::ULONG,
+                )
+            };
+            if status != hidpi::HIDP_STATUS_SUCCESS {
+                return Err(Error::with_code(status, "failed to read HID battery state"));
+            }
+            unsafe {
+                usages.set_len((n as usize).min(usages.capacity()));
+            }
+            Some(if usages.contains(&HID_USAGE_BATTERY_SYSTEM_CHARGING) {
+                BatteryState::Charging
+            }
+            else {
+                BatteryState::Discharging
+            })
+        }
+        None => None,
+    };
+    Ok(Battery { level, state })
+}
+
+fn led_usage(led: Led) -> hidusage::USAGE {
+    match led {
+        Led::NumLock => hidusage::HID_USAGE_LED_NUM_LOCK,
+        Led::CapsLock => hidusage::HID_USAGE_LED_CAPS_LOCK,
+        Led::ScrollLock => hidusage::HID_USAGE_LED_SCROLL_LOCK,
+        Led::Compose => hidusage::HID_USAGE_LED_COMPOSE,
+        Led::Kana => hidusage::HID_USAGE_LED_KANA,
+    }
+}
+
+/// Gets the output button capabilities of a HID on the LED usage page.
+fn hid_led_capabilities(
+    capabilities: &hidpi::HIDP_CAPS,
+    data: &mut PreparsedData,
+) -> Result<Vec<hidpi::HIDP_BUTTON_CAPS>, Error> {
+    unsafe {
+        let mut n = capabilities.NumberOutputButtonCaps;
+        let mut buttons = Vec::with_capacity(n as usize);
+        let status = hidpi::HidP_GetButtonCaps(
+            hidpi::HidP_Output,
+            buttons.as_mut_ptr(),
+            &mut n,
+            data.as_mut_ptr(),
+        );
+        if status == hidpi::HIDP_STATUS_SUCCESS {
+            buttons.set_len((n as usize).min(buttons.capacity()));
+            buttons.retain(|button| button.UsagePage == hidusage::HID_USAGE_PAGE_LED);
+            Ok(buttons)
+        }
+        else {
+            Err(Error::with_code(
+                status,
+                "failed to read HID output capabilities",
+            ))
+        }
+    }
+}
+
+pub fn hid_has_led(device: ntdef::HANDLE, led: Led) -> bool {
+    preparsed_data(device)
+        .and_then(|mut data| {
+            let capabilities = hid_capabilities(&mut data)?;
+            hid_led_capabilities(&capabilities, &mut data)
+        })
+        .map(|buttons| {
+            buttons
+                .iter()
+                .any(|button| button_usages(button).contains(&led_usage(led)))
+        })
+        .unwrap_or(false)
+}
+
+/// Sets an LED of a HID.
+///
+/// LEDs in the same report as `led` are set according to the lock state of
+/// the keyboard, because the current state of output reports cannot be read.
+pub fn hid_set_led(device: ntdef::HANDLE, led: Led, on: bool) -> Result<(), Error> {
+    let mut data = preparsed_data(device)?;
+    let capabilities = hid_capabilities(&mut data)?;
+    let buttons = hid_led_capabilities(&capabilities, &mut data)?;
+    let id = buttons
+        .iter()
+        .find(|button| button_usages(button).contains(&led_usage(led)))
+        .map(|button| button.ReportID)
+        .ok_or_else(|| Error::new(ErrorKind::Unsupported, "device does not have LED"))?;
+    let toggled = |key: raw::c_int| unsafe { winuser::GetKeyState(key) & 1 != 0 };
+    let mut usages = [
+        (Led::NumLock, toggled(winuser::VK_NUMLOCK)),
+        (Led::CapsLock, toggled(winuser::VK_CAPITAL)),
+        (Led::ScrollLock, toggled(winuser::VK_SCROLL)),
+        (Led::Compose, false),
+        (Led::Kana, toggled(winuser::VK_KANA)),
+    ]
+    .iter()
+    .map(|&(other, is_on)| (other, if other == led { on } else { is_on }))
+    .map(|(other, is_on)| (led_usage(other), is_on))
+    .filter(|&(usage, is_on)| {
+        is_on
+            && buttons
+                .iter()
+                .any(|button| button.ReportID == id && button_usages(button).contains(&usage))
+    })
+    .map(|(usage, _)| usage)
+    .collect::<Vec<_>>();
+    let mut report = vec![0u8; capabilities.OutputReportByteLength as usize];
+    unsafe {
+        let status = hidpi::HidP_InitializeReportForID(
+            hidpi::HidP_Output,
+            id,
+            data.as_mut_ptr(),
+            report.as_mut_ptr() as ntdef::PCHAR,
+            report.len() as minwindef::ULONG,
+        );
+        if status != hidpi::HIDP_STATUS_SUCCESS {
+            return Err(Error::with_code(status, "failed to initialize HID report"));
+        }
+        if !usages.is_empty() {
+            let mut n = usages.len() as minwindef::ULONG;
+            let status = hidpi::HidP_SetUsages(
+                hidpi::HidP_Output,
+                hidusage::HID_USAGE_PAGE_LED,
+                0,
+                usages.as_mut_ptr(),
+                &mut n,
+                data.as_mut_ptr(),
+                report.as_mut_ptr() as ntdef::PCHAR,
+                report.len() as minwindef::ULONG,
+            );
+            if status != hidpi::HIDP_STATUS_SUCCESS {
+                return Err(Error::with_code(status, "failed to set HID LEDs"));
+            }
+        }
+    }
+    HidFile::open(device, winnt::GENERIC_WRITE)?.write(&report)
+}
+
+/// Writes an output report to a HID.
+///
+/// The report is padded with zeros to the output report length of the
+/// device. Devices that are opened exclusively by the system, such as
+/// keyboards, may refuse output.
+pub fn hid_write_output_report(device: ntdef::HANDLE, report: &[u8]) -> Result<(), Error> {
+    let mut data = preparsed_data(device)?;
+    let size = hid_capabilities(&mut data)?.OutputReportByteLength as usize;
+    if size == 0 {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            "device has no output reports",
+        ));
+    }
+    if report.is_empty() || report.len() > size {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "output report length is invalid for device",
+        ));
     }
+    let mut padded = vec![0u8; size];
+    padded[..report.len()].copy_from_slice(report);
+    HidFile::open(device, winnt::GENERIC_WRITE)?.write(&padded)
 }
@@ -0,0 +1,214 @@
+use gaudium_core::error::{Error, ErrorKind};
+use gaudium_core::event::{ApplicationEvent, Event};
+use gaudium_core::platform;
+use gaudium_core::reactor::ThreadContext;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::ptr;
+use std::slice;
+use std::thread;
+use std::time::{Duration, Instant};
+use winapi::shared::{basetsd, minwindef, ntdef, windef, winerror};
+use winapi::um::{commctrl, errhandlingapi, handleapi, libloaderapi, synchapi, winnt, winuser};
+
+use crate::{reactor, WideNullTerminated};
+
+const INSTANCE_SUBCLASS_ID: basetsd::UINT_PTR = 0;
+
+/// Identifies `WM_COPYDATA` messages that carry forwarded arguments.
+const ARGUMENTS_TAG: basetsd::ULONG_PTR = 0x4741_5544;
+
+/// The duration that the owner is given to receive forwarded arguments, in
+/// milliseconds.
+const FORWARD_TIMEOUT: minwindef::UINT = 5000;
+
+/// The number of attempts made to find the window of the owner.
+///
+/// The owner creates its window after it creates its mutex, so the window may
+/// not yet exist when another instance is launched.
+const FIND_ATTEMPTS: usize = 50;
+const FIND_INTERVAL: Duration = Duration::from_millis(20);
+
+type Arguments = VecDeque<Vec<OsString>>;
+
+/// Owns a name via a named mutex.
+///
+/// The owner creates a hidden message-only window that is titled with the
+/// name. Other instances find this window and forward their arguments to it
+/// via `WM_COPYDATA`.
+pub struct SingleInstance {
+    mutex: winnt::HANDLE,
+    window: windef::HWND,
+    // Arguments that have been forwarded but not yet taken. This is shared
+    // with the window procedure.
+    arguments: *mut Arguments,
+}
+
+impl platform::SingleInstance for SingleInstance {
+    fn acquire(
+        _: &ThreadContext,
+        name: &str,
+        arguments: Vec<OsString>,
+    ) -> Result<Option<Self>, Error> {
+        // Backslashes are reserved in the names of kernel objects.
+        let name = name.replace('\\', "/");
+        let title = format!("gaudium-instance:{}", name).wide_null_terminated();
+        unsafe {
+            let mutex = synchapi::CreateMutexW(
+                ptr::null_mut(),
+                minwindef::FALSE,
+                format!("Local\\gaudium-instance:{}", name)
+                    .wide_null_terminated()
+                    .as_ptr(),
+            );
+            if mutex.is_null() {
+                return Err(crate::last_error("failed to create instance mutex"));
+            }
+            if errhandlingapi::GetLastError() == winerror::ERROR_ALREADY_EXISTS {
+                handleapi::CloseHandle(mutex);
+                forward(&title, arguments)?;
+                return Ok(None);
+            }
+            let window = winuser::CreateWindowExW(
+                0,
+                "STATIC".wide_null_terminated().as_ptr(),
+                title.as_ptr(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                winuser::HWND_MESSAGE,
+                ptr::null_mut(),
+                libloaderapi::GetModuleHandleW(ptr::null()),
+                ptr::null_mut(),
+            );
+            if window.is_null() {
+                let error = crate::last_error("failed to create instance window");
+                handleapi::CloseHandle(mutex);
+                return Err(error);
+            }
+            let arguments = Box::into_raw(Box::new(Arguments::new()));
+            if commctrl::SetWindowSubclass(
+                window,
+                Some(procedure),
+                INSTANCE_SUBCLASS_ID,
+                arguments as basetsd::DWORD_PTR,
+            ) == 0
+            {
+                let error = crate::last_error("failed to subclass instance window");
+                winuser::DestroyWindow(window);
+                drop(Box::from_raw(arguments));
+                handleapi::CloseHandle(mutex);
+                return Err(error);
+            }
+            Ok(Some(SingleInstance {
+                mutex,
+                window,
+                arguments,
+            }))
+        }
+    }
+
+    fn take_arguments(&mut self) -> Option<Vec<OsString>> {
+        unsafe { (*self.arguments).pop_front() }
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        unsafe {
+            commctrl::RemoveWindowSubclass(self.window, Some(procedure), INSTANCE_SUBCLASS_ID);
+            winuser::DestroyWindow(self.window);
+            drop(Box::from_raw(self.arguments));
+            handleapi::CloseHandle(self.mutex);
+        }
+    }
+}
+
+/// Forwards arguments to the window of the owner.
+///
+/// Each argument is terminated by a null character.
+unsafe fn forward(title: &[ntdef::WCHAR], arguments: Vec<OsString>) -> Result<(), Error> {
+    let mut data = Vec::new();
+    for argument in arguments {
+        data.extend(argument.encode_wide());
+        data.push(0);
+    }
+    let class = "STATIC".wide_null_terminated();
+    let window = (0..FIND_ATTEMPTS)
+        .find_map(|attempt| {
+            if attempt > 0 {
+                thread::sleep(FIND_INTERVAL);
+            }
+            let window = winuser::FindWindowExW(
+                winuser::HWND_MESSAGE,
+                ptr::null_mut(),
+                class.as_ptr(),
+                title.as_ptr(),
+            );
+            if window.is_null() {
+                None
+            }
+            else {
+                Some(window)
+            }
+        })
+        .ok_or_else(|| Error::new(ErrorKind::InvalidState, "instance owner not found"))?;
+    let copy = winuser::COPYDATASTRUCT {
+        dwData: ARGUMENTS_TAG,
+        cbData: (data.len() * 2) as minwindef::DWORD,
+        lpData: data.as_mut_ptr() as minwindef::LPVOID,
+    };
+    let mut result = 0;
+    if winuser::SendMessageTimeoutW(
+        window,
+        winuser::WM_COPYDATA,
+        0,
+        &copy as *const winuser::COPYDATASTRUCT as minwindef::LPARAM,
+        winuser::SMTO_ABORTIFHUNG,
+        FORWARD_TIMEOUT,
+        &mut result,
+    ) == 0
+    {
+        Err(crate::last_error("failed to forward arguments to instance"))
+    }
+    else {
+        Ok(())
+    }
+}
+
+unsafe extern "system" fn procedure(
+    window: windef::HWND,
+    message: minwindef::UINT,
+    wparam: minwindef::WPARAM,
+    lparam: minwindef::LPARAM,
+    _: basetsd::UINT_PTR,
+    arguments: basetsd::DWORD_PTR,
+) -> minwindef::LRESULT {
+    if message == winuser::WM_COPYDATA {
+        let copy = &*(lparam as *const winuser::COPYDATASTRUCT);
+        if copy.dwData == ARGUMENTS_TAG {
+            let data = if copy.cbData == 0 || copy.lpData.is_null() {
+                &[]
+            }
+            else {
+                slice::from_raw_parts(copy.lpData as *const ntdef::WCHAR, copy.cbData as usize / 2)
+            };
+            // Each argument is terminated, so the final segment is empty.
+            let mut forwarded: Vec<_> = data
+                .split(|&unit| unit == 0)
+                .map(OsString::from_wide)
+                .collect();
+            forwarded.pop();
+            (*(arguments as *mut Arguments)).push_back(forwarded);
+            let _ = reactor::react(Event::Application {
+                event: ApplicationEvent::InstanceLaunched,
+                timestamp: Instant::now(),
+            });
+            return minwindef::TRUE as minwindef::LRESULT;
+        }
+    }
+    commctrl::DefSubclassProc(window, message, wparam, lparam)
+}
@@ -1,18 +1,584 @@
-use gaudium_core::event::{ElementState, InputEvent, ModifierState};
-use winapi::shared::minwindef;
-use winapi::um::winuser;
+use gaudium_core::event::{
+    ElementState, InputEvent, KeyCode, ModifierState, ScanCode, ToggleState,
+};
+use gaudium_core::platform;
+use gaudium_core::reactor::ThreadContext;
+use lazy_static::lazy_static;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::mem;
+use std::os::raw;
+use winapi::shared::{minwindef, ntdef};
+use winapi::um::{libloaderapi, winnls, winnt, winuser};
 
-pub fn parse_raw_input(input: &winuser::RAWKEYBOARD) -> Result<InputEvent, ()> {
-    // TODO: Map the virtual keycode and modifier state.
-    Ok(InputEvent::KeyboardKeyChanged {
-        scancode: input.MakeCode as u32,
-        keycode: None,
-        state: if input.Flags & winuser::RI_KEY_BREAK as minwindef::USHORT != 0 {
-            ElementState::Released
+use crate::WideNullTerminated;
+
+thread_local! {
+    // Keys that are pressed on each device. Auto-repeat is reported as
+    // additional presses, so this is used to distinguish repeats.
+    static PRESSED: RefCell<HashSet<(usize, ScanCode)>> = RefCell::new(HashSet::new());
+    // The most recently observed keyboard layout.
+    static LAYOUT: Cell<usize> = Cell::new(unsafe { winuser::GetKeyboardLayout(0) } as usize);
+    // The dead key that is pending composition with the next key, if any.
+    static COMPOSER: RefCell<Composer> = RefCell::new(Composer::default());
+    // The leading surrogate of the character received via `WM_CHAR`, if any.
+    static SURROGATE: Cell<Option<u16>> = const { Cell::new(None) };
+}
+
+lazy_static! {
+    /// Whether or not `ToUnicodeEx` supports `TO_UNICODE_NO_STATE_CHANGE`.
+    static ref IS_TRANSLATION_STATELESS: bool =
+        unsafe { build_number() }.is_some_and(|build| build >= 14393);
+}
+
+/// Instructs `ToUnicodeEx` not to change the keyboard state of the kernel.
+///
+/// This is supported by Windows 10 version 1607 (build 14393) and later.
+/// Earlier versions ignore the flag and change the dead key state, so keys are
+/// only translated if it is supported. See `is_translation_stateless`.
+const TO_UNICODE_NO_STATE_CHANGE: minwindef::UINT = 0x4;
+
+/// Combining characters for the spacing characters that layouts report for
+/// dead keys.
+const DIACRITICS: &[(char, char)] = &[
+    ('`', '\u{300}'),
+    ('\u{B4}', '\u{301}'),
+    ('\'', '\u{301}'),
+    ('^', '\u{302}'),
+    ('~', '\u{303}'),
+    ('\u{AF}', '\u{304}'),
+    ('\u{2D8}', '\u{306}'),
+    ('\u{2D9}', '\u{307}'),
+    ('\u{A8}', '\u{308}'),
+    ('"', '\u{308}'),
+    ('\u{B0}', '\u{30A}'),
+    ('\u{2DA}', '\u{30A}'),
+    ('\u{2DD}', '\u{30B}'),
+    ('\u{2C7}', '\u{30C}'),
+    ('\u{B8}', '\u{327}'),
+    ('\u{2DB}', '\u{328}'),
+];
+
+/// The translation of a key by a keyboard layout.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Translation {
+    /// The key produces no characters, such as a modifier key.
+    None,
+    /// The key is a dead key, which modifies the following key. The payload
+    /// is the spacing character reported for the dead key.
+    Dead(char),
+    Text(Vec<char>),
+}
+
+/// Translates key presses into text, composing dead keys.
+///
+/// `ToUnicodeEx` stores dead keys in the kernel, where they are shared with
+/// `TranslateMessage`. Translating a key normally consumes or replaces a
+/// pending dead key, which would clobber the text of `WM_CHAR`, so keys are
+/// translated without changing the state of the kernel and dead keys are
+/// composed here instead.
+#[derive(Debug, Default)]
+struct Composer {
+    dead: Option<char>,
+}
+
+impl Composer {
+    /// Gets the characters entered by a translated key.
+    fn compose(&mut self, translation: Translation) -> Vec<char> {
+        match translation {
+            Translation::None => vec![],
+            // Dead keys that are pressed in succession are not composed.
+            Translation::Dead(dead) => match self.dead.take() {
+                Some(pending) => vec![pending, dead],
+                None => {
+                    self.dead = Some(dead);
+                    vec![]
+                }
+            },
+            Translation::Text(text) => {
+                // Control characters, such as escape, cancel any dead key.
+                let text = text
+                    .into_iter()
+                    .filter(|character| !character.is_control())
+                    .collect::<Vec<_>>();
+                match (self.dead.take(), text.split_first()) {
+                    (_, None) => vec![],
+                    // Space enters the spacing character of a dead key.
+                    (Some(dead), Some((&' ', []))) => vec![dead],
+                    (Some(dead), Some((&base, rest))) => match compose(dead, base) {
+                        Some(composed) => {
+                            let mut composed = vec![composed];
+                            composed.extend_from_slice(rest);
+                            composed
+                        }
+                        None => {
+                            let mut uncomposed = vec![dead];
+                            uncomposed.extend_from_slice(&text);
+                            uncomposed
+                        }
+                    },
+                    (None, Some(_)) => text,
+                }
+            }
         }
-        else {
-            ElementState::Pressed
+    }
+}
+
+/// Composes a base character with the diacritic of a dead key.
+///
+/// Returns `None` if there is no precomposed character.
+fn compose(dead: char, base: char) -> Option<char> {
+    let diacritic = DIACRITICS
+        .iter()
+        .find(|&&(spacing, _)| spacing == dead)
+        .map(|&(_, diacritic)| diacritic)?;
+    let mut decomposed = [0u16; 4];
+    let len = base.encode_utf16(&mut decomposed).len();
+    diacritic.encode_utf16(&mut decomposed[len..]);
+    let len = len + diacritic.len_utf16();
+    let mut composed = [0u16; 4];
+    let count = unsafe {
+        winnls::NormalizeString(
+            winnls::NormalizationC,
+            decomposed.as_ptr(),
+            len as raw::c_int,
+            composed.as_mut_ptr(),
+            composed.len() as raw::c_int,
+        )
+    };
+    if count <= 0 {
+        return None;
+    }
+    let mut characters = char::decode_utf16(composed[..count as usize].iter().cloned());
+    match (characters.next(), characters.next()) {
+        (Some(Ok(composed)), None) => Some(composed),
+        _ => None,
+    }
+}
+
+/// Translates a key using the given keyboard state and layout without
+/// changing the keyboard state of the kernel.
+fn translate(
+    key: minwindef::UINT,
+    scancode: minwindef::UINT,
+    state: &[minwindef::BYTE; 256],
+    layout: minwindef::HKL,
+) -> Translation {
+    let mut text = [0u16; 8];
+    let count = unsafe {
+        winuser::ToUnicodeEx(
+            key,
+            scancode,
+            state.as_ptr(),
+            text.as_mut_ptr(),
+            text.len() as raw::c_int,
+            TO_UNICODE_NO_STATE_CHANGE,
+            layout,
+        )
+    };
+    let decode = |count: usize| {
+        char::decode_utf16(text[..count].iter().cloned())
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>()
+    };
+    match count {
+        // The spacing character of a dead key is written to the buffer.
+        count if count < 0 => match decode(1).first() {
+            Some(&dead) => Translation::Dead(dead),
+            None => Translation::None,
+        },
+        0 => Translation::None,
+        count => Translation::Text(decode((count as usize).min(text.len()))),
+    }
+}
+
+/// Gets the build number of the operating system.
+///
+/// `GetVersionEx` reports the version for which the application is manifested,
+/// so `RtlGetVersion` is used instead.
+unsafe fn build_number() -> Option<minwindef::DWORD> {
+    type RtlGetVersion = unsafe extern "system" fn(*mut winnt::OSVERSIONINFOW) -> ntdef::NTSTATUS;
+
+    let module = libloaderapi::GetModuleHandleW("ntdll.dll".wide_null_terminated().as_ptr());
+    if module.is_null() {
+        return None;
+    }
+    let function =
+        libloaderapi::GetProcAddress(module, "RtlGetVersion\0".as_ptr() as ntdef::LPCSTR);
+    if function.is_null() {
+        return None;
+    }
+    let function = mem::transmute::<minwindef::FARPROC, RtlGetVersion>(function);
+    let mut version = mem::zeroed::<winnt::OSVERSIONINFOW>();
+    version.dwOSVersionInfoSize = mem::size_of::<winnt::OSVERSIONINFOW>() as minwindef::DWORD;
+    if function(&mut version) == 0 {
+        Some(version.dwBuildNumber)
+    }
+    else {
+        None
+    }
+}
+
+/// Gets whether or not text is translated from key presses.
+///
+/// If `ToUnicodeEx` cannot translate keys without changing the dead key state
+/// of the kernel, then text is instead received via `WM_CHAR`, which is
+/// composed by `TranslateMessage`.
+pub fn is_translation_stateless() -> bool {
+    *IS_TRANSLATION_STATELESS
+}
+
+/// Gets the characters entered by a key press in the calling thread.
+///
+/// This is used when a window receives `WM_KEYDOWN` or `WM_SYSKEYDOWN`, so the
+/// keyboard state and layout of the calling thread reflect the key press. Key
+/// presses with Alt held do not enter text unless Ctrl is also held, which is
+/// how AltGr is reported.
+pub fn received_characters(wparam: minwindef::WPARAM, lparam: minwindef::LPARAM) -> Vec<char> {
+    let mut state = [0; 256];
+    if unsafe { winuser::GetKeyboardState(state.as_mut_ptr()) } == 0 {
+        return vec![];
+    }
+    let is_pressed = |key: raw::c_int| state[key as usize] & 0x80 != 0;
+    if is_pressed(winuser::VK_MENU) && !is_pressed(winuser::VK_CONTROL) {
+        return vec![];
+    }
+    let translation = translate(
+        wparam as minwindef::UINT,
+        ((lparam >> 16) & 0xFF) as minwindef::UINT,
+        &state,
+        unsafe { winuser::GetKeyboardLayout(0) },
+    );
+    COMPOSER.with(|composer| composer.borrow_mut().compose(translation))
+}
+
+/// Gets the character received via `WM_CHAR`, if any.
+///
+/// Characters outside of the basic multilingual plane are received as a pair
+/// of surrogates, so the leading surrogate is retained until the trailing
+/// surrogate is received. Control characters are ignored.
+pub fn received_character(wparam: minwindef::WPARAM) -> Option<char> {
+    let unit = wparam as u16;
+    let units = match SURROGATE.with(|surrogate| surrogate.take()) {
+        Some(leading) => vec![leading, unit],
+        None if (0xD800..0xDC00).contains(&unit) => {
+            SURROGATE.with(|surrogate| surrogate.set(Some(unit)));
+            return None;
+        }
+        None => vec![unit],
+    };
+    char::decode_utf16(units)
+        .filter_map(Result::ok)
+        .find(|character| !character.is_control())
+}
+
+/// Updates the most recently observed keyboard layout and gets whether or not
+/// it has changed.
+///
+/// Layout changes are reported to all top-level windows, but should only be
+/// dispatched once.
+pub fn update_layout(layout: minwindef::HKL) -> bool {
+    LAYOUT.with(|previous| previous.replace(layout as usize) != layout as usize)
+}
+
+pub fn parse_raw_input(
+    device: ntdef::HANDLE,
+    input: &winuser::RAWKEYBOARD,
+) -> Result<InputEvent, ()> {
+    let scancode = input.MakeCode as ScanCode;
+    let state = if input.Flags & winuser::RI_KEY_BREAK as minwindef::USHORT != 0 {
+        ElementState::Released
+    }
+    else {
+        ElementState::Pressed
+    };
+    let key = (device as usize, scancode);
+    let repeat = PRESSED.with(|pressed| {
+        let mut pressed = pressed.borrow_mut();
+        match state {
+            ElementState::Pressed => !pressed.insert(key),
+            ElementState::Released => {
+                pressed.remove(&key);
+                false
+            }
+        }
+    });
+    Ok(InputEvent::KeyboardKeyChanged {
+        scancode,
+        keycode: keycode(
+            input.VKey as raw::c_int,
+            scancode,
+            input.Flags & winuser::RI_KEY_E0 as minwindef::USHORT != 0,
+        ),
+        state,
+        modifier: ModifierState {
+            toggles: {
+                let mut toggles = toggles();
+                // Raw Input is received before the key message that updates
+                // the key state of the thread, so a toggle key that has just
+                // been pressed has not yet flipped its toggle.
+                if let (ElementState::Pressed, false) = (state, repeat) {
+                    match input.VKey as raw::c_int {
+                        winuser::VK_CAPITAL => toggles.caps_lock = !toggles.caps_lock,
+                        winuser::VK_NUMLOCK => toggles.num_lock = !toggles.num_lock,
+                        winuser::VK_SCROLL => toggles.scroll_lock = !toggles.scroll_lock,
+                        _ => {}
+                    }
+                }
+                toggles
+            },
         },
-        modifier: ModifierState {},
+        repeat,
     })
 }
+
+/// Queries the toggle keys via `GetKeyState`.
+///
+/// This reflects the key state of the calling thread as of the most recently
+/// retrieved key message.
+pub fn toggles() -> ToggleState {
+    let is_toggled = |key| unsafe { winuser::GetKeyState(key) } & 1 != 0;
+    ToggleState {
+        caps_lock: is_toggled(winuser::VK_CAPITAL),
+        num_lock: is_toggled(winuser::VK_NUMLOCK),
+        scroll_lock: is_toggled(winuser::VK_SCROLL),
+    }
+}
+
+/// Translates a virtual key into a key code.
+///
+/// Virtual keys do not distinguish some keys, so the scan code and whether or
+/// not the scan code is extended (prefixed with `E0`) are also used.
+fn keycode(key: raw::c_int, scancode: ScanCode, extended: bool) -> Option<KeyCode> {
+    use KeyCode::*;
+
+    Some(match key {
+        0x30..=0x39 => return KeyCode::from_name(&format!("Digit{}", key - 0x30)),
+        0x41..=0x5A => return KeyCode::from_name(&char::from(key as u8).to_string()),
+        winuser::VK_F1..=winuser::VK_F24 => {
+            return KeyCode::from_name(&format!("F{}", key - winuser::VK_F1 + 1));
+        }
+        winuser::VK_NUMPAD0..=winuser::VK_NUMPAD9 => {
+            return KeyCode::from_name(&format!("Numpad{}", key - winuser::VK_NUMPAD0));
+        }
+        winuser::VK_ESCAPE => Escape,
+        winuser::VK_TAB => Tab,
+        winuser::VK_CAPITAL => CapsLock,
+        winuser::VK_SPACE => Space,
+        winuser::VK_RETURN if extended => NumpadEnter,
+        winuser::VK_RETURN => Enter,
+        winuser::VK_BACK => Backspace,
+        winuser::VK_INSERT => Insert,
+        winuser::VK_DELETE => Delete,
+        winuser::VK_HOME => Home,
+        winuser::VK_END => End,
+        winuser::VK_PRIOR => PageUp,
+        winuser::VK_NEXT => PageDown,
+        winuser::VK_LEFT => Left,
+        winuser::VK_RIGHT => Right,
+        winuser::VK_UP => Up,
+        winuser::VK_DOWN => Down,
+        winuser::VK_SNAPSHOT => PrintScreen,
+        winuser::VK_SCROLL => ScrollLock,
+        winuser::VK_PAUSE => Pause,
+        winuser::VK_APPS => Menu,
+        // The right shift key is distinguished by its scan code rather than
+        // the `E0` prefix.
+        winuser::VK_SHIFT if scancode == 0x36 => RightShift,
+        winuser::VK_SHIFT | winuser::VK_LSHIFT => LeftShift,
+        winuser::VK_RSHIFT => RightShift,
+        winuser::VK_CONTROL if extended => RightControl,
+        winuser::VK_CONTROL | winuser::VK_LCONTROL => LeftControl,
+        winuser::VK_RCONTROL => RightControl,
+        winuser::VK_MENU if extended => RightAlt,
+        winuser::VK_MENU | winuser::VK_LMENU => LeftAlt,
+        winuser::VK_RMENU => RightAlt,
+        winuser::VK_LWIN => LeftSuper,
+        winuser::VK_RWIN => RightSuper,
+        winuser::VK_NUMLOCK => NumLock,
+        winuser::VK_ADD => NumpadAdd,
+        winuser::VK_SUBTRACT => NumpadSubtract,
+        winuser::VK_MULTIPLY => NumpadMultiply,
+        winuser::VK_DIVIDE => NumpadDivide,
+        winuser::VK_DECIMAL => NumpadDecimal,
+        winuser::VK_OEM_3 => Grave,
+        winuser::VK_OEM_MINUS => Minus,
+        winuser::VK_OEM_PLUS => Equals,
+        winuser::VK_OEM_4 => LeftBracket,
+        winuser::VK_OEM_6 => RightBracket,
+        winuser::VK_OEM_5 => Backslash,
+        winuser::VK_OEM_1 => Semicolon,
+        winuser::VK_OEM_7 => Apostrophe,
+        winuser::VK_OEM_COMMA => Comma,
+        winuser::VK_OEM_PERIOD => Period,
+        winuser::VK_OEM_2 => Slash,
+        winuser::VK_OEM_102 => NonUsBackslash,
+        _ => return None,
+    })
+}
+
+/// Translates a key code into a virtual key.
+///
+/// This is the inverse of `keycode` and ignores the distinction between keys
+/// that share a virtual key, such as the enter keys.
+pub fn virtual_key(target: KeyCode) -> Option<raw::c_int> {
+    (0x01..=0xFE).find(|&key| {
+        keycode(key, 0, false) == Some(target) || keycode(key, 0, true) == Some(target)
+    })
+}
+
+/// The keyboard layout of the calling thread.
+pub struct KeyLayout(minwindef::HKL);
+
+impl platform::KeyLayout for KeyLayout {
+    fn from_context(_: &ThreadContext) -> Self {
+        KeyLayout(unsafe { winuser::GetKeyboardLayout(0) })
+    }
+
+    fn id(&self) -> u64 {
+        self.0 as usize as u64
+    }
+
+    fn language(&self) -> Option<String> {
+        // The low word of a layout handle is its language identifier.
+        let language = (self.0 as usize & 0xFFFF) as ntdef::LANGID;
+        let mut name = [0u16; winnt::LOCALE_NAME_MAX_LENGTH];
+        let len = unsafe {
+            winnls::LCIDToLocaleName(
+                winnt::MAKELCID(language, winnt::SORT_DEFAULT),
+                name.as_mut_ptr(),
+                name.len() as raw::c_int,
+                0,
+            )
+        };
+        // The length includes the terminating null.
+        if len > 1 {
+            Some(String::from_utf16_lossy(&name[..(len - 1) as usize]))
+        }
+        else {
+            None
+        }
+    }
+
+    fn keycode(&self, scancode: ScanCode) -> Option<KeyCode> {
+        let key =
+            unsafe { winuser::MapVirtualKeyExW(scancode, winuser::MAPVK_VSC_TO_VK_EX, self.0) };
+        if key == 0 {
+            None
+        }
+        else {
+            keycode(key as raw::c_int, scancode, false)
+        }
+    }
+
+    fn label(&self, scancode: ScanCode) -> Option<String> {
+        // `GetKeyNameTextW` accepts the scan code in the format of the
+        // `lParam` of `WM_KEYDOWN` and uses the layout of the calling thread.
+        let mut name = [0u16; 64];
+        let len = unsafe {
+            winuser::GetKeyNameTextW(
+                ((scancode & 0xFF) << 16) as ntdef::LONG,
+                name.as_mut_ptr(),
+                name.len() as raw::c_int,
+            )
+        };
+        if len > 0 {
+            Some(String::from_utf16_lossy(&name[..len as usize]))
+        }
+        else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winapi::shared::minwindef;
+    use winapi::um::winuser;
+
+    use crate::keyboard::{self, Composer, Translation};
+    use crate::WideNullTerminated;
+
+    fn load_layout(id: &str) -> minwindef::HKL {
+        let layout = unsafe {
+            winuser::LoadKeyboardLayoutW(
+                id.wide_null_terminated().as_ptr(),
+                winuser::KLF_NOTELLSHELL,
+            )
+        };
+        assert!(!layout.is_null());
+        layout
+    }
+
+    fn press(
+        composer: &mut Composer,
+        layout: minwindef::HKL,
+        key: minwindef::UINT,
+        is_shifted: bool,
+    ) -> Vec<char> {
+        let mut state = [0; 256];
+        state[key as usize] = 0x80;
+        if is_shifted {
+            state[winuser::VK_SHIFT as usize] = 0x80;
+        }
+        let scancode = unsafe { winuser::MapVirtualKeyExW(key, winuser::MAPVK_VK_TO_VSC, layout) };
+        composer.compose(keyboard::translate(key, scancode, &state, layout))
+    }
+
+    #[test]
+    fn compose_dead_keys() {
+        let mut composer = Composer::default();
+        assert!(composer.compose(Translation::Dead('^')).is_empty());
+        assert_eq!(
+            composer.compose(Translation::Text(vec!['a'])),
+            vec!['\u{E2}']
+        );
+        // Characters without a precomposed form are entered separately.
+        assert!(composer.compose(Translation::Dead('^')).is_empty());
+        assert_eq!(
+            composer.compose(Translation::Text(vec!['x'])),
+            vec!['^', 'x']
+        );
+        // Space enters the spacing character.
+        assert!(composer.compose(Translation::Dead('\u{B4}')).is_empty());
+        assert_eq!(
+            composer.compose(Translation::Text(vec![' '])),
+            vec!['\u{B4}']
+        );
+        // Control characters cancel a dead key and are never entered.
+        assert!(composer.compose(Translation::Dead('`')).is_empty());
+        assert!(composer
+            .compose(Translation::Text(vec!['\u{1B}']))
+            .is_empty());
+        assert_eq!(composer.compose(Translation::Text(vec!['e'])), vec!['e']);
+    }
+
+    #[test]
+    fn compose_layouts() {
+        const VK_A: minwindef::UINT = 0x41;
+        const VK_E: minwindef::UINT = 0x45;
+
+        // US-International: the apostrophe is a dead acute accent and the
+        // quotation mark a dead diaeresis.
+        let layout = load_layout("00020409");
+        let mut composer = Composer::default();
+        let quote = winuser::VK_OEM_7 as minwindef::UINT;
+        assert!(press(&mut composer, layout, quote, false).is_empty());
+        assert_eq!(press(&mut composer, layout, VK_E, false), vec!['\u{E9}']);
+        assert!(press(&mut composer, layout, quote, true).is_empty());
+        assert_eq!(press(&mut composer, layout, VK_A, true), vec!['\u{C4}']);
+
+        // German: the circumflex and acute accent keys are dead keys.
+        let layout = load_layout("00000407");
+        let mut composer = Composer::default();
+        let circumflex = winuser::VK_OEM_5 as minwindef::UINT;
+        let acute = winuser::VK_OEM_6 as minwindef::UINT;
+        assert!(press(&mut composer, layout, circumflex, false).is_empty());
+        assert_eq!(press(&mut composer, layout, VK_A, false), vec!['\u{E2}']);
+        assert!(press(&mut composer, layout, acute, false).is_empty());
+        assert_eq!(press(&mut composer, layout, VK_E, false), vec!['\u{E9}']);
+        // Translation does not change the dead key state of the kernel, so
+        // keys that follow are translated as usual.
+        assert_eq!(press(&mut composer, layout, VK_E, false), vec!['e']);
+    }
+}
@@ -1,25 +1,48 @@
 #![cfg(target_os = "windows")]
 
+use gaudium_core::error::{Error, ErrorKind};
 use num::{Integer, Num, One, Zero};
 use std::alloc::{self, Layout};
+use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::marker::PhantomData;
-use std::mem::{self, ManuallyDrop};
-use std::ops::{BitAnd, Deref};
-use std::os::raw;
+use std::mem;
+use std::ops::BitAnd;
 use std::os::windows::ffi::OsStrExt;
-use std::time::Duration;
-use winapi::shared::{minwindef, ntdef};
-use winapi::um::winbase;
-
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+use winapi::shared::{minwindef, ntdef, windef};
+use winapi::um::{errhandlingapi, sysinfoapi, winbase, winuser};
+
+mod accessibility;
+#[cfg(feature = "capture")]
+mod capture;
+mod console;
+mod dialog;
+mod display;
+mod frame;
+mod hotkey;
+#[cfg(feature = "test")]
+mod inject;
 mod input;
+mod instance;
 mod keyboard;
 mod mouse;
+mod pen;
+mod queue;
 mod reactor;
+mod timer;
 mod window;
+pub mod xinput;
 
+use gaudium_core::event::{ToggleState, WheelScroll};
 use gaudium_core::platform::{self, Proxy};
-use gaudium_core::window::WindowBuilder;
+use gaudium_core::reactor::ThreadContext;
+use gaudium_core::window::{SurfaceToken, Window, WindowBuilder};
+
+pub use crate::input::RawInputFilter;
+pub use crate::queue::QueueOverflow;
+pub use crate::reactor::RawMessage;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Binding {}
@@ -28,13 +51,77 @@ impl platform::PlatformBinding for Binding {
     type EventThread = reactor::Entry;
     type WindowBuilder = window::WindowBuilder;
     type Device = empty::Device;
-    type Display = empty::Display;
+    type Display = display::Display;
+    type Waker = reactor::Waker;
+    type KeyLayout = keyboard::KeyLayout;
+    type Dialog = dialog::Dialog;
+    type Hotkey = hotkey::Hotkey;
+    type Timer = timer::Timer;
+    type SingleInstance = instance::SingleInstance;
+    type RawEvent = reactor::RawMessage;
+
+    fn wheel_scroll() -> WheelScroll {
+        let mut lines: minwindef::UINT = 0;
+        let is_queried = unsafe {
+            winuser::SystemParametersInfoW(
+                winuser::SPI_GETWHEELSCROLLLINES,
+                0,
+                &mut lines as *mut minwindef::UINT as minwindef::LPVOID,
+                0,
+            ) != 0
+        };
+        match lines {
+            _ if !is_queried => WheelScroll::default(),
+            winuser::WHEEL_PAGESCROLL => WheelScroll::Page,
+            lines => WheelScroll::Lines(lines),
+        }
+    }
+
+    fn toggles() -> ToggleState {
+        keyboard::toggles()
+    }
 }
 
 pub trait WindowBuilderExt: Sized {
     fn with_title<T>(self, title: T) -> Self
     where
         T: AsRef<str>;
+
+    /// Sets the classes of devices from which raw input is received.
+    fn with_raw_input_filter(self, filter: RawInputFilter) -> Self;
+
+    /// Sets whether or not raw input is received while the window is in the
+    /// background. Defaults to `false`.
+    fn with_background_input(self, background: bool) -> Self;
+
+    /// Sets whether or not the pointer is captured while mouse buttons are
+    /// held. Defaults to `true`.
+    ///
+    /// While the pointer is captured, mouse messages are delivered to the
+    /// window even if the pointer leaves it, such as during a drag.
+    fn with_automatic_pointer_capture(self, automatic: bool) -> Self;
+
+    /// Sets the name of the window class.
+    ///
+    /// Windows are created with a class that is registered by Gaudium. If the
+    /// class is customized, then a separate class is registered with the
+    /// given name. Building the window fails if a class with the same name
+    /// and module is used with a different icon.
+    fn with_class_name<T>(self, name: T) -> Self
+    where
+        T: AsRef<str>;
+
+    /// Sets the icon of the window class to the icon resource with the given
+    /// ordinal in the module of the class.
+    ///
+    /// See `with_instance`.
+    fn with_class_icon_resource(self, icon: minwindef::WORD) -> Self;
+
+    /// Sets the module that registers the window class and owns the window.
+    /// Defaults to the module of the executable.
+    ///
+    /// This is useful when Gaudium is embedded in a DLL.
+    fn with_instance(self, instance: minwindef::HINSTANCE) -> Self;
 }
 
 impl WindowBuilderExt for WindowBuilder<Binding> {
@@ -44,6 +131,177 @@ impl WindowBuilderExt for WindowBuilder<Binding> {
     {
         self.map(move |inner| inner.with_title(title))
     }
+
+    fn with_raw_input_filter(self, filter: RawInputFilter) -> Self {
+        self.map(move |inner| inner.with_raw_input_filter(filter))
+    }
+
+    fn with_background_input(self, background: bool) -> Self {
+        self.map(move |inner| inner.with_background_input(background))
+    }
+
+    fn with_automatic_pointer_capture(self, automatic: bool) -> Self {
+        self.map(move |inner| inner.with_automatic_pointer_capture(automatic))
+    }
+
+    fn with_class_name<T>(self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.map(move |inner| inner.with_class_name(name))
+    }
+
+    fn with_class_icon_resource(self, icon: minwindef::WORD) -> Self {
+        self.map(move |inner| inner.with_class_icon_resource(icon))
+    }
+
+    fn with_instance(self, instance: minwindef::HINSTANCE) -> Self {
+        self.map(move |inner| inner.with_instance(instance))
+    }
+}
+
+pub trait WindowExt {
+    /// Re-registers raw input using the given filter.
+    fn set_raw_input_filter(&mut self, filter: RawInputFilter) -> Result<(), Error>;
+
+    /// Sets whether or not raw input is received while the window is in the
+    /// background.
+    fn set_background_input(&mut self, background: bool) -> Result<(), Error>;
+
+    /// Adds a function that observes the messages of the window before they
+    /// are handled.
+    ///
+    /// Hooks are invoked by the window procedure in the order in which they
+    /// were added and before the message is handled by the window. If a hook
+    /// returns a result, then the message is consumed: later hooks and the
+    /// window do not observe it and the result is returned by the window
+    /// procedure. `WM_DESTROY` cannot be consumed.
+    ///
+    /// Hooks must be added on the thread that owns the window and are dropped
+    /// when the window is destroyed. Hooks may be invoked while the reactor is
+    /// reacting, such as when the reactor modifies the window.
+    fn add_message_hook<F>(&mut self, f: F) -> Result<(), Error>
+    where
+        F: 'static
+            + FnMut(
+                windef::HWND,
+                minwindef::UINT,
+                minwindef::WPARAM,
+                minwindef::LPARAM,
+            ) -> Option<minwindef::LRESULT>;
+}
+
+impl WindowExt for Window<Binding> {
+    fn set_raw_input_filter(&mut self, filter: RawInputFilter) -> Result<(), Error> {
+        self.as_inner_mut().set_raw_input_filter(filter)
+    }
+
+    fn set_background_input(&mut self, background: bool) -> Result<(), Error> {
+        self.as_inner_mut().set_background_input(background)
+    }
+
+    fn add_message_hook<F>(&mut self, f: F) -> Result<(), Error>
+    where
+        F: 'static
+            + FnMut(
+                windef::HWND,
+                minwindef::UINT,
+                minwindef::WPARAM,
+                minwindef::LPARAM,
+            ) -> Option<minwindef::LRESULT>,
+    {
+        self.as_inner_mut().add_message_hook(Box::new(f))
+    }
+}
+
+pub trait ThreadContextExt {
+    /// Configures the event queue of the event thread.
+    ///
+    /// The event thread queues events that occur while the reactor is
+    /// reacting. The queue has a fixed capacity, which is at least one, and
+    /// never allocates unless the overflow policy is `QueueOverflow::Grow`.
+    /// When the queue overflows, a `QueueOverflowed` event is dispatched.
+    ///
+    /// This must be called before the reactor is returned to the event
+    /// thread, such as in the function given to
+    /// `EventThread::run_and_abort_with`. By default, the capacity is 256 and
+    /// mouse movement is coalesced.
+    fn set_event_queue(&self, capacity: usize, overflow: QueueOverflow);
+
+    /// Sets whether or not consecutive mouse movement is merged.
+    ///
+    /// When enabled, consecutive `MouseMoved` events from the same device and
+    /// window that occur within a flush are dispatched as a single event with
+    /// accumulated relative motion and the most recent absolute position.
+    /// This limits the rate of events from high polling rate mice. Disable
+    /// this to receive every sample.
+    ///
+    /// This must be called before the reactor is returned to the event
+    /// thread. Defaults to `true`.
+    fn set_mouse_coalescing(&self, is_coalescing: bool);
+
+    /// Sets whether or not `Resized` events are throttled.
+    ///
+    /// When enabled, `Resized` events are deferred until the end of a flush
+    /// and only the most recent dimensions of each window are dispatched.
+    /// While a window is resized interactively, the platform runs its own
+    /// event loop and pending dimensions are instead dispatched periodically
+    /// and when the resize ends. Dimensions are always dispatched before a
+    /// window's `ResizeEnded` and `Closed` events.
+    ///
+    /// This must be called before the reactor is returned to the event
+    /// thread. Defaults to `false`.
+    fn set_resize_throttling(&self, is_throttling: bool);
+
+    /// Enables or disables high-resolution waits with the given tolerance.
+    ///
+    /// By default, `WaitUntil` and `ReadyThrottled` wait with the granularity
+    /// of the system timer, which is typically about 15.6 ms, so the event
+    /// thread may resume well after the requested instant. When enabled, the
+    /// system timer resolution is raised for the lifetime of the event thread
+    /// and the end of each wait is spun, so that `Resumption::Timeout` occurs
+    /// no earlier than the requested instant and typically within the
+    /// tolerance after it. Smaller tolerances spin for longer and consume more
+    /// CPU time. Raising the timer resolution affects the entire system and
+    /// increases power consumption.
+    ///
+    /// This must be called before the reactor is returned to the event
+    /// thread. Defaults to `None` (disabled).
+    fn set_wait_tolerance(&self, tolerance: Option<Duration>);
+
+    /// Enables or disables console control events with the given grace
+    /// period.
+    ///
+    /// When enabled, pressing Ctrl+C or Ctrl+Break in the attached console or
+    /// closing the console dispatches `ApplicationEvent::Terminating`, so the
+    /// reactor can close its windows and abort cleanly. If the event thread is
+    /// still running when the grace period elapses, then the process exits.
+    /// Closing the console terminates the process regardless after a timeout
+    /// determined by the system. Disabled if `None`, which is the default, in
+    /// which case the default behavior of the console applies.
+    fn set_console_control(&self, grace: Option<Duration>) -> Result<(), Error>;
+}
+
+impl ThreadContextExt for ThreadContext {
+    fn set_event_queue(&self, capacity: usize, overflow: QueueOverflow) {
+        queue::configure(capacity, overflow);
+    }
+
+    fn set_mouse_coalescing(&self, is_coalescing: bool) {
+        queue::configure_mouse_coalescing(is_coalescing);
+    }
+
+    fn set_resize_throttling(&self, is_throttling: bool) {
+        queue::configure_resize_throttling(is_throttling);
+    }
+
+    fn set_wait_tolerance(&self, tolerance: Option<Duration>) {
+        reactor::configure_wait_tolerance(tolerance);
+    }
+
+    fn set_console_control(&self, grace: Option<Duration>) -> Result<(), Error> {
+        console::configure(grace)
+    }
 }
 
 trait DwordMilliseconds {
@@ -62,6 +320,51 @@ impl DwordMilliseconds for Duration {
     }
 }
 
+pub trait SurfaceTokenExt {
+    /// Gets the `HWND` of the window.
+    ///
+    /// The handle is invalid once the window has been destroyed. See
+    /// `SurfaceToken::is_alive`.
+    fn hwnd(&self) -> windef::HWND;
+
+    /// Gets the `HINSTANCE` of the module that created the window.
+    fn hinstance(&self) -> minwindef::HINSTANCE;
+}
+
+impl SurfaceTokenExt for SurfaceToken<Binding> {
+    fn hwnd(&self) -> windef::HWND {
+        self.as_inner().hwnd()
+    }
+
+    fn hinstance(&self) -> minwindef::HINSTANCE {
+        self.as_inner().hinstance()
+    }
+}
+
+/// Gets the time at which the most recently retrieved message was posted.
+///
+/// Message times are measured in milliseconds since the system started, so
+/// the elapsed time is subtracted from the current time.
+fn message_timestamp() -> Instant {
+    let now = Instant::now();
+    let elapsed = unsafe {
+        sysinfoapi::GetTickCount().wrapping_sub(winuser::GetMessageTime() as minwindef::DWORD)
+    };
+    now.checked_sub(Duration::from_millis(elapsed.into()))
+        .unwrap_or(now)
+}
+
+/// Gets the last error of the calling thread as reported by `GetLastError`.
+///
+/// This must be called immediately after the failed function, because many
+/// functions overwrite the last error.
+fn last_error<T>(context: T) -> Error
+where
+    T: Into<Cow<'static, str>>,
+{
+    Error::with_code(unsafe { errhandlingapi::GetLastError() } as i32, context)
+}
+
 trait WideNullTerminated: Sized {
     fn wide_null_terminated(self) -> Vec<ntdef::WCHAR>;
 }
@@ -78,45 +381,69 @@ where
     }
 }
 
-struct Buffer<T> {
-    raw: ManuallyDrop<*mut u8>,
+/// A zero-initialized heap allocation with at least the size and alignment of
+/// `T`.
+///
+/// Many Windows APIs write variable-length structures, such as a fixed header
+/// followed by trailing data, into buffers provided by the caller. The size of
+/// these buffers is only known at runtime and may exceed the size of `T`.
+/// Buffers are always deallocated with the same layout with which they were
+/// allocated.
+struct AlignedBuffer<T> {
+    raw: NonNull<u8>,
+    layout: Layout,
     phantom: PhantomData<T>,
 }
 
-impl<T> Buffer<T> {
-    pub fn new() -> Self {
-        let raw = ManuallyDrop::new(unsafe { alloc::alloc(Layout::new::<T>()) });
-        Buffer {
+impl<T> AlignedBuffer<T> {
+    /// Allocates a buffer of the given size in bytes.
+    ///
+    /// The buffer is never smaller than `T` nor empty.
+    pub fn from_size(size: usize) -> Result<Self, Error> {
+        let layout =
+            Layout::from_size_align(size.max(mem::size_of::<T>()).max(1), mem::align_of::<T>())
+                .map_err(|_| Error::new(ErrorKind::InvalidInput, "invalid buffer layout"))?;
+        let raw = NonNull::new(unsafe { alloc::alloc_zeroed(layout) })
+            .ok_or_else(|| Error::new(ErrorKind::Unsupported, "failed to allocate buffer"))?;
+        Ok(AlignedBuffer {
             raw,
+            layout,
             phantom: PhantomData,
-        }
+        })
     }
 
-    pub unsafe fn from_size(size: usize) -> Result<Self, ()> {
-        let raw = ManuallyDrop::new(alloc::alloc(
-            Layout::from_size_align(size, mem::align_of::<T>()).map_err(|_| ())?,
-        ));
-        Ok(Buffer {
-            raw,
-            phantom: PhantomData,
-        })
+    pub fn as_ptr(&self) -> *const T {
+        self.raw.as_ptr() as *const T
     }
 
-    pub fn as_mut_ptr(&mut self) -> *mut raw::c_void {
-        *self.raw.deref() as *mut raw::c_void
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.raw.as_ptr() as *mut T
     }
 
-    pub fn into_box(self) -> Box<T> {
-        let Buffer { raw, .. } = self;
-        let mut raw = ManuallyDrop::into_inner(raw);
-        unsafe { Box::from_raw(raw as *mut T) }
+    /// Gets a reference to the contents of the buffer as a `T`.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must contain a valid `T`. Buffers are zero-initialized, so
+    /// this is always the case for plain data for which zero is valid.
+    pub unsafe fn as_ref(&self) -> &T {
+        &*self.as_ptr()
+    }
+
+    /// Gets a mutable reference to the contents of the buffer as a `T`.
+    ///
+    /// # Safety
+    ///
+    /// See `as_ref`.
+    pub unsafe fn as_mut(&mut self) -> &mut T {
+        &mut *self.as_mut_ptr()
     }
 }
 
-impl<T> Drop for Buffer<T> {
+impl<T> Drop for AlignedBuffer<T> {
     fn drop(&mut self) {
         unsafe {
-            ManuallyDrop::drop(&mut self.raw);
+            alloc::dealloc(self.raw.as_ptr(), self.layout);
         }
     }
 }
@@ -135,8 +462,14 @@ where
 
 // TODO: Implement these types.
 mod empty {
+    use gaudium_core::device::{Battery, DeviceInfo, Led, SensorType, Usage};
+    use gaudium_core::error::{Error, ErrorKind};
     use gaudium_core::platform;
     use winapi::shared::ntdef;
+    use winapi::um::winuser;
+
+    use crate::input::{self, TryFromDeviceInfo};
+    use crate::xinput;
 
     #[derive(Eq, Hash, PartialEq)]
     pub struct Device(ntdef::HANDLE);
@@ -147,29 +480,71 @@ mod empty {
         fn connected() -> Self::Query {
             None
         }
-    }
 
-    impl platform::Handle for Device {
-        type Handle = ntdef::HANDLE;
+        fn info(handle: Self::Handle) -> Option<DeviceInfo> {
+            if let Some(index) = xinput::index(handle) {
+                return Some(xinput::info(index));
+            }
+            let info = input::device_info(handle).ok()?;
+            Some(DeviceInfo {
+                usage: Usage::try_from_device_info(&info),
+                button_count: match info.dwType {
+                    winuser::RIM_TYPEMOUSE => unsafe {
+                        Some(info.u.mouse().dwNumberOfButtons as u16)
+                    },
+                    winuser::RIM_TYPEHID => input::hid_button_total(handle).ok(),
+                    _ => None,
+                },
+                ..input::hid_identity(&info)
+            })
+        }
 
-        fn handle(&self) -> Self::Handle {
-            self.0
+        fn battery(handle: Self::Handle) -> Option<Battery> {
+            if let Some(index) = xinput::index(handle) {
+                return xinput::battery(index);
+            }
+            match input::device_info(handle).ok()?.dwType {
+                winuser::RIM_TYPEHID => input::hid_battery(handle).ok(),
+                _ => None,
+            }
+        }
+
+        // TODO: Parse motion sensors from HID reports, such as those of
+        //       DualShock controllers.
+        fn has_sensor(_: Self::Handle, _: SensorType) -> bool {
+            false
         }
     }
 
-    #[derive(Eq, Hash, PartialEq)]
-    pub struct Display(usize);
+    impl platform::Output for Device {
+        fn has_led(handle: Self::Handle, led: Led) -> bool {
+            xinput::index(handle).is_none() && input::hid_has_led(handle, led)
+        }
 
-    impl platform::Display for Display {
-        type Query = Option<Self>;
+        fn set_led(handle: Self::Handle, led: Led, on: bool) -> Result<(), Error> {
+            if xinput::index(handle).is_some() {
+                return Err(unsupported_output());
+            }
+            input::hid_set_led(handle, led, on)
+        }
 
-        fn connected() -> Self::Query {
-            None
+        fn send_output_report(handle: Self::Handle, report: &[u8]) -> Result<(), Error> {
+            if xinput::index(handle).is_some() {
+                return Err(unsupported_output());
+            }
+            input::hid_write_output_report(handle, report)
         }
     }
 
-    impl platform::Handle for Display {
-        type Handle = usize;
+    fn unsupported_output() -> Error {
+        Error::new(
+            ErrorKind::Unsupported,
+            "XInput controllers do not accept output reports",
+        )
+    }
+
+    impl platform::Handle for Device {
+        type Handle = ntdef::HANDLE;
 
         fn handle(&self) -> Self::Handle {
             self.0
@@ -179,11 +554,36 @@ mod empty {
 
 #[cfg(test)]
 mod tests {
+    use std::mem;
+
+    use crate::AlignedBuffer;
+
+    #[test]
+    fn aligned_buffer_layout() {
+        #[repr(align(16))]
+        struct Aligned([u8; 32]);
+
+        // The buffer is never smaller than `T`, even if a smaller size is
+        // requested.
+        let buffer = AlignedBuffer::<Aligned>::from_size(8).unwrap();
+        assert_eq!(buffer.layout.size(), mem::size_of::<Aligned>());
+        assert_eq!(buffer.as_ptr() as usize % mem::align_of::<Aligned>(), 0);
+        assert!(unsafe { buffer.as_ref() }.0.iter().all(|&byte| byte == 0));
+
+        let buffer = AlignedBuffer::<u32>::from_size(1024).unwrap();
+        assert_eq!(buffer.layout.size(), 1024);
+        assert_eq!(buffer.as_ptr() as usize % mem::align_of::<u32>(), 0);
+
+        // Zero-sized types still allocate.
+        let buffer = AlignedBuffer::<()>::from_size(0).unwrap();
+        assert_eq!(buffer.layout.size(), 1);
+    }
+
     #[test]
     fn test() {
         use gaudium_core::prelude::*;
-        use gaudium_core::reactor::{FromContext, Reactor, ThreadContext};
-        use gaudium_core::window::{Window, WindowBuilder, WindowHandle};
+        use gaudium_core::reactor::{FromContext, Reactor, Sinks, ThreadContext};
+        use gaudium_core::window::{Window, WindowBuilder};
         use std::sync::mpsc::{self, Sender};
         use std::thread::{self, JoinHandle};
 
@@ -196,7 +596,7 @@ mod tests {
         }
 
         impl FromContext<Binding> for TestReactor {
-            fn from_context(context: &ThreadContext) -> (WindowHandle<Binding>, Self) {
+            fn from_context(context: &ThreadContext) -> (Sinks<Binding>, Self) {
                 let window = WindowBuilder::<Binding>::default()
                     .build(context)
                     .expect("");
@@ -206,7 +606,7 @@ mod tests {
                         println!("{:?}", event);
                     }
                 });
-                (window.handle(), TestReactor { window, tx, handle })
+                (window.handle().into(), TestReactor { window, tx, handle })
             }
         }
 
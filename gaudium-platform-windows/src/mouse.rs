@@ -1,23 +1,44 @@
-use gaudium_core::display::IntoLogical;
+use gaudium_core::display::{IntoLogical, PhysicalPosition};
 use gaudium_core::event::{
     ElementState, InputEvent, ModifierState, MouseButton, MouseMovement, MouseWheelDelta,
 };
+use gaudium_core::window::MouseMode;
 use smallvec::SmallVec;
+use std::cell::Cell;
 use std::mem::MaybeUninit;
 use winapi::shared::{minwindef, ntdef, windef};
 use winapi::um::winuser;
 
+use crate::keyboard;
+
 const EVENT_BUFFER_SIZE: usize = 8;
 
+/// The maximum duration in milliseconds between wheel rotation reported via
+/// Raw Input and the corresponding wheel message.
+const WHEEL_MESSAGE_INTERVAL: ntdef::LONG = 100;
+
+thread_local! {
+    // Wheel rotation reported via Raw Input that has not yet been matched with
+    // a wheel message and the message time of the most recent rotation, for
+    // the vertical and horizontal axes.
+    static RAW_WHEEL: Cell<[(u32, ntdef::LONG); 2]> = const { Cell::new([(0, 0); 2]) };
+}
+
+// This flag is not exposed by `winapi`.
+const RI_MOUSE_HWHEEL: minwindef::USHORT = 0x0800;
+
 type InputEventBuffer = SmallVec<[InputEvent; EVENT_BUFFER_SIZE]>;
 
 pub fn parse_raw_input(
     _: windef::HWND,
     input: &winuser::RAWMOUSE,
+    mode: MouseMode,
 ) -> Result<impl AsRef<[InputEvent]> + IntoIterator<Item = InputEvent>, ()> {
-    let modifier = ModifierState {}; // TODO: Read modifiers.
+    let modifier = ModifierState {
+        toggles: keyboard::toggles(),
+    };
     let mut events = InputEventBuffer::new();
-    if let Ok(event) = parse_movement(input, modifier) {
+    if let Ok(event) = parse_movement(input, mode, modifier) {
         events.push(event);
     }
     if let Ok(event) = parse_wheel(input, modifier) {
@@ -27,14 +48,22 @@ pub fn parse_raw_input(
     Ok(events)
 }
 
-fn parse_movement(input: &winuser::RAWMOUSE, modifier: ModifierState) -> Result<InputEvent, ()> {
+fn parse_movement(
+    input: &winuser::RAWMOUSE,
+    mode: MouseMode,
+    modifier: ModifierState,
+) -> Result<InputEvent, ()> {
     let mut point = MaybeUninit::<windef::POINT>::uninit();
     let event = InputEvent::MouseMoved {
         movement: MouseMovement {
-            absolute: if unsafe { winuser::GetCursorPos(point.as_mut_ptr()) != 0 } {
+            // Absolute positions are meaningless when the cursor is hidden and
+            // confined, so they are not reported in relative mode.
+            absolute: if mode == MouseMode::Absolute
+                && unsafe { winuser::GetCursorPos(point.as_mut_ptr()) != 0 }
+            {
                 let dpi = 1.0; // TODO: Get the DPI factor.
                 let point = unsafe { point.assume_init() };
-                Some((point.x as i32, point.y as i32).into_logical(dpi))
+                Some(PhysicalPosition::new(point.x, point.y).into_logical(dpi))
             }
             else {
                 None
@@ -42,7 +71,7 @@ fn parse_movement(input: &winuser::RAWMOUSE, modifier: ModifierState) -> Result<
             // The `MOUSE_MOVE_RELATIVE` flag is typically set. If not, then
             // absolute motion events will be queued for each Raw Input event.
             relative: if crate::has_bit_flags(input.usFlags, winuser::MOUSE_MOVE_RELATIVE) {
-                Some((input.lLastX.into(), input.lLastY.into()))
+                Some((input.lLastX, input.lLastY).into())
             }
             else {
                 None
@@ -54,11 +83,11 @@ fn parse_movement(input: &winuser::RAWMOUSE, modifier: ModifierState) -> Result<
         InputEvent::MouseMoved {
             movement:
                 MouseMovement {
-                    relative: Some((x, y)),
+                    relative: Some(relative),
                     ..
                 },
             ..
-        } if x != 0.0.into() || y != 0.0.into() => Some(event),
+        } if !relative.is_zero() => Some(event),
         InputEvent::MouseMoved {
             movement: MouseMovement { relative: None, .. },
             ..
@@ -73,18 +102,70 @@ fn parse_movement(input: &winuser::RAWMOUSE, modifier: ModifierState) -> Result<
 }
 
 fn parse_wheel(input: &winuser::RAWMOUSE, modifier: ModifierState) -> Result<InputEvent, ()> {
-    if crate::has_bit_flags(input.usButtonFlags, winuser::RI_MOUSE_WHEEL) {
-        Ok(InputEvent::MouseWheelRotated {
-            delta: MouseWheelDelta::Rotational(
-                0.0,
-                ((input.usButtonData as ntdef::SHORT) / winuser::WHEEL_DELTA) as f64,
-            ),
-            modifier,
-        })
+    // Rotation is reported in multiples (or fractions for high-resolution
+    // wheels) of `WHEEL_DELTA`, which represents one detent.
+    let delta = (input.usButtonData as ntdef::SHORT) as f64 / winuser::WHEEL_DELTA as f64;
+    let (delta, axis) = if crate::has_bit_flags(input.usButtonFlags, winuser::RI_MOUSE_WHEEL) {
+        ((0.0, delta), 0)
+    }
+    else if crate::has_bit_flags(input.usButtonFlags, RI_MOUSE_HWHEEL) {
+        ((delta, 0.0), 1)
     }
     else {
-        Err(())
+        return Err(());
+    };
+    // The system also delivers a wheel message for this rotation.
+    let time = unsafe { winuser::GetMessageTime() };
+    RAW_WHEEL.with(|wheel| {
+        let mut pending = wheel.get();
+        pending[axis] = (pending[axis].0 + 1, time);
+        wheel.set(pending);
+    });
+    Ok(InputEvent::MouseWheelRotated {
+        delta: MouseWheelDelta::Rotational(delta.0, delta.1),
+        modifier,
+    })
+}
+
+/// Parses `WM_MOUSEWHEEL` and `WM_MOUSEHWHEEL` messages.
+///
+/// Precision touchpads do not produce Raw Input for scrolling and instead
+/// deliver only these messages. Wheels, including high-resolution wheels,
+/// produce Raw Input that is received before the corresponding message, so
+/// messages that follow wheel rotation reported via Raw Input are ignored.
+///
+/// Windows provides no physical distance for these deltas, so positional
+/// deltas are reported such that `WHEEL_DELTA` spans that many logical units.
+pub fn parse_wheel_message(
+    message: minwindef::UINT,
+    wparam: minwindef::WPARAM,
+) -> Result<InputEvent, ()> {
+    let delta = winuser::GET_WHEEL_DELTA_WPARAM(wparam) as f64;
+    let ((x, y), axis) = match message {
+        winuser::WM_MOUSEWHEEL => ((0.0, delta), 0),
+        winuser::WM_MOUSEHWHEEL => ((delta, 0.0), 1),
+        _ => return Err(()),
+    };
+    let time = unsafe { winuser::GetMessageTime() };
+    let is_raw = RAW_WHEEL.with(|wheel| {
+        let mut pending = wheel.get();
+        let (count, previous) = pending[axis];
+        // Rotation that is not matched promptly, such as rotation that is
+        // delivered to another window, is forgotten.
+        let is_raw = count > 0 && time.wrapping_sub(previous) <= WHEEL_MESSAGE_INTERVAL;
+        pending[axis].0 = if is_raw { count - 1 } else { 0 };
+        wheel.set(pending);
+        is_raw
+    });
+    if is_raw {
+        return Err(());
     }
+    Ok(InputEvent::MouseWheelRotated {
+        delta: MouseWheelDelta::Positional(x.into(), y.into()),
+        modifier: ModifierState {
+            toggles: keyboard::toggles(),
+        },
+    })
 }
 
 fn parse_buttons_into(
@@ -101,7 +182,6 @@ fn parse_buttons_into(
             });
         }
     };
-    // TODO: Read other button states.
     push_if(
         winuser::RI_MOUSE_LEFT_BUTTON_DOWN,
         MouseButton::Left,
@@ -132,5 +212,25 @@ fn parse_buttons_into(
         MouseButton::Center,
         ElementState::Released,
     );
+    push_if(
+        winuser::RI_MOUSE_BUTTON_4_DOWN,
+        MouseButton::Other(4),
+        ElementState::Pressed,
+    );
+    push_if(
+        winuser::RI_MOUSE_BUTTON_4_UP,
+        MouseButton::Other(4),
+        ElementState::Released,
+    );
+    push_if(
+        winuser::RI_MOUSE_BUTTON_5_DOWN,
+        MouseButton::Other(5),
+        ElementState::Pressed,
+    );
+    push_if(
+        winuser::RI_MOUSE_BUTTON_5_UP,
+        MouseButton::Other(5),
+        ElementState::Released,
+    );
     Ok(())
 }
@@ -0,0 +1,94 @@
+use gaudium_core::display::{IntoLogical, PhysicalPosition};
+use gaudium_core::event::{InputEvent, PenInfo};
+use std::mem;
+use winapi::shared::{minwindef, windef};
+use winapi::um::{winnt, winuser};
+
+/// The maximum pressure reported by `POINTER_PEN_INFO`.
+const MAX_PRESSURE: u32 = 1024;
+
+/// Parses a pointer message from a pen.
+///
+/// Returns the source device and event, or `Err` if the pointer is not a pen.
+/// Pointer messages from other devices, such as touch screens, are ignored.
+pub fn parse_pointer_message(
+    window: windef::HWND,
+    message: minwindef::UINT,
+    wparam: minwindef::WPARAM,
+) -> Result<(winnt::HANDLE, InputEvent), ()> {
+    let pointer = minwindef::LOWORD(wparam as minwindef::DWORD) as u32;
+    let pen = unsafe {
+        let mut kind = 0;
+        if winuser::GetPointerType(pointer, &mut kind) == 0 || kind != winuser::PT_PEN {
+            return Err(());
+        }
+        let mut pen = mem::zeroed::<winuser::POINTER_PEN_INFO>();
+        if winuser::GetPointerPenInfo(pointer, &mut pen) == 0 {
+            return Err(());
+        }
+        pen
+    };
+    let device = pen.pointerInfo.sourceDevice;
+    let event = match message {
+        winuser::WM_POINTERENTER => InputEvent::PenProximityChanged { in_range: true },
+        winuser::WM_POINTERLEAVE => InputEvent::PenProximityChanged { in_range: false },
+        winuser::WM_POINTERDOWN | winuser::WM_POINTERUP | winuser::WM_POINTERUPDATE => {
+            let mut point = pen.pointerInfo.ptPixelLocation;
+            unsafe {
+                winuser::ScreenToClient(window, &mut point);
+            }
+            let dpi = 1.0; // TODO: Get the DPI factor.
+            InputEvent::PenMoved {
+                position: PhysicalPosition::new(point.x, point.y).into_logical(dpi),
+                info: parse_pen_info(&pen),
+            }
+        }
+        _ => return Err(()),
+    };
+    Ok((device, event))
+}
+
+fn parse_pen_info(pen: &winuser::POINTER_PEN_INFO) -> PenInfo {
+    let flags = pen.pointerInfo.pointerFlags;
+    let is_in_contact = crate::has_bit_flags(flags, winuser::POINTER_FLAG_INCONTACT);
+    let raw_pressure = if crate::has_bit_flags(pen.penMask, winuser::PEN_MASK_PRESSURE) {
+        pen.pressure.min(MAX_PRESSURE)
+    }
+    else {
+        0
+    };
+    let tilt = |mask, tilt: i32| {
+        if crate::has_bit_flags(pen.penMask, mask) {
+            f64::from(tilt.clamp(-90, 90))
+        }
+        else {
+            0.0
+        }
+    };
+    PenInfo {
+        pressure: if is_in_contact {
+            f64::from(raw_pressure) / f64::from(MAX_PRESSURE)
+        }
+        else {
+            0.0
+        },
+        raw_pressure,
+        tilt: (
+            tilt(winuser::PEN_MASK_TILT_X, pen.tiltX),
+            tilt(winuser::PEN_MASK_TILT_Y, pen.tiltY),
+        ),
+        barrel_buttons: if crate::has_bit_flags(pen.penFlags, winuser::PEN_FLAG_BARREL) {
+            1
+        }
+        else {
+            0
+        },
+        // The eraser flag is set while the eraser is in contact, and the
+        // inverted flag is set while it hovers.
+        eraser: crate::has_bit_flags(
+            pen.penFlags,
+            winuser::PEN_FLAG_ERASER | winuser::PEN_FLAG_INVERTED,
+        ),
+        hover: !is_in_contact,
+    }
+}
@@ -0,0 +1,410 @@
+use gaudium_core::event::{Event, InputEvent, MouseMovement, WindowCloseState, WindowEvent};
+use gaudium_core::window::WindowHandle;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::iter;
+use std::mem;
+
+use crate::Binding;
+
+const DEFAULT_CAPACITY: usize = 256;
+
+thread_local! {
+    static CONFIGURATION: Cell<(usize, QueueOverflow)> =
+        Cell::new((DEFAULT_CAPACITY, QueueOverflow::default()));
+    static IS_COALESCING_MOUSE_MOTION: Cell<bool> = const { Cell::new(true) };
+    static IS_THROTTLING_RESIZE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Determines how the event queue of an event thread behaves when it is full.
+///
+/// The event thread queues events that occur while the reactor is reacting,
+/// such as events dispatched by window procedures, and events that are
+/// produced in batches, such as buffered raw input.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum QueueOverflow {
+    /// Mouse movement is merged into the most recently queued event if it is
+    /// also mouse movement from the same device and window. Any other event
+    /// displaces the oldest queued event as with `DropOldest`.
+    #[default]
+    CoalesceMouseMotion,
+    /// The oldest queued event is discarded.
+    ///
+    /// Events that connect or disconnect devices and events that commit the
+    /// closure of windows are never discarded, so the oldest of any other
+    /// event is discarded instead.
+    DropOldest,
+    /// The queue allocates additional storage and no events are discarded.
+    Grow,
+}
+
+/// Configures the event queue of event threads subsequently started on the
+/// calling thread.
+pub fn configure(capacity: usize, overflow: QueueOverflow) {
+    CONFIGURATION.with(|configuration| configuration.set((capacity.max(1), overflow)));
+}
+
+/// Configures whether or not event threads subsequently started on the
+/// calling thread coalesce mouse movement.
+pub fn configure_mouse_coalescing(is_coalescing: bool) {
+    IS_COALESCING_MOUSE_MOTION.with(|coalescing| coalescing.set(is_coalescing));
+}
+
+pub fn is_coalescing_mouse_motion() -> bool {
+    IS_COALESCING_MOUSE_MOTION.with(Cell::get)
+}
+
+/// Configures whether or not event threads subsequently started on the
+/// calling thread throttle `Resized` events.
+pub fn configure_resize_throttling(is_throttling: bool) {
+    IS_THROTTLING_RESIZE.with(|throttling| throttling.set(is_throttling));
+}
+
+pub fn is_throttling_resize() -> bool {
+    IS_THROTTLING_RESIZE.with(Cell::get)
+}
+
+/// Merges mouse movement into a previous event.
+///
+/// Returns `false` if the events cannot be merged, in which case the previous
+/// event is unchanged. Absolute positions are replaced and relative motions
+/// are accumulated.
+pub fn coalesce(previous: &mut Event<Binding>, next: &Event<Binding>) -> bool {
+    match (previous, next) {
+        (
+            &mut Event::Input {
+                device,
+                window,
+                event:
+                    InputEvent::MouseMoved {
+                        ref mut movement,
+                        ref mut modifier,
+                    },
+                ref mut timestamp,
+                ..
+            },
+            &Event::Input {
+                device: next_device,
+                window: next_window,
+                event:
+                    InputEvent::MouseMoved {
+                        movement: next_movement,
+                        modifier: next_modifier,
+                    },
+                timestamp: next_timestamp,
+                ..
+            },
+        ) if device == next_device && window == next_window => {
+            *movement = MouseMovement {
+                absolute: next_movement.absolute.or(movement.absolute),
+                relative: match (movement.relative, next_movement.relative) {
+                    (Some(relative), Some(next)) => Some(relative + next),
+                    (relative, None) | (None, relative) => relative,
+                },
+            };
+            *modifier = next_modifier;
+            *timestamp = next_timestamp;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// A fixed-capacity ring buffer of events.
+///
+/// Storage is allocated when the queue is created. Pushing events never
+/// allocates unless the overflow policy is `Grow`, in which case events that
+/// do not fit are stored on the heap until the ring buffer has space.
+pub struct EventQueue {
+    buffer: Box<[Option<Event<Binding>>]>,
+    head: usize,
+    len: usize,
+    spill: VecDeque<Event<Binding>>,
+    overflow: QueueOverflow,
+    is_overflowed: bool,
+    discarded: usize,
+}
+
+impl EventQueue {
+    /// Creates a queue using the configuration of the calling thread.
+    ///
+    /// See `configure`.
+    pub fn from_configuration() -> Self {
+        let (capacity, overflow) = CONFIGURATION.with(Cell::get);
+        EventQueue::new(capacity, overflow)
+    }
+
+    pub fn new(capacity: usize, overflow: QueueOverflow) -> Self {
+        EventQueue {
+            buffer: vec![None; capacity.max(1)].into_boxed_slice(),
+            head: 0,
+            len: 0,
+            spill: VecDeque::new(),
+            overflow,
+            is_overflowed: false,
+            discarded: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len + self.spill.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push_back(&mut self, event: Event<Binding>) {
+        let capacity = self.buffer.len();
+        if self.len < capacity && self.spill.is_empty() {
+            self.buffer[(self.head + self.len) % capacity] = Some(event);
+            self.len += 1;
+            return;
+        }
+        self.is_overflowed = true;
+        match self.overflow {
+            QueueOverflow::CoalesceMouseMotion => {
+                let tail = (self.head + self.len - 1) % capacity;
+                let previous = match self.spill.back_mut() {
+                    Some(previous) => Some(previous),
+                    None => self.buffer[tail].as_mut(),
+                };
+                if let Some(previous) = previous {
+                    if coalesce(previous, &event) {
+                        self.discarded += 1;
+                        return;
+                    }
+                }
+                self.displace(event);
+            }
+            QueueOverflow::DropOldest => self.displace(event),
+            QueueOverflow::Grow => self.spill.push_back(event),
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<Event<Binding>> {
+        if self.len == 0 {
+            return None;
+        }
+        let capacity = self.buffer.len();
+        let event = self.buffer[self.head].take();
+        self.head = (self.head + 1) % capacity;
+        self.len -= 1;
+        // Move spilled events into the ring buffer as space becomes available
+        // so that events remain in order.
+        if let Some(spilled) = self.spill.pop_front() {
+            self.buffer[(self.head + self.len) % capacity] = Some(spilled);
+            self.len += 1;
+        }
+        event
+    }
+
+    /// Removes events for a window that has been destroyed.
+    ///
+    /// Window events for the window are discarded and input events are
+    /// detached from it. The order of the remaining events is unchanged and
+    /// the removal is not considered an overflow.
+    pub fn purge(&mut self, window: WindowHandle<Binding>) {
+        let events = iter::from_fn(|| self.pop_front())
+            .filter_map(|event| match event {
+                Event::Window { window: other, .. } if other == window => None,
+                Event::Input {
+                    device,
+                    window: Some(other),
+                    event,
+                    focus,
+                    timestamp,
+                } if other == window => Some(Event::Input {
+                    device,
+                    window: None,
+                    event,
+                    focus,
+                    timestamp,
+                }),
+                event => Some(event),
+            })
+            .collect::<Vec<_>>();
+        let capacity = self.buffer.len();
+        self.head = 0;
+        for event in events {
+            if self.len < capacity {
+                self.buffer[self.len] = Some(event);
+                self.len += 1;
+            }
+            else {
+                self.spill.push_back(event);
+            }
+        }
+    }
+
+    /// Gets the number of events discarded (or merged) since the queue last
+    /// overflowed, if it has overflowed since this function was last called.
+    pub fn take_overflow(&mut self) -> Option<usize> {
+        if self.is_overflowed {
+            self.is_overflowed = false;
+            Some(mem::take(&mut self.discarded))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Discards the oldest event that is not a lifecycle event and queues the
+    /// given event. The queue must be full.
+    ///
+    /// If all queued events are lifecycle events, then the given event is
+    /// discarded instead unless it is also a lifecycle event, in which case it
+    /// is spilled as with `Grow`.
+    fn displace(&mut self, event: Event<Binding>) {
+        let capacity = self.buffer.len();
+        let oldest = (0..self.len).find(|offset| {
+            !self.buffer[(self.head + offset) % capacity]
+                .as_ref()
+                .is_some_and(is_lifecycle)
+        });
+        match oldest {
+            Some(offset) => {
+                // Move newer events forward to close the gap.
+                for offset in offset..(self.len - 1) {
+                    let next = self.buffer[(self.head + offset + 1) % capacity].take();
+                    self.buffer[(self.head + offset) % capacity] = next;
+                }
+                self.len -= 1;
+                self.discarded += 1;
+                if let Some(spilled) = self.spill.pop_front() {
+                    self.buffer[(self.head + self.len) % capacity] = Some(spilled);
+                    self.len += 1;
+                    self.spill.push_back(event);
+                }
+                else {
+                    self.buffer[(self.head + self.len) % capacity] = Some(event);
+                    self.len += 1;
+                }
+            }
+            None if is_lifecycle(&event) => self.spill.push_back(event),
+            None => self.discarded += 1,
+        }
+    }
+}
+
+/// Determines if an event changes the set of devices or windows, in which
+/// case it is never discarded by the queue.
+fn is_lifecycle(event: &Event<Binding>) -> bool {
+    matches!(
+        *event,
+        Event::Input {
+            event: InputEvent::Connected { .. } | InputEvent::Disconnected,
+            ..
+        } | Event::Window {
+            event: WindowEvent::Closed(WindowCloseState::Committed),
+            ..
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use gaudium_core::device::DeviceHandle;
+    use gaudium_core::event::{Event, InputEvent, InputFocus, WindowCloseState, WindowEvent};
+    use gaudium_core::window::WindowHandle;
+    use gaudium_core::FromRawHandle;
+    use std::iter;
+    use std::time::Instant;
+    use winapi::shared::{ntdef, windef};
+
+    use crate::queue::{EventQueue, QueueOverflow};
+    use crate::Binding;
+
+    fn focused(window: usize) -> Event<Binding> {
+        Event::Window {
+            window: WindowHandle::from_raw_handle(window as windef::HWND),
+            event: WindowEvent::Focused(true),
+            timestamp: Instant::now(),
+        }
+    }
+
+    fn closed(window: usize) -> Event<Binding> {
+        Event::Window {
+            window: WindowHandle::from_raw_handle(window as windef::HWND),
+            event: WindowEvent::Closed(WindowCloseState::Committed),
+            timestamp: Instant::now(),
+        }
+    }
+
+    fn disconnected(device: usize) -> Event<Binding> {
+        Event::Input {
+            device: DeviceHandle::from_raw_handle(device as ntdef::HANDLE),
+            window: None,
+            event: InputEvent::Disconnected,
+            focus: InputFocus::Foreground,
+            timestamp: Instant::now(),
+        }
+    }
+
+    fn drain(queue: &mut EventQueue) -> Vec<Event<Binding>> {
+        iter::from_fn(|| queue.pop_front()).collect()
+    }
+
+    #[test]
+    fn wraparound() {
+        let events: Vec<_> = (1..=4).map(focused).collect();
+        let mut queue = EventQueue::new(3, QueueOverflow::DropOldest);
+        queue.push_back(events[0]);
+        queue.push_back(events[1]);
+        assert_eq!(queue.pop_front(), Some(events[0]));
+        queue.push_back(events[2]);
+        queue.push_back(events[3]);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(drain(&mut queue), events[1..]);
+        assert_eq!(queue.take_overflow(), None);
+    }
+
+    #[test]
+    fn grow_spills_in_order() {
+        let events: Vec<_> = (1..=6).map(focused).collect();
+        let mut queue = EventQueue::new(2, QueueOverflow::Grow);
+        for event in &events[..5] {
+            queue.push_back(*event);
+        }
+        assert_eq!(queue.pop_front(), Some(events[0]));
+        queue.push_back(events[5]);
+        assert_eq!(queue.len(), 5);
+        assert_eq!(drain(&mut queue), events[1..]);
+        // Nothing is discarded, but the overflow is still reported.
+        assert_eq!(queue.take_overflow(), Some(0));
+        assert_eq!(queue.take_overflow(), None);
+    }
+
+    #[test]
+    fn drop_oldest_counts_overflow() {
+        let events: Vec<_> = (1..=4).map(focused).collect();
+        let mut queue = EventQueue::new(2, QueueOverflow::DropOldest);
+        for event in &events {
+            queue.push_back(*event);
+        }
+        assert_eq!(drain(&mut queue), events[2..]);
+        assert_eq!(queue.take_overflow(), Some(2));
+        assert_eq!(queue.take_overflow(), None);
+    }
+
+    #[test]
+    fn drop_oldest_retains_lifecycle_events() {
+        let events = [closed(1), focused(2), disconnected(3), focused(4)];
+        let mut queue = EventQueue::new(3, QueueOverflow::DropOldest);
+        for event in &events {
+            queue.push_back(*event);
+        }
+        assert_eq!(drain(&mut queue), [events[0], events[2], events[3]]);
+        assert_eq!(queue.take_overflow(), Some(1));
+
+        // If only lifecycle events are queued, then other events are
+        // discarded and lifecycle events are spilled.
+        let events = [closed(1), disconnected(2), focused(3), closed(4)];
+        let mut queue = EventQueue::new(2, QueueOverflow::CoalesceMouseMotion);
+        for event in &events {
+            queue.push_back(*event);
+        }
+        assert_eq!(drain(&mut queue), [events[0], events[1], events[3]]);
+        assert_eq!(queue.take_overflow(), Some(1));
+    }
+}
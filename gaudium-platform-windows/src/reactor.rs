@@ -1,21 +1,36 @@
-use gaudium_core::event::{ApplicationEvent, Event, Resumption};
+use gaudium_core::error::{Error, ErrorKind};
+use gaudium_core::event::{
+    ApplicationEvent, Event, InputEvent, Resumption, WindowCloseState, WindowEvent,
+};
 use gaudium_core::platform;
-use gaudium_core::reactor::{Poll, Reaction, Reactor, ThreadContext};
+use gaudium_core::reactor::instrument;
+use gaudium_core::reactor::{Poll, Reaction, Reactor, Sinks, ThreadContext, ThreadPriority};
+use gaudium_core::shell::hotkey::HotkeyId;
 use gaudium_core::window::WindowHandle;
+use gaudium_core::FromRawHandle;
 use std::cell::Cell;
-use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::hint;
 use std::mem;
+use std::os::raw;
 use std::process;
 use std::ptr;
-use std::time::Instant;
-use winapi::shared::{minwindef, winerror};
-use winapi::um::winuser;
+use std::time::{Duration, Instant};
+use winapi::shared::{basetsd, minwindef, windef, winerror};
+use winapi::um::{processthreadsapi, timeapi, winbase, winuser};
 
-use crate::{Binding, DwordMilliseconds};
+use crate::input;
+use crate::queue::{self, EventQueue};
+use crate::xinput::Controllers;
+use crate::{console, timer, window, Binding, DwordMilliseconds};
 
 use ApplicationEvent::Flushed;
+use ApplicationEvent::HotkeyPressed;
+use ApplicationEvent::Idle;
+use ApplicationEvent::QueueOverflowed;
 use ApplicationEvent::Resumed;
 use Poll::Ready;
+use Poll::ReadyThrottled;
 use Poll::Wait;
 use Poll::WaitUntil;
 use Reaction::Abort;
@@ -23,11 +38,43 @@ use Reaction::Continue;
 
 thread_local! {
     static EVENT_THREAD: Cell<Option<*mut dyn React>> = Cell::new(None);
+    static WAIT_TOLERANCE: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// The resolution of the system timer requested while high-resolution waits
+/// are enabled, in milliseconds.
+const TIMER_RESOLUTION: minwindef::UINT = 1;
+
+/// The margin before the requested instant at which a high-resolution wait
+/// stops sleeping and begins spinning.
+///
+/// Even with a raised timer resolution, sleeps may overshoot by about a
+/// millisecond due to scheduling.
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+/// A platform message that is not otherwise modeled by events.
+///
+/// Messages at or above `WM_USER` that are not handled by the event thread
+/// are dispatched as `Event::Platform` when platform events are enabled. This
+/// includes registered messages and messages posted to the thread.
+///
+/// Events may be queued before they are dispatched, so any pointers in the
+/// parameters of a sent message may no longer be valid and must not be
+/// dereferenced.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawMessage {
+    /// The window that received the message or `None` if the message was
+    /// posted to the thread.
+    pub window: Option<WindowHandle<Binding>>,
+    pub message: minwindef::UINT,
+    pub wparam: minwindef::WPARAM,
+    pub lparam: minwindef::LPARAM,
 }
 
 trait React {
     fn react(&mut self, event: Event<Binding>) -> Reaction;
     fn enqueue(&mut self, event: Event<Binding>);
+    fn flush_resizes(&mut self);
 }
 
 pub struct EventThread<R>
@@ -37,7 +84,25 @@ where
     reactor: R,
     reaction: Reaction<Poll>,
     context: ThreadContext,
-    queue: VecDeque<Event<Binding>>,
+    queue: EventQueue,
+    // Mouse movement that has not yet been dispatched. Consecutive movement
+    // is merged into this event when coalescing.
+    motion: Option<Event<Binding>>,
+    // `Resized` events that have not yet been dispatched when throttling, at
+    // most one per window.
+    resizes: Vec<Event<Binding>>,
+    // The events of the batch being dispatched. The buffer is reused by each
+    // batch.
+    batch: Vec<Event<Binding>>,
+    controllers: Controllers,
+    // The tolerance of high-resolution waits, if enabled.
+    tolerance: Option<Duration>,
+    is_coalescing: bool,
+    is_throttling_resize: bool,
+    is_reacting: bool,
+    // Whether or not only application events have been dispatched since the
+    // current flush started.
+    is_idle: bool,
 }
 
 impl<R> EventThread<R>
@@ -49,59 +114,271 @@ where
             reactor,
             reaction: Default::default(),
             context,
-            queue: VecDeque::with_capacity(16),
+            queue: EventQueue::from_configuration(),
+            motion: None,
+            resizes: vec![],
+            batch: vec![],
+            controllers: Controllers::new(),
+            tolerance: WAIT_TOLERANCE.with(Cell::get),
+            is_coalescing: queue::is_coalescing_mouse_motion(),
+            is_throttling_resize: queue::is_throttling_resize(),
+            is_reacting: false,
+            is_idle: false,
         }
     }
 
     #[allow(clippy::useless_transmute)]
-    unsafe fn run(mut self) -> minwindef::UINT {
+    unsafe fn run(mut self) -> Result<minwindef::UINT, Error> {
+        // The reactor is aborted even if the event loop fails to start, so
+        // that the state of the event thread is released.
+        let _configuration = match ThreadConfiguration::apply(&self.context) {
+            Ok(configuration) => configuration,
+            Err(error) => {
+                self.abort();
+                return Err(error);
+            }
+        };
         EVENT_THREAD.with(|thread| {
             thread.set(Some(mem::transmute::<&mut dyn React, *mut dyn React>(
                 &mut self,
             )));
         });
+        let _resolution = self.tolerance.map(|_| TimerResolution::begin());
         let message = &mut mem::zeroed();
+        let mut error = None;
+        let mut resumed = Instant::now();
         'react: loop {
+            instrument::instrument(&self.context, |instrumentation| {
+                instrumentation.flush_started(Instant::now())
+            });
+            self.is_idle = true;
             while winuser::PeekMessageW(message, ptr::null_mut(), 0, 0, winuser::PM_REMOVE) != 0 {
                 if (*message).message == winuser::WM_QUIT {
                     break 'react;
                 }
                 dispatch(message); // May call `react`.
             }
-            self.react(Event::Application { event: Flushed });
-            while let Some(event) = self.queue.pop_front() {
+            for event in self.controllers.poll(Instant::now()) {
                 self.react(event);
             }
+            self.flush_motion();
+            self.flush_resizes();
+            self.react(Event::Application {
+                event: Flushed,
+                timestamp: Instant::now(),
+            });
+            self.flush_queue();
+            if let Some(discarded) = self.queue.take_overflow() {
+                self.react(Event::Application {
+                    event: QueueOverflowed { discarded },
+                    timestamp: Instant::now(),
+                });
+            }
+            instrument::instrument(&self.context, |instrumentation| {
+                instrumentation.flush_completed(Instant::now())
+            });
             self.poll();
+            if self.is_idle {
+                self.idle();
+            }
             let resumption = match self.reaction {
+                // Do not wait if events were queued while polling.
+                Continue(_) if !self.queue.is_empty() => Resumption::Poll,
                 Continue(Wait) => {
-                    if winuser::GetMessageW(message, ptr::null_mut(), 0, 0) == 0 {
-                        break 'react;
+                    match winuser::GetMessageW(message, ptr::null_mut(), 0, 0) {
+                        0 => break 'react,
+                        -1 => {
+                            error = Some(crate::last_error("failed to get message"));
+                            break 'react;
+                        }
+                        _ => {}
                     }
                     dispatch(message); // May call `react`.
                     Resumption::Poll
                 }
-                Continue(WaitUntil(until)) => match wait_for_message_until(until) {
+                Continue(WaitUntil(until)) => match self.wait_for_message_until(until) {
                     Ok(resumption) => resumption,
-                    Err(_) => Resumption::Poll,
+                    Err(wait) => {
+                        error = Some(wait);
+                        break 'react;
+                    }
                 },
                 Continue(Ready) => Resumption::Poll,
+                // Sleep for the remainder of the duration, but wake for any
+                // messages so that event dispatch is not delayed.
+                Continue(ReadyThrottled(duration)) => {
+                    match self.wait_for_message_until(resumed + duration) {
+                        Ok(_) => Resumption::Poll,
+                        Err(wait) => {
+                            error = Some(wait);
+                            break 'react;
+                        }
+                    }
+                }
                 Abort => break 'react,
             };
+            resumed = Instant::now();
+            instrument::instrument(&self.context, |instrumentation| {
+                instrumentation.resumed(resumed, resumption)
+            });
             self.react(Event::Application {
                 event: Resumed(resumption),
+                timestamp: resumed,
             });
         }
+        // Destroy any remaining windows while the event thread is still
+        // available, so that the reactor observes their destruction before it
+        // is dropped.
+        window::destroy_thread_windows();
+        self.flush_queue();
+        // Release process-wide registrations of the event thread, so that
+        // they do not accumulate if event threads are run repeatedly.
+        input::unregister_destroyed();
+        window::release_window_classes();
+        timer::cancel_all();
+        console::unregister();
         EVENT_THREAD.with(|thread| {
             thread.set(None);
         });
         self.abort(); // Drop the reactor and all state.
-        if (*message).message == winuser::WM_QUIT {
-            (*message).wParam as minwindef::UINT
+        if let Some(error) = error {
+            Err(error)
+        }
+        else if (*message).message == winuser::WM_QUIT {
+            Ok((*message).wParam as minwindef::UINT)
         }
         else {
-            0
+            Ok(0)
+        }
+    }
+
+    unsafe fn wait_for_message_until(&self, until: Instant) -> Result<Resumption, Error> {
+        match self.tolerance {
+            Some(tolerance) => wait_for_message_until_precisely(until, tolerance),
+            None => wait_for_message_until(until),
+        }
+    }
+
+    /// Dispatches `Idle` if the reactor is about to wait and polls the
+    /// reactor again.
+    fn idle(&mut self) {
+        let deadline = match (self.context.idle_budget(), self.reaction) {
+            (Some(budget), Continue(Wait)) => Instant::now() + budget,
+            (Some(budget), Continue(WaitUntil(until))) => until.min(Instant::now() + budget),
+            _ => return,
+        };
+        self.react(Event::Application {
+            event: Idle { deadline },
+            timestamp: Instant::now(),
+        });
+        self.poll();
+    }
+
+    /// Dispatches any coalesced mouse movement.
+    fn flush_motion(&mut self) {
+        if let Some(event) = self.motion.take() {
+            self.dispatch(event);
+        }
+    }
+
+    /// Dispatches queued events to the reactor in batches.
+    ///
+    /// Events queued while the reactor handles a batch are dispatched in
+    /// another batch.
+    fn flush_queue(&mut self) {
+        // The buffer is taken while the batch is dispatched and is restored
+        // afterwards, so that it is reused by each batch.
+        let mut events = mem::take(&mut self.batch);
+        loop {
+            // Any coalesced mouse movement precedes the queued events.
+            events.extend(self.motion.take());
+            while let Some(event) = self.queue.pop_front() {
+                let is_coalesced = self.is_coalescing
+                    && events
+                        .last_mut()
+                        .is_some_and(|previous| queue::coalesce(previous, &event));
+                if !is_coalesced {
+                    events.push(event);
+                }
+            }
+            if events.is_empty() {
+                break;
+            }
+            self.dispatch_all(&events);
+            events.clear();
+        }
+        self.batch = events;
+    }
+
+    fn dispatch_all(&mut self, events: &[Event<Binding>]) -> Reaction {
+        if events
+            .iter()
+            .any(|event| !matches!(event, Event::Application { .. }))
+        {
+            self.is_idle = false;
         }
+        let depth = events.len();
+        instrument::instrument(&self.context, |instrumentation| {
+            instrumentation.dispatch_started(Instant::now(), depth)
+        });
+        // Only overwrite the reaction if an `Abort` was emitted.
+        self.is_reacting = true;
+        let reaction = self.reactor.react_all(&self.context, events.into());
+        self.is_reacting = false;
+        instrument::instrument(&self.context, |instrumentation| {
+            instrumentation.dispatch_completed(Instant::now())
+        });
+        // Pending events for windows whose closure is committed in the batch
+        // are purged after the batch is dispatched. The reactor discards any
+        // events for such windows within the batch.
+        for event in events {
+            if let Event::Window {
+                window,
+                event: WindowEvent::Closed(WindowCloseState::Committed),
+                ..
+            } = *event
+            {
+                self.queue.purge(window);
+            }
+        }
+        if let Abort = reaction {
+            self.reaction = Abort;
+        }
+        reaction
+    }
+
+    fn dispatch(&mut self, event: Event<Binding>) -> Reaction {
+        // No events for a window are dispatched after its closure is
+        // committed, so any pending events for the window are purged.
+        let closed = match event {
+            Event::Window {
+                window,
+                event: WindowEvent::Closed(WindowCloseState::Committed),
+                ..
+            } => Some(window),
+            _ => None,
+        };
+        if !matches!(event, Event::Application { .. }) {
+            self.is_idle = false;
+        }
+        let depth = self.queue.len();
+        instrument::instrument(&self.context, |instrumentation| {
+            instrumentation.dispatch_started(Instant::now(), depth)
+        });
+        // Only overwrite the reaction if an `Abort` was emitted.
+        self.is_reacting = true;
+        let reaction = self.reactor.react(&self.context, event);
+        self.is_reacting = false;
+        instrument::instrument(&self.context, |instrumentation| {
+            instrumentation.dispatch_completed(Instant::now())
+        });
+        if let Some(window) = closed {
+            self.queue.purge(window);
+        }
+        if let Abort = reaction {
+            self.reaction = Abort;
+        }
+        reaction
     }
 
     fn abort(self) {
@@ -111,7 +388,13 @@ where
 
     fn poll(&mut self) -> Reaction<Poll> {
         // Only overwrite the reaction if it is not in the `Abort` state.
+        // Events dispatched while polling are queued. See `react`.
+        self.is_reacting = true;
         let reaction = self.reactor.poll(&self.context);
+        self.is_reacting = false;
+        instrument::instrument(&self.context, |instrumentation| {
+            instrumentation.polled(Instant::now(), reaction)
+        });
         if let Continue(_) = self.reaction {
             self.reaction = reaction;
         }
@@ -124,56 +407,182 @@ where
     R: Reactor<Binding>,
 {
     fn react(&mut self, event: Event<Binding>) -> Reaction {
-        // Only overwrite the reaction if an `Abort` was emitted.
-        let reaction = self.reactor.react(&self.context, event);
-        if let Abort = reaction {
-            self.reaction = Abort;
+        if let Event::Platform { .. } = event {
+            if !self.context.is_platform_events_enabled() {
+                return Continue(());
+            }
         }
-        reaction
+        // Window procedures may execute while the reactor is reacting, such as
+        // when a window is dropped by the reactor. Queue any such events
+        // rather than reentering the reactor.
+        if self.is_reacting {
+            self.queue.push_back(event);
+            return Continue(());
+        }
+        // Replace the pending dimensions of the window, if any. The most
+        // recent dimensions precede the end of a resize and the closure of
+        // the window.
+        if self.is_throttling_resize {
+            match event {
+                Event::Window {
+                    window,
+                    event: WindowEvent::Resized { .. },
+                    ..
+                } => {
+                    match self.resizes.iter_mut().find(|resize| {
+                        matches!(resize, Event::Window { window: other, .. } if *other == window)
+                    }) {
+                        Some(resize) => {
+                            *resize = event;
+                        }
+                        None => {
+                            self.resizes.push(event);
+                        }
+                    }
+                    return Continue(());
+                }
+                Event::Window {
+                    event: WindowEvent::ResizeEnded | WindowEvent::Closed(_),
+                    ..
+                } => {
+                    self.flush_resizes();
+                }
+                _ => {}
+            }
+        }
+        // Merge consecutive mouse movement. Movement is dispatched before any
+        // other event so that events remain in order.
+        if self.is_coalescing {
+            if let Event::Input {
+                event: InputEvent::MouseMoved { .. },
+                ..
+            } = event
+            {
+                let is_coalesced = match self.motion {
+                    Some(ref mut motion) => queue::coalesce(motion, &event),
+                    None => false,
+                };
+                if !is_coalesced {
+                    self.flush_motion();
+                    self.motion = Some(event);
+                }
+                return Continue(());
+            }
+            self.flush_motion();
+        }
+        self.dispatch(event)
     }
 
     fn enqueue(&mut self, event: Event<Binding>) {
         self.queue.push_back(event);
     }
+
+    /// Dispatches any throttled `Resized` events.
+    fn flush_resizes(&mut self) {
+        if self.is_reacting {
+            return;
+        }
+        self.flush_motion();
+        for event in mem::take(&mut self.resizes) {
+            self.dispatch(event);
+        }
+    }
 }
 
 pub struct Entry;
 
+/// Resumes the event loop by posting a message to the event thread.
+pub struct Waker(minwindef::DWORD);
+
+impl platform::Waker for Waker {
+    fn from_context(_: &ThreadContext) -> Self {
+        Waker(unsafe { processthreadsapi::GetCurrentThreadId() })
+    }
+
+    fn wake(&self) {
+        // The message is not associated with a window and is ignored when it
+        // is dispatched, but it interrupts `GetMessageW` and similar.
+        unsafe {
+            winuser::PostThreadMessageW(self.0, winuser::WM_NULL, 0, 0);
+        }
+    }
+}
+
 impl platform::Abort<Binding> for Entry {
-    fn run_and_abort<R>(context: ThreadContext, _: WindowHandle<Binding>, reactor: R) -> !
+    fn run_and_abort<R>(context: ThreadContext, sinks: Sinks<Binding>, reactor: R) -> !
     where
         R: Reactor<Binding>,
     {
-        unsafe { process::exit(EventThread::new(context, reactor).run() as i32) }
+        unsafe {
+            process::exit(
+                match create_headless_sink(&sinks)
+                    .and_then(|_| EventThread::new(context, reactor).run())
+                {
+                    Ok(code) => code as i32,
+                    Err(_) => 1,
+                },
+            )
+        }
     }
 }
 
 impl platform::Join<Binding> for Entry {
-    fn run_and_join<R>(context: ThreadContext, _: WindowHandle<Binding>, reactor: R)
+    fn run_and_join<R>(
+        context: ThreadContext,
+        sinks: Sinks<Binding>,
+        reactor: R,
+    ) -> Result<(), Error>
     where
         R: Reactor<Binding>,
     {
-        unsafe {
-            EventThread::new(context, reactor).run();
+        let sink = create_headless_sink(&sinks)?;
+        let result = unsafe { EventThread::new(context, reactor).run().map(|_| ()) };
+        if let Some(sink) = sink {
+            unsafe {
+                winuser::DestroyWindow(sink);
+            }
         }
+        result
+    }
+}
+
+// Headless event threads have no window to receive raw input, so a
+// message-only window is created in its place.
+fn create_headless_sink(sinks: &Sinks<Binding>) -> Result<Option<windef::HWND>, Error> {
+    if sinks.is_headless() {
+        window::create_message_only().map(Some)
+    }
+    else {
+        Ok(None)
     }
 }
 
-pub unsafe fn react(event: Event<Binding>) -> Result<Reaction, ()> {
+pub unsafe fn react(event: Event<Binding>) -> Result<Reaction, Error> {
     EVENT_THREAD.with(move |thread| {
         thread
             .get()
-            .ok_or_else(|| ())
+            .ok_or_else(no_event_thread)
             .map(|thread| (*thread).react(event))
     })
 }
 
-pub unsafe fn enqueue<I>(events: I) -> Result<(), ()>
+/// Dispatches any throttled `Resized` events, such as periodically while a
+/// window is resized interactively.
+pub unsafe fn flush_resizes() -> Result<(), Error> {
+    EVENT_THREAD.with(|thread| {
+        thread
+            .get()
+            .ok_or_else(no_event_thread)
+            .map(|thread| (*thread).flush_resizes())
+    })
+}
+
+pub unsafe fn enqueue<I>(events: I) -> Result<(), Error>
 where
     I: IntoIterator<Item = Event<Binding>>,
 {
     EVENT_THREAD.with(move |thread| {
-        thread.get().ok_or_else(|| ()).map(|thread| {
+        thread.get().ok_or_else(no_event_thread).map(|thread| {
             for event in events {
                 (*thread).enqueue(event);
             }
@@ -181,29 +590,223 @@ where
     })
 }
 
-pub unsafe fn wait_for_message_until(until: Instant) -> Result<Resumption, ()> {
+/// Waits for a message or until the given instant.
+///
+/// If the instant has already elapsed, then this does not block but still
+/// resumes with `Resumption::Interrupt` if a message is available, so that
+/// `WaitUntil` behaves consistently regardless of how long the reactor takes
+/// to react.
+pub unsafe fn wait_for_message_until(until: Instant) -> Result<Resumption, Error> {
     let now = Instant::now();
-    if until >= now {
-        if winuser::MsgWaitForMultipleObjectsEx(
-            0,
-            ptr::null(),
-            (until - now).dword_milliseconds(),
-            winuser::QS_ALLEVENTS,
-            winuser::MWMO_INPUTAVAILABLE,
-        ) == winerror::WAIT_TIMEOUT
-        {
-            Ok(Resumption::Timeout(now))
+    match winuser::MsgWaitForMultipleObjectsEx(
+        0,
+        ptr::null(),
+        until.saturating_duration_since(now).dword_milliseconds(),
+        winuser::QS_ALLEVENTS,
+        winuser::MWMO_INPUTAVAILABLE,
+    ) {
+        winerror::WAIT_TIMEOUT => Ok(Resumption::Timeout(now)),
+        winbase::WAIT_FAILED => Err(crate::last_error("failed to wait for message")),
+        _ => Ok(Resumption::Interrupt(now)),
+    }
+}
+
+/// Waits for a message or until the given instant, resuming within the given
+/// tolerance after the instant.
+///
+/// This sleeps until shortly before the instant and then spins for the
+/// remainder of the wait, polling for messages so that input still
+/// interrupts the wait. The system timer resolution must be raised via
+/// `TimerResolution` for the sleep to be accurate.
+unsafe fn wait_for_message_until_precisely(
+    until: Instant,
+    tolerance: Duration,
+) -> Result<Resumption, Error> {
+    let now = Instant::now();
+    let sleep = until
+        .saturating_duration_since(now)
+        .saturating_sub(SPIN_MARGIN.saturating_sub(tolerance));
+    if sleep >= Duration::from_millis(1) {
+        if let Resumption::Interrupt(_) = wait_for_message_until(now + sleep)? {
+            return Ok(Resumption::Interrupt(now));
         }
-        else {
-            Ok(Resumption::Interrupt(now))
+    }
+    loop {
+        if Instant::now() >= until {
+            return Ok(Resumption::Timeout(now));
+        }
+        if let Resumption::Interrupt(_) = wait_for_message_until(now)? {
+            return Ok(Resumption::Interrupt(now));
         }
+        hint::spin_loop();
     }
-    else {
-        Err(())
+}
+
+/// Raises the resolution of the system timer until dropped.
+///
+/// This affects the entire system and increases power consumption, so it is
+/// only used while high-resolution waits are enabled.
+struct TimerResolution;
+
+impl TimerResolution {
+    fn begin() -> Self {
+        unsafe {
+            timeapi::timeBeginPeriod(TIMER_RESOLUTION);
+        }
+        TimerResolution
     }
 }
 
+impl Drop for TimerResolution {
+    fn drop(&mut self) {
+        unsafe {
+            timeapi::timeEndPeriod(TIMER_RESOLUTION);
+        }
+    }
+}
+
+/// Configures high-resolution waits for event threads subsequently started
+/// on the calling thread.
+pub fn configure_wait_tolerance(tolerance: Option<Duration>) {
+    WAIT_TOLERANCE.with(|wait| wait.set(tolerance));
+}
+
+/// Dispatches a platform message that is not otherwise modeled by events.
+///
+/// The event is discarded by the event thread unless platform events are
+/// enabled.
+pub unsafe fn react_message(
+    window: Option<windef::HWND>,
+    message: minwindef::UINT,
+    wparam: minwindef::WPARAM,
+    lparam: minwindef::LPARAM,
+) {
+    let _ = react(Event::Platform {
+        data: RawMessage {
+            window: window.map(WindowHandle::from_raw_handle),
+            message,
+            wparam,
+            lparam,
+        },
+        timestamp: crate::message_timestamp(),
+    });
+}
+
+/// Applies the priority and affinity of the event thread, if any.
+///
+/// The previous priority and affinity of the thread are restored when the
+/// configuration is dropped.
+struct ThreadConfiguration {
+    priority: Option<raw::c_int>,
+    affinity: Option<basetsd::DWORD_PTR>,
+}
+
+impl ThreadConfiguration {
+    fn apply(context: &ThreadContext) -> Result<Self, Error> {
+        // Any changes are restored if a later change fails.
+        let mut configuration = ThreadConfiguration {
+            priority: None,
+            affinity: None,
+        };
+        unsafe {
+            let thread = processthreadsapi::GetCurrentThread();
+            if let Some(priority) = context.thread_priority() {
+                let previous = processthreadsapi::GetThreadPriority(thread);
+                if previous == winbase::THREAD_PRIORITY_ERROR_RETURN as raw::c_int {
+                    return Err(crate::last_error("failed to get thread priority"));
+                }
+                let priority = match priority {
+                    ThreadPriority::Lowest => winbase::THREAD_PRIORITY_LOWEST,
+                    ThreadPriority::BelowNormal => winbase::THREAD_PRIORITY_BELOW_NORMAL,
+                    ThreadPriority::Normal => winbase::THREAD_PRIORITY_NORMAL,
+                    ThreadPriority::AboveNormal => winbase::THREAD_PRIORITY_ABOVE_NORMAL,
+                    ThreadPriority::Highest => winbase::THREAD_PRIORITY_HIGHEST,
+                    ThreadPriority::TimeCritical => winbase::THREAD_PRIORITY_TIME_CRITICAL,
+                };
+                if processthreadsapi::SetThreadPriority(thread, priority as raw::c_int) == 0 {
+                    return Err(crate::last_error("failed to set thread priority"));
+                }
+                configuration.priority = Some(previous);
+            }
+            if let Some(mask) = context.thread_affinity() {
+                if mask == 0 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "thread affinity mask is empty",
+                    ));
+                }
+                let mask = basetsd::DWORD_PTR::try_from(mask).map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        "thread affinity mask exceeds processors",
+                    )
+                })?;
+                let previous = winbase::SetThreadAffinityMask(thread, mask);
+                if previous == 0 {
+                    return Err(crate::last_error("failed to set thread affinity"));
+                }
+                configuration.affinity = Some(previous);
+            }
+        }
+        Ok(configuration)
+    }
+}
+
+impl Drop for ThreadConfiguration {
+    fn drop(&mut self) {
+        unsafe {
+            let thread = processthreadsapi::GetCurrentThread();
+            if let Some(affinity) = self.affinity {
+                winbase::SetThreadAffinityMask(thread, affinity);
+            }
+            if let Some(priority) = self.priority {
+                processthreadsapi::SetThreadPriority(thread, priority);
+            }
+        }
+    }
+}
+
+fn no_event_thread() -> Error {
+    Error::new(ErrorKind::InvalidState, "no event thread is running")
+}
+
 unsafe fn dispatch(message: *mut winuser::MSG) {
+    // Hotkeys are registered without a window, so `WM_HOTKEY` is posted to
+    // the thread and is never dispatched to a window procedure.
+    if (*message).hwnd.is_null() && (*message).message == winuser::WM_HOTKEY {
+        let _ = react(Event::Application {
+            event: HotkeyPressed(HotkeyId::from_raw_handle((*message).wParam as u32)),
+            timestamp: crate::message_timestamp(),
+        });
+        return;
+    }
+    // Timers are likewise set without a window.
+    if (*message).hwnd.is_null() && (*message).message == winuser::WM_TIMER {
+        if let Some(id) = timer::elapse((*message).wParam) {
+            let _ = react(Event::Application {
+                event: ApplicationEvent::Timer(id),
+                timestamp: crate::message_timestamp(),
+            });
+        }
+        return;
+    }
+    // Console control events are posted by the console control handler.
+    if (*message).hwnd.is_null() && (*message).message == *console::WM_CONSOLE_CONTROL {
+        let _ = react(Event::Application {
+            event: ApplicationEvent::Terminating,
+            timestamp: crate::message_timestamp(),
+        });
+        return;
+    }
+    if (*message).hwnd.is_null() && (*message).message >= winuser::WM_USER {
+        react_message(
+            None,
+            (*message).message,
+            (*message).wParam,
+            (*message).lParam,
+        );
+        return;
+    }
     winuser::TranslateMessage(message);
     winuser::DispatchMessageW(message); // May call `reactor::react`.
 }
@@ -0,0 +1,99 @@
+use gaudium_core::error::Error;
+use gaudium_core::platform;
+use gaudium_core::reactor::{Recurrence, ThreadContext, TimerId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr;
+use std::time::Duration;
+use winapi::shared::{basetsd, minwindef};
+use winapi::um::winuser;
+
+thread_local! {
+    // Timers of the event thread keyed by the identifiers assigned by
+    // `SetTimer`.
+    static TIMERS: RefCell<HashMap<basetsd::UINT_PTR, (TimerId, Recurrence)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Sets timers via `SetTimer` without a window, so that `WM_TIMER` is posted
+/// to the event thread.
+///
+/// Thread timers are assigned identifiers by the system, so the identifiers
+/// chosen by the application are mapped to these identifiers. `WM_TIMER`
+/// messages that are posted while a modal loop is running, such as when a
+/// window is resized interactively, are discarded by that loop.
+pub struct Timer;
+
+impl platform::Timer for Timer {
+    fn set(
+        _: &ThreadContext,
+        id: TimerId,
+        duration: Duration,
+        recurrence: Recurrence,
+    ) -> Result<(), Error> {
+        cancel(id);
+        let milliseconds = duration.as_millis().clamp(
+            winuser::USER_TIMER_MINIMUM as u128,
+            winuser::USER_TIMER_MAXIMUM as u128,
+        ) as minwindef::UINT;
+        let timer = unsafe { winuser::SetTimer(ptr::null_mut(), 0, milliseconds, None) };
+        if timer == 0 {
+            Err(crate::last_error("failed to set timer"))
+        }
+        else {
+            TIMERS.with(|timers| timers.borrow_mut().insert(timer, (id, recurrence)));
+            Ok(())
+        }
+    }
+
+    fn cancel(_: &ThreadContext, id: TimerId) {
+        cancel(id);
+    }
+}
+
+fn cancel(id: TimerId) {
+    TIMERS.with(|timers| {
+        timers.borrow_mut().retain(|&timer, &mut (other, _)| {
+            if other == id {
+                unsafe {
+                    winuser::KillTimer(ptr::null_mut(), timer);
+                }
+                false
+            }
+            else {
+                true
+            }
+        })
+    });
+}
+
+/// Gets the identifier of the timer that posted a `WM_TIMER` message to the
+/// event thread, if any.
+///
+/// Timers that do not recur are killed.
+pub fn elapse(timer: basetsd::UINT_PTR) -> Option<TimerId> {
+    TIMERS.with(|timers| {
+        let mut timers = timers.borrow_mut();
+        let (id, recurrence) = *timers.get(&timer)?;
+        if let Recurrence::Once = recurrence {
+            timers.remove(&timer);
+            unsafe {
+                winuser::KillTimer(ptr::null_mut(), timer);
+            }
+        }
+        Some(id)
+    })
+}
+
+/// Kills all timers of the event thread.
+///
+/// This is called when the event loop stops.
+pub fn cancel_all() {
+    TIMERS.with(|timers| {
+        for (timer, _) in timers.borrow_mut().drain() {
+            unsafe {
+                winuser::KillTimer(ptr::null_mut(), timer);
+            }
+        }
+    });
+}
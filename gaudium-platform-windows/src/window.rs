@@ -1,62 +1,412 @@
+use gaudium_core::accessibility::{AccessibleElement, Role};
 use gaudium_core::device::{DeviceHandle, Usage};
-use gaudium_core::display::{IntoLogical, IntoPhysical, LogicalUnit};
-use gaudium_core::event::{Event, InputEvent, WindowCloseState, WindowEvent};
-use gaudium_core::platform::{self, Handle as _, WindowBuilder as _};
+use gaudium_core::display::{
+    DisplayHandle, IntoLogical, IntoPhysical, LogicalPosition, LogicalSize, PhysicalPosition,
+    PhysicalSize,
+};
+use gaudium_core::error::{Error, ErrorKind};
+use gaudium_core::event::{
+    self, ApplicationEvent, Event, InputEvent, InputFocus, WindowCloseState, WindowEvent,
+};
+use gaudium_core::keyboard::KeyLayoutId;
+use gaudium_core::platform::{self, Surface as _, WindowBuilder as _};
 use gaudium_core::reactor::ThreadContext;
-use gaudium_core::window::WindowHandle;
+#[cfg(feature = "capture")]
+use gaudium_core::window::Image;
+use gaudium_core::window::{
+    CursorIcon, Fullscreen, Geometry, HitTest, HitTestResult, Icon, MouseMode, WindowHandle,
+    WindowKind, ZOrder,
+};
 use gaudium_core::FromRawHandle;
 use lazy_static::lazy_static;
+use std::any::Any;
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::mem;
+use std::os::raw;
 use std::ptr;
-use winapi::shared::{basetsd, minwindef, ntdef, windef};
-use winapi::um::{commctrl, libloaderapi, winuser};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use winapi::shared::{basetsd, minwindef, ntdef, windef, winerror};
+use winapi::um::{
+    commctrl, dbt, dwmapi, errhandlingapi, libloaderapi, processthreadsapi, wingdi, winuser,
+};
 
-use crate::input::{self, TryFromDeviceInfo};
-use crate::{keyboard, mouse, reactor, WideNullTerminated};
+use crate::accessibility::{Accessibility, Update};
+use crate::input::{self, RawInputFilter, TryFromDeviceInfo};
+use crate::{display, frame, keyboard, mouse, pen, reactor, WideNullTerminated};
 
 const WINDOW_SUBCLASS_ID: basetsd::UINT_PTR = 0;
 
+/// The timer that dispatches throttled `Resized` events while a window is
+/// resized interactively, during which the event loop does not flush.
+const RESIZE_TIMER_ID: basetsd::UINT_PTR = 1;
+const RESIZE_TIMER_INTERVAL: minwindef::UINT = 16;
+
+/// Registered window classes and the number of event threads that hold a
+/// reference to each.
+///
+/// A class is registered when the first reference is acquired and is
+/// unregistered when the last reference is released, so that processes that
+/// run event threads repeatedly via `run_and_join` do not leak classes.
+static WINDOW_CLASSES: Mutex<Vec<(WindowClass, usize)>> = Mutex::new(Vec::new());
+
+thread_local! {
+    // The window classes to which the calling thread holds a reference.
+    static THREAD_WINDOW_CLASSES: RefCell<Vec<WindowClass>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The serial number assigned to the next window.
+///
+/// Window handles may be recycled, so serial numbers identify windows when
+/// their destruction is requested from other threads.
+static NEXT_SERIAL: AtomicUsize = AtomicUsize::new(1);
+
 lazy_static! {
     static ref WM_DROP: minwindef::UINT =
-        unsafe { winuser::RegisterWindowMessageA("WM_DROP".as_ptr() as ntdef::LPCSTR) };
-    static ref WINDOW_CLASS_NAME: Vec<ntdef::WCHAR> = {
-        let name = "GAUDIUM_WINDOW_CLASS".wide_null_terminated();
-        unsafe {
-            let class = winuser::WNDCLASSEXW {
-                cbSize: mem::size_of::<winuser::WNDCLASSEXW>() as minwindef::UINT,
-                style: winuser::CS_HREDRAW | winuser::CS_VREDRAW | winuser::CS_OWNDC,
-                lpfnWndProc: Some(winuser::DefWindowProcW),
-                cbClsExtra: 0,
-                cbWndExtra: 0,
-                hInstance: libloaderapi::GetModuleHandleW(ptr::null()),
-                hIcon: ptr::null_mut(),
-                hCursor: ptr::null_mut(),
-                hbrBackground: ptr::null_mut(),
-                lpszMenuName: ptr::null(),
-                lpszClassName: name.as_ptr(),
-                hIconSm: ptr::null_mut(),
-            };
-            winuser::RegisterClassExW(&class);
+        unsafe { winuser::RegisterWindowMessageA("WM_DROP\0".as_ptr() as ntdef::LPCSTR) };
+    static ref WM_SET_MOUSE_MODE: minwindef::UINT =
+        unsafe { winuser::RegisterWindowMessageA("WM_SET_MOUSE_MODE\0".as_ptr() as ntdef::LPCSTR) };
+    static ref WM_SET_CURSOR_ICON: minwindef::UINT = unsafe {
+        winuser::RegisterWindowMessageA("WM_SET_CURSOR_ICON\0".as_ptr() as ntdef::LPCSTR)
+    };
+    static ref WM_SET_CONSTRAINTS: minwindef::UINT = unsafe {
+        winuser::RegisterWindowMessageA("WM_SET_CONSTRAINTS\0".as_ptr() as ntdef::LPCSTR)
+    };
+    static ref WM_SET_POINTER_CAPTURE: minwindef::UINT = unsafe {
+        winuser::RegisterWindowMessageA("WM_SET_POINTER_CAPTURE\0".as_ptr() as ntdef::LPCSTR)
+    };
+    static ref WM_SET_MODAL: minwindef::UINT =
+        unsafe { winuser::RegisterWindowMessageA("WM_SET_MODAL\0".as_ptr() as ntdef::LPCSTR) };
+    static ref WM_SET_ACCESSIBILITY: minwindef::UINT = unsafe {
+        winuser::RegisterWindowMessageA("WM_SET_ACCESSIBILITY\0".as_ptr() as ntdef::LPCSTR)
+    };
+    static ref WM_REQUEST_FRAME: minwindef::UINT =
+        unsafe { winuser::RegisterWindowMessageA("WM_REQUEST_FRAME\0".as_ptr() as ntdef::LPCSTR) };
+    static ref WM_SET_USER_DATA: minwindef::UINT =
+        unsafe { winuser::RegisterWindowMessageA("WM_SET_USER_DATA\0".as_ptr() as ntdef::LPCSTR) };
+    static ref WINDOW_CLASS_NAME: Vec<ntdef::WCHAR> = "GAUDIUM_WINDOW_CLASS".wide_null_terminated();
+    static ref GET_DPI_FOR_WINDOW: Option<GetDpiForWindow> = unsafe { load_get_dpi_for_window() };
+}
+
+type GetDpiForWindow = unsafe extern "system" fn(windef::HWND) -> minwindef::UINT;
+
+type UserData = Box<dyn Any + Send>;
+
+/// A function that observes the messages of a window before they are handled.
+///
+/// If the function returns a result, then the message is consumed.
+pub type MessageHook = Box<
+    dyn FnMut(
+        windef::HWND,
+        minwindef::UINT,
+        minwindef::WPARAM,
+        minwindef::LPARAM,
+    ) -> Option<minwindef::LRESULT>,
+>;
+
+/// Per-window state used by the window procedure.
+///
+/// Window state is allocated when a window is created and is freed when the
+/// window is destroyed.
+#[derive(Default)]
+pub struct WindowState {
+    // Whether or not `WM_MOUSELEAVE` has been requested via `TrackMouseEvent`.
+    // This is used to detect the mouse entering the window.
+    is_tracking_mouse: bool,
+    mouse_mode: MouseMode,
+    // The cursor set when the pointer is within the client area. If `None`,
+    // then the arrow cursor is used.
+    cursor: Option<windef::HCURSOR>,
+    // Whether or not the pointer is captured while mouse buttons are held.
+    is_pointer_capture_automatic: bool,
+    // Whether or not the pointer has been captured via `set_pointer_capture`.
+    // Manual capture is not released when mouse buttons are released.
+    is_pointer_captured: bool,
+    constraints: Constraints,
+    user_data: Option<UserData>,
+    // Whether or not the window is the message-only sink of a headless event
+    // thread. Such windows are not exposed to reactors.
+    is_message_only: bool,
+    // Whether or not the window has disabled its owner via `set_modal`.
+    is_modal: bool,
+    // The serial number of the window. See `NEXT_SERIAL`.
+    serial: usize,
+    // The liveness flag shared with surface tokens, if any have been created.
+    // This is cleared when the window is destroyed.
+    is_alive: Option<Arc<AtomicBool>>,
+    // The UI Automation provider of the window, if accessibility has been
+    // configured.
+    accessibility: Option<Accessibility>,
+    // Whether or not a frame has been requested via `request_frame` and
+    // `WindowEvent::FrameReady` has not yet been dispatched.
+    is_frame_requested: bool,
+    // Functions that observe messages before they are handled, in the order
+    // in which they were added. See `add_message_hook`.
+    hooks: Vec<Rc<RefCell<MessageHook>>>,
+    // Whether or not the window is fullscreen. This is set by
+    // `set_fullscreen`, because fullscreen windows are otherwise
+    // indistinguishable from normal windows in `WM_SIZE`.
+    is_fullscreen: bool,
+    // The state of the window that was last dispatched via
+    // `WindowEvent::StateChanged`.
+    show_state: event::WindowState,
+    // Whether or not the window is being resized interactively. This is set
+    // by the first `WM_SIZING` within a modal sizing or moving loop.
+    is_resizing: bool,
+    // Determines the region of the window at a position in `WM_NCHITTEST`.
+    // See `set_hit_test`.
+    hit_test: Option<HitTest>,
+}
+
+/// The position at which a window is created.
+#[derive(Clone, Copy, Debug)]
+enum Origin {
+    /// The position is chosen by the system via `CW_USEDEFAULT`.
+    Default,
+    /// The outer top-left corner of the window is at the given position.
+    At(PhysicalPosition),
+    /// The window is centered in the given area.
+    Centered(PhysicalPosition, PhysicalSize),
+}
+
+/// Constraints on the dimensions of the client area of a window in physical
+/// units.
+#[derive(Clone, Copy, Debug, Default)]
+struct Constraints {
+    min_dimensions: Option<(u32, u32)>,
+    max_dimensions: Option<(u32, u32)>,
+    aspect_ratio: Option<f64>,
+}
+
+impl Constraints {
+    /// Gets the dimensions nearest to the given dimensions that satisfy the
+    /// constraints.
+    ///
+    /// The aspect ratio is applied first, so minimum and maximum dimensions
+    /// take precedence if they conflict with it.
+    fn fit(&self, dimensions: (u32, u32)) -> (u32, u32) {
+        let (mut width, mut height) = dimensions;
+        if let Some(ratio) = self.aspect_ratio {
+            height = (f64::from(width) / ratio).round() as u32;
+        }
+        if let Some((min_width, min_height)) = self.min_dimensions {
+            width = width.max(min_width);
+            height = height.max(min_height);
+        }
+        if let Some((max_width, max_height)) = self.max_dimensions {
+            width = width.min(max_width);
+            height = height.min(max_height);
+        }
+        (width, height)
+    }
+}
+
+/// A window class.
+///
+/// Classes are identified by their name and module. Windows are created with
+/// the default class unless the class is customized via `WindowBuilderExt`, in
+/// which case a separate class is registered.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct WindowClass {
+    name: Vec<ntdef::WCHAR>,
+    // The icon resource is identified by its ordinal.
+    icon: Option<minwindef::WORD>,
+    // The module is stored as an address so that classes can be shared
+    // between threads.
+    instance: usize,
+}
+
+impl WindowClass {
+    fn instance(&self) -> minwindef::HINSTANCE {
+        self.instance as minwindef::HINSTANCE
+    }
+
+    /// Acquires a reference to the class for the calling thread, registering
+    /// it if necessary.
+    ///
+    /// The reference is released by `release_window_classes`. Fails if the class
+    /// cannot be registered or if a class with the same name and module has
+    /// been acquired with other attributes.
+    fn acquire(&self) -> Result<(), Error> {
+        if THREAD_WINDOW_CLASSES.with(|classes| classes.borrow().contains(self)) {
+            return Ok(());
         }
-        name
+        let mut classes = WINDOW_CLASSES.lock().unwrap();
+        match classes
+            .iter_mut()
+            .find(|(class, _)| class.name == self.name && class.instance == self.instance)
+        {
+            Some((class, _)) if class != self => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "window class is registered with other attributes",
+                ));
+            }
+            Some((_, references)) => {
+                *references += 1;
+            }
+            None => {
+                unsafe {
+                    let icon = match self.icon {
+                        Some(icon) => {
+                            let icon = winuser::LoadIconW(
+                                self.instance(),
+                                winuser::MAKEINTRESOURCEW(icon),
+                            );
+                            if icon.is_null() {
+                                return Err(crate::last_error("failed to load class icon"));
+                            }
+                            icon
+                        }
+                        None => ptr::null_mut(),
+                    };
+                    let class = winuser::WNDCLASSEXW {
+                        cbSize: mem::size_of::<winuser::WNDCLASSEXW>() as minwindef::UINT,
+                        style: winuser::CS_HREDRAW | winuser::CS_VREDRAW | winuser::CS_OWNDC,
+                        lpfnWndProc: Some(winuser::DefWindowProcW),
+                        cbClsExtra: 0,
+                        cbWndExtra: 0,
+                        hInstance: self.instance(),
+                        hIcon: icon,
+                        hCursor: ptr::null_mut(),
+                        hbrBackground: ptr::null_mut(),
+                        lpszMenuName: ptr::null(),
+                        lpszClassName: self.name.as_ptr(),
+                        hIconSm: ptr::null_mut(),
+                    };
+                    // The class may already have been registered by another
+                    // instance of this crate within the process.
+                    if winuser::RegisterClassExW(&class) == 0
+                        && errhandlingapi::GetLastError() != winerror::ERROR_CLASS_ALREADY_EXISTS
+                    {
+                        return Err(crate::last_error("failed to register window class"));
+                    }
+                }
+                classes.push((self.clone(), 1));
+            }
+        }
+        THREAD_WINDOW_CLASSES.with(|classes| classes.borrow_mut().push(self.clone()));
+        Ok(())
+    }
+}
+
+impl Default for WindowClass {
+    fn default() -> Self {
+        WindowClass {
+            name: WINDOW_CLASS_NAME.clone(),
+            icon: None,
+            instance: unsafe { libloaderapi::GetModuleHandleW(ptr::null()) } as usize,
+        }
+    }
+}
+
+/// Releases the references of the calling thread to window classes, if any.
+///
+/// Classes are unregistered when no references remain. This is used when an
+/// event loop stops, after the windows of the thread have been destroyed.
+pub fn release_window_classes() {
+    let released = THREAD_WINDOW_CLASSES.with(|classes| mem::take(&mut *classes.borrow_mut()));
+    if released.is_empty() {
+        return;
+    }
+    let mut classes = WINDOW_CLASSES.lock().unwrap();
+    for class in released {
+        if let Some(index) = classes.iter().position(|(other, _)| *other == class) {
+            classes[index].1 -= 1;
+            if classes[index].1 == 0 {
+                classes.swap_remove(index);
+                // Unregistration fails if any windows of the class remain,
+                // such as windows created by threads that have no event loop.
+                unsafe {
+                    winuser::UnregisterClassW(class.name.as_ptr(), class.instance());
+                }
+            }
+        }
+    }
+}
+
+/// Loads `GetDpiForWindow`, which is not available before Windows 10
+/// (version 1607).
+unsafe fn load_get_dpi_for_window() -> Option<GetDpiForWindow> {
+    let module = libloaderapi::GetModuleHandleW("user32.dll".wide_null_terminated().as_ptr());
+    if module.is_null() {
+        return None;
+    }
+    let function =
+        libloaderapi::GetProcAddress(module, "GetDpiForWindow\0".as_ptr() as ntdef::LPCSTR);
+    if function.is_null() {
+        None
+    }
+    else {
+        Some(mem::transmute::<minwindef::FARPROC, GetDpiForWindow>(
+            function,
+        ))
+    }
+}
+
+/// Gets the DPI factor of a window.
+///
+/// If `window` is null or `GetDpiForWindow` is unavailable, then this is the
+/// DPI factor of the display device context, which is the system DPI unless
+/// the window is per-monitor DPI aware.
+fn dpi_factor(window: windef::HWND) -> f64 {
+    let dpi = unsafe {
+        GET_DPI_FOR_WINDOW
+            .map(|get_dpi_for_window| get_dpi_for_window(window))
+            .filter(|dpi| *dpi != 0)
+            .unwrap_or_else(|| {
+                let context = winuser::GetDC(window);
+                if context.is_null() {
+                    return 0;
+                }
+                let dpi = wingdi::GetDeviceCaps(context, wingdi::LOGPIXELSX);
+                winuser::ReleaseDC(window, context);
+                dpi as minwindef::UINT
+            })
     };
+    if dpi == 0 {
+        1.0
+    }
+    else {
+        f64::from(dpi) / f64::from(winuser::USER_DEFAULT_SCREEN_DPI)
+    }
+}
+
+fn into_physical_dimensions(dimensions: LogicalSize, dpi: f64) -> (u32, u32) {
+    let PhysicalSize { width, height } = dimensions.into_physical(dpi);
+    (width.into(), height.into())
 }
 
-// TODO: This will typically leak given the current structure of window
-//       destruction.
-#[derive(Debug, Default)]
-pub struct WindowState;
+/// Converts an opacity into the alpha of a layered window.
+fn alpha(opacity: f32) -> u8 {
+    (opacity * 255.0).round() as u8
+}
 
 pub struct WindowBuilder {
     title: String,
     dimensions: (u32, u32),
-    // TODO: This should be more than a simple `bool`. A display must be
-    //       targeted, for example. Improve this once it is possible to query
-    //       and target displays.
-    exclusive: bool,
+    origin: Origin,
+    geometry: Option<Geometry>,
+    fullscreen: Option<Fullscreen>,
     parent: Option<windef::HWND>,
+    owner: Option<windef::HWND>,
+    kind: WindowKind,
+    icon: Option<Icon>,
+    is_always_on_top: bool,
+    is_active: bool,
+    has_decorations: bool,
+    is_transparent: bool,
+    opacity: f32,
+    is_resizable: bool,
+    constraints: Constraints,
+    raw_input_filter: RawInputFilter,
+    is_background_input: bool,
+    is_pointer_capture_automatic: bool,
+    class: WindowClass,
 }
 
 impl WindowBuilder {
@@ -68,20 +418,84 @@ impl WindowBuilder {
         self
     }
 
-    pub fn with_dimensions<T>(mut self, dimensions: (T, T)) -> Self
+    pub fn with_dimensions<T>(mut self, dimensions: T) -> Self
+    where
+        T: Into<LogicalSize>,
+    {
+        self.dimensions = into_physical_dimensions(dimensions.into(), dpi_factor(ptr::null_mut()));
+        self
+    }
+
+    pub fn with_raw_input_filter(mut self, filter: RawInputFilter) -> Self {
+        self.raw_input_filter = filter;
+        self
+    }
+
+    pub fn with_background_input(mut self, background: bool) -> Self {
+        self.is_background_input = background;
+        self
+    }
+
+    pub fn with_automatic_pointer_capture(mut self, automatic: bool) -> Self {
+        self.is_pointer_capture_automatic = automatic;
+        self
+    }
+
+    pub fn with_class_name<T>(mut self, name: T) -> Self
     where
-        T: Into<LogicalUnit>,
+        T: AsRef<str>,
     {
-        let dpi = 1.0; // TODO: Get the DPI factor.
-        let (width, height) = dimensions.into_physical(dpi);
-        self.dimensions = (width.into(), height.into());
+        self.class.name = name.as_ref().wide_null_terminated();
         self
     }
 
-    fn with_parent_window(mut self, parent: &Window) -> Self {
-        self.parent = Some(parent.handle());
+    pub fn with_class_icon_resource(mut self, icon: minwindef::WORD) -> Self {
+        self.class.icon = Some(icon);
         self
     }
+
+    pub fn with_instance(mut self, instance: minwindef::HINSTANCE) -> Self {
+        self.class.instance = instance as usize;
+        self
+    }
+
+    /// Rejects configurations that cannot produce a meaningful window.
+    fn validate(&self) -> Result<(), Error> {
+        let invalid = |context| Err(Error::new(ErrorKind::InvalidInput, context));
+        if self.title.contains('\0') {
+            return invalid("window title contains a null character");
+        }
+        if let Some((_, name)) = self.class.name.split_last() {
+            if name.is_empty() || name.contains(&0) {
+                return invalid("window class name is empty or contains a null character");
+            }
+        }
+        if self.class.instance == 0 {
+            return invalid("window class module is null");
+        }
+        if self.dimensions.0 == 0 || self.dimensions.1 == 0 {
+            return invalid("window dimensions are zero");
+        }
+        let Constraints {
+            min_dimensions,
+            max_dimensions,
+            aspect_ratio,
+        } = self.constraints;
+        if let (Some((min_width, min_height)), Some((max_width, max_height))) =
+            (min_dimensions, max_dimensions)
+        {
+            if min_width > max_width || min_height > max_height {
+                return invalid("minimum window dimensions exceed maximum window dimensions");
+            }
+        }
+        if aspect_ratio.is_some_and(|ratio| !ratio.is_finite() || ratio <= 0.0) {
+            return invalid("window aspect ratio is not positive and finite");
+        }
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return invalid("window opacity is not in the range [0, 1]");
+        }
+        Ok(())
+    }
 }
 
 impl Default for WindowBuilder {
@@ -89,8 +503,24 @@ impl Default for WindowBuilder {
         WindowBuilder {
             title: String::new(),
             dimensions: (640, 480),
-            exclusive: false,
+            origin: Origin::Default,
+            geometry: None,
+            fullscreen: None,
             parent: None,
+            owner: None,
+            kind: WindowKind::Normal,
+            icon: None,
+            is_always_on_top: false,
+            is_active: true,
+            has_decorations: true,
+            is_transparent: false,
+            opacity: 1.0,
+            is_resizable: true,
+            constraints: Constraints::default(),
+            raw_input_filter: RawInputFilter::default(),
+            is_background_input: false,
+            is_pointer_capture_automatic: true,
+            class: WindowClass::default(),
         }
     }
 }
@@ -98,25 +528,287 @@ impl Default for WindowBuilder {
 impl platform::WindowBuilder for WindowBuilder {
     type Window = Window;
 
-    fn build(self, context: &ThreadContext) -> Result<Self::Window, ()> {
+    fn build(self, context: &ThreadContext) -> Result<Self::Window, Error> {
         Window::new(self, context)
     }
+
+    fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    fn with_always_on_top(mut self, always_on_top: bool) -> Self {
+        self.is_always_on_top = always_on_top;
+        self
+    }
+
+    fn with_active(mut self, active: bool) -> Self {
+        self.is_active = active;
+        self
+    }
+
+    fn with_decorations(mut self, decorations: bool) -> Self {
+        self.has_decorations = decorations;
+        self
+    }
+
+    fn with_transparent(mut self, transparent: bool) -> Self {
+        self.is_transparent = transparent;
+        self
+    }
+
+    fn with_resizable(mut self, resizable: bool) -> Self {
+        self.is_resizable = resizable;
+        self
+    }
+
+    fn with_min_dimensions(mut self, dimensions: LogicalSize) -> Self {
+        self.constraints.min_dimensions = Some(into_physical_dimensions(
+            dimensions,
+            dpi_factor(ptr::null_mut()),
+        ));
+        self
+    }
+
+    fn with_max_dimensions(mut self, dimensions: LogicalSize) -> Self {
+        self.constraints.max_dimensions = Some(into_physical_dimensions(
+            dimensions,
+            dpi_factor(ptr::null_mut()),
+        ));
+        self
+    }
+
+    fn with_kind(mut self, kind: WindowKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    fn with_owner(mut self, owner: windef::HWND) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    fn with_parent(mut self, parent: windef::HWND) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    fn with_position(mut self, position: PhysicalPosition) -> Self {
+        self.origin = Origin::At(position);
+        self
+    }
+
+    fn with_centered_in(mut self, position: PhysicalPosition, area: PhysicalSize) -> Self {
+        self.origin = Origin::Centered(position, area);
+        self
+    }
+
+    fn with_geometry(mut self, geometry: Geometry) -> Self {
+        self.geometry = Some(geometry);
+        self
+    }
+
+    fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    fn with_fullscreen(mut self, fullscreen: Fullscreen) -> Self {
+        self.fullscreen = Some(fullscreen);
+        self
+    }
+
+    fn with_aspect_ratio(mut self, ratio: f64) -> Self {
+        self.constraints.aspect_ratio = Some(ratio);
+        self
+    }
+}
+
+struct IconHandle(windef::HICON);
+
+impl IconHandle {
+    fn from_icon(icon: &Icon) -> Result<Self, Error> {
+        // Icons use BGRA pixels. Transparency is determined by the alpha
+        // channel, so the AND mask is empty. Rows of the mask are aligned to
+        // `WORD`s.
+        let mut bgra = icon.rgba().to_vec();
+        for pixel in bgra.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        let mask = vec![0u8; (icon.width() as usize).div_ceil(16) * 2 * icon.height() as usize];
+        let handle = unsafe {
+            winuser::CreateIcon(
+                ptr::null_mut(),
+                icon.width() as raw::c_int,
+                icon.height() as raw::c_int,
+                1,
+                32,
+                mask.as_ptr(),
+                bgra.as_ptr(),
+            )
+        };
+        if handle.is_null() {
+            Err(crate::last_error("failed to create icon"))
+        }
+        else {
+            Ok(IconHandle(handle))
+        }
+    }
+}
+
+impl Drop for IconHandle {
+    fn drop(&mut self) {
+        unsafe {
+            winuser::DestroyIcon(self.0);
+        }
+    }
+}
+
+/// A cursor created from a custom image.
+///
+/// System cursors are shared and are never destroyed, so only custom cursors
+/// use this type.
+struct CursorHandle(windef::HCURSOR);
+
+impl CursorHandle {
+    fn from_icon(icon: &Icon, hotspot: (u32, u32)) -> Result<Self, Error> {
+        let mut bgra = icon.rgba().to_vec();
+        for pixel in bgra.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        let mask = vec![0u8; (icon.width() as usize).div_ceil(16) * 2 * icon.height() as usize];
+        unsafe {
+            let color = wingdi::CreateBitmap(
+                icon.width() as raw::c_int,
+                icon.height() as raw::c_int,
+                1,
+                32,
+                bgra.as_ptr() as *const _,
+            );
+            let mask = wingdi::CreateBitmap(
+                icon.width() as raw::c_int,
+                icon.height() as raw::c_int,
+                1,
+                1,
+                mask.as_ptr() as *const _,
+            );
+            let mut info = winuser::ICONINFO {
+                fIcon: minwindef::FALSE,
+                xHotspot: hotspot.0,
+                yHotspot: hotspot.1,
+                hbmMask: mask,
+                hbmColor: color,
+            };
+            let handle = if color.is_null() || mask.is_null() {
+                ptr::null_mut()
+            }
+            else {
+                winuser::CreateIconIndirect(&mut info)
+            };
+            // The cursor copies the bitmaps, so they are always deleted.
+            if !color.is_null() {
+                wingdi::DeleteObject(color as *mut _);
+            }
+            if !mask.is_null() {
+                wingdi::DeleteObject(mask as *mut _);
+            }
+            if handle.is_null() {
+                Err(crate::last_error("failed to create cursor"))
+            }
+            else {
+                Ok(CursorHandle(handle))
+            }
+        }
+    }
+}
+
+impl Drop for CursorHandle {
+    fn drop(&mut self) {
+        unsafe {
+            winuser::DestroyCursor(self.0);
+        }
+    }
+}
+
+fn system_cursor(icon: &CursorIcon) -> ntdef::LPCWSTR {
+    match *icon {
+        CursorIcon::Arrow | CursorIcon::Custom { .. } => winuser::IDC_ARROW,
+        CursorIcon::Text => winuser::IDC_IBEAM,
+        CursorIcon::Hand => winuser::IDC_HAND,
+        CursorIcon::Crosshair => winuser::IDC_CROSS,
+        CursorIcon::Wait => winuser::IDC_WAIT,
+        CursorIcon::Progress => winuser::IDC_APPSTARTING,
+        CursorIcon::Help => winuser::IDC_HELP,
+        CursorIcon::NotAllowed => winuser::IDC_NO,
+        CursorIcon::Move => winuser::IDC_SIZEALL,
+        CursorIcon::ResizeHorizontal => winuser::IDC_SIZEWE,
+        CursorIcon::ResizeVertical => winuser::IDC_SIZENS,
+        CursorIcon::ResizeNorthwestSoutheast => winuser::IDC_SIZENWSE,
+        CursorIcon::ResizeNortheastSouthwest => winuser::IDC_SIZENESW,
+    }
 }
 
 pub struct Window {
     handle: windef::HWND,
+    serial: usize,
+    class_name: Vec<ntdef::WCHAR>,
     children: HashSet<Window>,
+    icon: Option<IconHandle>,
+    cursor: Option<CursorHandle>,
+    // A copy of the constraints used by the window procedure. Constraints are
+    // always replaced as a whole.
+    constraints: Constraints,
+    raw_input_filter: RawInputFilter,
+    is_background_input: bool,
+    fullscreen: Option<FullscreenState>,
+}
+
+/// The fullscreen mode of a window and the state that is restored when it
+/// leaves fullscreen.
+struct FullscreenState {
+    fullscreen: Fullscreen,
+    monitor: windef::HMONITOR,
+    placement: Placement,
+}
+
+/// Styles and placement of a window that are replaced while it is fullscreen.
+#[derive(Clone, Copy)]
+struct Placement {
+    style: minwindef::DWORD,
+    extended_style: minwindef::DWORD,
+    placement: winuser::WINDOWPLACEMENT,
 }
 
 impl Window {
-    fn new(builder: WindowBuilder, _: &ThreadContext) -> Result<Self, ()> {
+    fn new(builder: WindowBuilder, _: &ThreadContext) -> Result<Self, Error> {
+        builder.validate()?;
         let WindowBuilder {
             ref title,
             dimensions,
+            origin,
+            geometry,
             mut parent,
+            owner,
+            kind,
+            icon,
+            is_always_on_top,
+            is_active,
+            has_decorations,
+            is_transparent,
+            opacity,
+            is_resizable,
+            constraints,
+            raw_input_filter,
+            is_background_input,
+            is_pointer_capture_automatic,
+            fullscreen,
+            class,
             ..
         } = builder;
-        let (parent, style, extended_style) = if let Some(parent) = parent.take() {
+        class.acquire()?;
+        let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+        let dimensions = constraints.fit(dimensions);
+        let (parent, mut style, mut extended_style) = if let Some(parent) = parent.take() {
             (
                 parent,
                 winuser::WS_CAPTION | winuser::WS_CHILD | winuser::WS_VISIBLE,
@@ -124,15 +816,57 @@ impl Window {
             )
         }
         else {
+            // Top-level windows are shown after they are created so that
+            // activation can be controlled. The parent of a top-level window
+            // is its owner.
+            let (style, extended_style) = match kind {
+                WindowKind::Normal => (
+                    winuser::WS_OVERLAPPEDWINDOW,
+                    winuser::WS_EX_APPWINDOW | winuser::WS_EX_WINDOWEDGE,
+                ),
+                WindowKind::Popup => (winuser::WS_POPUP, winuser::WS_EX_TOOLWINDOW),
+                WindowKind::Tooltip => (
+                    winuser::WS_POPUP,
+                    winuser::WS_EX_NOACTIVATE | winuser::WS_EX_TOOLWINDOW | winuser::WS_EX_TOPMOST,
+                ),
+                WindowKind::Utility => (
+                    winuser::WS_OVERLAPPEDWINDOW
+                        & !(winuser::WS_MINIMIZEBOX | winuser::WS_MAXIMIZEBOX),
+                    winuser::WS_EX_TOOLWINDOW | winuser::WS_EX_WINDOWEDGE,
+                ),
+            };
             (
-                ptr::null_mut(),
-                winuser::WS_CLIPCHILDREN
-                    | winuser::WS_CLIPSIBLINGS
-                    | winuser::WS_OVERLAPPEDWINDOW
-                    | winuser::WS_VISIBLE,
-                winuser::WS_EX_APPWINDOW | winuser::WS_EX_WINDOWEDGE,
+                owner.unwrap_or(ptr::null_mut()),
+                winuser::WS_CLIPCHILDREN | winuser::WS_CLIPSIBLINGS | style,
+                extended_style
+                    | if is_always_on_top {
+                        winuser::WS_EX_TOPMOST
+                    }
+                    else {
+                        0
+                    },
             )
         };
+        if !has_decorations {
+            style &= !(winuser::WS_CAPTION
+                | winuser::WS_THICKFRAME
+                | winuser::WS_SYSMENU
+                | winuser::WS_MINIMIZEBOX
+                | winuser::WS_MAXIMIZEBOX);
+            if style & winuser::WS_CHILD == 0 {
+                style |= winuser::WS_POPUP;
+            }
+            extended_style &= !winuser::WS_EX_WINDOWEDGE;
+        }
+        if !is_resizable {
+            style &= !(winuser::WS_THICKFRAME | winuser::WS_MAXIMIZEBOX);
+        }
+        // Windows are only layered while they are translucent, because layered
+        // windows are more expensive to compose.
+        let is_layered = opacity < 1.0;
+        if is_layered {
+            extended_style |= winuser::WS_EX_LAYERED;
+        }
         let rectangle = unsafe {
             let mut rectangle = windef::RECT {
                 left: 0,
@@ -143,22 +877,79 @@ impl Window {
             winuser::AdjustWindowRectEx(&mut rectangle, style, 0, extended_style);
             rectangle
         };
+        let (width, height) = (
+            rectangle.right - rectangle.left,
+            rectangle.bottom - rectangle.top,
+        );
+        // Geometry describes the outer bounds of the window, so it is used
+        // as-is rather than adjusted.
+        let ((width, height), origin) = match geometry {
+            Some(Geometry {
+                position,
+                dimensions,
+                ..
+            }) => (
+                (dimensions.width.into(), dimensions.height.into()),
+                Origin::At(position),
+            ),
+            None => ((width, height), origin),
+        };
+        let (x, y) = match origin {
+            Origin::Default => (winuser::CW_USEDEFAULT, winuser::CW_USEDEFAULT),
+            Origin::At(position) => (position.x.into(), position.y.into()),
+            Origin::Centered(position, area) => {
+                let position =
+                    gaudium_core::display::center(position, area, PhysicalSize::new(width, height));
+                (position.x.into(), position.y.into())
+            }
+        };
         let handle = unsafe {
             let handle = winuser::CreateWindowExW(
                 extended_style,
-                WINDOW_CLASS_NAME.as_ptr(),
+                class.name.as_ptr(),
                 title.wide_null_terminated().as_ptr() as ntdef::LPCWSTR,
                 style,
-                winuser::CW_USEDEFAULT,
-                winuser::CW_USEDEFAULT,
-                rectangle.right - rectangle.left,
-                rectangle.bottom - rectangle.top,
+                x,
+                y,
+                width,
+                height,
                 parent,
                 ptr::null_mut(),
-                libloaderapi::GetModuleHandleW(ptr::null()),
+                class.instance(),
                 ptr::null_mut(),
             );
-            let state = Box::into_raw(Box::new(WindowState::default()));
+            if handle.is_null() {
+                return Err(crate::last_error("failed to create window"));
+            }
+            // Destroys the window if it cannot be initialized. Once the window
+            // is subclassed, the subclass must be removed and its state freed
+            // first.
+            let abandon = |error: Error| {
+                winuser::DestroyWindow(handle);
+                error
+            };
+            // Layered windows are not drawn until their attributes are set.
+            if is_layered
+                && winuser::SetLayeredWindowAttributes(
+                    handle,
+                    0,
+                    alpha(opacity),
+                    winuser::LWA_ALPHA,
+                ) == 0
+            {
+                return Err(abandon(crate::last_error(
+                    "failed to set layered window attributes",
+                )));
+            }
+            if is_transparent {
+                enable_transparency(handle).map_err(abandon)?;
+            }
+            let state = Box::into_raw(Box::new(WindowState {
+                constraints,
+                is_pointer_capture_automatic,
+                serial,
+                ..Default::default()
+            }));
             if commctrl::SetWindowSubclass(
                 handle,
                 Some(procedure),
@@ -166,86 +957,958 @@ impl Window {
                 state as basetsd::DWORD_PTR,
             ) == 0
             {
-                return Err(());
+                let error = crate::last_error("failed to subclass window");
+                drop(Box::from_raw(state));
+                return Err(abandon(error));
+            }
+            if let Err(error) = input::register(handle, raw_input_filter, is_background_input) {
+                // Remove the subclass so that `Closed` is not dispatched for
+                // a window that was never returned.
+                commctrl::RemoveWindowSubclass(handle, Some(procedure), WINDOW_SUBCLASS_ID);
+                drop(Box::from_raw(state));
+                return Err(abandon(error));
             }
             handle
         };
-        input::register(handle).unwrap();
-        Ok(Window {
+        // Establish the initially connected displays. Changes are detected
+        // against this state.
+        let _ = display::changes();
+        let mut window = Window {
             handle,
+            serial,
+            class_name: class.name,
             children: HashSet::new(),
-        })
+            icon: None,
+            cursor: None,
+            constraints,
+            raw_input_filter,
+            is_background_input,
+            fullscreen: None,
+        };
+        if icon.is_some() {
+            window.set_icon(icon)?;
+        }
+        if style & winuser::WS_CHILD == 0 {
+            if fullscreen.is_some() {
+                window.set_fullscreen(fullscreen)?;
+            }
+            unsafe {
+                winuser::ShowWindow(
+                    handle,
+                    // Tooltips are never activated.
+                    if geometry.is_some_and(|geometry| geometry.is_maximized) {
+                        winuser::SW_SHOWMAXIMIZED
+                    }
+                    else if is_active && kind != WindowKind::Tooltip {
+                        winuser::SW_SHOW
+                    }
+                    else {
+                        winuser::SW_SHOWNOACTIVATE
+                    },
+                );
+            }
+        }
+        Ok(window)
     }
 
-    pub fn insert(&mut self, builder: WindowBuilder, context: &ThreadContext) -> Result<(), ()> {
-        let builder = builder.with_parent_window(self);
+    pub fn insert(&mut self, builder: WindowBuilder, context: &ThreadContext) -> Result<(), Error> {
+        let builder = builder.with_parent(self.handle);
         builder
             .build(context)
             .map(|window| self.children.insert(window))
             .map(|_| ())
     }
 
-    pub fn transform<T>(&self, position: (T, T)) -> Result<(LogicalUnit, LogicalUnit), ()>
+    pub fn transform<T>(&self, position: T) -> Result<LogicalPosition, Error>
     where
-        T: Into<LogicalUnit>,
+        T: Into<LogicalPosition>,
     {
-        let dpi = 1.0; // TODO: Get the DPI factor.
-        let (x, y) = position.into_physical(dpi);
+        let dpi = dpi_factor(self.handle);
+        let PhysicalPosition { x, y } = position.into().into_physical(dpi);
         let mut point = windef::POINT {
             x: x.into(),
             y: y.into(),
         };
         unsafe {
             if winuser::ScreenToClient(self.handle, &mut point) != 0 {
-                Ok((point.x as i32, point.y as i32).into_logical(dpi))
+                Ok(PhysicalPosition::new(point.x, point.y).into_logical(dpi))
             }
             else {
-                Err(())
+                Err(crate::last_error("failed to transform position"))
             }
         }
     }
 
-    pub fn class_name(&self) -> &[ntdef::WCHAR] {
-        WINDOW_CLASS_NAME.as_slice()
+    /// Registers raw input for the window using the given filter.
+    ///
+    /// See `RawInputFilter`.
+    pub fn set_raw_input_filter(&mut self, filter: RawInputFilter) -> Result<(), Error> {
+        input::register(self.handle, filter, self.is_background_input)?;
+        self.raw_input_filter = filter;
+        Ok(())
     }
-}
 
-impl Drop for Window {
-    fn drop(&mut self) {
-        unsafe {
-            winuser::PostMessageW(self.handle, *WM_DROP, 0, 0);
-        }
+    /// Sets whether or not raw input is received while the window is in the
+    /// background.
+    ///
+    /// Background input is reported with `InputFocus::Background`.
+    pub fn set_background_input(&mut self, background: bool) -> Result<(), Error> {
+        input::register(self.handle, self.raw_input_filter, background)?;
+        self.is_background_input = background;
+        Ok(())
     }
-}
-
-impl Eq for Window {}
 
-impl platform::Handle for Window {
-    type Handle = windef::HWND;
+    /// Adds a function that observes the messages of the window before they
+    /// are handled.
+    ///
+    /// Hooks are stored in the window state, so they must be added on the
+    /// thread that owns the window and are dropped when it is destroyed.
+    pub fn add_message_hook(&mut self, hook: MessageHook) -> Result<(), Error> {
+        match unsafe { Window::state(self.handle) } {
+            Some(state) if state.serial == self.serial => {
+                state.hooks.push(Rc::new(RefCell::new(hook)));
+                Ok(())
+            }
+            _ => Err(Error::new(
+                ErrorKind::InvalidState,
+                "message hooks must be added on the thread that owns the window",
+            )),
+        }
+    }
 
-    fn handle(&self) -> Self::Handle {
-        self.handle
+    pub fn class_name(&self) -> &[ntdef::WCHAR] {
+        self.class_name.as_slice()
     }
-}
 
-impl Hash for Window {
-    fn hash<H>(&self, state: &mut H)
-    where
-        H: Hasher,
-    {
-        self.handle.hash(state);
+    /// Moves the window in the z-order after the given window (or the given
+    /// special `HWND_*` value) without moving, resizing, or activating it.
+    fn set_position_after(&mut self, after: windef::HWND) -> Result<(), Error> {
+        unsafe {
+            if winuser::SetWindowPos(
+                self.handle,
+                after,
+                0,
+                0,
+                0,
+                0,
+                winuser::SWP_NOACTIVATE | winuser::SWP_NOMOVE | winuser::SWP_NOSIZE,
+            ) == 0
+            {
+                Err(crate::last_error("failed to set window position"))
+            }
+            else {
+                Ok(())
+            }
+        }
     }
-}
 
-impl PartialEq for Window {
-    fn eq(&self, other: &Self) -> bool {
+    /// Gets the state of the window with the given handle.
+    ///
+    /// Returns `None` if the window has been destroyed or if it is not owned
+    /// by the calling thread.
+    unsafe fn state<'a>(handle: windef::HWND) -> Option<&'a mut WindowState> {
+        if winuser::GetWindowThreadProcessId(handle, ptr::null_mut())
+            != processthreadsapi::GetCurrentThreadId()
+        {
+            return None;
+        }
+        let mut state = 0;
+        if commctrl::GetWindowSubclass(handle, Some(procedure), WINDOW_SUBCLASS_ID, &mut state) != 0
+            && state != 0
+        {
+            Some(&mut *(state as *mut WindowState))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Enters or leaves fullscreen by replacing the styles and placement of
+    /// the window.
+    fn set_fullscreen_placement(&mut self, fullscreen: Option<Fullscreen>) -> Result<(), Error> {
+        // Leave any previous fullscreen mode, but only restore the placement
+        // of the window if it is leaving fullscreen entirely.
+        let placement = match self.fullscreen.take() {
+            Some(FullscreenState {
+                fullscreen: previous,
+                monitor,
+                placement,
+            }) => {
+                if let Fullscreen::Exclusive(_) = previous {
+                    <display::Display as platform::Display>::set_mode(monitor, None)?;
+                }
+                Some(placement)
+            }
+            None => None,
+        };
+        let fullscreen = match fullscreen {
+            Some(fullscreen) => fullscreen,
+            None => {
+                if let Some(ref placement) = placement {
+                    self.restore_placement(placement);
+                }
+                return Ok(());
+            }
+        };
+        let placement = match placement {
+            Some(placement) => placement,
+            None => self.placement()?,
+        };
+        let monitor =
+            unsafe { winuser::MonitorFromWindow(self.handle, winuser::MONITOR_DEFAULTTONEAREST) };
+        if let Fullscreen::Exclusive(mode) = fullscreen {
+            if let Err(error) =
+                <display::Display as platform::Display>::set_mode(monitor, Some(mode))
+            {
+                self.restore_placement(&placement);
+                return Err(error);
+            }
+        }
+        // The bounds of the display are queried after any change in mode.
+        let bounds = match display::monitor_info(monitor) {
+            Some(info) => info.rcMonitor,
+            None => {
+                self.restore_placement(&placement);
+                return Err(crate::last_error("failed to get display bounds"));
+            }
+        };
+        unsafe {
+            winuser::SetWindowLongW(
+                self.handle,
+                winuser::GWL_STYLE,
+                (placement.style & !(winuser::WS_CAPTION | winuser::WS_THICKFRAME)) as ntdef::LONG,
+            );
+            winuser::SetWindowLongW(
+                self.handle,
+                winuser::GWL_EXSTYLE,
+                (placement.extended_style
+                    & !(winuser::WS_EX_CLIENTEDGE
+                        | winuser::WS_EX_DLGMODALFRAME
+                        | winuser::WS_EX_STATICEDGE
+                        | winuser::WS_EX_WINDOWEDGE)) as ntdef::LONG,
+            );
+            winuser::SetWindowPos(
+                self.handle,
+                winuser::HWND_TOP,
+                bounds.left,
+                bounds.top,
+                bounds.right - bounds.left,
+                bounds.bottom - bounds.top,
+                winuser::SWP_FRAMECHANGED | winuser::SWP_NOACTIVATE | winuser::SWP_NOOWNERZORDER,
+            );
+        }
+        self.fullscreen = Some(FullscreenState {
+            fullscreen,
+            monitor,
+            placement,
+        });
+        Ok(())
+    }
+
+    fn placement(&self) -> Result<Placement, Error> {
+        unsafe {
+            let mut placement = mem::zeroed::<winuser::WINDOWPLACEMENT>();
+            placement.length = mem::size_of::<winuser::WINDOWPLACEMENT>() as minwindef::UINT;
+            if winuser::GetWindowPlacement(self.handle, &mut placement) == 0 {
+                return Err(crate::last_error("failed to get window placement"));
+            }
+            Ok(Placement {
+                style: winuser::GetWindowLongW(self.handle, winuser::GWL_STYLE) as minwindef::DWORD,
+                extended_style: winuser::GetWindowLongW(self.handle, winuser::GWL_EXSTYLE)
+                    as minwindef::DWORD,
+                placement,
+            })
+        }
+    }
+
+    fn restore_placement(&self, placement: &Placement) {
+        unsafe {
+            winuser::SetWindowLongW(
+                self.handle,
+                winuser::GWL_STYLE,
+                placement.style as ntdef::LONG,
+            );
+            // Opacity may have changed while the window was fullscreen, so
+            // the current layering is kept.
+            let layered = winuser::GetWindowLongW(self.handle, winuser::GWL_EXSTYLE)
+                as minwindef::DWORD
+                & winuser::WS_EX_LAYERED;
+            winuser::SetWindowLongW(
+                self.handle,
+                winuser::GWL_EXSTYLE,
+                ((placement.extended_style & !winuser::WS_EX_LAYERED) | layered) as ntdef::LONG,
+            );
+            winuser::SetWindowPlacement(self.handle, &placement.placement);
+            // Apply the restored frame.
+            winuser::SetWindowPos(
+                self.handle,
+                ptr::null_mut(),
+                0,
+                0,
+                0,
+                0,
+                winuser::SWP_FRAMECHANGED
+                    | winuser::SWP_NOACTIVATE
+                    | winuser::SWP_NOMOVE
+                    | winuser::SWP_NOOWNERZORDER
+                    | winuser::SWP_NOSIZE
+                    | winuser::SWP_NOZORDER,
+            );
+        }
+    }
+
+    fn window_rectangle(&self) -> Result<windef::RECT, Error> {
+        unsafe {
+            let mut rectangle = mem::zeroed::<windef::RECT>();
+            if winuser::GetWindowRect(self.handle, &mut rectangle) != 0 {
+                Ok(rectangle)
+            }
+            else {
+                Err(crate::last_error("failed to get window rectangle"))
+            }
+        }
+    }
+
+    fn set_constraints(&mut self, constraints: Constraints) {
+        self.constraints = constraints;
+        // The window procedure reads the constraints, so they are replaced on
+        // the thread that owns the window. `SendMessageW` blocks until the
+        // message is processed, so the constraints outlive the message.
+        unsafe {
+            winuser::SendMessageW(
+                self.handle,
+                *WM_SET_CONSTRAINTS,
+                0,
+                &self.constraints as *const Constraints as minwindef::LPARAM,
+            );
+        }
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        // Restore the mode of the display if the window is in exclusive
+        // fullscreen.
+        if let Some(FullscreenState {
+            fullscreen: Fullscreen::Exclusive(_),
+            monitor,
+            ..
+        }) = self.fullscreen
+        {
+            let _ = <display::Display as platform::Display>::set_mode(monitor, None);
+        }
+        unsafe {
+            // Windows can only be destroyed by the thread that owns them, so
+            // destruction is requested if the window is dropped on any other
+            // thread. The request includes the serial number of the window,
+            // so that it has no effect if the window has already been
+            // destroyed and its handle recycled by another window.
+            if winuser::GetWindowThreadProcessId(self.handle, ptr::null_mut())
+                == processthreadsapi::GetCurrentThreadId()
+            {
+                if matches!(Window::state(self.handle), Some(state) if state.serial == self.serial)
+                {
+                    destroy(self.handle);
+                }
+            }
+            else {
+                winuser::PostMessageW(self.handle, *WM_DROP, self.serial, 0);
+            }
+        }
+    }
+}
+
+impl Eq for Window {}
+
+impl platform::Handle for Window {
+    type Handle = windef::HWND;
+
+    fn handle(&self) -> Self::Handle {
+        self.handle
+    }
+}
+
+impl Hash for Window {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.handle.hash(state);
+    }
+}
+
+impl PartialEq for Window {
+    fn eq(&self, other: &Self) -> bool {
         self.handle.eq(&other.handle)
     }
 }
 
+/// Exposes the `HWND` and `HINSTANCE` of a window to other threads.
+#[derive(Clone, Debug)]
+pub struct SurfaceToken {
+    window: windef::HWND,
+    instance: minwindef::HINSTANCE,
+    is_alive: Arc<AtomicBool>,
+}
+
+impl SurfaceToken {
+    pub fn hwnd(&self) -> windef::HWND {
+        self.window
+    }
+
+    pub fn hinstance(&self) -> minwindef::HINSTANCE {
+        self.instance
+    }
+}
+
+// Handles are only exposed via the token and are never dereferenced by it.
+unsafe impl Send for SurfaceToken {}
+unsafe impl Sync for SurfaceToken {}
+
+impl platform::SurfaceToken for SurfaceToken {
+    fn is_alive(&self) -> bool {
+        self.is_alive.load(Ordering::Acquire)
+    }
+}
+
+impl Window {
+    fn update_accessibility(&mut self, update: Update) {
+        // Window state is only accessed on the thread that owns the window, so
+        // the update is applied by the window procedure.
+        let mut update = Some(update);
+        unsafe {
+            winuser::SendMessageW(
+                self.handle,
+                *WM_SET_ACCESSIBILITY,
+                0,
+                &mut update as *mut Option<Update> as minwindef::LPARAM,
+            );
+        }
+    }
+}
+
+impl platform::Accessible for Window {
+    fn set_accessible_name(&mut self, name: String) -> Result<(), Error> {
+        self.update_accessibility(Update::Name(name));
+        Ok(())
+    }
+
+    fn set_accessible_role(&mut self, role: Role) -> Result<(), Error> {
+        self.update_accessibility(Update::Role(role));
+        Ok(())
+    }
+
+    fn set_accessible_focus(&mut self, element: Option<AccessibleElement>) -> Result<(), Error> {
+        self.update_accessibility(Update::Focus(element));
+        Ok(())
+    }
+
+    fn notify_accessible_change(&mut self, element: AccessibleElement) -> Result<(), Error> {
+        self.update_accessibility(Update::Change(element));
+        Ok(())
+    }
+}
+
+impl platform::Surface for Window {
+    type Token = SurfaceToken;
+
+    fn set_icon(&mut self, icon: Option<Icon>) -> Result<(), Error> {
+        let icon = match icon {
+            Some(ref icon) => Some(IconHandle::from_icon(icon)?),
+            None => None,
+        };
+        let handle = icon.as_ref().map_or(ptr::null_mut(), |icon| icon.0);
+        unsafe {
+            for &size in &[winuser::ICON_SMALL, winuser::ICON_BIG] {
+                winuser::SendMessageW(
+                    self.handle,
+                    winuser::WM_SETICON,
+                    size as minwindef::WPARAM,
+                    handle as minwindef::LPARAM,
+                );
+            }
+        }
+        // Replace (and destroy) any previous icon only after the window no
+        // longer references it.
+        self.icon = icon;
+        Ok(())
+    }
+
+    fn set_mouse_mode(&mut self, mode: MouseMode) -> Result<(), Error> {
+        // The cursor must be manipulated on the thread that owns the window,
+        // so the mode is applied by the window procedure.
+        unsafe {
+            winuser::SendMessageW(
+                self.handle,
+                *WM_SET_MOUSE_MODE,
+                match mode {
+                    MouseMode::Absolute => 0,
+                    MouseMode::Relative => 1,
+                },
+                0,
+            );
+        }
+        Ok(())
+    }
+
+    fn set_cursor_icon(&mut self, icon: CursorIcon) -> Result<(), Error> {
+        let cursor = match icon {
+            CursorIcon::Custom { ref image, hotspot } => {
+                Some(CursorHandle::from_icon(image, hotspot)?)
+            }
+            _ => None,
+        };
+        let handle = match cursor {
+            Some(ref cursor) => cursor.0,
+            None => unsafe { winuser::LoadCursorW(ptr::null_mut(), system_cursor(&icon)) },
+        };
+        // The cursor can only be set on the thread that owns the window, so
+        // the cursor is applied by the window procedure.
+        unsafe {
+            winuser::SendMessageW(
+                self.handle,
+                *WM_SET_CURSOR_ICON,
+                0,
+                handle as minwindef::LPARAM,
+            );
+        }
+        // Replace (and destroy) any previous custom cursor only after the
+        // window no longer references it.
+        self.cursor = cursor;
+        Ok(())
+    }
+
+    fn set_pointer_capture(&mut self, capture: bool) -> Result<(), Error> {
+        // Capture can only be set on the thread that owns the window, so
+        // capture is applied by the window procedure.
+        unsafe {
+            winuser::SendMessageW(
+                self.handle,
+                *WM_SET_POINTER_CAPTURE,
+                capture as minwindef::WPARAM,
+                0,
+            );
+        }
+        Ok(())
+    }
+
+    fn focus(&mut self) -> Result<(), Error> {
+        unsafe {
+            if winuser::SetForegroundWindow(self.handle) == 0 {
+                Err(crate::last_error("failed to focus window"))
+            }
+            else {
+                Ok(())
+            }
+        }
+    }
+
+    fn set_focus(&mut self) -> Result<(), Error> {
+        // Keyboard focus is local to the thread, so this does not bring the
+        // window to the foreground. See `focus`.
+        // `SetFocus` also returns null if no window previously had focus.
+        unsafe {
+            errhandlingapi::SetLastError(0);
+            if winuser::SetFocus(self.handle).is_null()
+                && errhandlingapi::GetLastError() != winerror::ERROR_SUCCESS
+            {
+                Err(crate::last_error("failed to set keyboard focus"))
+            }
+            else {
+                Ok(())
+            }
+        }
+    }
+
+    fn set_hit_test(&mut self, hit_test: Option<HitTest>) -> Result<(), Error> {
+        match unsafe { Window::state(self.handle) } {
+            Some(state) if state.serial == self.serial => {
+                state.hit_test = hit_test;
+                Ok(())
+            }
+            _ => Err(Error::new(
+                ErrorKind::InvalidState,
+                "hit-testing must be set on the thread that owns the window",
+            )),
+        }
+    }
+
+    fn set_always_on_top(&mut self, always_on_top: bool) -> Result<(), Error> {
+        self.set_position_after(if always_on_top {
+            winuser::HWND_TOPMOST
+        }
+        else {
+            winuser::HWND_NOTOPMOST
+        })
+    }
+
+    fn set_z_order(&mut self, order: ZOrder) -> Result<(), Error> {
+        self.set_position_after(match order {
+            ZOrder::Top => winuser::HWND_TOP,
+            ZOrder::Bottom => winuser::HWND_BOTTOM,
+        })
+    }
+
+    fn set_min_dimensions(&mut self, dimensions: Option<LogicalSize>) -> Result<(), Error> {
+        let dpi = dpi_factor(self.handle);
+        self.set_constraints(Constraints {
+            min_dimensions: dimensions.map(|dimensions| into_physical_dimensions(dimensions, dpi)),
+            ..self.constraints
+        });
+        Ok(())
+    }
+
+    fn set_max_dimensions(&mut self, dimensions: Option<LogicalSize>) -> Result<(), Error> {
+        let dpi = dpi_factor(self.handle);
+        self.set_constraints(Constraints {
+            max_dimensions: dimensions.map(|dimensions| into_physical_dimensions(dimensions, dpi)),
+            ..self.constraints
+        });
+        Ok(())
+    }
+
+    fn set_aspect_ratio(&mut self, ratio: Option<f64>) -> Result<(), Error> {
+        self.set_constraints(Constraints {
+            aspect_ratio: ratio,
+            ..self.constraints
+        });
+        Ok(())
+    }
+
+    fn is_opacity_supported() -> bool {
+        true
+    }
+
+    fn set_modal(&mut self, modal: bool) -> Result<(), Error> {
+        unsafe {
+            if winuser::GetWindow(self.handle, winuser::GW_OWNER).is_null() {
+                return Err(Error::new(ErrorKind::InvalidState, "window has no owner"));
+            }
+            // The window procedure tracks whether or not the owner has been
+            // disabled, so that it can be enabled when the window is
+            // destroyed.
+            winuser::SendMessageW(self.handle, *WM_SET_MODAL, modal as minwindef::WPARAM, 0);
+        }
+        Ok(())
+    }
+
+    fn request_frame(&mut self) -> Result<(), Error> {
+        // Requests are coalesced by the window procedure.
+        unsafe {
+            winuser::SendMessageW(self.handle, *WM_REQUEST_FRAME, 0, 0);
+        }
+        Ok(())
+    }
+
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Error> {
+        unsafe {
+            let extended_style =
+                winuser::GetWindowLongW(self.handle, winuser::GWL_EXSTYLE) as minwindef::DWORD;
+            // The window is only layered while it is translucent, because
+            // layered windows are more expensive to compose. Transparency is
+            // instead provided by the DWM.
+            if opacity < 1.0 {
+                if extended_style & winuser::WS_EX_LAYERED == 0 {
+                    winuser::SetWindowLongW(
+                        self.handle,
+                        winuser::GWL_EXSTYLE,
+                        (extended_style | winuser::WS_EX_LAYERED) as ntdef::LONG,
+                    );
+                }
+                if winuser::SetLayeredWindowAttributes(
+                    self.handle,
+                    0,
+                    alpha(opacity),
+                    winuser::LWA_ALPHA,
+                ) == 0
+                {
+                    return Err(crate::last_error("failed to set window opacity"));
+                }
+            }
+            else if extended_style & winuser::WS_EX_LAYERED != 0 {
+                winuser::SetWindowLongW(
+                    self.handle,
+                    winuser::GWL_EXSTYLE,
+                    (extended_style & !winuser::WS_EX_LAYERED) as ntdef::LONG,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: Option<Fullscreen>) -> Result<(), Error> {
+        self.set_fullscreen_placement(fullscreen)?;
+        unsafe {
+            if let Some(state) = Window::state(self.handle) {
+                state.is_fullscreen = self.fullscreen.is_some();
+                update_show_state(self.handle, state, crate::message_timestamp());
+            }
+        }
+        Ok(())
+    }
+
+    fn set_maximized(&mut self, maximized: bool) -> Result<(), Error> {
+        unsafe {
+            if maximized {
+                winuser::ShowWindow(self.handle, winuser::SW_MAXIMIZE);
+            }
+            else if winuser::IsZoomed(self.handle) != 0 {
+                winuser::ShowWindow(self.handle, winuser::SW_RESTORE);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_minimized(&mut self, minimized: bool) -> Result<(), Error> {
+        unsafe {
+            if minimized {
+                winuser::ShowWindow(self.handle, winuser::SW_MINIMIZE);
+            }
+            else if winuser::IsIconic(self.handle) != 0 {
+                winuser::ShowWindow(self.handle, winuser::SW_RESTORE);
+            }
+        }
+        Ok(())
+    }
+
+    fn restore(&mut self) -> Result<(), Error> {
+        if self.fullscreen.is_some() {
+            self.set_fullscreen(None)?;
+        }
+        // A minimized window that was maximized is first restored to being
+        // maximized.
+        self.set_minimized(false)?;
+        self.set_maximized(false)
+    }
+
+    fn outer_position(&self) -> Result<LogicalPosition, Error> {
+        let dpi = dpi_factor(self.handle);
+        self.window_rectangle()
+            .map(|rectangle| PhysicalPosition::new(rectangle.left, rectangle.top).into_logical(dpi))
+    }
+
+    fn inner_size(&self) -> Result<LogicalSize, Error> {
+        let dpi = dpi_factor(self.handle);
+        unsafe {
+            let mut rectangle = mem::zeroed::<windef::RECT>();
+            if winuser::GetClientRect(self.handle, &mut rectangle) != 0 {
+                Ok(PhysicalSize::new(rectangle.right, rectangle.bottom).into_logical(dpi))
+            }
+            else {
+                Err(crate::last_error("failed to get client rectangle"))
+            }
+        }
+    }
+
+    fn outer_size(&self) -> Result<LogicalSize, Error> {
+        let dpi = dpi_factor(self.handle);
+        self.window_rectangle().map(|rectangle| {
+            PhysicalSize::new(
+                rectangle.right - rectangle.left,
+                rectangle.bottom - rectangle.top,
+            )
+            .into_logical(dpi)
+        })
+    }
+
+    fn is_focused(&self) -> bool {
+        // Windows may be queried from any thread, so the foreground window is
+        // used rather than the (thread-local) active window.
+        unsafe { winuser::GetForegroundWindow() == self.handle }
+    }
+
+    fn is_minimized(&self) -> bool {
+        unsafe { winuser::IsIconic(self.handle) != 0 }
+    }
+
+    fn geometry(&self) -> Result<Geometry, Error> {
+        unsafe {
+            let monitor =
+                winuser::MonitorFromWindow(self.handle, winuser::MONITOR_DEFAULTTONEAREST);
+            let display = <display::Display as platform::Display>::work_area(monitor);
+            let is_minimized = winuser::IsIconic(self.handle) != 0;
+            let is_maximized = winuser::IsZoomed(self.handle) != 0;
+            let mut placement = mem::zeroed::<winuser::WINDOWPLACEMENT>();
+            placement.length = mem::size_of::<winuser::WINDOWPLACEMENT>() as minwindef::UINT;
+            if winuser::GetWindowPlacement(self.handle, &mut placement) == 0 {
+                return Err(crate::last_error("failed to get window placement"));
+            }
+            let rectangle = if is_minimized || is_maximized {
+                // The normal position is in workspace coordinates (relative
+                // to the work area of the display) unless the window is a
+                // tool window.
+                let mut rectangle = placement.rcNormalPosition;
+                let extended_style =
+                    winuser::GetWindowLongW(self.handle, winuser::GWL_EXSTYLE) as minwindef::DWORD;
+                if extended_style & winuser::WS_EX_TOOLWINDOW == 0 {
+                    if let Some(info) = display::monitor_info(monitor) {
+                        let (dx, dy) = (
+                            info.rcWork.left - info.rcMonitor.left,
+                            info.rcWork.top - info.rcMonitor.top,
+                        );
+                        rectangle.left += dx;
+                        rectangle.right += dx;
+                        rectangle.top += dy;
+                        rectangle.bottom += dy;
+                    }
+                }
+                rectangle
+            }
+            else {
+                self.window_rectangle()?
+            };
+            Ok(Geometry {
+                position: PhysicalPosition::new(rectangle.left, rectangle.top),
+                dimensions: PhysicalSize::new(
+                    rectangle.right - rectangle.left,
+                    rectangle.bottom - rectangle.top,
+                ),
+                display,
+                is_maximized: is_maximized
+                    || (is_minimized && placement.flags & winuser::WPF_RESTORETOMAXIMIZED != 0),
+            })
+        }
+    }
+
+    #[cfg(feature = "capture")]
+    fn capture(&self) -> Result<Image, Error> {
+        crate::capture::capture(self.handle)
+    }
+
+    #[cfg(feature = "test")]
+    fn inject_input(&self, event: InputEvent) -> Result<(), Error> {
+        crate::inject::inject(self.handle, event)
+    }
+
+    fn surface_token(&self) -> Self::Token {
+        // If the window has already been destroyed, then the token is never
+        // alive.
+        let is_alive = match unsafe { Window::state(self.handle) } {
+            Some(state) => state
+                .is_alive
+                .get_or_insert_with(|| Arc::new(AtomicBool::new(true)))
+                .clone(),
+            None => Arc::new(AtomicBool::new(false)),
+        };
+        SurfaceToken {
+            window: self.handle,
+            instance: unsafe {
+                winuser::GetWindowLongPtrW(self.handle, winuser::GWLP_HINSTANCE)
+                    as minwindef::HINSTANCE
+            },
+            is_alive,
+        }
+    }
+
+    fn set_user_data(&mut self, data: Option<Box<dyn Any + Send>>) -> Result<(), Error> {
+        // Window state is only accessed on the thread that owns the window, so
+        // the data is moved there by the window procedure.
+        let mut data = data;
+        unsafe {
+            winuser::SendMessageW(
+                self.handle,
+                *WM_SET_USER_DATA,
+                0,
+                &mut data as *mut Option<UserData> as minwindef::LPARAM,
+            );
+        }
+        Ok(())
+    }
+
+    fn with_user_data(handle: Self::Handle, f: &mut dyn FnMut(&mut dyn Any)) {
+        // The data is removed from the window state while the function
+        // executes, because the function may replace it.
+        let data = unsafe { Window::state(handle).and_then(|state| state.user_data.take()) };
+        if let Some(mut data) = data {
+            f(data.as_mut());
+            if let Some(state) = unsafe { Window::state(handle) } {
+                if state.user_data.is_none() {
+                    state.user_data = Some(data);
+                }
+            }
+        }
+    }
+}
+
+/// Creates a message-only window that receives raw input on behalf of a
+/// headless event thread.
+///
+/// Message-only windows are never visible nor active, so raw input is always
+/// registered for the background.
+pub fn create_message_only() -> Result<windef::HWND, Error> {
+    let class = WindowClass::default();
+    class.acquire()?;
+    unsafe {
+        let handle = winuser::CreateWindowExW(
+            0,
+            class.name.as_ptr(),
+            ptr::null(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            winuser::HWND_MESSAGE,
+            ptr::null_mut(),
+            class.instance(),
+            ptr::null_mut(),
+        );
+        if handle.is_null() {
+            return Err(crate::last_error("failed to create message-only window"));
+        }
+        let state = Box::into_raw(Box::new(WindowState {
+            is_message_only: true,
+            ..Default::default()
+        }));
+        if commctrl::SetWindowSubclass(
+            handle,
+            Some(procedure),
+            WINDOW_SUBCLASS_ID,
+            state as basetsd::DWORD_PTR,
+        ) == 0
+        {
+            let error = crate::last_error("failed to subclass window");
+            drop(Box::from_raw(state));
+            winuser::DestroyWindow(handle);
+            return Err(error);
+        }
+        if let Err(error) = input::register(handle, RawInputFilter::default(), true) {
+            // Destroying the window frees its state.
+            winuser::DestroyWindow(handle);
+            return Err(error);
+        }
+        Ok(handle)
+    }
+}
+
 unsafe impl Send for Window {}
 unsafe impl Sync for Window {}
 
+/// Dispatches `WindowEvent::StateChanged` if the state of a window differs
+/// from the state that was last dispatched.
+unsafe fn update_show_state(window: windef::HWND, state: &mut WindowState, timestamp: Instant) {
+    let show_state = if winuser::IsIconic(window) != 0 {
+        event::WindowState::Minimized
+    }
+    else if state.is_fullscreen {
+        event::WindowState::Fullscreen
+    }
+    else if winuser::IsZoomed(window) != 0 {
+        event::WindowState::Maximized
+    }
+    else {
+        event::WindowState::Normal
+    };
+    if show_state != state.show_state {
+        state.show_state = show_state;
+        let _ = reactor::react(Event::Window {
+            window: WindowHandle::from_raw_handle(window),
+            event: WindowEvent::StateChanged(show_state),
+            timestamp,
+        });
+    }
+}
+
 unsafe extern "system" fn procedure(
     window: windef::HWND,
     message: minwindef::UINT,
@@ -254,50 +1917,402 @@ unsafe extern "system" fn procedure(
     _: basetsd::UINT_PTR,
     state: basetsd::DWORD_PTR,
 ) -> minwindef::LRESULT {
-    let state = &mut *(state as *mut WindowState);
+    let mut state = &mut *(state as *mut WindowState);
+    // Hooks execute from a snapshot of the window state, because they may add
+    // hooks or destroy the window. Hooks remain in the window state, so messages
+    // sent while a hook executes are observed by the other hooks, but a hook
+    // does not observe messages sent while it is executing. Destruction cannot
+    // be consumed, because the window state must be freed.
+    if !state.hooks.is_empty() {
+        let hooks = state.hooks.clone();
+        let result = hooks.iter().find_map(|hook| {
+            hook.try_borrow_mut()
+                .ok()
+                .and_then(|mut hook| (*hook)(window, message, wparam, lparam))
+        });
+        state = match Window::state(window) {
+            Some(state) => state,
+            // The window has been destroyed by a hook.
+            None => return result.unwrap_or(0),
+        };
+        match (message, result) {
+            (winuser::WM_DESTROY, _) | (_, None) => {}
+            (_, Some(result)) => return result,
+        }
+    }
+    let timestamp = crate::message_timestamp();
     match message {
         winuser::WM_CLOSE => {
             let _ = reactor::react(Event::Window {
                 window: WindowHandle::from_raw_handle(window),
                 event: WindowEvent::Closed(WindowCloseState::Requested),
+                timestamp,
             });
             return 0; // Do NOT destroy the window yet.
         }
-        // TODO: This will typically not execute (for the last window)
-        //       given the current structure of window destruction.
         winuser::WM_DESTROY => {
-            let _ = Box::from_raw(state);
+            // Modal windows are typically destroyed via `destroy`, which
+            // enables the owner beforehand. This handles windows that are
+            // destroyed otherwise, such as along with their owner.
+            set_modal(window, state, false);
+            // `ShowCursor` maintains a display counter and the cursor remains
+            // confined after the window is destroyed, so both are restored.
+            if let MouseMode::Relative = state.mouse_mode {
+                winuser::ShowCursor(minwindef::TRUE);
+                confine_cursor(window, false);
+            }
+            if let Some(is_alive) = state.is_alive.take() {
+                is_alive.store(false, Ordering::Release);
+            }
+            // Disconnect the UI Automation provider while the window exists.
+            state.accessibility.take();
+            if !state.is_message_only {
+                let _ = reactor::react(Event::Window {
+                    window: WindowHandle::from_raw_handle(window),
+                    event: WindowEvent::Closed(WindowCloseState::Committed),
+                    timestamp,
+                });
+            }
+            // Free the state (and any user data) only after the reactor has
+            // observed the destruction. The subclass is removed first so that
+            // any remaining messages do not reference the state.
+            commctrl::RemoveWindowSubclass(window, Some(procedure), WINDOW_SUBCLASS_ID);
+            drop(Box::from_raw(state));
+        }
+        winuser::WM_ENABLE => {
+            let _ = reactor::react(Event::Window {
+                window: WindowHandle::from_raw_handle(window),
+                event: if wparam != 0 {
+                    WindowEvent::Enabled
+                }
+                else {
+                    WindowEvent::Disabled
+                },
+                timestamp,
+            });
+        }
+        winuser::WM_ACTIVATE => {
+            if let MouseMode::Relative = state.mouse_mode {
+                confine_cursor(
+                    window,
+                    minwindef::LOWORD(wparam as minwindef::DWORD) != winuser::WA_INACTIVE,
+                );
+            }
+        }
+        winuser::WM_SETFOCUS | winuser::WM_KILLFOCUS => {
+            let _ = reactor::react(Event::Window {
+                window: WindowHandle::from_raw_handle(window),
+                event: WindowEvent::Focused(message == winuser::WM_SETFOCUS),
+                timestamp,
+            });
+        }
+        // Text is translated from key presses without disturbing the dead key
+        // state used by `TranslateMessage`. See `keyboard::Composer`.
+        winuser::WM_KEYDOWN | winuser::WM_SYSKEYDOWN if keyboard::is_translation_stateless() => {
+            for character in keyboard::received_characters(wparam, lparam) {
+                let _ = reactor::react(Event::Window {
+                    window: WindowHandle::from_raw_handle(window),
+                    event: WindowEvent::ReceivedCharacter(character),
+                    timestamp,
+                });
+            }
+        }
+        // Otherwise, text is received from `TranslateMessage`.
+        winuser::WM_CHAR if !keyboard::is_translation_stateless() => {
+            if let Some(character) = keyboard::received_character(wparam) {
+                let _ = reactor::react(Event::Window {
+                    window: WindowHandle::from_raw_handle(window),
+                    event: WindowEvent::ReceivedCharacter(character),
+                    timestamp,
+                });
+            }
+        }
+        winuser::WM_MOVE | winuser::WM_SIZE => {
+            if let MouseMode::Relative = state.mouse_mode {
+                confine_cursor(window, winuser::GetActiveWindow() == window);
+            }
+            if message == winuser::WM_MOVE {
+                // Minimized windows are moved out of view, which is not
+                // reported. The position is that of the window rectangle, like
+                // `outer_position`.
+                let mut rectangle = mem::zeroed::<windef::RECT>();
+                if winuser::IsIconic(window) == 0
+                    && winuser::GetWindowRect(window, &mut rectangle) != 0
+                {
+                    let _ = reactor::react(Event::Window {
+                        window: WindowHandle::from_raw_handle(window),
+                        event: WindowEvent::Moved {
+                            position: PhysicalPosition::new(rectangle.left, rectangle.top),
+                            dpi: dpi_factor(window),
+                        },
+                        timestamp,
+                    });
+                }
+            }
+            if message == winuser::WM_SIZE {
+                update_show_state(window, state, timestamp);
+                if wparam != winuser::SIZE_MINIMIZED as minwindef::WPARAM {
+                    let dimensions = PhysicalSize::new(
+                        u32::from(minwindef::LOWORD(lparam as minwindef::DWORD)),
+                        u32::from(minwindef::HIWORD(lparam as minwindef::DWORD)),
+                    );
+                    let _ = reactor::react(Event::Window {
+                        window: WindowHandle::from_raw_handle(window),
+                        event: WindowEvent::Resized {
+                            dimensions,
+                            dpi: dpi_factor(window),
+                        },
+                        timestamp,
+                    });
+                }
+            }
+        }
+        // Apply the rectangle suggested for the new DPI. The resulting `WM_MOVE`
+        // and `WM_SIZE` report the new DPI factor.
+        winuser::WM_DPICHANGED => {
+            let rectangle = &*(lparam as *const windef::RECT);
+            winuser::SetWindowPos(
+                window,
+                ptr::null_mut(),
+                rectangle.left,
+                rectangle.top,
+                rectangle.right - rectangle.left,
+                rectangle.bottom - rectangle.top,
+                winuser::SWP_NOZORDER | winuser::SWP_NOACTIVATE,
+            );
+            return 0;
+        }
+        winuser::WM_EXITSIZEMOVE => {
+            if state.is_resizing {
+                state.is_resizing = false;
+                winuser::KillTimer(window, RESIZE_TIMER_ID);
+                let _ = reactor::react(Event::Window {
+                    window: WindowHandle::from_raw_handle(window),
+                    event: WindowEvent::ResizeEnded,
+                    timestamp,
+                });
+            }
+        }
+        winuser::WM_TIMER if wparam == RESIZE_TIMER_ID => {
+            let _ = reactor::flush_resizes();
+            return 0;
+        }
+        winuser::WM_NCHITTEST => {
+            if let Some(ref hit_test) = state.hit_test {
+                // The position is in screen coordinates and may be negative
+                // on displays to the left of or above the primary display.
+                let mut point = windef::POINT {
+                    x: minwindef::LOWORD(lparam as minwindef::DWORD) as i16 as ntdef::LONG,
+                    y: minwindef::HIWORD(lparam as minwindef::DWORD) as i16 as ntdef::LONG,
+                };
+                winuser::ScreenToClient(window, &mut point);
+                let dpi = dpi_factor(window);
+                let position = PhysicalPosition::new(point.x, point.y).into_logical(dpi);
+                return match hit_test(position) {
+                    HitTestResult::Client => winuser::HTCLIENT,
+                    HitTestResult::Caption => winuser::HTCAPTION,
+                    HitTestResult::Left => winuser::HTLEFT,
+                    HitTestResult::Right => winuser::HTRIGHT,
+                    HitTestResult::Top => winuser::HTTOP,
+                    HitTestResult::Bottom => winuser::HTBOTTOM,
+                    HitTestResult::TopLeft => winuser::HTTOPLEFT,
+                    HitTestResult::TopRight => winuser::HTTOPRIGHT,
+                    HitTestResult::BottomLeft => winuser::HTBOTTOMLEFT,
+                    HitTestResult::BottomRight => winuser::HTBOTTOMRIGHT,
+                } as minwindef::LRESULT;
+            }
+        }
+        winuser::WM_SETCURSOR => {
+            // Only the client area uses the cursor of the window. The frame
+            // uses the cursors provided by the default window procedure.
+            if minwindef::LOWORD(lparam as minwindef::DWORD) as minwindef::LRESULT
+                == winuser::HTCLIENT
+            {
+                set_cursor(state);
+                return minwindef::TRUE as minwindef::LRESULT;
+            }
+        }
+        winuser::WM_GETMINMAXINFO => {
+            let info = &mut *(lparam as *mut winuser::MINMAXINFO);
+            if let Some(dimensions) = state.constraints.min_dimensions {
+                let (x, y) = outer_dimensions(window, dimensions);
+                info.ptMinTrackSize = windef::POINT { x, y };
+            }
+            if let Some(dimensions) = state.constraints.max_dimensions {
+                let (x, y) = outer_dimensions(window, dimensions);
+                info.ptMaxTrackSize = windef::POINT { x, y };
+                info.ptMaxSize = windef::POINT { x, y };
+            }
+            return 0;
+        }
+        winuser::WM_SIZING => {
+            // `WM_ENTERSIZEMOVE` is also sent when a window is moved, so the
+            // resize begins when the window is first sized.
+            if !state.is_resizing {
+                state.is_resizing = true;
+                winuser::SetTimer(window, RESIZE_TIMER_ID, RESIZE_TIMER_INTERVAL, None);
+                let _ = reactor::react(Event::Window {
+                    window: WindowHandle::from_raw_handle(window),
+                    event: WindowEvent::ResizeStarted,
+                    timestamp,
+                });
+            }
+            if let Some(ratio) = state.constraints.aspect_ratio {
+                let rectangle = &mut *(lparam as *mut windef::RECT);
+                let (frame_width, frame_height) = outer_dimensions(window, (0, 0));
+                // Adjust the edge that is not being dragged. When a corner is
+                // dragged, the width determines the height.
+                match wparam as minwindef::UINT {
+                    winuser::WMSZ_TOP | winuser::WMSZ_BOTTOM => {
+                        let height = rectangle.bottom - rectangle.top - frame_height;
+                        rectangle.right = rectangle.left
+                            + (f64::from(height.max(0)) * ratio).round() as ntdef::LONG
+                            + frame_width;
+                    }
+                    edge => {
+                        let width = rectangle.right - rectangle.left - frame_width;
+                        let height =
+                            (f64::from(width.max(0)) / ratio).round() as ntdef::LONG + frame_height;
+                        if edge == winuser::WMSZ_TOPLEFT || edge == winuser::WMSZ_TOPRIGHT {
+                            rectangle.top = rectangle.bottom - height;
+                        }
+                        else {
+                            rectangle.bottom = rectangle.top + height;
+                        }
+                    }
+                }
+                return minwindef::TRUE as minwindef::LRESULT;
+            }
+        }
+        winuser::WM_MOUSEMOVE => {
+            if !state.is_tracking_mouse {
+                let mut track = winuser::TRACKMOUSEEVENT {
+                    cbSize: mem::size_of::<winuser::TRACKMOUSEEVENT>() as minwindef::DWORD,
+                    dwFlags: winuser::TME_LEAVE,
+                    hwndTrack: window,
+                    dwHoverTime: 0,
+                };
+                if winuser::TrackMouseEvent(&mut track) != 0 {
+                    state.is_tracking_mouse = true;
+                    let _ = reactor::react(Event::Window {
+                        window: WindowHandle::from_raw_handle(window),
+                        event: WindowEvent::MouseEntered,
+                        timestamp,
+                    });
+                }
+            }
+        }
+        winuser::WM_LBUTTONDOWN
+        | winuser::WM_RBUTTONDOWN
+        | winuser::WM_MBUTTONDOWN
+        | winuser::WM_XBUTTONDOWN => {
+            // Capture the pointer so that messages are delivered to the window
+            // while buttons are held, even if the pointer leaves the window.
+            if state.is_pointer_capture_automatic && winuser::GetCapture() != window {
+                winuser::SetCapture(window);
+            }
+        }
+        winuser::WM_LBUTTONUP
+        | winuser::WM_RBUTTONUP
+        | winuser::WM_MBUTTONUP
+        | winuser::WM_XBUTTONUP => {
+            const BUTTONS: minwindef::WORD = (winuser::MK_LBUTTON
+                | winuser::MK_RBUTTON
+                | winuser::MK_MBUTTON
+                | winuser::MK_XBUTTON1
+                | winuser::MK_XBUTTON2)
+                as minwindef::WORD;
+            if state.is_pointer_capture_automatic
+                && !state.is_pointer_captured
+                && winuser::GET_KEYSTATE_WPARAM(wparam) & BUTTONS == 0
+                && winuser::GetCapture() == window
+            {
+                winuser::ReleaseCapture();
+            }
+        }
+        winuser::WM_CAPTURECHANGED => {
+            if lparam as windef::HWND != window {
+                state.is_pointer_captured = false;
+            }
+        }
+        winuser::WM_MOUSELEAVE => {
+            state.is_tracking_mouse = false;
             let _ = reactor::react(Event::Window {
                 window: WindowHandle::from_raw_handle(window),
-                event: WindowEvent::Closed(WindowCloseState::Committed),
+                event: WindowEvent::MouseLeft,
+                timestamp,
             });
         }
+        winuser::WM_MOUSEWHEEL | winuser::WM_MOUSEHWHEEL => {
+            if let Ok(event) = mouse::parse_wheel_message(message, wparam) {
+                // The originating device is unknown, so a null handle is used.
+                let _ = reactor::react(Event::Input {
+                    device: DeviceHandle::from_raw_handle(ptr::null_mut()),
+                    window: Some(WindowHandle::from_raw_handle(window)),
+                    event,
+                    focus: InputFocus::Foreground,
+                    timestamp,
+                });
+            }
+        }
+        winuser::WM_POINTERENTER
+        | winuser::WM_POINTERLEAVE
+        | winuser::WM_POINTERDOWN
+        | winuser::WM_POINTERUP
+        | winuser::WM_POINTERUPDATE => {
+            // Pointer messages are not consumed, so that the default window
+            // procedure emulates the mouse.
+            if let Ok((device, event)) = pen::parse_pointer_message(window, message, wparam) {
+                let _ = reactor::react(Event::Input {
+                    device: DeviceHandle::from_raw_handle(device),
+                    window: Some(WindowHandle::from_raw_handle(window)),
+                    event,
+                    focus: InputFocus::Foreground,
+                    timestamp,
+                });
+            }
+        }
         winuser::WM_INPUT => {
             if let Ok(mut input) = input::raw_input(lparam as winuser::HRAWINPUT) {
                 let device = input.header.hDevice;
+                let focus = if winuser::GET_RAWINPUT_CODE_WPARAM(wparam) == winuser::RIM_INPUTSINK {
+                    InputFocus::Background
+                }
+                else {
+                    InputFocus::Foreground
+                };
                 match input.header.dwType {
                     winuser::RIM_TYPEKEYBOARD => {
-                        if let Ok(event) = keyboard::parse_raw_input(input.data.keyboard()) {
+                        if let Ok(event) = keyboard::parse_raw_input(device, input.data.keyboard())
+                        {
                             let _ = reactor::react(Event::Input {
                                 device: DeviceHandle::from_raw_handle(device),
                                 window: None,
                                 event,
+                                focus,
+                                timestamp,
                             });
                         }
                     }
                     winuser::RIM_TYPEMOUSE => {
-                        if let Ok(events) = mouse::parse_raw_input(window, input.data.mouse()) {
+                        if let Ok(events) =
+                            mouse::parse_raw_input(window, input.data.mouse(), state.mouse_mode)
+                        {
                             let _ =
                                 reactor::enqueue(events.into_iter().map(|event| Event::Input {
                                     device: DeviceHandle::from_raw_handle(device),
                                     window: None,
                                     event,
+                                    focus,
+                                    timestamp,
                                 }));
                         }
                     }
                     // TODO: Enqueue events for game controllers.
                     // TODO: Marshal game controller data.
-                    winuser::RIM_TYPEHID => {
+                    // XInput controllers are polled by the event thread. See
+                    // the `xinput` module.
+                    winuser::RIM_TYPEHID if !input::is_xinput_device(device) => {
                         if let Ok(mut data) = input::preparsed_data(device) {
                             let _ = input::hid_capabilities(&mut data)
                                 .and_then(|capabilities| {
@@ -318,7 +2333,45 @@ unsafe extern "system" fn procedure(
                 }
             }
         }
-        winuser::WM_INPUT_DEVICE_CHANGE => {
+        winuser::WM_POWERBROADCAST => {
+            let event = match wparam {
+                winuser::PBT_APMSUSPEND => Some(ApplicationEvent::Suspended),
+                // `PBT_APMRESUMESUSPEND` is only sent in addition to this
+                // message if the system was awakened by the user, so it is
+                // ignored.
+                winuser::PBT_APMRESUMEAUTOMATIC => Some(ApplicationEvent::Awakened),
+                _ => None,
+            };
+            if let Some(event) = event {
+                let _ = reactor::react(Event::Application { event, timestamp });
+            }
+        }
+        winuser::WM_QUERYENDSESSION => {
+            let _ = reactor::react(Event::Application {
+                event: ApplicationEvent::Terminating,
+                timestamp,
+            });
+            return minwindef::TRUE as minwindef::LRESULT; // Allow the session to end.
+        }
+        winuser::WM_DEVICECHANGE if wparam == dbt::DBT_DEVNODES_CHANGED => {
+            react_display_changes(timestamp);
+        }
+        winuser::WM_DISPLAYCHANGE => {
+            react_display_changes(timestamp);
+        }
+        winuser::WM_INPUTLANGCHANGE => {
+            if keyboard::update_layout(lparam as minwindef::HKL) {
+                let _ = reactor::react(Event::Application {
+                    event: ApplicationEvent::KeyboardLayoutChanged(KeyLayoutId::from_raw_handle(
+                        lparam as usize as u64,
+                    )),
+                    timestamp,
+                });
+            }
+        }
+        // XInput controllers are polled by the event thread, which reports
+        // their connection.
+        winuser::WM_INPUT_DEVICE_CHANGE if !input::is_xinput_device(lparam as ntdef::HANDLE) => {
             let device = lparam as ntdef::HANDLE;
             let _ = reactor::react(Event::Input {
                 device: DeviceHandle::from_raw_handle(device),
@@ -333,14 +2386,284 @@ unsafe extern "system" fn procedure(
                 else {
                     InputEvent::Disconnected
                 },
+                focus: InputFocus::Foreground,
+                timestamp,
             });
         }
+        winuser::WM_GETOBJECT => {
+            if let Some(result) = state
+                .accessibility
+                .as_ref()
+                .and_then(|accessibility| accessibility.get_object(wparam, lparam))
+            {
+                return result;
+            }
+        }
         // Handle application-specific messages.
         _ => {
             if message == *WM_DROP {
-                winuser::DestroyWindow(window);
+                if state.serial == wparam {
+                    destroy(window);
+                }
+            }
+            else if message == *WM_SET_MODAL {
+                set_modal(window, state, wparam != 0);
+                return 0;
+            }
+            else if message == *WM_SET_MOUSE_MODE {
+                let mode = if wparam == 0 {
+                    MouseMode::Absolute
+                }
+                else {
+                    MouseMode::Relative
+                };
+                if mode != state.mouse_mode {
+                    state.mouse_mode = mode;
+                    // `ShowCursor` maintains a display counter, so it is only
+                    // called when the mode changes.
+                    let is_relative = mode == MouseMode::Relative;
+                    winuser::ShowCursor(!is_relative as minwindef::BOOL);
+                    confine_cursor(window, is_relative && winuser::GetActiveWindow() == window);
+                }
+                return 0;
+            }
+            else if message == *WM_SET_CURSOR_ICON {
+                state.cursor = Some(lparam as windef::HCURSOR);
+                // Apply the cursor immediately if the pointer is within the
+                // client area. Otherwise, it is applied by `WM_SETCURSOR`.
+                let mut position = windef::POINT { x: 0, y: 0 };
+                if winuser::GetCursorPos(&mut position) != 0
+                    && winuser::WindowFromPoint(position) == window
+                {
+                    let mut client = position;
+                    let mut rectangle = mem::zeroed::<windef::RECT>();
+                    winuser::ScreenToClient(window, &mut client);
+                    winuser::GetClientRect(window, &mut rectangle);
+                    if winuser::PtInRect(&rectangle, client) != 0 {
+                        set_cursor(state);
+                    }
+                }
+                return 0;
+            }
+            else if message == *WM_SET_POINTER_CAPTURE {
+                if wparam != 0 {
+                    winuser::SetCapture(window);
+                    state.is_pointer_captured = true;
+                }
+                else {
+                    state.is_pointer_captured = false;
+                    if winuser::GetCapture() == window {
+                        winuser::ReleaseCapture();
+                    }
+                }
+                return 0;
+            }
+            else if message == *WM_SET_ACCESSIBILITY {
+                if let Some(update) = (*(lparam as *mut Option<Update>)).take() {
+                    state
+                        .accessibility
+                        .get_or_insert_with(|| Accessibility::new(window))
+                        .update(update);
+                }
+                return 0;
+            }
+            else if message == *WM_REQUEST_FRAME {
+                if !state.is_frame_requested {
+                    state.is_frame_requested = true;
+                    frame::request(window, state.serial);
+                }
+                return 0;
+            }
+            else if message == *frame::WM_FRAME_READY {
+                // Ignore frames requested by a destroyed window that shared
+                // this handle.
+                if state.serial == wparam && state.is_frame_requested {
+                    state.is_frame_requested = false;
+                    let _ = reactor::react(Event::Window {
+                        window: WindowHandle::from_raw_handle(window),
+                        event: WindowEvent::FrameReady,
+                        timestamp,
+                    });
+                }
+                return 0;
+            }
+            else if message == *WM_SET_USER_DATA {
+                state.user_data = (*(lparam as *mut Option<UserData>)).take();
+                return 0;
+            }
+            else if message == *WM_SET_CONSTRAINTS {
+                state.constraints = *(lparam as *const Constraints);
+                apply_constraints(window, &state.constraints);
+                return 0;
+            }
+            else if message >= winuser::WM_USER {
+                reactor::react_message(Some(window), message, wparam, lparam);
             }
         }
     }
     commctrl::DefSubclassProc(window, message, wparam, lparam)
 }
+
+/// Destroys a window.
+///
+/// If the window is modal, then its owner is enabled before the window is
+/// destroyed so that the owner is activated in its place.
+unsafe fn destroy(window: windef::HWND) {
+    if let Some(state) = Window::state(window) {
+        set_modal(window, state, false);
+    }
+    winuser::DestroyWindow(window);
+}
+
+/// Composites the client area of a window using the alpha channel of its
+/// contents.
+///
+/// Blur behind is enabled with an empty region, so nothing is blurred, but
+/// the DWM blends the client area with per-pixel alpha. Renderers must
+/// present premultiplied alpha for the window to appear transparent.
+unsafe fn enable_transparency(window: windef::HWND) -> Result<(), Error> {
+    let region = wingdi::CreateRectRgn(0, 0, -1, -1);
+    let blur = dwmapi::DWM_BLURBEHIND {
+        dwFlags: dwmapi::DWM_BB_ENABLE | dwmapi::DWM_BB_BLURREGION,
+        fEnable: minwindef::TRUE,
+        hRgnBlur: region,
+        fTransitionOnMaximized: minwindef::FALSE,
+    };
+    let result = dwmapi::DwmEnableBlurBehindWindow(window, &blur);
+    wingdi::DeleteObject(region as windef::HGDIOBJ);
+    if winerror::SUCCEEDED(result) {
+        Ok(())
+    }
+    else {
+        Err(Error::with_code(
+            result,
+            "failed to enable window transparency",
+        ))
+    }
+}
+
+/// Disables or enables the owner of a window.
+unsafe fn set_modal(window: windef::HWND, state: &mut WindowState, modal: bool) {
+    if state.is_modal != modal {
+        let owner = winuser::GetWindow(window, winuser::GW_OWNER);
+        if !owner.is_null() {
+            winuser::EnableWindow(owner, (!modal) as minwindef::BOOL);
+            state.is_modal = modal;
+        }
+    }
+}
+
+unsafe fn set_cursor(state: &WindowState) {
+    winuser::SetCursor(
+        state
+            .cursor
+            .unwrap_or_else(|| winuser::LoadCursorW(ptr::null_mut(), winuser::IDC_ARROW)),
+    );
+}
+
+/// Destroys all windows owned by the calling thread.
+///
+/// This is used when an event loop stops so that windows are destroyed (and
+/// `Closed(Committed)` events are dispatched) before the reactor is dropped.
+pub unsafe fn destroy_thread_windows() {
+    unsafe extern "system" fn push(
+        window: windef::HWND,
+        windows: minwindef::LPARAM,
+    ) -> minwindef::BOOL {
+        (*(windows as *mut Vec<windef::HWND>)).push(window);
+        minwindef::TRUE
+    }
+
+    // Windows are collected before any are destroyed, because destroying a
+    // window also destroys its children.
+    let mut windows = Vec::new();
+    winuser::EnumThreadWindows(
+        processthreadsapi::GetCurrentThreadId(),
+        Some(push),
+        &mut windows as *mut Vec<windef::HWND> as minwindef::LPARAM,
+    );
+    for window in windows {
+        let mut state = 0;
+        // Only destroy windows created by this crate.
+        if commctrl::GetWindowSubclass(window, Some(procedure), WINDOW_SUBCLASS_ID, &mut state) != 0
+        {
+            destroy(window);
+        }
+    }
+}
+
+/// Confines the cursor to the client area of a window or releases the cursor.
+unsafe fn confine_cursor(window: windef::HWND, confine: bool) {
+    if confine {
+        let mut rectangle = mem::zeroed::<windef::RECT>();
+        if winuser::GetClientRect(window, &mut rectangle) != 0 {
+            winuser::MapWindowPoints(
+                window,
+                ptr::null_mut(),
+                &mut rectangle as *mut windef::RECT as *mut windef::POINT,
+                2,
+            );
+            winuser::ClipCursor(&rectangle);
+        }
+    }
+    else {
+        winuser::ClipCursor(ptr::null());
+    }
+}
+
+/// Gets the dimensions of a window for the given dimensions of its client
+/// area, including any frame and decorations.
+unsafe fn outer_dimensions(window: windef::HWND, dimensions: (u32, u32)) -> (i32, i32) {
+    let mut rectangle = windef::RECT {
+        left: 0,
+        top: 0,
+        right: dimensions.0 as ntdef::LONG,
+        bottom: dimensions.1 as ntdef::LONG,
+    };
+    winuser::AdjustWindowRectEx(
+        &mut rectangle,
+        winuser::GetWindowLongW(window, winuser::GWL_STYLE) as minwindef::DWORD,
+        0,
+        winuser::GetWindowLongW(window, winuser::GWL_EXSTYLE) as minwindef::DWORD,
+    );
+    (
+        rectangle.right - rectangle.left,
+        rectangle.bottom - rectangle.top,
+    )
+}
+
+/// Resizes a window if its client area does not satisfy the given
+/// constraints.
+unsafe fn apply_constraints(window: windef::HWND, constraints: &Constraints) {
+    let mut rectangle = mem::zeroed::<windef::RECT>();
+    if winuser::GetClientRect(window, &mut rectangle) != 0 {
+        let dimensions = (rectangle.right as u32, rectangle.bottom as u32);
+        let fitted = constraints.fit(dimensions);
+        if fitted != dimensions {
+            let (width, height) = outer_dimensions(window, fitted);
+            winuser::SetWindowPos(
+                window,
+                ptr::null_mut(),
+                0,
+                0,
+                width,
+                height,
+                winuser::SWP_NOACTIVATE | winuser::SWP_NOMOVE | winuser::SWP_NOZORDER,
+            );
+        }
+    }
+}
+
+fn react_display_changes(timestamp: Instant) {
+    // Display messages are broadcast to all top-level windows, but changes are
+    // only reported once, because they are determined against the most
+    // recently observed state.
+    let events = display::changes()
+        .into_iter()
+        .map(|(display, event)| Event::Display {
+            display: DisplayHandle::from_raw_handle(display),
+            event,
+            timestamp,
+        });
+    let _ = unsafe { reactor::enqueue(events) };
+}
@@ -0,0 +1,261 @@
+//! XInput game controllers, such as Xbox controllers.
+//!
+//! XInput controllers are polled each time the event thread flushes its
+//! messages rather than read via raw input, which often combines their
+//! triggers into a single axis. Raw input from XInput-capable devices is
+//! ignored. Because XInput provides no notifications, input from controllers
+//! is only observed when the event thread resumes; reactors that wait for
+//! events indefinitely should poll with `WaitUntil` or `Ready` while
+//! controllers are in use.
+//!
+//! XInput controllers are identified by device handles that are derived from
+//! their user index rather than raw input handles. Axes and buttons are
+//! reported using the standard mapping given by the constants in this module.
+//! Stick axes are in the range `[-1.0, 1.0]`, where negative values are left
+//! and up, and trigger axes are in the range `[0.0, 1.0]`.
+
+use gaudium_core::device::{Battery, DeviceHandle, DeviceInfo, Usage};
+use gaudium_core::event::{
+    ElementState, Event, GameControllerAxis, GameControllerButton, InputEvent, InputFocus,
+};
+use gaudium_core::FromRawHandle;
+use std::mem;
+use std::time::{Duration, Instant};
+use winapi::shared::{minwindef, ntdef, winerror};
+use winapi::um::{processthreadsapi, winuser, xinput};
+
+use crate::Binding;
+
+pub const AXIS_LEFT_X: GameControllerAxis = 0;
+pub const AXIS_LEFT_Y: GameControllerAxis = 1;
+pub const AXIS_RIGHT_X: GameControllerAxis = 2;
+pub const AXIS_RIGHT_Y: GameControllerAxis = 3;
+pub const AXIS_LEFT_TRIGGER: GameControllerAxis = 4;
+pub const AXIS_RIGHT_TRIGGER: GameControllerAxis = 5;
+
+pub const BUTTON_A: GameControllerButton = 0;
+pub const BUTTON_B: GameControllerButton = 1;
+pub const BUTTON_X: GameControllerButton = 2;
+pub const BUTTON_Y: GameControllerButton = 3;
+pub const BUTTON_LEFT_SHOULDER: GameControllerButton = 4;
+pub const BUTTON_RIGHT_SHOULDER: GameControllerButton = 5;
+pub const BUTTON_BACK: GameControllerButton = 6;
+pub const BUTTON_START: GameControllerButton = 7;
+pub const BUTTON_LEFT_STICK: GameControllerButton = 8;
+pub const BUTTON_RIGHT_STICK: GameControllerButton = 9;
+pub const BUTTON_DPAD_UP: GameControllerButton = 10;
+pub const BUTTON_DPAD_DOWN: GameControllerButton = 11;
+pub const BUTTON_DPAD_LEFT: GameControllerButton = 12;
+pub const BUTTON_DPAD_RIGHT: GameControllerButton = 13;
+
+const BUTTONS: [(minwindef::WORD, GameControllerButton); 14] = [
+    (xinput::XINPUT_GAMEPAD_A, BUTTON_A),
+    (xinput::XINPUT_GAMEPAD_B, BUTTON_B),
+    (xinput::XINPUT_GAMEPAD_X, BUTTON_X),
+    (xinput::XINPUT_GAMEPAD_Y, BUTTON_Y),
+    (xinput::XINPUT_GAMEPAD_LEFT_SHOULDER, BUTTON_LEFT_SHOULDER),
+    (xinput::XINPUT_GAMEPAD_RIGHT_SHOULDER, BUTTON_RIGHT_SHOULDER),
+    (xinput::XINPUT_GAMEPAD_BACK, BUTTON_BACK),
+    (xinput::XINPUT_GAMEPAD_START, BUTTON_START),
+    (xinput::XINPUT_GAMEPAD_LEFT_THUMB, BUTTON_LEFT_STICK),
+    (xinput::XINPUT_GAMEPAD_RIGHT_THUMB, BUTTON_RIGHT_STICK),
+    (xinput::XINPUT_GAMEPAD_DPAD_UP, BUTTON_DPAD_UP),
+    (xinput::XINPUT_GAMEPAD_DPAD_DOWN, BUTTON_DPAD_DOWN),
+    (xinput::XINPUT_GAMEPAD_DPAD_LEFT, BUTTON_DPAD_LEFT),
+    (xinput::XINPUT_GAMEPAD_DPAD_RIGHT, BUTTON_DPAD_RIGHT),
+];
+
+/// The minimum interval between queries of disconnected user indices.
+///
+/// Querying a disconnected user index is expensive, so connections are only
+/// detected at this interval.
+const SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Tracks the state of XInput controllers between flushes.
+pub(crate) struct Controllers {
+    gamepads: [Option<xinput::XINPUT_GAMEPAD>; xinput::XUSER_MAX_COUNT as usize],
+    scanned: Option<Instant>,
+}
+
+impl Controllers {
+    pub(crate) fn new() -> Self {
+        Controllers {
+            gamepads: [None; xinput::XUSER_MAX_COUNT as usize],
+            scanned: None,
+        }
+    }
+
+    /// Polls controllers and gets events for any changes since the previous
+    /// poll.
+    ///
+    /// A controller that is connected is reported as connected followed by
+    /// any buttons that are pressed and axes that are not at rest.
+    pub(crate) fn poll(&mut self, timestamp: Instant) -> Vec<Event<Binding>> {
+        let is_scanning = !matches!(
+            self.scanned,
+            Some(scanned) if timestamp.saturating_duration_since(scanned) < SCAN_INTERVAL
+        );
+        if is_scanning {
+            self.scanned = Some(timestamp);
+        }
+        let focus = focus();
+        let mut events = vec![];
+        for (index, previous) in self.gamepads.iter_mut().enumerate() {
+            if previous.is_none() && !is_scanning {
+                continue;
+            }
+            let device = DeviceHandle::from_raw_handle(handle(index as minwindef::DWORD));
+            let mut push = |event| {
+                events.push(Event::Input {
+                    device,
+                    window: None,
+                    event,
+                    focus,
+                    timestamp,
+                })
+            };
+            match gamepad(index as minwindef::DWORD) {
+                Some(gamepad) => {
+                    let rest = match previous.replace(gamepad) {
+                        Some(previous) => previous,
+                        None => {
+                            push(InputEvent::Connected {
+                                usage: Some(Usage::GameController),
+                            });
+                            unsafe { mem::zeroed() }
+                        }
+                    };
+                    changes(&rest, &gamepad, push);
+                }
+                None => {
+                    if previous.take().is_some() {
+                        push(InputEvent::Disconnected);
+                    }
+                }
+            }
+        }
+        events
+    }
+}
+
+/// Gets the device handle of a user index.
+///
+/// These handles are taken from the end of the address space, where they
+/// cannot collide with raw input handles.
+fn handle(index: minwindef::DWORD) -> ntdef::HANDLE {
+    (usize::MAX - index as usize) as ntdef::HANDLE
+}
+
+/// Gets the user index of a device handle, if it is an XInput controller.
+pub(crate) fn index(handle: ntdef::HANDLE) -> Option<minwindef::DWORD> {
+    let index = usize::MAX - handle as usize;
+    if index < xinput::XUSER_MAX_COUNT as usize {
+        Some(index as minwindef::DWORD)
+    }
+    else {
+        None
+    }
+}
+
+pub(crate) fn info(_: minwindef::DWORD) -> DeviceInfo {
+    DeviceInfo {
+        usage: Some(Usage::GameController),
+        button_count: Some(BUTTONS.len() as u16),
+        ..Default::default()
+    }
+}
+
+/// Queries the battery of a controller.
+///
+/// XInput reports coarse levels and does not report charging.
+pub(crate) fn battery(index: minwindef::DWORD) -> Option<Battery> {
+    let mut information = unsafe { mem::zeroed::<xinput::XINPUT_BATTERY_INFORMATION>() };
+    if unsafe {
+        xinput::XInputGetBatteryInformation(
+            index,
+            xinput::BATTERY_DEVTYPE_GAMEPAD,
+            &mut information,
+        )
+    } != winerror::ERROR_SUCCESS
+    {
+        return None;
+    }
+    match information.BatteryType {
+        xinput::BATTERY_TYPE_DISCONNECTED | xinput::BATTERY_TYPE_WIRED => None,
+        _ => Some(Battery {
+            level: match information.BatteryLevel {
+                xinput::BATTERY_LEVEL_EMPTY => Some(0.0),
+                xinput::BATTERY_LEVEL_LOW => Some(1.0 / 3.0),
+                xinput::BATTERY_LEVEL_MEDIUM => Some(2.0 / 3.0),
+                xinput::BATTERY_LEVEL_FULL => Some(1.0),
+                _ => None,
+            },
+            state: None,
+        }),
+    }
+}
+
+fn gamepad(index: minwindef::DWORD) -> Option<xinput::XINPUT_GAMEPAD> {
+    let mut state = unsafe { mem::zeroed::<xinput::XINPUT_STATE>() };
+    if unsafe { xinput::XInputGetState(index, &mut state) } == winerror::ERROR_SUCCESS {
+        Some(state.Gamepad)
+    }
+    else {
+        None
+    }
+}
+
+fn axes(gamepad: &xinput::XINPUT_GAMEPAD) -> [(GameControllerAxis, f64); 6] {
+    let stick = |value: i16| (f64::from(value) / f64::from(i16::MAX)).max(-1.0);
+    let trigger = |value: u8| f64::from(value) / f64::from(u8::MAX);
+    [
+        (AXIS_LEFT_X, stick(gamepad.sThumbLX)),
+        (AXIS_LEFT_Y, -stick(gamepad.sThumbLY)),
+        (AXIS_RIGHT_X, stick(gamepad.sThumbRX)),
+        (AXIS_RIGHT_Y, -stick(gamepad.sThumbRY)),
+        (AXIS_LEFT_TRIGGER, trigger(gamepad.bLeftTrigger)),
+        (AXIS_RIGHT_TRIGGER, trigger(gamepad.bRightTrigger)),
+    ]
+}
+
+fn changes<F>(previous: &xinput::XINPUT_GAMEPAD, gamepad: &xinput::XINPUT_GAMEPAD, mut f: F)
+where
+    F: FnMut(InputEvent),
+{
+    for &(mask, button) in BUTTONS.iter() {
+        let is_pressed = gamepad.wButtons & mask != 0;
+        if is_pressed != (previous.wButtons & mask != 0) {
+            f(InputEvent::GameControllerButtonChanged {
+                button,
+                state: if is_pressed {
+                    ElementState::Pressed
+                }
+                else {
+                    ElementState::Released
+                },
+            });
+        }
+    }
+    for (&(axis, value), &(_, other)) in axes(gamepad).iter().zip(axes(previous).iter()) {
+        if value != other {
+            f(InputEvent::GameControllerAxisChanged { axis, value });
+        }
+    }
+}
+
+/// Gets the focus of controller input, which is in the foreground if a window
+/// of the event thread is the foreground window.
+fn focus() -> InputFocus {
+    unsafe {
+        let window = winuser::GetForegroundWindow();
+        if !window.is_null()
+            && winuser::GetWindowThreadProcessId(window, std::ptr::null_mut())
+                == processthreadsapi::GetCurrentThreadId()
+        {
+            InputFocus::Foreground
+        }
+        else {
+            InputFocus::Background
+        }
+    }
+}
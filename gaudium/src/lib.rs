@@ -29,8 +29,8 @@
 //! ```rust,no_run
 //! use gaudium::platform::{Binding, WindowBuilderExt};
 //! use gaudium::prelude::*;
-//! use gaudium::reactor::{EventThread, FromContext, Reactor, ThreadContext};
-//! use gaudium::window::{Window, WindowBuilder, WindowHandle};
+//! use gaudium::reactor::{EventThread, FromContext, Reactor, Sinks, ThreadContext};
+//! use gaudium::window::{Window, WindowBuilder};
 //! use std::sync::mpsc::{self, Sender};
 //! use std::thread::{self, JoinHandle};
 //!
@@ -41,7 +41,7 @@
 //! }
 //!
 //! impl FromContext<Binding> for TestReactor {
-//!     fn from_context(context: &ThreadContext) -> (WindowHandle, Self) {
+//!     fn from_context(context: &ThreadContext) -> (Sinks, Self) {
 //!         let window = WindowBuilder::default()
 //!             .with_title("Gaudium")
 //!             .build(context)
@@ -52,7 +52,7 @@
 //!                 println!("{:?}", event);
 //!             }
 //!         });
-//!         (window.handle(), TestReactor { window, tx, handle })
+//!         (window.handle().into(), TestReactor { window, tx, handle })
 //!     }
 //! }
 //!
@@ -80,39 +80,50 @@
 
 #![allow(unknown_lints)] // Allow clippy lints.
 
-pub use gaudium_core::framework;
+pub use gaudium_core::{accessibility, error, framework};
 
 pub mod device {
     use crate::platform::Binding;
 
-    pub use gaudium_core::device::Usage;
+    pub use gaudium_core::device::{
+        Battery, BatteryState, DeviceId, DeviceInfo, Led, Output, SensorType, Usage,
+    };
 
     pub type DeviceHandle = gaudium_core::device::DeviceHandle<Binding>;
 }
 
 pub mod display {
+    use crate::platform::Binding;
+
     pub use gaudium_core::display::{
-        FromLogical, FromPhysical, IntoLogical, IntoPhysical, LogicalUnit, PhysicalUnit,
+        FromLogical, FromPhysical, IntoLogical, IntoPhysical, LogicalPosition, LogicalSize,
+        LogicalUnit, PhysicalPosition, PhysicalSize, PhysicalUnit, Vector, VideoMode,
     };
 
-    // TODO: This type will be parameterized by platform.
-    //
-    //   pub type DisplayHandle = gaudium_core::display::DisplayHandle<Platform>;
-    pub use gaudium_core::display::DisplayHandle;
+    pub type DisplayHandle = gaudium_core::display::DisplayHandle<Binding>;
 }
 
 pub mod event {
     use crate::platform::Binding;
 
     pub use gaudium_core::event::{
-        ApplicationEvent, ElementState, GameControllerAxis, GameControllerButton, InputEvent,
-        KeyCode, ModifierState, MouseButton, MouseMovement, MouseWheelDelta, RelativeMotion,
-        ScanCode, WindowCloseState, WindowEvent, WindowPosition,
+        ApplicationEvent, DisplayEvent, ElementState, EventKind, GameControllerAxis,
+        GameControllerButton, InputEvent, InputFocus, KeyCode, ModifierState, MouseButton,
+        MouseMovement, MouseWheelDelta, PenInfo, RelativeMotion, ScanCode, ToggleState,
+        WindowCloseState, WindowEvent, WindowPosition, WindowState,
     };
 
     pub type Event = gaudium_core::event::Event<Binding>;
 }
 
+pub mod keyboard {
+    use crate::platform::Binding;
+
+    pub use gaudium_core::keyboard::KeyLayoutId;
+
+    pub type KeyLayout = gaudium_core::keyboard::KeyLayout<Binding>;
+}
+
 pub mod platform {
     #[cfg(all(
         not(any(target_os = "linux", target_os = "windows")),
@@ -125,7 +136,10 @@ pub mod platform {
     #[cfg(target_os = "linux")]
     pub use gaudium_platform_empty::{Binding, WindowBuilderExt};
     #[cfg(target_os = "windows")]
-    pub use gaudium_platform_windows::{Binding, WindowBuilderExt};
+    pub use gaudium_platform_windows::{
+        xinput, Binding, QueueOverflow, RawInputFilter, SurfaceTokenExt, ThreadContextExt,
+        WindowBuilderExt, WindowExt,
+    };
 }
 
 pub mod prelude {
@@ -142,15 +156,69 @@ pub mod prelude {
 pub mod reactor {
     use crate::platform::Binding;
 
-    pub use gaudium_core::reactor::{FromContext, Poll, Reaction, Reactor, ThreadContext};
+    pub use gaudium_core::reactor::{
+        from_fn_with, DynReactor, EventThreadProxy, FromContext, PanicPolicy, Poll, Reaction,
+        Reactor, Recurrence, ThreadContext, ThreadPriority, TimerId,
+    };
+
+    pub mod instrument {
+        pub use gaudium_core::reactor::instrument::{Collector, Instrumentation, Statistics};
+    }
+
+    pub mod prefilter {
+        pub use gaudium_core::reactor::prefilter::PreFilter;
+    }
 
+    pub mod watchdog {
+        pub use gaudium_core::reactor::watchdog::{Stall, Watchdog};
+    }
+
+    pub type EventBatch<'a> = gaudium_core::reactor::EventBatch<'a, Binding>;
     pub type EventThread<R> = gaudium_core::reactor::EventThread<Binding, R>;
+    pub type EventThreadBuilder = gaudium_core::reactor::EventThreadBuilder<Binding>;
+    pub type Sink = gaudium_core::reactor::Sink<Binding>;
+    pub type Sinks = gaudium_core::reactor::Sinks<Binding>;
     pub type StatefulReactor<T, F> = gaudium_core::reactor::StatefulReactor<Binding, T, F>;
 }
 
+pub mod shell {
+    pub mod dialog {
+        use crate::platform::Binding;
+
+        pub use gaudium_core::shell::dialog::{
+            FileFilter, MessageButtons, MessageLevel, MessageResponse,
+        };
+
+        pub type FileDialog = gaudium_core::shell::dialog::FileDialog<Binding>;
+        pub type MessageDialog = gaudium_core::shell::dialog::MessageDialog<Binding>;
+    }
+
+    pub mod hotkey {
+        use crate::platform::Binding;
+
+        pub use gaudium_core::shell::hotkey::{HotkeyId, Modifiers};
+
+        pub type Hotkey = gaudium_core::shell::hotkey::Hotkey<Binding>;
+    }
+
+    pub mod instance {
+        use crate::platform::Binding;
+
+        pub type SingleInstance = gaudium_core::shell::instance::SingleInstance<Binding>;
+    }
+}
+
 pub mod window {
     use crate::platform::Binding;
 
+    #[cfg(feature = "capture")]
+    pub use gaudium_core::window::Image;
+    pub use gaudium_core::window::{
+        CursorIcon, Fullscreen, Geometry, HitTestResult, Icon, MouseMode, WindowId, WindowKind,
+        ZOrder,
+    };
+
+    pub type SurfaceToken = gaudium_core::window::SurfaceToken<Binding>;
     pub type Window = gaudium_core::window::Window<Binding>;
     pub type WindowBuilder = gaudium_core::window::WindowBuilder<Binding>;
     pub type WindowHandle = gaudium_core::window::WindowHandle<Binding>;